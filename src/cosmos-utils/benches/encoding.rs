@@ -0,0 +1,110 @@
+//! Throughput of the manual protobuf encoding used to build and sign transactions.
+//! Run with `cargo bench -p cosmos-utils`, CI compares the results to the previous run of `main`.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use cosmos_sdk_proto::cosmos::{
+    auth::v1beta1::{BaseAccount, QueryAccountResponse},
+    crypto::{multisig::LegacyAminoPubKey, secp256k1::PubKey},
+};
+use cosmos_utils::calculate_fee_for_gas;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use ic_cosmos::types::{
+    create_sign_doc_bytes, encode_msg_send, parse_account_info_from_abci, CosmosCoin, CosmosMessage, CosmosTransaction,
+    SignMode,
+};
+use prost::Message;
+use prost_types::Any;
+use serde_json::json;
+
+const ADDRESS: &str = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+
+fn msg_send_value() -> serde_json::Value {
+    json!({
+        "from_address": ADDRESS,
+        "to_address": ADDRESS,
+        "amount": [{ "denom": "uatom", "amount": "1000000" }]
+    })
+}
+
+fn transaction(message_count: usize) -> CosmosTransaction {
+    CosmosTransaction {
+        messages: (0..message_count)
+            .map(|_| CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: msg_send_value(),
+            })
+            .collect(),
+        fee: vec![CosmosCoin {
+            denom: "uatom".to_string(),
+            amount: "5000".to_string(),
+        }],
+        gas_limit: 200_000,
+        memo: "benchmark".to_string(),
+        chain_id: "cosmoshub-4".to_string(),
+        account_number: 12345,
+        sequence: 42,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    }
+}
+
+/// Base64 `QueryAccountResponse` of a multisig account, about 500 bytes
+fn account_response() -> String {
+    let public_keys = (0..5u8)
+        .map(|i| Any {
+            type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(),
+            value: PubKey { key: vec![i; 33] }.encode_to_vec(),
+        })
+        .collect();
+    let account = BaseAccount {
+        address: ADDRESS.to_string(),
+        pub_key: Some(Any {
+            type_url: "/cosmos.crypto.multisig.LegacyAminoPubKey".to_string(),
+            value: LegacyAminoPubKey {
+                threshold: 3,
+                public_keys,
+            }
+            .encode_to_vec(),
+        }),
+        account_number: 12345,
+        sequence: 42,
+    };
+    let response = QueryAccountResponse {
+        account: Some(Any {
+            type_url: "/cosmos.auth.v1beta1.BaseAccount".to_string(),
+            value: account.encode_to_vec(),
+        }),
+    };
+    STANDARD.encode(response.encode_to_vec())
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encoding");
+    group.throughput(Throughput::Elements(1));
+
+    let value = msg_send_value();
+    group.bench_function("encode_msg_send", |b| {
+        b.iter(|| encode_msg_send(black_box(&value)).unwrap())
+    });
+
+    let transaction = transaction(5);
+    let public_key = [2u8; 33];
+    group.bench_function("create_sign_doc_bytes_5_messages", |b| {
+        b.iter(|| create_sign_doc_bytes(black_box(&transaction), black_box(&public_key)).unwrap())
+    });
+
+    let response = account_response();
+    group.bench_function("parse_account_info_from_abci", |b| {
+        b.iter(|| parse_account_info_from_abci(black_box(&response)).unwrap())
+    });
+
+    group.bench_function("calculate_fee_for_gas", |b| {
+        b.iter(|| calculate_fee_for_gas(black_box(200_000), black_box(0.025)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_encoding);
+criterion_main!(benches);
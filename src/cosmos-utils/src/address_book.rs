@@ -0,0 +1,122 @@
+//! Named bech32 addresses persisted to `~/.cosmos-utils/addresses.json`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::json_store::{validate_name, JsonStore, JsonStoreError};
+
+/// Prefix used on the command line to reference an address book entry, e.g. `@my-validator`
+pub const ADDRESS_REFERENCE_PREFIX: char = '@';
+
+/// Errors returned by the address book
+#[derive(Debug, thiserror::Error)]
+pub enum AddressBookError {
+    #[error("Invalid bech32 address '{0}'")]
+    InvalidAddress(String),
+    #[error("No address named '{0}' in the address book")]
+    UnknownName(String),
+    #[error("Address book: {0}")]
+    Store(#[from] JsonStoreError),
+}
+
+/// Named addresses backed by a JSON file
+#[derive(Debug)]
+pub struct AddressBook {
+    store: JsonStore<BTreeMap<String, String>>,
+}
+
+impl AddressBook {
+    /// Opens the address book at `~/.cosmos-utils/addresses.json`, empty if the file does not exist yet
+    pub fn open() -> Result<Self, AddressBookError> {
+        Ok(Self {
+            store: JsonStore::open("addresses.json")?,
+        })
+    }
+
+    /// Opens the address book stored at `path`, empty if the file does not exist yet
+    pub fn open_at(path: impl Into<PathBuf>) -> Result<Self, AddressBookError> {
+        Ok(Self {
+            store: JsonStore::open_at(path)?,
+        })
+    }
+
+    /// Writes the address book back to its file
+    pub fn save(&self) -> Result<(), AddressBookError> {
+        Ok(self.store.save()?)
+    }
+
+    /// Adds or replaces the address saved under `name`
+    pub fn add(&mut self, name: &str, address: &str) -> Result<(), AddressBookError> {
+        validate_name(name)?;
+        validate_bech32_address(address)?;
+        self.store.get_mut().insert(name.to_string(), address.to_string());
+        Ok(())
+    }
+
+    /// Returns the address saved under `name`
+    pub fn lookup(&self, name: &str) -> Option<String> {
+        self.store.get().get(name).cloned()
+    }
+
+    /// Returns all `(name, address)` entries sorted by name
+    pub fn list(&self) -> Vec<(String, String)> {
+        self.store
+            .get()
+            .iter()
+            .map(|(name, address)| (name.clone(), address.clone()))
+            .collect()
+    }
+
+    /// Removes the address saved under `name`, returning `true` if it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.store.get_mut().remove(name).is_some()
+    }
+
+    /// Resolves a `@name` reference via the address book, or validates a full bech32 address
+    pub fn resolve(&self, input: &str) -> Result<String, AddressBookError> {
+        match input.strip_prefix(ADDRESS_REFERENCE_PREFIX) {
+            Some(name) => self
+                .lookup(name)
+                .ok_or_else(|| AddressBookError::UnknownName(name.to_string())),
+            None => {
+                validate_bech32_address(input)?;
+                Ok(input.to_string())
+            }
+        }
+    }
+}
+
+fn validate_bech32_address(address: &str) -> Result<(), AddressBookError> {
+    bech32::decode(address)
+        .map(|_| ())
+        .map_err(|_| AddressBookError::InvalidAddress(address.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    const VALIDATOR: &str = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+
+    #[test]
+    fn test_address_book_roundtrip() {
+        let (_dir, path) = temp_file_path("addresses.json");
+        let mut book = AddressBook::open_at(&path).unwrap();
+        assert!(book.list().is_empty());
+
+        book.add("my-validator", VALIDATOR).unwrap();
+        assert!(book.add("bad name", VALIDATOR).is_err());
+        assert!(book.add("typo", "cosmosvaloper1invalid").is_err());
+        book.save().unwrap();
+
+        let mut book = AddressBook::open_at(&path).unwrap();
+        assert_eq!(book.lookup("my-validator").as_deref(), Some(VALIDATOR));
+        assert_eq!(book.resolve("@my-validator").unwrap(), VALIDATOR);
+        assert_eq!(book.resolve(VALIDATOR).unwrap(), VALIDATOR);
+        assert!(book.resolve("@unknown").is_err());
+
+        assert!(book.remove("my-validator"));
+        assert!(!book.remove("my-validator"));
+    }
+}
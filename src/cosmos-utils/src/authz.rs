@@ -0,0 +1,129 @@
+//! Authz grants, letting a grantee such as the wallet canister execute messages with `MsgExec` on
+//! behalf of a granter.
+
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmos_sdk_proto::cosmos::authz::v1beta1::{GenericAuthorization, Grant, QueryGrantsRequest, QueryGrantsResponse};
+use ic_cosmos::types::format_block_time;
+use prost::Message;
+use prost_types::Any;
+
+use crate::{abci_query, CosmosClientConfig};
+
+/// Type URL of the authorization of any message of a given type
+const GENERIC_AUTHORIZATION_TYPE_URL: &str = "/cosmos.authz.v1beta1.GenericAuthorization";
+
+/// An authorization granted by a granter to a grantee
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthzGrantInfo {
+    /// The authorization, e.g. a `/cosmos.authz.v1beta1.GenericAuthorization`
+    pub authorization: Any,
+    /// RFC 3339 time at which the grant expires, `None` for a grant without expiration
+    pub expiration: Option<String>,
+    /// Whether the grant has not expired yet
+    pub is_valid: bool,
+}
+
+/// Returns the grant of `granter` allowing `grantee` to execute messages of `msg_type_url`,
+/// e.g. `/cosmos.bank.v1beta1.MsgSend`, to be checked before sending a `MsgExec`.
+/// Fails if there is no such grant.
+pub fn verify_authz_grant(
+    grantee: &str,
+    granter: &str,
+    msg_type_url: &str,
+    rpc_url: &str,
+) -> Result<AuthzGrantInfo, Box<dyn Error>> {
+    let config = CosmosClientConfig {
+        rpc_url: rpc_url.to_string(),
+        ..Default::default()
+    };
+    let request = QueryGrantsRequest {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        msg_type_url: msg_type_url.to_string(),
+        pagination: None,
+    };
+    let value = abci_query("/cosmos.authz.v1beta1.Query/Grants", &request.encode_to_vec(), &config)?;
+    let response = QueryGrantsResponse::decode(&value[..])?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    find_grant(response.grants, msg_type_url, now)
+        .ok_or_else(|| format!("No grant of {} from {} to {}", msg_type_url, granter, grantee).into())
+}
+
+/// Returns the first of `grants` authorizing `msg_type_url`, valid if it has not expired at `now_nanos`.
+/// Authorizations other than a `GenericAuthorization` are specific to a message type and match any.
+fn find_grant(grants: Vec<Grant>, msg_type_url: &str, now_nanos: u64) -> Option<AuthzGrantInfo> {
+    grants.into_iter().find_map(|grant| {
+        let authorization = grant.authorization?;
+        if authorization.type_url == GENERIC_AUTHORIZATION_TYPE_URL
+            && generic_authorization_msg(&authorization.value).is_some_and(|msg| msg != msg_type_url)
+        {
+            return None;
+        }
+        let expiration_nanos = grant.expiration.map(|expiration| {
+            u64::try_from(expiration.seconds)
+                .unwrap_or(0)
+                .saturating_mul(1_000_000_000)
+                .saturating_add(expiration.nanos.max(0) as u64)
+        });
+        Some(AuthzGrantInfo {
+            authorization,
+            expiration: expiration_nanos.map(format_block_time),
+            is_valid: expiration_nanos.map_or(true, |expiration| expiration > now_nanos),
+        })
+    })
+}
+
+/// Returns the `msg` of an encoded `GenericAuthorization`
+fn generic_authorization_msg(value: &[u8]) -> Option<String> {
+    GenericAuthorization::decode(value)
+        .ok()
+        .map(|authorization| authorization.msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::Timestamp;
+
+    use super::*;
+
+    fn generic_grant(msg: &str, expiration_secs: Option<i64>) -> Grant {
+        Grant {
+            authorization: Some(Any {
+                type_url: GENERIC_AUTHORIZATION_TYPE_URL.to_string(),
+                value: GenericAuthorization { msg: msg.to_string() }.encode_to_vec(),
+            }),
+            expiration: expiration_secs.map(|seconds| Timestamp { seconds, nanos: 0 }),
+        }
+    }
+
+    #[test]
+    fn test_find_grant() {
+        let now = 1_700_000_000_000_000_000;
+        let grants = vec![
+            generic_grant("/cosmos.staking.v1beta1.MsgDelegate", None),
+            generic_grant("/cosmos.bank.v1beta1.MsgSend", Some(1_705_322_096)),
+        ];
+
+        let grant = find_grant(grants.clone(), "/cosmos.bank.v1beta1.MsgSend", now).unwrap();
+        assert_eq!(grant.authorization.type_url, GENERIC_AUTHORIZATION_TYPE_URL);
+        assert_eq!(grant.expiration.as_deref(), Some("2024-01-15T12:34:56Z"));
+        assert!(grant.is_valid);
+        let expired = find_grant(
+            grants.clone(),
+            "/cosmos.bank.v1beta1.MsgSend",
+            1_705_322_096_000_000_000,
+        )
+        .unwrap();
+        assert!(!expired.is_valid);
+
+        let grant = find_grant(grants.clone(), "/cosmos.staking.v1beta1.MsgDelegate", now).unwrap();
+        assert_eq!(grant.expiration, None);
+        assert!(grant.is_valid);
+
+        assert_eq!(find_grant(grants, "/cosmos.gov.v1beta1.MsgVote", now), None);
+    }
+}
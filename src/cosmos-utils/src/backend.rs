@@ -0,0 +1,53 @@
+//! [`SigningBackend`] used to build transactions with [`ic_cosmos::types::CosmosTransactionBuilder`]
+//! from the command line.
+
+use ic_cosmos::types::{
+    decode_simulate_response, encode_simulate_query, CosmosTransaction, SigningBackend, TX_SIMULATE_PATH,
+};
+use serde_json::json;
+
+use crate::CosmosClientConfig;
+
+/// Simulates transactions against the provider testnet with the blocking reqwest client
+#[derive(Debug, Clone)]
+pub struct ReqwestBackend {
+    pub config: CosmosClientConfig,
+    /// Compressed secp256k1 public key of the signer
+    pub public_key: Vec<u8>,
+}
+
+impl ReqwestBackend {
+    pub fn new(config: CosmosClientConfig, public_key: Vec<u8>) -> Self {
+        Self { config, public_key }
+    }
+}
+
+impl SigningBackend for ReqwestBackend {
+    async fn simulate(&self, transaction: &CosmosTransaction) -> Result<u64, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "abci_query",
+            "params": {
+                "path": TX_SIMULATE_PATH,
+                "data": encode_simulate_query(transaction, &self.public_key)?,
+                "height": "0",
+                "prove": false
+            }
+        });
+        let response_json = self.config.post_json_rpc(&request).map_err(|e| e.to_string())?;
+
+        if let Some(error) = response_json.get("error") {
+            return Err(format!("Simulation RPC error: {}", error));
+        }
+        let response = &response_json["result"]["response"];
+        if response["code"].as_i64().unwrap_or(0) != 0 {
+            return Err(format!(
+                "Simulation failed: {}",
+                response["log"].as_str().unwrap_or("Unknown error")
+            ));
+        }
+        let value = response["value"].as_str().ok_or("Missing simulation response value")?;
+        decode_simulate_response(value)
+    }
+}
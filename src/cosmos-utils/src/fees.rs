@@ -0,0 +1,133 @@
+//! Fee selection for chains accepting fees in several denoms.
+
+use std::collections::HashMap;
+
+use ic_cosmos::types::CosmosCoin;
+pub use ic_cosmos::types::GasPrice;
+#[cfg(feature = "native")]
+use ic_cosmos::types::{cosmos_protobuf::find_field, parse_min_gas_prices, NODE_CONFIG_PATH};
+
+#[cfg(feature = "native")]
+use crate::{abci_query, CosmosClientConfig, CosmosError};
+use crate::{calculate_fee_for_gas, DEFAULT_GAS_PRICES};
+
+/// Placeholder USD prices per base unit of well-known denoms, used to compare fees across denoms
+const USD_PRICES: &[(&str, f64)] = &[
+    ("uatom", 0.000_005),
+    ("uosmo", 0.000_000_5),
+    ("uusdc", 0.000_001),
+    ("untrn", 0.000_000_4),
+];
+
+/// Returns the gas prices of the node behind `config`, from its `minimum-gas-prices` setting
+#[cfg(feature = "native")]
+pub fn node_gas_prices(config: &CosmosClientConfig) -> Result<Vec<GasPrice>, CosmosError> {
+    let value = abci_query(NODE_CONFIG_PATH, &[], config)?;
+    // minimum_gas_price = 1
+    let min_gas_prices = find_field(&value, 1)
+        .map_err(CosmosError::DecodingError)?
+        .and_then(|field| field.as_string())
+        .unwrap_or_default();
+    parse_min_gas_prices(&min_gas_prices)
+        .map(with_default_gas_prices)
+        .map_err(CosmosError::DecodingError)
+}
+
+/// Returns the positive `gas_prices`, or [`DEFAULT_GAS_PRICES`] if there are none, e.g. when the
+/// node accepts transactions without fees
+pub fn with_default_gas_prices(gas_prices: Vec<GasPrice>) -> Vec<GasPrice> {
+    let gas_prices: Vec<GasPrice> = gas_prices.into_iter().filter(|price| price.price > 0.0).collect();
+    if !gas_prices.is_empty() {
+        return gas_prices;
+    }
+    DEFAULT_GAS_PRICES
+        .iter()
+        .map(|(denom, price)| GasPrice {
+            denom: denom.to_string(),
+            price: *price,
+        })
+        .collect()
+}
+
+/// Returns the USD price of one base unit of `denom`, if known
+pub fn usd_price(denom: &str) -> Option<f64> {
+    USD_PRICES
+        .iter()
+        .find(|(known, _)| *known == denom)
+        .map(|(_, price)| *price)
+}
+
+/// Picks the fee for `gas_limit` with the lowest USD value among the denoms the user can afford.
+/// Denoms without a known USD price are only used when no priced denom is affordable.
+pub fn select_optimal_fee(
+    gas_limit: u64,
+    available_prices: &[GasPrice],
+    available_balances: &HashMap<String, u64>,
+) -> Option<CosmosCoin> {
+    available_prices
+        .iter()
+        .map(|gas_price| (gas_price, calculate_fee_for_gas(gas_limit, gas_price.price)))
+        .filter(|(gas_price, amount)| {
+            available_balances
+                .get(&gas_price.denom)
+                .is_some_and(|balance| balance >= amount)
+        })
+        .min_by(|(a, a_amount), (b, b_amount)| {
+            let a_usd = usd_price(&a.denom).map(|price| price * *a_amount as f64);
+            let b_usd = usd_price(&b.denom).map(|price| price * *b_amount as f64);
+            match (a_usd, b_usd) {
+                (Some(a_usd), Some(b_usd)) => a_usd.total_cmp(&b_usd),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a_amount.cmp(b_amount),
+            }
+        })
+        .map(|(gas_price, amount)| CosmosCoin::new(gas_price.denom.clone(), amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ic_cosmos::types::parse_min_gas_prices;
+
+    use super::*;
+
+    #[test]
+    fn test_select_optimal_fee() {
+        let prices = parse_min_gas_prices("0.01uatom,0.025uosmo,0.001ibc/UNKNOWN").unwrap();
+        let mut balances = HashMap::from([("uatom".to_string(), 1_000_000), ("uosmo".to_string(), 1_000_000)]);
+
+        // 2000 uatom = $0.01, 5000 uosmo = $0.0025
+        let fee = select_optimal_fee(200_000, &prices, &balances).unwrap();
+        assert_eq!(fee, CosmosCoin::new("uosmo", "5000"));
+
+        balances.insert("uosmo".to_string(), 100);
+        let fee = select_optimal_fee(200_000, &prices, &balances).unwrap();
+        assert_eq!(fee, CosmosCoin::new("uatom", "2000"));
+
+        let balances = HashMap::from([("ibc/UNKNOWN".to_string(), 1_000)]);
+        let fee = select_optimal_fee(200_000, &prices, &balances).unwrap();
+        assert_eq!(fee.denom, "ibc/UNKNOWN");
+
+        assert!(select_optimal_fee(200_000, &prices, &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_with_default_gas_prices() {
+        let balances = HashMap::from([("uatom".to_string(), 1_000_000), ("uosmo".to_string(), 1_000_000)]);
+
+        // Only uatom without the prices of the node
+        let default_prices = with_default_gas_prices(parse_min_gas_prices("").unwrap());
+        assert_eq!(
+            default_prices,
+            with_default_gas_prices(parse_min_gas_prices("0uatom").unwrap())
+        );
+        let fee = select_optimal_fee(200_000, &default_prices, &balances).unwrap();
+        assert_eq!(fee, CosmosCoin::new("uatom", "2000"));
+
+        // The cheaper uosmo once the node accepts it
+        let node_prices = with_default_gas_prices(parse_min_gas_prices("0.01uatom,0.025uosmo").unwrap());
+        assert_eq!(node_prices.len(), 2);
+        let fee = select_optimal_fee(200_000, &node_prices, &balances).unwrap();
+        assert_eq!(fee, CosmosCoin::new("uosmo", "5000"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Finality of the blocks, confirmed before relaying IBC packets or releasing bridged funds.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use ic_cosmos::analytics::FinalityInfo;
+use ic_cosmos::analytics::{block_finality, VALIDATOR_SET_PAGE_LIMIT};
+use ic_cosmos::types::{CommitResult, ValidatorWithPriority, ValidatorsResult};
+use serde_json::json;
+
+use crate::{history::rpc_result, CosmosClientConfig, CosmosError};
+
+/// Delay between two polls of the commit of the next block, shorter than a block of most chains
+const FINALITY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Waits until the block at `height` is final, i.e. validators with more than 2/3 of the voting
+/// power signed the commit of the next block. Polls `rpc_url` until `timeout_secs` elapsed, the
+/// next block may not be committed yet.
+pub fn wait_for_block_finality(height: u64, rpc_url: &str, timeout_secs: u64) -> Result<FinalityInfo, CosmosError> {
+    let config = CosmosClientConfig {
+        rpc_url: rpc_url.to_string(),
+        ..Default::default()
+    };
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    let mut validators = None;
+    loop {
+        let last_error = match rpc_result("commit", json!({ "height": (height + 1).to_string() }), &config)
+            .and_then(|commit| Ok(serde_json::from_value::<CommitResult>(commit)?))
+        {
+            Ok(commit) => {
+                if validators.is_none() {
+                    validators = Some(get_validator_set(height + 1, &config)?);
+                }
+                if let Some(finality) = block_finality(&commit, validators.as_deref().unwrap_or_default()) {
+                    return Ok(finality);
+                }
+                None
+            }
+            Err(e) => Some(e),
+        };
+        if Instant::now() >= deadline {
+            let reason = last_error.map_or_else(|| "not enough voting power signed".to_string(), |e| e.to_string());
+            return Err(CosmosError::Timeout(format!(
+                "Block {} is not final after {} seconds: {}",
+                height, timeout_secs, reason
+            )));
+        }
+        thread::sleep(FINALITY_POLL_INTERVAL);
+    }
+}
+
+/// Returns the complete validator set at `height`
+fn get_validator_set(height: u64, config: &CosmosClientConfig) -> Result<Vec<ValidatorWithPriority>, CosmosError> {
+    let mut validators = Vec::new();
+    for page in 1.. {
+        let result: ValidatorsResult = serde_json::from_value(rpc_result(
+            "validators",
+            json!({
+                "height": height.to_string(),
+                "page": page.to_string(),
+                "per_page": VALIDATOR_SET_PAGE_LIMIT.to_string(),
+            }),
+            config,
+        )?)?;
+        let total: usize = result.total.parse()?;
+        if result.validators.is_empty() {
+            break;
+        }
+        validators.extend(result.validators);
+        if validators.len() >= total {
+            break;
+        }
+    }
+    Ok(validators)
+}
@@ -0,0 +1,159 @@
+//! Gas estimates of the common message types, used when a transaction cannot be simulated.
+
+use std::collections::{BTreeMap, HashMap};
+
+use ic_cosmos::types::CosmosMessage;
+use serde::{Deserialize, Serialize};
+
+/// Type URL of the CosmWasm contract execution, whose gas grows with the size of the message
+const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+/// Gas of a message type in a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimate {
+    /// Gas of a transaction with a single message of the type, including the ante handler
+    pub base: u64,
+    /// Gas of every other message of the type in the same transaction
+    pub per_additional_message: u64,
+    /// Multiplier applied to the estimate, for the variability of the gas used
+    pub buffer: f64,
+}
+
+impl GasEstimate {
+    pub const fn new(base: u64, per_additional_message: u64, buffer: f64) -> Self {
+        Self {
+            base,
+            per_additional_message,
+            buffer,
+        }
+    }
+
+    /// Returns the buffered gas of `message_count` messages, 0 for no message
+    pub fn gas_for(&self, message_count: usize) -> u64 {
+        if message_count == 0 {
+            return 0;
+        }
+        let additional = self.per_additional_message.saturating_mul(message_count as u64 - 1);
+        (self.base.saturating_add(additional) as f64 * self.buffer).round() as u64
+    }
+}
+
+/// Gas estimates by message type URL. The default table holds the gas used on mainnet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimateTable {
+    pub estimates: HashMap<String, GasEstimate>,
+    /// Estimate of the message types missing from `estimates`
+    pub default: GasEstimate,
+    /// Gas added per byte of the JSON value of a `MsgExecuteContract`, for the deserialization
+    /// and storage writes of the contract
+    pub execute_contract_gas_per_byte: u64,
+}
+
+impl Default for GasEstimateTable {
+    fn default() -> Self {
+        let estimates = [
+            ("/cosmos.bank.v1beta1.MsgSend", GasEstimate::new(97_000, 10_000, 1.3)),
+            (
+                "/cosmos.staking.v1beta1.MsgDelegate",
+                GasEstimate::new(320_000, 320_000, 1.1),
+            ),
+            (
+                "/cosmos.staking.v1beta1.MsgUndelegate",
+                GasEstimate::new(320_000, 320_000, 1.1),
+            ),
+            (
+                "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+                GasEstimate::new(350_000, 350_000, 1.1),
+            ),
+            (
+                "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+                GasEstimate::new(80_000, 5_000, 1.2),
+            ),
+            ("/cosmos.gov.v1beta1.MsgVote", GasEstimate::new(75_000, 75_000, 1.2)),
+            ("/cosmos.gov.v1.MsgVote", GasEstimate::new(75_000, 75_000, 1.2)),
+            (MSG_EXECUTE_CONTRACT_TYPE_URL, GasEstimate::new(150_000, 150_000, 1.3)),
+        ]
+        .into_iter()
+        .map(|(type_url, estimate)| (type_url.to_string(), estimate))
+        .collect();
+        Self {
+            estimates,
+            default: GasEstimate::new(250_000, 250_000, 1.0),
+            execute_contract_gas_per_byte: 20,
+        }
+    }
+}
+
+impl GasEstimateTable {
+    /// Sets the estimate of `type_url`
+    pub fn with_estimate(mut self, type_url: impl Into<String>, estimate: GasEstimate) -> Self {
+        self.estimates.insert(type_url.into(), estimate);
+        self
+    }
+
+    /// Returns the estimate of `type_url`, or the default estimate of unknown types
+    pub fn estimate(&self, type_url: &str) -> GasEstimate {
+        self.estimates.get(type_url).copied().unwrap_or(self.default)
+    }
+
+    /// Returns the buffered gas of `message_count` messages of `type_url` in a transaction
+    pub fn gas_for(&self, type_url: &str, message_count: usize) -> u64 {
+        self.estimate(type_url).gas_for(message_count)
+    }
+
+    /// Returns the gas of a transaction with `messages`, the sum of the estimates of their types.
+    /// Contract executions also take the size of their messages into account.
+    pub fn gas_for_messages(&self, messages: &[CosmosMessage]) -> u64 {
+        let mut counts = BTreeMap::new();
+        for message in messages {
+            *counts.entry(message.type_url.as_str()).or_insert(0) += 1;
+        }
+        let execute_bytes: usize = messages
+            .iter()
+            .filter(|message| message.type_url == MSG_EXECUTE_CONTRACT_TYPE_URL)
+            .map(|message| message.value.to_string().len())
+            .sum();
+        counts
+            .into_iter()
+            .map(|(type_url, count)| self.gas_for(type_url, count))
+            .sum::<u64>()
+            .saturating_add(self.execute_contract_gas_per_byte.saturating_mul(execute_bytes as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn message(type_url: &str, value: serde_json::Value) -> CosmosMessage {
+        CosmosMessage {
+            type_url: type_url.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_gas_estimate_table() {
+        let table = GasEstimateTable::default();
+        assert_eq!(table.gas_for("/cosmos.bank.v1beta1.MsgSend", 1), 126_100);
+        assert_eq!(table.gas_for("/cosmos.bank.v1beta1.MsgSend", 3), 152_100);
+        assert_eq!(table.gas_for("/cosmos.bank.v1beta1.MsgSend", 0), 0);
+        assert_eq!(table.gas_for("/ibc.applications.transfer.v1.MsgTransfer", 2), 500_000);
+
+        let withdraw = message("/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward", json!({}));
+        let vote = message("/cosmos.gov.v1beta1.MsgVote", json!({}));
+        assert_eq!(
+            table.gas_for_messages(&[withdraw.clone(), vote, withdraw]),
+            102_000 + 90_000
+        );
+
+        let execute = message(MSG_EXECUTE_CONTRACT_TYPE_URL, json!({ "msg": { "claim": {} } }));
+        let bytes = execute.value.to_string().len() as u64;
+        assert_eq!(table.gas_for_messages(&[execute]), 195_000 + 20 * bytes);
+
+        let table = table.with_estimate("/cosmos.bank.v1beta1.MsgSend", GasEstimate::new(100_000, 0, 1.0));
+        assert_eq!(table.gas_for("/cosmos.bank.v1beta1.MsgSend", 2), 100_000);
+    }
+}
@@ -0,0 +1,184 @@
+//! Gas used by analyzed transactions, persisted to `~/.cosmos-utils/gas-history.json`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::json_store::{JsonStore, JsonStoreError};
+
+/// Samples older than this window are dropped from the history, in seconds
+pub const GAS_HISTORY_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Percentile of the observed gas used that suggested limits must cover
+const SUGGESTED_LIMIT_PERCENTILE: f64 = 0.95;
+
+/// Buffer added to the percentile of the observed gas used, in percent
+const SUGGESTED_LIMIT_BUFFER_PERCENT: u64 = 10;
+
+/// Errors returned by the gas history
+#[derive(Debug, thiserror::Error)]
+pub enum GasHistoryError {
+    #[error("Gas history: {0}")]
+    Store(#[from] JsonStoreError),
+}
+
+/// Gas used by a successful single message transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSample {
+    pub message_type: String,
+    pub gas_used: u64,
+    /// Time of the analysis, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Gas used by a message type over the history window
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasUsageStats {
+    pub message_type: String,
+    pub samples: usize,
+    pub average: f64,
+    pub p95: u64,
+    /// The 95th percentile plus a 10% buffer
+    pub suggested_limit: u64,
+}
+
+/// Gas samples backed by a JSON file
+#[derive(Debug)]
+pub struct GasHistory {
+    store: JsonStore<Vec<GasSample>>,
+}
+
+impl GasHistory {
+    /// Opens the history at `~/.cosmos-utils/gas-history.json`, empty if the file does not exist yet
+    pub fn open() -> Result<Self, GasHistoryError> {
+        Ok(Self {
+            store: JsonStore::open("gas-history.json")?,
+        })
+    }
+
+    /// Opens the history stored at `path`, empty if the file does not exist yet
+    pub fn open_at(path: impl Into<PathBuf>) -> Result<Self, GasHistoryError> {
+        Ok(Self {
+            store: JsonStore::open_at(path)?,
+        })
+    }
+
+    /// Writes the history back to its file
+    pub fn save(&self) -> Result<(), GasHistoryError> {
+        Ok(self.store.save()?)
+    }
+
+    /// Adds a sample taken at `now` and drops the samples that left the window
+    pub fn record(&mut self, message_type: &str, gas_used: u64, now: u64) {
+        let samples = self.store.get_mut();
+        samples.push(GasSample {
+            message_type: message_type.to_string(),
+            gas_used,
+            timestamp: now,
+        });
+        samples.retain(|sample| sample.timestamp + GAS_HISTORY_WINDOW_SECS >= now);
+    }
+
+    /// Returns the statistics of every message type over the window ending at `now`, sorted by message type
+    pub fn stats(&self, now: u64) -> Vec<GasUsageStats> {
+        let mut gas_by_type: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+        for sample in self
+            .store
+            .get()
+            .iter()
+            .filter(|sample| sample.timestamp + GAS_HISTORY_WINDOW_SECS >= now)
+        {
+            gas_by_type
+                .entry(&sample.message_type)
+                .or_default()
+                .push(sample.gas_used);
+        }
+
+        gas_by_type
+            .into_iter()
+            .map(|(message_type, mut gas_used)| {
+                gas_used.sort_unstable();
+                // Nearest rank percentile
+                let rank = (SUGGESTED_LIMIT_PERCENTILE * gas_used.len() as f64).ceil() as usize;
+                let p95 = gas_used[rank.max(1) - 1];
+                GasUsageStats {
+                    message_type: message_type.to_string(),
+                    samples: gas_used.len(),
+                    average: gas_used.iter().sum::<u64>() as f64 / gas_used.len() as f64,
+                    p95,
+                    suggested_limit: p95 + (p95 * SUGGESTED_LIMIT_BUFFER_PERCENT).div_ceil(100),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the message type and the gas used of a transaction result, as returned by the wallet.
+/// Only successful transactions with a single message are sampled: the gas of a transaction
+/// cannot be split between its messages, and failed transactions may have stopped early.
+pub fn gas_sample_from_result(result: &serde_json::Value) -> Option<(String, u64)> {
+    let data = &result["data"];
+    if data["code"].as_u64().unwrap_or(0) != 0 {
+        return None;
+    }
+    let messages = data["tx"]["body"]["messages"].as_array()?;
+    let [message] = messages.as_slice() else {
+        return None;
+    };
+    let gas_used = data["gas_used"].as_str()?.parse().ok()?;
+    Some((message["@type"].as_str()?.to_string(), gas_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    const MSG_SEND: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+    #[test]
+    fn test_gas_history_stats() {
+        let (_dir, path) = temp_file_path("gas-history.json");
+        let mut history = GasHistory::open_at(&path).unwrap();
+        history.record(MSG_SEND, 500_000, 0);
+        let now = GAS_HISTORY_WINDOW_SECS + 1;
+        for gas_used in (1..=20).map(|i| 100_000 + i * 1_000) {
+            history.record(MSG_SEND, gas_used, now);
+        }
+        history.record("/cosmos.staking.v1beta1.MsgDelegate", 330_000, now);
+        history.save().unwrap();
+
+        let history = GasHistory::open_at(&path).unwrap();
+        let stats = history.stats(now);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].message_type, MSG_SEND);
+        // The sample out of the window was dropped
+        assert_eq!(stats[0].samples, 20);
+        assert_eq!(stats[0].average, 110_500.0);
+        assert_eq!(stats[0].p95, 119_000);
+        assert_eq!(stats[0].suggested_limit, 130_900);
+        assert_eq!(stats[1].p95, 330_000);
+    }
+
+    #[test]
+    fn test_gas_sample_from_result() {
+        let result = |code: u64, messages: usize| {
+            json!({
+                "data": {
+                    "code": code,
+                    "gas_used": "104000",
+                    "tx": { "body": { "messages": vec![json!({ "@type": MSG_SEND }); messages] } }
+                }
+            })
+        };
+        assert_eq!(
+            gas_sample_from_result(&result(0, 1)),
+            Some((MSG_SEND.to_string(), 104_000))
+        );
+        assert_eq!(gas_sample_from_result(&result(11, 1)), None);
+        assert_eq!(gas_sample_from_result(&result(0, 2)), None);
+    }
+}
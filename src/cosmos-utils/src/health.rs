@@ -0,0 +1,233 @@
+//! Health of a set of RPC nodes, checked in parallel and compared with each other.
+
+use std::fs;
+use std::path::Path;
+use std::thread::ScopedJoinHandle;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use ic_cosmos::types::{is_catching_up_at, MAX_SYNCED_BLOCK_AGE_NANOS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{history::rpc_result, CosmosClientConfig, CosmosError};
+
+/// Number of blocks a node can be behind the median height before it is flagged as lagging
+pub const DEFAULT_MAX_BLOCKS_BEHIND: u64 = 10;
+
+/// Contents of a health check config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheckConfig {
+    /// CometBFT RPC endpoints of the checked nodes
+    pub endpoints: Vec<String>,
+    /// Nodes more blocks than this behind the median height are flagged as lagging
+    #[serde(default = "default_max_blocks_behind")]
+    pub max_blocks_behind: u64,
+}
+
+fn default_max_blocks_behind() -> u64 {
+    DEFAULT_MAX_BLOCKS_BEHIND
+}
+
+impl HealthCheckConfig {
+    /// Reads a JSON config file, e.g. `{ "endpoints": ["https://...", "https://..."],
+    /// "max_blocks_behind": 10 }`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CosmosError> {
+        let config: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if config.endpoints.is_empty() {
+            return Err(CosmosError::DecodingError(
+                "Health check config lists no endpoint".to_string(),
+            ));
+        }
+        Ok(config)
+    }
+}
+
+/// Health of one node, from its `health`, `status` and `abci_info` responses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeHealth {
+    pub rpc_url: String,
+    /// Whether the `health` call succeeded
+    pub is_healthy: bool,
+    pub latest_block_height: Option<u64>,
+    /// CometBFT version of the node
+    pub node_version: Option<String>,
+    /// Version of the application, e.g. the Gaia release
+    pub app_version: Option<String>,
+    pub chain_id: Option<String>,
+    /// Whether the node reports catching up, or its latest block is more than
+    /// [`MAX_SYNCED_BLOCK_AGE_NANOS`] old
+    pub catching_up: Option<bool>,
+    /// Time taken by the slowest of the three calls
+    pub response_time_ms: u64,
+    /// Errors of the failed calls
+    pub errors: Vec<String>,
+    /// Whether the node is more than the allowed number of blocks behind the median height
+    pub lagging: bool,
+}
+
+/// Checks every endpoint of `config` in parallel, one thread per endpoint, and flags the nodes
+/// lagging behind the median height. The results are in the order of the config.
+pub fn check_network_health(config: &HealthCheckConfig, base: &CosmosClientConfig) -> Vec<NodeHealth> {
+    let mut nodes: Vec<NodeHealth> = std::thread::scope(|scope| {
+        let handles: Vec<_> = config
+            .endpoints
+            .iter()
+            .map(|rpc_url| {
+                scope.spawn(move || {
+                    check_node_health(&CosmosClientConfig {
+                        rpc_url: rpc_url.clone(),
+                        ..base.clone()
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .zip(&config.endpoints)
+            .map(|(handle, rpc_url)| {
+                handle.join().unwrap_or_else(|_| NodeHealth {
+                    rpc_url: rpc_url.clone(),
+                    errors: vec!["Health check panicked".to_string()],
+                    ..Default::default()
+                })
+            })
+            .collect()
+    });
+    flag_lagging_nodes(&mut nodes, config.max_blocks_behind);
+    nodes
+}
+
+/// Calls `health`, `status` and `abci_info` on the node of `config` in parallel. A failed call
+/// leaves its fields unset and adds its error to [`NodeHealth::errors`].
+pub fn check_node_health(config: &CosmosClientConfig) -> NodeHealth {
+    let timed_call = |method: &str| {
+        let start = Instant::now();
+        let result = rpc_result(method, json!({}), config).map_err(|e| format!("{}: {}", method, e));
+        (result, start.elapsed().as_millis() as u64)
+    };
+    let ((health, health_ms), (status, status_ms), (abci_info, abci_info_ms)) = std::thread::scope(|scope| {
+        let health = scope.spawn(|| timed_call("health"));
+        let status = scope.spawn(|| timed_call("status"));
+        let abci_info = scope.spawn(|| timed_call("abci_info"));
+        (join_call(health), join_call(status), join_call(abci_info))
+    });
+
+    let mut node = NodeHealth {
+        rpc_url: config.rpc_url.clone(),
+        is_healthy: health.is_ok(),
+        response_time_ms: health_ms.max(status_ms).max(abci_info_ms),
+        ..Default::default()
+    };
+    match status {
+        Ok(status) => {
+            node.latest_block_height = status["sync_info"]["latest_block_height"]
+                .as_str()
+                .and_then(|height| height.parse().ok());
+            let latest_block_time = status["sync_info"]["latest_block_time"].as_str().unwrap_or_default();
+            node.catching_up = status["sync_info"]["catching_up"].as_bool().map(|catching_up| {
+                is_catching_up_at(catching_up, latest_block_time, now_nanos(), MAX_SYNCED_BLOCK_AGE_NANOS)
+            });
+            node.node_version = string_field(&status["node_info"]["version"]);
+            node.chain_id = string_field(&status["node_info"]["network"]);
+        }
+        Err(e) => node.errors.push(e),
+    }
+    match abci_info {
+        Ok(abci_info) => node.app_version = string_field(&abci_info["response"]["version"]),
+        Err(e) => node.errors.push(e),
+    }
+    if let Err(e) = health {
+        node.errors.push(e);
+    }
+    node
+}
+
+/// Returns the median of the latest block heights of the nodes that reported one
+pub fn median_height(nodes: &[NodeHealth]) -> Option<u64> {
+    let mut heights: Vec<u64> = nodes.iter().filter_map(|node| node.latest_block_height).collect();
+    if heights.is_empty() {
+        return None;
+    }
+    heights.sort_unstable();
+    let middle = heights.len() / 2;
+    if heights.len() % 2 == 0 {
+        Some(heights[middle - 1] + (heights[middle] - heights[middle - 1]) / 2)
+    } else {
+        Some(heights[middle])
+    }
+}
+
+/// Flags the nodes more than `max_blocks_behind` blocks behind the median height
+pub fn flag_lagging_nodes(nodes: &mut [NodeHealth], max_blocks_behind: u64) {
+    let Some(median) = median_height(nodes) else {
+        return;
+    };
+    for node in nodes {
+        node.lagging = node
+            .latest_block_height
+            .is_some_and(|height| median.saturating_sub(height) > max_blocks_behind);
+    }
+}
+
+/// Returns the result and duration of a call made by a thread of [`check_node_health`]
+fn join_call(handle: ScopedJoinHandle<'_, (Result<Value, String>, u64)>) -> (Result<Value, String>, u64) {
+    handle
+        .join()
+        .unwrap_or_else(|_| (Err("Health check panicked".to_string()), 0))
+}
+
+fn now_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+fn string_field(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    fn node(rpc_url: &str, latest_block_height: Option<u64>) -> NodeHealth {
+        NodeHealth {
+            rpc_url: rpc_url.to_string(),
+            is_healthy: latest_block_height.is_some(),
+            latest_block_height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_load_health_check_config() {
+        let (_dir, path) = temp_file_path("health.json");
+        fs::write(&path, r#"{ "endpoints": ["https://cosmos-rpc.publicnode.com"] }"#).unwrap();
+        let config = HealthCheckConfig::load(&path).unwrap();
+        assert_eq!(config.endpoints, vec!["https://cosmos-rpc.publicnode.com".to_string()]);
+        assert_eq!(config.max_blocks_behind, DEFAULT_MAX_BLOCKS_BEHIND);
+
+        fs::write(&path, r#"{ "endpoints": [], "max_blocks_behind": 5 }"#).unwrap();
+        assert!(HealthCheckConfig::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_flag_lagging_nodes() {
+        let mut nodes = vec![
+            node("https://a", Some(1_000_100)),
+            node("https://b", Some(1_000_098)),
+            node("https://c", Some(1_000_050)),
+            node("https://d", None),
+        ];
+        assert_eq!(median_height(&nodes), Some(1_000_098));
+        flag_lagging_nodes(&mut nodes, 10);
+        let lagging: Vec<_> = nodes.iter().map(|node| node.lagging).collect();
+        assert_eq!(lagging, vec![false, false, true, false]);
+
+        nodes.pop();
+        nodes.push(node("https://e", Some(1_000_102)));
+        assert_eq!(median_height(&nodes), Some(1_000_099));
+        assert_eq!(median_height(&[node("https://d", None)]), None);
+    }
+}
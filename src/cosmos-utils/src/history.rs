@@ -0,0 +1,223 @@
+//! Historical queries scanning the results of a range of blocks.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{convert_address_prefix, json_rpc_error, CosmosClientConfig, CosmosError};
+
+/// Largest number of blocks scanned by a history query, each block costs at least one RPC call
+pub const MAX_HISTORY_BLOCK_RANGE: u64 = 10_000;
+
+/// Number of blocks scanned back from the latest block when no start height is given
+pub const DEFAULT_HISTORY_BLOCKS: u64 = 100;
+
+/// Event types carrying the new `commission_rate` of a validator
+const COMMISSION_EVENT_TYPES: &[&str] = &["update_validator", "edit_validator"];
+
+/// A change of the commission rate of a validator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommissionChangeEvent {
+    pub height: u64,
+    /// New commission rate as a decimal string, e.g. "0.050000000000000000"
+    pub new_rate: String,
+    /// Time of the block, RFC 3339
+    pub timestamp: String,
+}
+
+/// Returns the commission rate changes of `validator_address` (`cosmosvaloper1...`) between
+/// `start_height` and `end_height` included, by scanning the block results of every height.
+/// Requires a CometBFT 0.38 node, which reports `finalize_block_events` with plain text attributes.
+pub fn get_validator_commission_history(
+    validator_address: &str,
+    start_height: u64,
+    end_height: u64,
+    config: &CosmosClientConfig,
+) -> Result<Vec<CommissionChangeEvent>, CosmosError> {
+    if start_height == 0 || end_height < start_height {
+        return Err(CosmosError::DecodingError(format!(
+            "Invalid block range {}..{}",
+            start_height, end_height
+        )));
+    }
+    if end_height - start_height >= MAX_HISTORY_BLOCK_RANGE {
+        return Err(CosmosError::DecodingError(format!(
+            "Block range exceeds {} blocks",
+            MAX_HISTORY_BLOCK_RANGE
+        )));
+    }
+    let operator_account = operator_account_address(validator_address)?;
+
+    let mut changes = Vec::new();
+    for height in start_height..=end_height {
+        let block_results = rpc_result("block_results", json!({ "height": height.to_string() }), config)?;
+        let rates = parse_commission_changes(&block_results, validator_address, &operator_account);
+        if rates.is_empty() {
+            continue;
+        }
+        let header = rpc_result("header", json!({ "height": height.to_string() }), config)?;
+        let timestamp = header["header"]["time"].as_str().unwrap_or_default().to_string();
+        changes.extend(rates.into_iter().map(|new_rate| CommissionChangeEvent {
+            height,
+            new_rate,
+            timestamp: timestamp.clone(),
+        }));
+    }
+    Ok(changes)
+}
+
+/// Returns the height of the latest block of the node
+pub fn latest_block_height(config: &CosmosClientConfig) -> Result<u64, CosmosError> {
+    let status = rpc_result("status", json!({}), config)?;
+    Ok(status["sync_info"]["latest_block_height"]
+        .as_str()
+        .ok_or_else(|| CosmosError::DecodingError("Missing latest_block_height in status".to_string()))?
+        .parse()?)
+}
+
+/// Returns the commission rates set for `validator_address` in the JSON `block_results` of a block.
+/// Block events name the validator in a `validator` attribute, while the event of `MsgEditValidator`
+/// only holds the new rate, so its transaction is matched on the `sender` of its messages instead.
+pub fn parse_commission_changes(block_results: &Value, validator_address: &str, operator_account: &str) -> Vec<String> {
+    let mut rates = commission_rates(&block_results["finalize_block_events"], |validator| {
+        validator == Some(validator_address)
+    });
+    let successful_txs = block_results["txs_results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|tx_result| tx_result["code"].as_u64().unwrap_or(0) == 0);
+    for tx_result in successful_txs {
+        let events = &tx_result["events"];
+        let sent_by_operator = events
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|event| event["type"] == "message" && attribute(event, "sender") == Some(operator_account));
+        rates.extend(commission_rates(events, |validator| match validator {
+            Some(validator) => validator == validator_address,
+            None => sent_by_operator,
+        }));
+    }
+    rates
+}
+
+/// Returns the `commission_rate` of the commission events in `events` whose `validator`
+/// attribute, if any, is accepted by `matches`
+fn commission_rates(events: &Value, matches: impl Fn(Option<&str>) -> bool) -> Vec<String> {
+    events
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|event| {
+            COMMISSION_EVENT_TYPES
+                .iter()
+                .any(|event_type| event["type"] == *event_type)
+                && matches(attribute(event, "validator"))
+        })
+        .filter_map(|event| attribute(event, "commission_rate").map(str::to_string))
+        .collect()
+}
+
+fn attribute<'a>(event: &'a Value, key: &str) -> Option<&'a str> {
+    event["attributes"]
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute["key"] == key)?["value"]
+        .as_str()
+}
+
+/// Returns the account address of the operator of `validator_address`, e.g. `cosmos1...` for `cosmosvaloper1...`
+fn operator_account_address(validator_address: &str) -> Result<String, CosmosError> {
+    let (hrp, _) = validator_address
+        .split_once('1')
+        .ok_or_else(|| CosmosError::EncodingError(format!("Invalid validator address: {}", validator_address)))?;
+    let prefix = hrp.strip_suffix("valoper").ok_or_else(|| {
+        CosmosError::EncodingError(format!("Not a validator operator address: {}", validator_address))
+    })?;
+    convert_address_prefix(validator_address, prefix)
+}
+
+/// Calls the CometBFT RPC `method` and returns its `result`
+pub(crate) fn rpc_result(method: &str, params: Value, config: &CosmosClientConfig) -> Result<Value, CosmosError> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params
+    });
+    let mut response_json = config.post_json_rpc(&request)?;
+    if let Some(error) = response_json.get("error") {
+        return Err(json_rpc_error(error));
+    }
+    Ok(response_json["result"].take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALIDATOR: &str = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+    const OPERATOR: &str = "cosmos1e5yfpc8l6g4808fclmlyd38tjgxuwshn7xzkvf";
+
+    #[test]
+    fn test_operator_account_address() {
+        assert_eq!(operator_account_address(VALIDATOR).unwrap(), OPERATOR);
+        assert!(operator_account_address(OPERATOR).is_err());
+    }
+
+    #[test]
+    fn test_parse_commission_changes() {
+        let block_results = json!({
+            "height": "100",
+            "finalize_block_events": [
+                {
+                    "type": "update_validator",
+                    "attributes": [
+                        { "key": "validator", "value": VALIDATOR, "index": true },
+                        { "key": "commission_rate", "value": "0.070000000000000000", "index": true }
+                    ]
+                },
+                {
+                    "type": "update_validator",
+                    "attributes": [
+                        { "key": "validator", "value": "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0" },
+                        { "key": "commission_rate", "value": "0.100000000000000000" }
+                    ]
+                }
+            ],
+            "txs_results": [
+                {
+                    "code": 0,
+                    "events": [
+                        {
+                            "type": "message",
+                            "attributes": [{ "key": "sender", "value": OPERATOR }]
+                        },
+                        {
+                            "type": "edit_validator",
+                            "attributes": [
+                                { "key": "commission_rate", "value": "0.050000000000000000" },
+                                { "key": "min_self_delegation", "value": "1" }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "code": 0,
+                    "events": [
+                        {
+                            "type": "edit_validator",
+                            "attributes": [{ "key": "commission_rate", "value": "0.200000000000000000" }]
+                        }
+                    ]
+                }
+            ]
+        });
+
+        assert_eq!(
+            parse_commission_changes(&block_results, VALIDATOR, OPERATOR),
+            vec!["0.070000000000000000".to_string(), "0.050000000000000000".to_string()]
+        );
+        assert!(parse_commission_changes(&json!({ "height": "101" }), VALIDATOR, OPERATOR).is_empty());
+    }
+}
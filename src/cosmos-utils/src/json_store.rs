@@ -0,0 +1,108 @@
+//! Values persisted as JSON to files under `~/.cosmos-utils`, e.g. the address book.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Errors returned when opening or saving a [`JsonStore`]
+#[derive(Debug, thiserror::Error)]
+pub enum JsonStoreError {
+    #[error("Could not determine the home directory")]
+    NoHomeDir,
+    #[error("Invalid name '{0}': use letters, digits, '-' or '_'")]
+    InvalidName(String),
+    #[error("I/O error on {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Invalid JSON file {0}: {1}")]
+    Json(PathBuf, serde_json::Error),
+}
+
+/// A value backed by a JSON file, its default until the file exists
+#[derive(Debug)]
+pub struct JsonStore<T> {
+    path: PathBuf,
+    value: T,
+}
+
+impl<T: Default + Serialize + DeserializeOwned> JsonStore<T> {
+    /// Opens the store at `~/.cosmos-utils/<file_name>`
+    pub fn open(file_name: &str) -> Result<Self, JsonStoreError> {
+        let dir = crate::config_dir().ok_or(JsonStoreError::NoHomeDir)?;
+        Self::open_at(dir.join(file_name))
+    }
+
+    /// Opens the store at `path`
+    pub fn open_at(path: impl Into<PathBuf>) -> Result<Self, JsonStoreError> {
+        let path = path.into();
+        let value = if path.exists() {
+            let json = fs::read_to_string(&path).map_err(|e| JsonStoreError::Io(path.clone(), e))?;
+            serde_json::from_str(&json).map_err(|e| JsonStoreError::Json(path.clone(), e))?
+        } else {
+            T::default()
+        };
+        Ok(Self { path, value })
+    }
+
+    /// Writes the value back to its file
+    pub fn save(&self) -> Result<(), JsonStoreError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| JsonStoreError::Io(parent.to_path_buf(), e))?;
+        }
+        let json = serde_json::to_string_pretty(&self.value).map_err(|e| JsonStoreError::Json(self.path.clone(), e))?;
+        fs::write(&self.path, json).map_err(|e| JsonStoreError::Io(self.path.clone(), e))
+    }
+
+    /// Returns the stored value
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the stored value for changes, written by the next [`Self::save`]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// Checks that `name` only uses letters, digits, '-' or '_', as the names of the entries of the
+/// address book or of the templates
+pub fn validate_name(name: &str) -> Result<(), JsonStoreError> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(JsonStoreError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    #[test]
+    fn test_json_store_roundtrip() {
+        let (_dir, path) = temp_file_path("store.json");
+        let mut store = JsonStore::<BTreeMap<String, u64>>::open_at(&path).unwrap();
+        assert!(store.get().is_empty());
+        store.get_mut().insert("gas".to_string(), 200_000);
+        store.save().unwrap();
+
+        let store = JsonStore::<BTreeMap<String, u64>>::open_at(&path).unwrap();
+        assert_eq!(store.get().get("gas"), Some(&200_000));
+
+        fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            JsonStore::<BTreeMap<String, u64>>::open_at(&path),
+            Err(JsonStoreError::Json(..))
+        ));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("my-validator_2").is_ok());
+        for name in ["", "bad name", "@ref", "../escape"] {
+            assert!(matches!(validate_name(name), Err(JsonStoreError::InvalidName(_))));
+        }
+    }
+}
@@ -1,31 +1,333 @@
 //! Core Cosmos utilities logic extracted from main.rs for testability and reuse.
+//!
+//! The `native` default feature enables the blocking RPC client and the `dfx` signer. Browser
+//! builds disable it and enable `wasm` instead, which keeps the message types, the transaction
+//! encoding and adds the `wasm::JsSigningBackend` signing through a JavaScript callback.
+
+// Most private helpers are only used by the blocking client
+#![cfg_attr(not(feature = "native"), allow(dead_code, unused_imports))]
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use bech32::{self, ToBase32};
-use bs58;
+use bech32::{self, FromBase32, ToBase32};
+use candid::{IDLArgs, IDLValue};
 use cosmos_sdk_proto::cosmos::{
-    bank::v1beta1::MsgSend,
+    bank::v1beta1::{MsgSend, QueryAllBalancesRequest, QueryAllBalancesResponse},
+    base::query::v1beta1::PageRequest,
     base::v1beta1::Coin,
     crypto::secp256k1::PubKey,
-    staking::v1beta1::MsgDelegate,
+    gov::v1beta1::VoteOption,
+    staking::v1beta1::{
+        Delegation, MsgDelegate, QueryDelegatorDelegationsRequest, QueryDelegatorDelegationsResponse,
+        QueryValidatorRequest, QueryValidatorResponse,
+    },
     tx::signing::v1beta1::SignMode,
     tx::v1beta1::{AuthInfo, Fee, ModeInfo, SignerInfo, Tx, TxBody},
 };
-use hex;
+use ic_cosmos::explorer::{build_explorer_url, ExplorerResource};
+use ic_cosmos::types::{
+    encode_message_to_protobuf, normalize_ecdsa_signature, normalize_secp256k1_pubkey, validate_vote_weights,
+    CosmosCoin, CosmosMessage, KeyError, SignatureError, WeightedVoteOption,
+};
+use log::{debug, error, info, warn};
 use prost::Message;
 use prost_types::Any;
+#[cfg(feature = "native")]
 use reqwest::blocking::Client;
 use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sha2::{Digest as Sha256Digest, Sha256};
-use std::error::Error;
-use std::process::Command;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+#[cfg(feature = "native")]
+use std::process::{Command, Output};
+#[cfg(feature = "native")]
+use std::time::Duration;
+
+pub mod address_book;
+#[cfg(feature = "native")]
+pub mod authz;
+#[cfg(feature = "native")]
+pub mod backend;
+pub mod fees;
+#[cfg(feature = "native")]
+pub mod finality;
+pub mod gas_estimates;
+pub mod gas_history;
+#[cfg(feature = "native")]
+pub mod health;
+#[cfg(feature = "native")]
+pub mod history;
+pub mod json_store;
+#[cfg(feature = "native")]
+pub mod keyfile;
+mod logging;
+#[cfg(feature = "native")]
+pub mod portfolio;
+pub mod simulation;
+#[cfg(feature = "native")]
+pub mod staking;
+pub mod template;
+#[cfg(test)]
+mod test_utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use address_book::{AddressBook, AddressBookError};
+#[cfg(feature = "native")]
+pub use authz::{verify_authz_grant, AuthzGrantInfo};
+#[cfg(feature = "native")]
+pub use backend::ReqwestBackend;
+#[cfg(feature = "native")]
+pub use fees::node_gas_prices;
+pub use fees::{select_optimal_fee, with_default_gas_prices, GasPrice};
+#[cfg(feature = "native")]
+pub use finality::{wait_for_block_finality, FinalityInfo};
+pub use gas_estimates::{GasEstimate, GasEstimateTable};
+pub use gas_history::{gas_sample_from_result, GasHistory, GasHistoryError, GasUsageStats};
+#[cfg(feature = "native")]
+pub use health::{check_network_health, HealthCheckConfig, NodeHealth};
+#[cfg(feature = "native")]
+pub use history::{get_validator_commission_history, latest_block_height, CommissionChangeEvent};
+#[cfg(feature = "native")]
+pub use keyfile::LocalKeyfileSigningBackend;
+pub use log::LevelFilter;
+pub use logging::init_logging;
+#[cfg(feature = "native")]
+pub use portfolio::{
+    estimate_usd_value, get_portfolio, get_stride_host_zone, get_stride_redemption_rate, ChainPortfolio, CosmosConfig,
+    PortfolioConfig,
+};
+pub use simulation::{parse_simulation_error, SimulationError};
+#[cfg(feature = "native")]
+pub use staking::{
+    build_compound_transaction, generate_compound_transaction, get_staking_position_summary, DelegationInfo,
+    StakingPositionSummary, UnbondingInfo,
+};
+pub use template::{TemplateError, TemplateStore, TransactionTemplate};
+#[cfg(feature = "wasm")]
+pub use wasm::JsSigningBackend;
 
 /// Supported message types for transaction generation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MessageType {
-    Send,
-    Delegate,
+    /// Bank transfer, to the wallet itself when `to_address` is not set
+    Send { to_address: Option<String> },
+    /// Delegation, to [`DEFAULT_VALIDATOR_ADDRESS`] when `validator_address` is not set
+    Delegate { validator_address: Option<String> },
+    /// Split vote on a governance proposal, with `(option, weight)` pairs like `("yes", "0.5")`
+    VoteWeighted {
+        proposal_id: u64,
+        options: Vec<(String, String)>,
+    },
+    /// Governance proposal spending `amount` uatom from the community pool to `recipient`
+    CommunityPoolSpendProposal {
+        title: String,
+        description: String,
+        recipient: String,
+        amount: u64,
+    },
+    /// Stride liquid staking of the `host_denom` tokens of the wallet for stTokens
+    LiquidStake { host_denom: String },
+    /// Stride redemption of stTokens of `host_zone`, to the wallet itself when `receiver` is not set
+    LiquidUnstake {
+        host_zone: String,
+        receiver: Option<String>,
+    },
+}
+
+/// Amount in uatom sent or delegated when the template does not set one
+pub const DEFAULT_AMOUNT: u64 = 1000;
+
+/// Gas prices accepted by the provider testnet, in order of preference
+pub const DEFAULT_GAS_PRICES: &[(&str, f64)] = &[("uatom", 0.01)];
+
+/// Initial deposit in uatom of a proposal when the template does not set an amount
+pub const DEFAULT_PROPOSAL_DEPOSIT: u64 = 1_000_000;
+
+/// Gas limit of a community pool spend proposal, which the simulation does not support
+pub const COMMUNITY_POOL_SPEND_GAS: u64 = 200_000;
+
+/// Gas limit of a Stride `MsgLiquidStake`, about the gas used on chain
+pub const LIQUID_STAKE_GAS: u64 = 150_000;
+
+/// Gas limit of a Stride `MsgRedeemStake`, about the gas used on chain
+pub const LIQUID_UNSTAKE_GAS: u64 = 200_000;
+
+/// Testnet validator used by the delegate transaction when no validator is given
+pub const DEFAULT_VALIDATOR_ADDRESS: &str = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+
+/// Chain id of the provider testnet
+pub const PROVIDER_TESTNET_CHAIN_ID: &str = "provider";
+
+/// CometBFT RPC endpoint of the provider testnet, the default node of every command
+pub const PROVIDER_TESTNET_RPC_URL: &str = "https://rpc.testcosmos.directory/cosmosicsprovidertestnet";
+
+/// Timeouts and limits of the HTTP client used to talk to the RPC node, and signer of the transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CosmosClientConfig {
+    /// CometBFT RPC endpoint of the node
+    pub rpc_url: String,
+    pub connect_timeout_secs: u64,
+    pub request_timeout_secs: u64,
+    /// Responses larger than this are rejected
+    pub max_response_bytes: usize,
+    /// Keyfile of a [`LocalKeyfileSigningBackend`] signing the transactions, instead of the wallet
+    /// canister called through `dfx`
+    pub keyfile: Option<PathBuf>,
+}
+
+impl Default for CosmosClientConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: PROVIDER_TESTNET_RPC_URL.to_string(),
+            connect_timeout_secs: 5,
+            request_timeout_secs: 30,
+            max_response_bytes: 10 * 1024 * 1024,
+            keyfile: None,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl CosmosClientConfig {
+    /// Builds a blocking HTTP client with the configured timeouts
+    pub fn build_client(&self) -> Result<Client, reqwest::Error> {
+        Client::builder()
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .timeout(Duration::from_secs(self.request_timeout_secs))
+            .build()
+    }
+
+    /// Posts the JSON-RPC `request` to the node and returns the JSON response,
+    /// failing if the response exceeds `max_response_bytes`
+    fn post_json_rpc(&self, request: &serde_json::Value) -> Result<serde_json::Value, CosmosError> {
+        let bytes = self.build_client()?.post(&self.rpc_url).json(request).send()?.bytes()?;
+        if bytes.len() > self.max_response_bytes {
+            return Err(CosmosError::DecodingError(format!(
+                "Response of {} bytes exceeds the limit of {} bytes",
+                bytes.len(),
+                self.max_response_bytes
+            )));
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// Errors returned when decoding a signed transaction from user input
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("Transaction input is empty")]
+    Empty,
+    #[error("Invalid hex transaction: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("Invalid base64 transaction: {0}")]
+    Base64(#[from] base64::DecodeError),
+}
+
+/// Errors returned by the functions of this crate
+#[derive(Debug, thiserror::Error)]
+pub enum CosmosError {
+    #[error("Network error: {0}")]
+    NetworkError(#[from] reqwest::Error),
+    /// A message, address or amount is invalid or cannot be encoded
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
+    /// A response of the node or of the canister, or a user input, cannot be decoded
+    #[error("Decoding error: {0}")]
+    DecodingError(String),
+    /// The node returned a JSON-RPC error, or the ABCI query failed with a non-zero code
+    #[error("RPC error (code {code}): {message}")]
+    RpcError { code: i64, message: String },
+    #[error("Account {0} does not exist yet. Please fund it first.")]
+    AccountNotFound(String),
+    /// The Stride host zone is halted, its redemption rate does not value the stTokens
+    #[error("Stride host zone {0} is halted")]
+    HostZoneHalted(String),
+    /// The signature is neither `expected` raw bytes nor a DER sequence
+    #[error("Invalid signature of {got} bytes, expected {expected} raw bytes or a DER sequence")]
+    InsufficientSignatureLength { got: usize, expected: usize },
+    /// `dfx` could not be run, or its output is not the expected canister response
+    #[error("dfx error: {0}")]
+    DfxError(String),
+    /// The keyfile cannot be read or does not hold a secp256k1 private key
+    #[error("Keyfile error: {0}")]
+    KeyfileError(String),
+    /// The node rejected the simulated transaction
+    #[error(transparent)]
+    SimulationFailed(#[from] SimulationError),
+    /// A wait, e.g. for the finality of a block, did not complete in time
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// Converts the errors of the decoders used by this crate into [`CosmosError::DecodingError`]
+macro_rules! impl_from_decoding_error {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for CosmosError {
+                fn from(e: $error) -> Self {
+                    CosmosError::DecodingError(e.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_decoding_error!(
+    prost::DecodeError,
+    base64::DecodeError,
+    hex::FromHexError,
+    bs58::decode::Error,
+    candid::Error,
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    std::num::TryFromIntError,
+    std::string::FromUtf8Error,
+    KeyError,
+    ParseError,
+);
+
+impl From<bech32::Error> for CosmosError {
+    fn from(e: bech32::Error) -> Self {
+        CosmosError::EncodingError(e.to_string())
+    }
+}
+
+/// Returns the error of the JSON-RPC `error` object of a node response
+fn json_rpc_error(error: &serde_json::Value) -> CosmosError {
+    let message = error["message"].as_str().unwrap_or("Unknown error");
+    CosmosError::RpcError {
+        code: error["code"].as_i64().unwrap_or_default(),
+        message: match error["data"].as_str() {
+            Some(data) => format!("{}: {}", message, data),
+            None => message.to_string(),
+        },
+    }
+}
+
+/// ABCI query code returned by the auth module for an address that has never received funds
+const ACCOUNT_NOT_FOUND_CODE: i64 = 6;
+
+/// On-chain state of an account as returned by [`get_account_status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountStatus {
+    Exists {
+        account_number: u64,
+        sequence: u64,
+    },
+    /// The address has never received funds
+    NotFound,
+    /// The node rejected the query with a code other than "not found"
+    QueryError {
+        code: i64,
+        log: String,
+    },
 }
 
 #[derive(Message)]
@@ -58,10 +360,18 @@ pub struct BaseAccount {
     pub sequence: u64,
 }
 
-pub fn public_key_to_cosmos_address(public_key: &str) -> Result<String, Box<dyn Error>> {
-    let decoded = bs58::decode(public_key).into_vec()?;
+/// Returns the `~/.cosmos-utils` directory holding the persisted CLI data
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".cosmos-utils"))
+}
+
+/// Returns the Cosmos address of a base58 encoded secp256k1 public key, compressed or not
+pub fn public_key_to_cosmos_address(public_key: &str) -> Result<String, CosmosError> {
+    let decoded = normalize_secp256k1_pubkey(&bs58::decode(public_key).into_vec()?)?;
     let mut hasher = Sha256::new();
-    hasher.update(&decoded);
+    hasher.update(decoded);
     let sha256_hash = hasher.finalize();
     let mut hasher = Ripemd160::new();
     hasher.update(sha256_hash);
@@ -71,12 +381,14 @@ pub fn public_key_to_cosmos_address(public_key: &str) -> Result<String, Box<dyn
     Ok(encoded)
 }
 
+#[cfg(feature = "native")]
 pub fn create_send_transaction(
     from_address: &str,
     to_address: &str,
     amount: u64,
     signature: Option<Vec<u8>>,
-) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
+    config: &CosmosClientConfig,
+) -> Result<(Vec<u8>, Vec<u8>), CosmosError> {
     let msg_send = MsgSend {
         from_address: from_address.to_string(),
         to_address: to_address.to_string(),
@@ -96,7 +408,7 @@ pub fn create_send_transaction(
         extension_options: vec![],
         non_critical_extension_options: vec![],
     };
-    let public_key = get_public_key_from_canister()?;
+    let public_key = signer_public_key(config)?;
     let pub_key = PubKey {
         key: bs58::decode(&public_key).into_vec()?,
     };
@@ -115,7 +427,7 @@ pub fn create_send_transaction(
     };
 
     // Get account info
-    let (account_number, sequence) = get_account_info(from_address)?;
+    let (account_number, sequence) = get_account_info(from_address, None, config)?;
 
     let auth_info = AuthInfo {
         signer_infos: vec![SignerInfo {
@@ -148,26 +460,19 @@ pub fn create_send_transaction(
         signatures: vec![],
     };
     if let Some(sig) = signature {
-        if sig.len() < 64 {
-            eprintln!(
-                "Error: signature length is {} bytes, expected at least 64. Signature (hex): {}",
-                sig.len(),
-                hex::encode(&sig)
-            );
-            return Err(format!("Signature too short: got {} bytes, expected at least 64", sig.len()).into());
-        }
-        // Use the raw 64-byte signature as Cosmos expects
-        tx.signatures = vec![sig[..64].to_vec()];
+        tx.signatures = vec![raw_signature(&sig)?.to_vec()];
     }
     Ok((tx.encode_to_vec(), sign_bytes))
 }
 
+#[cfg(feature = "native")]
 pub fn create_delegate_transaction(
     delegator_address: &str,
     validator_address: &str,
     amount: u64,
     signature: Option<Vec<u8>>,
-) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
+    config: &CosmosClientConfig,
+) -> Result<(Vec<u8>, Vec<u8>), CosmosError> {
     let msg_delegate = MsgDelegate {
         delegator_address: delegator_address.to_string(),
         validator_address: validator_address.to_string(),
@@ -190,7 +495,7 @@ pub fn create_delegate_transaction(
         non_critical_extension_options: vec![],
     };
 
-    let public_key = get_public_key_from_canister()?;
+    let public_key = signer_public_key(config)?;
     let pub_key = PubKey {
         key: bs58::decode(&public_key).into_vec()?,
     };
@@ -209,7 +514,7 @@ pub fn create_delegate_transaction(
     };
 
     // Get account info
-    let (account_number, sequence) = get_account_info(delegator_address)?;
+    let (account_number, sequence) = get_account_info(delegator_address, None, config)?;
 
     let auth_info = AuthInfo {
         signer_infos: vec![SignerInfo {
@@ -242,24 +547,39 @@ pub fn create_delegate_transaction(
         signatures: vec![],
     };
     if let Some(sig) = signature {
-        if sig.len() < 64 {
-            eprintln!(
-                "Error: signature length is {} bytes, expected at least 64. Signature (hex): {}",
-                sig.len(),
-                hex::encode(&sig)
-            );
-            return Err(format!("Signature too short: got {} bytes, expected at least 64", sig.len()).into());
-        }
-        // Use the raw 64-byte signature as Cosmos expects
-        tx.signatures = vec![sig[..64].to_vec()];
+        tx.signatures = vec![raw_signature(&sig)?.to_vec()];
     }
     Ok((tx.encode_to_vec(), sign_bytes))
 }
 
-pub fn get_public_key_from_canister() -> Result<String, Box<dyn Error>> {
-    let output = Command::new("dfx")
-        .args(["canister", "call", "cosmos_wallet", "address"])
-        .output()?;
+/// Returns the raw 64-byte `r || s` signature of the raw, recoverable or DER encoded `signature`
+fn raw_signature(signature: &[u8]) -> Result<[u8; 64], CosmosError> {
+    normalize_ecdsa_signature(signature).map_err(|e| {
+        error!("Error: {}. Signature (hex): {}", e, hex::encode(signature));
+        match e {
+            SignatureError::UnknownFormat => CosmosError::InsufficientSignatureLength {
+                got: signature.len(),
+                expected: 64,
+            },
+            SignatureError::InvalidDer(_) | SignatureError::InvalidRecoveryId(_) => {
+                CosmosError::DecodingError(e.to_string())
+            }
+        }
+    })
+}
+
+/// Runs `dfx` with `args` and returns its output
+#[cfg(feature = "native")]
+fn run_dfx(args: &[&str]) -> Result<Output, CosmosError> {
+    Command::new("dfx")
+        .args(args)
+        .output()
+        .map_err(|e| CosmosError::DfxError(format!("Failed to run dfx: {}", e)))
+}
+
+#[cfg(feature = "native")]
+pub fn get_public_key_from_canister() -> Result<String, CosmosError> {
+    let output = run_dfx(&["canister", "call", "cosmos_wallet", "address"])?;
 
     let stdout = String::from_utf8(output.stdout)?;
     // Remove quotes and whitespace from the output
@@ -269,10 +589,9 @@ pub fn get_public_key_from_canister() -> Result<String, Box<dyn Error>> {
     Ok(public_key.to_string())
 }
 
-pub fn get_cosmos_address_from_canister() -> Result<String, Box<dyn Error>> {
-    let output = Command::new("dfx")
-        .args(["canister", "call", "cosmos_wallet", "cosmosAddress"])
-        .output()?;
+#[cfg(feature = "native")]
+pub fn get_cosmos_address_from_canister() -> Result<String, CosmosError> {
+    let output = run_dfx(&["canister", "call", "cosmos_wallet", "cosmosAddress"])?;
 
     let stdout = String::from_utf8(output.stdout)?;
 
@@ -287,65 +606,98 @@ pub fn get_cosmos_address_from_canister() -> Result<String, Box<dyn Error>> {
 
     // If we can't parse the success format, check for error
     if stdout.contains("Err") {
-        return Err("Failed to get cosmos address from canister".into());
+        return Err(CosmosError::DfxError(format!(
+            "Failed to get cosmos address from canister: {}",
+            stdout.trim()
+        )));
     }
 
-    Err("Unexpected response format from cosmosAddress canister call".into())
+    Err(CosmosError::DfxError(
+        "Unexpected response format from cosmosAddress canister call".to_string(),
+    ))
+}
+
+/// Returns `bytes` escaped for a Candid `blob` text literal
+fn candid_blob_text(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut text, byte| {
+        let _ = write!(text, "\\{:02X}", byte);
+        text
+    })
 }
 
-pub fn get_signature_from_canister(sign_bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
-    let output = Command::new("dfx")
-        .args([
-            "canister",
-            "call",
-            "--update",
-            "cosmos_wallet",
-            "signMessage",
-            &format!(
-                "(blob \"{}\")",
-                sign_bytes.iter().map(|b| format!("\\{:02X}", b)).collect::<String>()
-            ),
-        ])
-        .output()?;
-
-    eprintln!("Canister stdout: {}", String::from_utf8_lossy(&output.stdout));
-    eprintln!("Canister stderr: {}", String::from_utf8_lossy(&output.stderr));
+#[cfg(feature = "native")]
+pub fn get_signature_from_canister(sign_bytes: &[u8]) -> Result<Vec<u8>, CosmosError> {
+    let blob = format!("(blob \"{}\")", candid_blob_text(sign_bytes));
+    let output = run_dfx(&[
+        "canister",
+        "call",
+        "--update",
+        "--output",
+        "raw",
+        "cosmos_wallet",
+        "signMessage",
+        &blob,
+    ])?;
+
+    debug!("Canister stdout: {}", String::from_utf8_lossy(&output.stdout));
+    debug!("Canister stderr: {}", String::from_utf8_lossy(&output.stderr));
 
     let stdout = String::from_utf8(output.stdout)?;
+    decode_signature_blob(&stdout)
+}
 
-    // Extract the blob content between quotes
-    let blob_content = stdout
-        .split("blob \"")
-        .nth(1)
-        .ok_or_else(|| "Failed to find blob content")?
-        .split("\"")
-        .next()
-        .ok_or_else(|| "Failed to parse blob content")?;
-
-    // Convert escaped hex sequences to bytes
-    let signature = blob_content
-        .split("\\")
-        .filter(|s| !s.is_empty())
-        .map(|s| {
-            // Handle special cases like \n, \r, etc.
-            if s.starts_with('n') {
-                Ok(b'\n')
-            } else if s.starts_with('r') {
-                Ok(b'\r')
-            } else if s.starts_with('t') {
-                Ok(b'\t')
-            } else {
-                // Parse hex bytes
-                u8::from_str_radix(s, 16).map_err(|e| format!("Failed to parse hex: {}", e).into())
-            }
-        })
-        .collect::<Result<Vec<u8>, Box<dyn Error>>>()?;
+/// Returns the base58 public key of the signer: the key of `config.keyfile` if set, otherwise
+/// the key of the wallet canister
+#[cfg(feature = "native")]
+pub fn signer_public_key(config: &CosmosClientConfig) -> Result<String, CosmosError> {
+    match &config.keyfile {
+        Some(path) => Ok(LocalKeyfileSigningBackend::load(path, config.clone())?.public_key_base58()),
+        None => get_public_key_from_canister(),
+    }
+}
+
+/// Returns the Cosmos address of the signer of `config`, see [`signer_public_key`]
+#[cfg(feature = "native")]
+pub fn signer_cosmos_address(config: &CosmosClientConfig) -> Result<String, CosmosError> {
+    match &config.keyfile {
+        Some(path) => LocalKeyfileSigningBackend::load(path, config.clone())?.cosmos_address(),
+        None => get_cosmos_address_from_canister(),
+    }
+}
+
+/// Signs `sign_bytes` with the signer of `config`, see [`signer_public_key`]
+#[cfg(feature = "native")]
+pub fn sign_with_signer(sign_bytes: &[u8], config: &CosmosClientConfig) -> Result<Vec<u8>, CosmosError> {
+    match &config.keyfile {
+        Some(path) => Ok(LocalKeyfileSigningBackend::load(path, config.clone())?.sign(sign_bytes)),
+        None => get_signature_from_canister(sign_bytes),
+    }
+}
 
-    Ok(signature)
+/// Decodes the `blob` returned by `signMessage` from the hex encoded Candid message
+/// printed by `dfx canister call --output raw`.
+pub fn decode_signature_blob(raw_output: &str) -> Result<Vec<u8>, CosmosError> {
+    let bytes = hex::decode(raw_output.trim())?;
+    let args = IDLArgs::from_bytes(&bytes)?;
+    match args.args.first() {
+        Some(IDLValue::Blob(signature)) => Ok(signature.clone()),
+        Some(IDLValue::Vec(values)) => values
+            .iter()
+            .map(|value| match value {
+                IDLValue::Nat8(byte) => Ok(*byte),
+                other => Err(CosmosError::DfxError(format!("Unexpected signature byte: {}", other))),
+            })
+            .collect(),
+        Some(other) => Err(CosmosError::DfxError(format!(
+            "Unexpected signMessage response: {}",
+            other
+        ))),
+        None => Err(CosmosError::DfxError("Empty signMessage response".to_string())),
+    }
 }
 
-pub fn print_transaction_json(tx_bytes: &[u8], title: &str, pretty: bool) -> Result<String, Box<dyn Error>> {
-    if let Ok(tx) = Tx::decode(&tx_bytes[..]) {
+pub fn print_transaction_json(tx_bytes: &[u8], title: &str, pretty: bool) -> Result<String, CosmosError> {
+    if let Ok(tx) = Tx::decode(tx_bytes) {
         let json_obj = json!({
             "body": {
                 "messages": tx.body.as_ref().map(|b| &b.messages).unwrap_or(&vec![]).iter().map(|msg| {
@@ -442,12 +794,12 @@ pub fn print_transaction_json(tx_bytes: &[u8], title: &str, pretty: bool) -> Res
         };
 
         if !title.is_empty() {
-            println!("{}:", title);
+            info!("{}:", title);
         }
-        println!("{}", json_output);
+        info!("{}", json_output);
         Ok(json_output)
     } else {
-        Err("Failed to decode transaction".into())
+        Err(CosmosError::DecodingError("Failed to decode transaction".to_string()))
     }
 }
 
@@ -471,75 +823,58 @@ fn encode_length_delimited(tag: u8, data: &[u8]) -> Vec<u8> {
     result
 }
 
+/// Messages and memo of a transaction whose gas is estimated by [`estimate_gas_for_transaction`]
+#[derive(Debug, Clone)]
+pub struct EstimationRequest {
+    pub messages: Vec<CosmosMessage>,
+    pub memo: String,
+}
+
+/// Returns the message of its JSON representation, typed by its `@type`
+fn cosmos_message_from_json(value: serde_json::Value) -> Result<CosmosMessage, CosmosError> {
+    let type_url = value["@type"]
+        .as_str()
+        .ok_or_else(|| CosmosError::EncodingError("Missing @type in message".to_string()))?
+        .to_string();
+    Ok(CosmosMessage { type_url, value })
+}
+
 /// Estimate gas for a transaction by simulating it
-pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Result<u64, Box<dyn Error>> {
+#[cfg(feature = "native")]
+pub fn estimate_gas_for_transaction(
+    request: &EstimationRequest,
+    config: &CosmosClientConfig,
+) -> Result<u64, CosmosError> {
     // Get public key and cosmos address for simulation
-    let public_key = get_public_key_from_canister()?;
+    let public_key = signer_public_key(config)?;
     let cosmos_address = public_key_to_cosmos_address(&public_key)?;
     let pk_bytes = bs58::decode(&public_key).into_vec()?;
 
     // Get account info for simulation
-    let (account_number, sequence) = get_account_info(&cosmos_address)?;
+    let (account_number, sequence) = get_account_info(&cosmos_address, None, config)?;
 
     // Build a complete transaction for simulation
-    let messages_array = transaction_json["body"]["messages"]
-        .as_array()
-        .ok_or("Missing messages array")?;
-
-    if messages_array.is_empty() {
-        return Err("No messages to simulate".into());
+    let messages = &request.messages;
+    if messages.is_empty() {
+        return Err(CosmosError::EncodingError("No messages to simulate".to_string()));
     }
 
-    // Create messages for the transaction body
-    let mut tx_messages = Vec::new();
-
-    // Encode each message for the simulation
-    for msg_json in messages_array {
-        let type_url = msg_json["@type"].as_str().ok_or("Missing @type")?;
-
-        let msg_bytes = match type_url {
-            "/cosmos.bank.v1beta1.MsgSend" => {
-                let msg_send = MsgSend {
-                    from_address: msg_json["from_address"].as_str().unwrap_or("").to_string(),
-                    to_address: msg_json["to_address"].as_str().unwrap_or("").to_string(),
-                    amount: msg_json["amount"]
-                        .as_array()
-                        .unwrap_or(&Vec::new())
-                        .iter()
-                        .map(|coin| Coin {
-                            denom: coin["denom"].as_str().unwrap_or("").to_string(),
-                            amount: coin["amount"].as_str().unwrap_or("0").to_string(),
-                        })
-                        .collect(),
-                };
-                msg_send.encode_to_vec()
-            }
-            "/cosmos.staking.v1beta1.MsgDelegate" => {
-                let msg_delegate = MsgDelegate {
-                    delegator_address: msg_json["delegator_address"].as_str().unwrap_or("").to_string(),
-                    validator_address: msg_json["validator_address"].as_str().unwrap_or("").to_string(),
-                    amount: Some(Coin {
-                        denom: msg_json["amount"]["denom"].as_str().unwrap_or("uatom").to_string(),
-                        amount: msg_json["amount"]["amount"].as_str().unwrap_or("0").to_string(),
-                    }),
-                };
-                msg_delegate.encode_to_vec()
-            }
-            _ => return Err(format!("Unsupported message type for simulation: {}", type_url).into()),
-        };
-
-        let msg_any = Any {
-            type_url: type_url.to_string(),
-            value: msg_bytes,
-        };
-        tx_messages.push(msg_any);
-    }
+    // Encode each message for the simulation, failing on missing fields
+    let tx_messages = messages
+        .iter()
+        .map(|message| -> Result<Any, CosmosError> {
+            Ok(Any {
+                type_url: message.type_url.clone(),
+                value: encode_message_to_protobuf(message)
+                    .map_err(|e| CosmosError::EncodingError(format!("Cannot simulate {}: {}", message.type_url, e)))?,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     // Create transaction body with proper messages
-    let memo = transaction_json["body"]["memo"].as_str().unwrap_or("");
     let tx_body = TxBody {
         messages: tx_messages,
-        memo: memo.to_string(),
+        memo: request.memo.clone(),
         timeout_height: 0,
         extension_options: vec![],
         non_critical_extension_options: vec![],
@@ -591,19 +926,10 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
     let sign_bytes = sign_doc.encode_to_vec();
 
     // Get signature from canister for simulation
-    println!("Getting signature from canister for simulation...");
-    let signature = get_signature_from_canister(&sign_bytes)?;
+    info!("Getting signature from canister for simulation...");
+    let signature = sign_with_signer(&sign_bytes, config)?;
 
-    // Ensure signature is 64 bytes (truncate if longer)
-    let signature = if signature.len() >= 64 {
-        signature[..64].to_vec()
-    } else {
-        return Err(format!(
-            "Signature too short: got {} bytes, expected at least 64",
-            signature.len()
-        )
-        .into());
-    };
+    let signature = raw_signature(&signature)?.to_vec();
 
     // Build tx for simulation (with signatures)
     let tx = Tx {
@@ -619,7 +945,6 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
     let query_data = hex::encode(&simulate_request_bytes);
 
     // Make the simulation RPC call
-    let client = Client::new();
     let request = json!({
         "jsonrpc": "2.0",
         "id": 1,
@@ -632,17 +957,12 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
         }
     });
 
-    println!("Making simulation request with real signature...");
-    let response = client
-        .post("https://rpc.testcosmos.directory/cosmosicsprovidertestnet")
-        .json(&request)
-        .send()?;
-
-    let response_json: serde_json::Value = response.json()?;
+    info!("Making simulation request with real signature...");
+    let response_json = config.post_json_rpc(&request)?;
 
     // Check for error
     if let Some(error) = response_json.get("error") {
-        return Err(format!("Simulation RPC error: {}", error).into());
+        return Err(json_rpc_error(error));
     }
 
     let result_code = response_json["result"]["response"]["code"].as_i64().unwrap_or(-1);
@@ -650,31 +970,25 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
         let log = response_json["result"]["response"]["log"]
             .as_str()
             .unwrap_or("Unknown error");
-        println!("Simulation failed: {}", log);
-        // Fall back to conservative estimate
-        let message_type = messages_array[0]["@type"].as_str().unwrap_or("");
-        let fallback_gas = match message_type {
-            "/cosmos.bank.v1beta1.MsgSend" => 125_000u64, // Updated based on actual usage: ~97k-104k
-            "/cosmos.staking.v1beta1.MsgDelegate" => 350_000u64, // Updated based on actual usage: ~324k-344k
-            _ => 250_000u64,
-        };
-        println!("Using fallback estimate: {}", fallback_gas);
-        return Ok(fallback_gas);
+        warn!("Simulation failed: {}", log);
+        // The transaction would fail the same way once broadcast, a fallback estimate would hide it
+        let code = u32::try_from(result_code).unwrap_or(u32::MAX);
+        return Err(parse_simulation_error(log, code).into());
     }
 
     // Parse simulation response
     let response_value = response_json["result"]["response"]["value"]
         .as_str()
-        .ok_or("Missing simulation response value")?;
+        .ok_or_else(|| CosmosError::DecodingError("Missing simulation response value".to_string()))?;
 
     let decoded = STANDARD.decode(response_value)?;
 
     // Parse SimulateResponse to extract gas_used
     // The structure should be: SimulateResponse { gas_info: { gas_wanted, gas_used }, result }
 
-    println!("Simulation successful! Parsing gas usage...");
+    info!("Simulation successful! Parsing gas usage...");
     let decoded_hex = hex::encode(&decoded);
-    println!(
+    debug!(
         "Simulation response (hex): {}",
         &decoded_hex[..std::cmp::min(200, decoded_hex.len())]
     );
@@ -688,17 +1002,11 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
                 // gas_used field tag
                 let mut pos = i + 1;
                 if let Ok(gas_used) = read_varint_at(&decoded, &mut pos) {
-                    if gas_used > 50_000 && gas_used < 2_000_000 {
+                    if gas_used > 50_000 && gas_used < 2_000_000 * messages.len() as u64 {
                         // Reasonable range
-                        // Use different buffer multipliers based on message type
-                        let message_type = messages_array[0]["@type"].as_str().unwrap_or("");
-                        let buffer_multiplier = match message_type {
-                            "/cosmos.bank.v1beta1.MsgSend" => 1.25, // Send needs more buffer due to variability
-                            "/cosmos.staking.v1beta1.MsgDelegate" => 1.15, // Delegate is more predictable
-                            _ => 1.2,
-                        };
+                        let buffer_multiplier = gas_buffer_multiplier(messages);
                         let with_buffer = (gas_used as f64 * buffer_multiplier) as u64;
-                        println!(
+                        info!(
                             "✅ Simulated gas_used: {}, recommended: {} ({}x buffer)",
                             gas_used, with_buffer, buffer_multiplier
                         );
@@ -710,18 +1018,47 @@ pub fn estimate_gas_for_transaction(transaction_json: &serde_json::Value) -> Res
     }
 
     // If simulation parsing fails, fall back to conservative estimate
-    let message_type = messages_array[0]["@type"].as_str().unwrap_or("");
-    let fallback_gas = match message_type {
-        "/cosmos.bank.v1beta1.MsgSend" => 125_000u64, // Updated based on actual usage: ~97k-104k
-        "/cosmos.staking.v1beta1.MsgDelegate" => 350_000u64, // Updated based on actual usage: ~324k-344k
-        _ => 250_000u64,
-    };
-    println!("Simulation parsing failed, using fallback estimate: {}", fallback_gas);
+    let fallback_gas = fallback_gas_for_messages(messages);
+    warn!("Simulation parsing failed, using fallback estimate: {}", fallback_gas);
     Ok(fallback_gas)
 }
 
+/// Largest buffer applied to the simulated gas, whatever the number of messages
+const MAX_GAS_BUFFER_MULTIPLIER: f64 = 1.3;
+
+/// Additional buffer per message after the first, for the per-message overhead the simulation
+/// does not always capture
+const GAS_BUFFER_PER_EXTRA_MESSAGE: f64 = 0.05;
+
+/// Returns the buffer applied to the simulated gas of `messages`: the largest buffer of their
+/// types, increased for every additional message and capped at `MAX_GAS_BUFFER_MULTIPLIER`
+pub fn gas_buffer_multiplier(messages: &[CosmosMessage]) -> f64 {
+    let type_multiplier = messages
+        .iter()
+        .map(|message| match message.type_url.as_str() {
+            "/cosmos.bank.v1beta1.MsgSend" => 1.25, // Send needs more buffer due to variability
+            "/cosmos.staking.v1beta1.MsgDelegate" => 1.15, // Delegate is more predictable
+            _ => 1.2,
+        })
+        .fold(1.0, f64::max);
+    let extra_messages = messages.len().saturating_sub(1) as f64;
+    (type_multiplier + GAS_BUFFER_PER_EXTRA_MESSAGE * extra_messages).min(MAX_GAS_BUFFER_MULTIPLIER)
+}
+
+/// Gas estimate of `messages` from the default [`GasEstimateTable`], used when the simulation
+/// response cannot be parsed
+fn fallback_gas_for_messages(messages: &[CosmosMessage]) -> u64 {
+    GasEstimateTable::default().gas_for_messages(messages)
+}
+
+/// Gas of a transaction with a single message of `message_type` in the default
+/// [`GasEstimateTable`], used when the simulation fails
+pub fn fallback_gas_for_message_type(message_type: &str) -> u64 {
+    GasEstimateTable::default().gas_for(message_type, 1)
+}
+
 /// Helper function to read varint at specific position
-fn read_varint_at(data: &[u8], pos: &mut usize) -> Result<u64, Box<dyn Error>> {
+fn read_varint_at(data: &[u8], pos: &mut usize) -> Result<u64, CosmosError> {
     let mut value = 0u64;
     let mut shift = 0;
 
@@ -737,11 +1074,13 @@ fn read_varint_at(data: &[u8], pos: &mut usize) -> Result<u64, Box<dyn Error>> {
         shift += 7;
 
         if shift >= 64 {
-            return Err("Varint too long".into());
+            return Err(CosmosError::DecodingError("Varint too long".to_string()));
         }
     }
 
-    Err("Unexpected end of data while reading varint".into())
+    Err(CosmosError::DecodingError(
+        "Unexpected end of data while reading varint".to_string(),
+    ))
 }
 
 /// Calculate appropriate fee based on gas limit
@@ -749,83 +1088,258 @@ pub fn calculate_fee_for_gas(gas_limit: u64, gas_price: f64) -> u64 {
     (gas_limit as f64 * gas_price).ceil() as u64
 }
 
-pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn Error>> {
-    let cosmos_address = get_cosmos_address_from_canister()?;
-    println!("Cosmos address: {}", cosmos_address);
+/// Parse a vote option name like `yes`, `no_with_veto` or `VOTE_OPTION_ABSTAIN`
+pub fn parse_vote_option(option: &str) -> Result<VoteOption, CosmosError> {
+    let name = option.to_ascii_uppercase();
+    match name.strip_prefix("VOTE_OPTION_").unwrap_or(&name) {
+        "YES" => Ok(VoteOption::Yes),
+        "ABSTAIN" => Ok(VoteOption::Abstain),
+        "NO" => Ok(VoteOption::No),
+        "NO_WITH_VETO" => Ok(VoteOption::NoWithVeto),
+        _ => Err(CosmosError::EncodingError(format!("Invalid vote option: {}", option))),
+    }
+}
+
+/// Build the JSON representation of a MsgVoteWeighted message, with weights that are positive
+/// and sum to 1.0 as checked by [`validate_vote_weights`]
+fn vote_weighted_message_json(
+    voter: &str,
+    proposal_id: u64,
+    options: &[(String, String)],
+) -> Result<serde_json::Value, CosmosError> {
+    let weighted_options = options
+        .iter()
+        .map(|(option, weight)| -> Result<WeightedVoteOption, CosmosError> {
+            Ok(WeightedVoteOption {
+                option: option.parse().map_err(CosmosError::EncodingError)?,
+                weight: weight.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, CosmosError>>()?;
+    validate_vote_weights(&weighted_options).map_err(CosmosError::EncodingError)?;
+    let options = options
+        .iter()
+        .map(|(option, weight)| -> Result<serde_json::Value, CosmosError> {
+            Ok(json!({
+                "option": parse_vote_option(option)?.as_str_name(),
+                "weight": weight
+            }))
+        })
+        .collect::<Result<Vec<_>, CosmosError>>()?;
+    Ok(json!({
+        "@type": "/cosmos.gov.v1beta1.MsgVoteWeighted",
+        "proposal_id": proposal_id.to_string(),
+        "voter": voter,
+        "options": options
+    }))
+}
+
+/// Returns the address of the same key as the bech32 `address` on the chain with the `prefix` HRP
+pub fn convert_address_prefix(address: &str, prefix: &str) -> Result<String, CosmosError> {
+    let (_, data, variant) = bech32::decode(address)?;
+    Ok(bech32::encode(prefix, data, variant)?)
+}
+
+/// Encodes the 20-byte account address given as 40 hex characters to bech32 with the `prefix` HRP
+pub fn hex_to_bech32(hex_address: &str, prefix: &str) -> Result<String, CosmosError> {
+    let bytes = hex::decode(hex_address)?;
+    if bytes.len() != 20 {
+        return Err(CosmosError::EncodingError(format!(
+            "Expected a 20-byte hex address, got {} bytes",
+            bytes.len()
+        )));
+    }
+    Ok(bech32::encode(prefix, bytes.to_base32(), bech32::Variant::Bech32)?)
+}
+
+/// Returns the payload of the bech32 `address` as uppercase hex, the format of CometBFT addresses
+pub fn bech32_to_hex(address: &str) -> Result<String, CosmosError> {
+    let (_, data, _) = bech32::decode(address)?;
+    Ok(hex::encode_upper(Vec::<u8>::from_base32(&data)?))
+}
+
+/// Returns the Stride address of the same key as `cosmos_address`
+fn to_stride_address(cosmos_address: &str) -> Result<String, CosmosError> {
+    convert_address_prefix(cosmos_address, "stride")
+}
+
+/// Build the JSON representation of a Stride MsgLiquidStake or MsgRedeemStake message,
+/// signed by the Stride address of `cosmos_address`
+fn stride_message_json(
+    message_type: &MessageType,
+    cosmos_address: &str,
+    amount: &str,
+) -> Result<serde_json::Value, CosmosError> {
+    let creator = to_stride_address(cosmos_address)?;
+    match message_type {
+        MessageType::LiquidStake { host_denom } => Ok(json!({
+            "@type": "/stride.stakeibc.MsgLiquidStake",
+            "creator": creator,
+            "amount": amount,
+            "host_denom": host_denom
+        })),
+        MessageType::LiquidUnstake { host_zone, receiver } => Ok(json!({
+            "@type": "/stride.stakeibc.MsgRedeemStake",
+            "creator": creator,
+            "amount": amount,
+            "host_zone": host_zone,
+            "receiver": receiver.as_deref().unwrap_or(cosmos_address)
+        })),
+        _ => Err(CosmosError::EncodingError("Not a Stride message".to_string())),
+    }
+}
+
+/// Build the JSON representation of a v1beta1 MsgSubmitProposal with a CommunityPoolSpendProposal content
+fn community_pool_spend_message_json(
+    proposer: &str,
+    title: &str,
+    description: &str,
+    recipient: &str,
+    amount: u64,
+    deposit: u64,
+) -> serde_json::Value {
+    json!({
+        "@type": "/cosmos.gov.v1beta1.MsgSubmitProposal",
+        "content": {
+            "@type": "/cosmos.distribution.v1beta1.CommunityPoolSpendProposal",
+            "title": title,
+            "description": description,
+            "recipient": recipient,
+            "amount": [{ "denom": "uatom", "amount": amount.to_string() }]
+        },
+        "initial_deposit": [{ "denom": "uatom", "amount": deposit.to_string() }],
+        "proposer": proposer
+    })
+}
+
+#[cfg(feature = "native")]
+pub fn generate_raw_transaction(message_type: MessageType, config: &CosmosClientConfig) -> Result<(), CosmosError> {
+    generate_templated_transaction(&TransactionTemplate::new(message_type), config)
+}
 
-    // First, create a base transaction to estimate gas
-    let base_json = match message_type {
-        MessageType::Send => {
-            println!("Generating MsgSend transaction for IC Cosmos wallet...");
+/// Generates a wallet transaction from `template`, using the template amount and memo when set
+#[cfg(feature = "native")]
+pub fn generate_templated_transaction(
+    template: &TransactionTemplate,
+    config: &CosmosClientConfig,
+) -> Result<(), CosmosError> {
+    let message_type = &template.message_type;
+    let amount = template.base_amount.unwrap_or(DEFAULT_AMOUNT).to_string();
+    let cosmos_address = signer_cosmos_address(config)?;
+    info!("Cosmos address: {}", cosmos_address);
+
+    // First, create the message of the transaction to estimate gas
+    let base_message = match message_type {
+        MessageType::Send { to_address } => {
+            info!("Generating MsgSend transaction for IC Cosmos wallet...");
+            let to_address = to_address.as_deref().unwrap_or(&cosmos_address);
+            info!("Recipient address: {}", to_address);
             json!({
-                "body": {
-                    "messages": [
-                        {
-                            "@type": "/cosmos.bank.v1beta1.MsgSend",
-                            "from_address": cosmos_address,
-                            "to_address": cosmos_address,
-                            "amount": [
-                                {
-                                    "denom": "uatom",
-                                    "amount": "1000"
-                                }
-                            ]
-                        }
-                    ]
-                }
+                "@type": "/cosmos.bank.v1beta1.MsgSend",
+                "from_address": cosmos_address,
+                "to_address": to_address,
+                "amount": [
+                    {
+                        "denom": "uatom",
+                        "amount": amount
+                    }
+                ]
             })
         }
-        MessageType::Delegate => {
-            println!("Generating MsgDelegate transaction for IC Cosmos wallet...");
-            let validator_address = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
-            println!("Validator address: {}", validator_address);
+        MessageType::Delegate { validator_address } => {
+            info!("Generating MsgDelegate transaction for IC Cosmos wallet...");
+            let validator_address = validator_address.as_deref().unwrap_or(DEFAULT_VALIDATOR_ADDRESS);
+            info!("Validator address: {}", validator_address);
             json!({
-                "body": {
-                    "messages": [
-                        {
-                            "@type": "/cosmos.staking.v1beta1.MsgDelegate",
-                            "delegator_address": cosmos_address,
-                            "validator_address": validator_address,
-                            "amount": {
-                                "denom": "uatom",
-                                "amount": "1000"
-                            }
-                        }
-                    ]
+                "@type": "/cosmos.staking.v1beta1.MsgDelegate",
+                "delegator_address": cosmos_address,
+                "validator_address": validator_address,
+                "amount": {
+                    "denom": "uatom",
+                    "amount": amount
                 }
             })
         }
+        MessageType::VoteWeighted { proposal_id, options } => {
+            info!("Generating MsgVoteWeighted transaction for IC Cosmos wallet...");
+            info!("Proposal ID: {}", proposal_id);
+            vote_weighted_message_json(&cosmos_address, *proposal_id, options)?
+        }
+        MessageType::CommunityPoolSpendProposal {
+            title,
+            description,
+            recipient,
+            amount,
+        } => {
+            info!("Generating community pool spend proposal for IC Cosmos wallet...");
+            info!("Recipient address: {}", recipient);
+            let deposit = template.base_amount.unwrap_or(DEFAULT_PROPOSAL_DEPOSIT);
+            community_pool_spend_message_json(&cosmos_address, title, description, recipient, *amount, deposit)
+        }
+        MessageType::LiquidStake { .. } | MessageType::LiquidUnstake { .. } => {
+            info!("Generating Stride liquid staking transaction for IC Cosmos wallet...");
+            stride_message_json(message_type, &cosmos_address, &amount)?
+        }
+    };
+    let estimation_request = EstimationRequest {
+        messages: vec![cosmos_message_from_json(base_message)?],
+        memo: template.memo.clone().unwrap_or_default(),
     };
 
-    // Estimate gas requirement
-    let estimated_gas = estimate_gas_for_transaction(&base_json)?;
+    // Estimate gas requirement, the Cosmos Hub testnet cannot simulate Stride messages
+    let estimated_gas = match message_type {
+        MessageType::CommunityPoolSpendProposal { .. } => COMMUNITY_POOL_SPEND_GAS,
+        MessageType::LiquidStake { .. } => LIQUID_STAKE_GAS,
+        MessageType::LiquidUnstake { .. } => LIQUID_UNSTAKE_GAS,
+        _ => estimate_gas_for_transaction(&estimation_request, config)?,
+    };
     let gas_limit = estimated_gas.to_string();
 
-    // Calculate fee (using 0.01 uatom per gas unit, optimized for lower fees)
-    let gas_price = 0.01;
-    let fee_amount = calculate_fee_for_gas(estimated_gas, gas_price);
+    // Pay the fee in the cheapest denom accepted by the node that the wallet can afford, falling
+    // back to the first accepted denom
+    let gas_prices = node_gas_prices(config).unwrap_or_else(|e| {
+        warn!(
+            "Failed to query the gas prices of the node, assuming the default ones: {}",
+            e
+        );
+        with_default_gas_prices(Vec::new())
+    });
+    let balances = get_all_balances(&cosmos_address, config).unwrap_or_else(|e| {
+        warn!(
+            "Failed to query balances, paying the fee in {}: {}",
+            gas_prices[0].denom, e
+        );
+        HashMap::new()
+    });
+    let fee = select_optimal_fee(estimated_gas, &gas_prices, &balances).unwrap_or_else(|| {
+        CosmosCoin::new(
+            gas_prices[0].denom.clone(),
+            calculate_fee_for_gas(estimated_gas, gas_prices[0].price).to_string(),
+        )
+    });
 
-    println!("Estimated gas needed: {}", estimated_gas);
-    println!("Calculated fee: {} uatom", fee_amount);
+    info!("Estimated gas needed: {}", estimated_gas);
+    info!("Calculated fee: {} {}", fee.amount, fee.denom);
 
     let json_obj = match message_type {
-        MessageType::Send => {
+        MessageType::Send { to_address } => {
+            let to_address = to_address.as_deref().unwrap_or(&cosmos_address);
             json!({
                 "body": {
                     "messages": [
                         {
                             "@type": "/cosmos.bank.v1beta1.MsgSend",
                             "from_address": cosmos_address,
-                            "to_address": cosmos_address,
+                            "to_address": to_address,
                             "amount": [
                                 {
                                     "denom": "uatom",
-                                    "amount": "1000"
+                                    "amount": amount
                                 }
                             ]
                         }
                     ],
-                    "memo": "Send transaction",
+                    "memo": template.memo.as_deref().unwrap_or("Send transaction"),
                     "timeout_height": "0",
                     "extension_options": [],
                     "non_critical_extension_options": []
@@ -835,8 +1349,8 @@ pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn
                     "fee": {
                         "amount": [
                             {
-                                "denom": "uatom",
-                                "amount": fee_amount.to_string()
+                                "denom": fee.denom,
+                                "amount": fee.amount
                             }
                         ],
                         "gas_limit": gas_limit,
@@ -847,8 +1361,8 @@ pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn
                 "signatures": []
             })
         }
-        MessageType::Delegate => {
-            let validator_address = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+        MessageType::Delegate { validator_address } => {
+            let validator_address = validator_address.as_deref().unwrap_or(DEFAULT_VALIDATOR_ADDRESS);
             json!({
                 "body": {
                     "messages": [
@@ -858,11 +1372,11 @@ pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn
                             "validator_address": validator_address,
                             "amount": {
                                 "denom": "uatom",
-                                "amount": "1000"
+                                "amount": amount
                             }
                         }
                     ],
-                    "memo": "Delegate to validator",
+                    "memo": template.memo.as_deref().unwrap_or("Delegate to validator"),
                     "timeout_height": "0",
                     "extension_options": [],
                     "non_critical_extension_options": []
@@ -872,8 +1386,94 @@ pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn
                     "fee": {
                         "amount": [
                             {
-                                "denom": "uatom",
-                                "amount": fee_amount.to_string()
+                                "denom": fee.denom,
+                                "amount": fee.amount
+                            }
+                        ],
+                        "gas_limit": gas_limit,
+                        "payer": "",
+                        "granter": ""
+                    }
+                },
+                "signatures": []
+            })
+        }
+        MessageType::VoteWeighted { proposal_id, options } => {
+            json!({
+                "body": {
+                    "messages": [vote_weighted_message_json(&cosmos_address, *proposal_id, options)?],
+                    "memo": template.memo.as_deref().unwrap_or("Weighted vote"),
+                    "timeout_height": "0",
+                    "extension_options": [],
+                    "non_critical_extension_options": []
+                },
+                "auth_info": {
+                    "signer_infos": [],
+                    "fee": {
+                        "amount": [
+                            {
+                                "denom": fee.denom,
+                                "amount": fee.amount
+                            }
+                        ],
+                        "gas_limit": gas_limit,
+                        "payer": "",
+                        "granter": ""
+                    }
+                },
+                "signatures": []
+            })
+        }
+        MessageType::CommunityPoolSpendProposal {
+            title,
+            description,
+            recipient,
+            amount,
+        } => {
+            let deposit = template.base_amount.unwrap_or(DEFAULT_PROPOSAL_DEPOSIT);
+            let message =
+                community_pool_spend_message_json(&cosmos_address, title, description, recipient, *amount, deposit);
+            json!({
+                "body": {
+                    "messages": [message],
+                    "memo": template.memo.as_deref().unwrap_or("Community pool spend proposal"),
+                    "timeout_height": "0",
+                    "extension_options": [],
+                    "non_critical_extension_options": []
+                },
+                "auth_info": {
+                    "signer_infos": [],
+                    "fee": {
+                        "amount": [
+                            {
+                                "denom": fee.denom,
+                                "amount": fee.amount
+                            }
+                        ],
+                        "gas_limit": gas_limit,
+                        "payer": "",
+                        "granter": ""
+                    }
+                },
+                "signatures": []
+            })
+        }
+        MessageType::LiquidStake { .. } | MessageType::LiquidUnstake { .. } => {
+            json!({
+                "body": {
+                    "messages": [stride_message_json(message_type, &cosmos_address, &amount)?],
+                    "memo": template.memo.as_deref().unwrap_or("Stride liquid staking"),
+                    "timeout_height": "0",
+                    "extension_options": [],
+                    "non_critical_extension_options": []
+                },
+                "auth_info": {
+                    "signer_infos": [],
+                    "fee": {
+                        "amount": [
+                            {
+                                "denom": fee.denom,
+                                "amount": fee.amount
                             }
                         ],
                         "gas_limit": gas_limit,
@@ -889,70 +1489,71 @@ pub fn generate_raw_transaction(message_type: MessageType) -> Result<(), Box<dyn
     let compact_json = serde_json::to_string(&json_obj)?;
     let escaped_json = compact_json.replace("\"", "\\\"");
 
-    println!("\nTransaction JSON:");
-    println!("{}", serde_json::to_string_pretty(&json_obj)?);
+    info!("\nTransaction JSON:");
+    info!("{}", serde_json::to_string_pretty(&json_obj)?);
 
-    println!("\nTo send this transaction with the IC Cosmos wallet, run:");
-    println!("dfx canister call cosmos_wallet sendCosmosTransaction '(variant {{ Testnet }}, null, \"provider\", \"{}\")' --update", escaped_json);
+    info!("\nTo send this transaction with the IC Cosmos wallet, run:");
+    info!("dfx canister call cosmos_wallet sendCosmosTransaction '(variant {{ Testnet }}, null, \"provider\", \"{}\")' --update", escaped_json);
 
     Ok(())
 }
 
-pub fn build_transaction() -> Result<(), Box<dyn Error>> {
+#[cfg(feature = "native")]
+pub fn build_transaction(config: &CosmosClientConfig) -> Result<(), CosmosError> {
     // Show address generation
-    println!("=== Address Generation ===");
+    info!("=== Address Generation ===");
 
-    println!("Generated with: dfx canister call cosmos_wallet address");
-    let public_key = get_public_key_from_canister()?;
-    println!("Secp256k1 public key: {}", public_key);
-    println!("Generated with: dfx canister call cosmos_wallet cosmosAddress");
-    let cosmos_address = get_cosmos_address_from_canister()?;
-    println!("Cosmos address: {}", cosmos_address);
+    info!("Generated with: dfx canister call cosmos_wallet address");
+    let public_key = signer_public_key(config)?;
+    info!("Secp256k1 public key: {}", public_key);
+    info!("Generated with: dfx canister call cosmos_wallet cosmosAddress");
+    let cosmos_address = signer_cosmos_address(config)?;
+    info!("Cosmos address: {}", cosmos_address);
 
     // Show the transaction structure in JSON format
-    println!("\n=== Raw Transaction ===");
-    let (tx_bytes, sign_bytes) = create_send_transaction(&cosmos_address, &cosmos_address, 1000, None)?;
+    info!("\n=== Raw Transaction ===");
+    let (tx_bytes, sign_bytes) = create_send_transaction(&cosmos_address, &cosmos_address, 1000, None, config)?;
     print_transaction_json(&tx_bytes, "", true)?;
 
     // Decode and display the SignDoc structure in human-readable format
-    println!("\n=== What's Being Signed (Canonical Sign Document) ===");
+    info!("\n=== What's Being Signed (Canonical Sign Document) ===");
     if let Ok(sign_doc) = SignDoc::decode(&sign_bytes[..]) {
-        println!("Chain ID: {}", sign_doc.chain_id);
-        println!("Account Number: {}", sign_doc.account_number);
+        info!("Chain ID: {}", sign_doc.chain_id);
+        info!("Account Number: {}", sign_doc.account_number);
 
         // Decode and display the body
         if let Ok(tx_body) = TxBody::decode(sign_doc.body_bytes.as_slice()) {
-            println!("Transaction Body:");
+            info!("Transaction Body:");
             if !tx_body.memo.is_empty() {
-                println!("  Memo: \"{}\"", tx_body.memo);
+                info!("  Memo: \"{}\"", tx_body.memo);
             }
-            println!("  Messages: {} message(s)", tx_body.messages.len());
+            info!("  Messages: {} message(s)", tx_body.messages.len());
 
             for (i, msg) in tx_body.messages.iter().enumerate() {
-                println!("    Message {}: {}", i + 1, msg.type_url);
+                info!("    Message {}: {}", i + 1, msg.type_url);
 
                 // Decode specific message types for better readability
                 match msg.type_url.as_str() {
                     "/cosmos.bank.v1beta1.MsgSend" => {
                         if let Ok(msg_send) = MsgSend::decode(msg.value.as_slice()) {
-                            println!("      From: {}", msg_send.from_address);
-                            println!("      To: {}", msg_send.to_address);
+                            info!("      From: {}", msg_send.from_address);
+                            info!("      To: {}", msg_send.to_address);
                             for coin in &msg_send.amount {
-                                println!("      Amount: {} {}", coin.amount, coin.denom);
+                                info!("      Amount: {} {}", coin.amount, coin.denom);
                             }
                         }
                     }
                     "/cosmos.staking.v1beta1.MsgDelegate" => {
                         if let Ok(msg_delegate) = MsgDelegate::decode(msg.value.as_slice()) {
-                            println!("      Delegator: {}", msg_delegate.delegator_address);
-                            println!("      Validator: {}", msg_delegate.validator_address);
+                            info!("      Delegator: {}", msg_delegate.delegator_address);
+                            info!("      Validator: {}", msg_delegate.validator_address);
                             if let Some(amount) = &msg_delegate.amount {
-                                println!("      Amount: {} {}", amount.amount, amount.denom);
+                                info!("      Amount: {} {}", amount.amount, amount.denom);
                             }
                         }
                     }
                     _ => {
-                        println!("      Value: {} bytes (binary data)", msg.value.len());
+                        info!("      Value: {} bytes (binary data)", msg.value.len());
                     }
                 }
             }
@@ -961,56 +1562,53 @@ pub fn build_transaction() -> Result<(), Box<dyn Error>> {
         // Decode and display the auth info
         if let Ok(auth_info) = AuthInfo::decode(sign_doc.auth_info_bytes.as_slice()) {
             if let Some(fee) = &auth_info.fee {
-                println!("Fee & Gas:");
+                info!("Fee & Gas:");
                 for coin in &fee.amount {
-                    println!("  Fee: {} {}", coin.amount, coin.denom);
+                    info!("  Fee: {} {}", coin.amount, coin.denom);
                 }
-                println!("  Gas Limit: {}", fee.gas_limit);
+                info!("  Gas Limit: {}", fee.gas_limit);
             }
 
             for (i, signer) in auth_info.signer_infos.iter().enumerate() {
-                println!("Signer {}: sequence {}", i + 1, signer.sequence);
+                info!("Signer {}: sequence {}", i + 1, signer.sequence);
             }
         }
     } else {
-        println!("Failed to decode SignDoc structure");
+        error!("Failed to decode SignDoc structure");
     }
 
     // Show the canonical sign document encodings
-    println!("\nCanonical sign document encodings:");
-    println!("Base64: {}", STANDARD.encode(&sign_bytes));
-    println!("Hex: {}", hex::encode(&sign_bytes));
+    info!("\nCanonical sign document encodings:");
+    info!("Base64: {}", STANDARD.encode(&sign_bytes));
+    debug!("Hex: {}", hex::encode(&sign_bytes));
 
     // Show the canister call command
-    println!("\nTo sign this document with the canister:");
-    println!("dfx canister call --update cosmos_wallet signMessage \\");
-    println!(
-        "  '(blob \"{}\")'",
-        sign_bytes.iter().map(|b| format!("\\{:02X}", b)).collect::<String>()
-    );
+    info!("\nTo sign this document with the canister:");
+    info!("dfx canister call --update cosmos_wallet signMessage \\");
+    info!("  '(blob \"{}\")'", candid_blob_text(&sign_bytes));
 
-    println!("\nGetting signature from canister...");
-    let signature = get_signature_from_canister(&sign_bytes)?;
+    info!("\nGetting signature from canister...");
+    let signature = sign_with_signer(&sign_bytes, config)?;
 
     // Show signature encoding details
-    println!("\n=== Signature Details ===");
-    println!("Raw signature (hex): {}", hex::encode(&signature));
-    println!("Signature (base64): {}", STANDARD.encode(&signature));
+    info!("\n=== Signature Details ===");
+    debug!("Raw signature (hex): {}", hex::encode(&signature));
+    info!("Signature (base64): {}", STANDARD.encode(&signature));
 
-    println!("\nCreating signed transaction...");
-    let (final_tx, _) = create_send_transaction(&cosmos_address, &cosmos_address, 1000, Some(signature))?;
+    info!("\nCreating signed transaction...");
+    let (final_tx, _) = create_send_transaction(&cosmos_address, &cosmos_address, 1000, Some(signature), config)?;
 
     // Show the final signed transaction in JSON format only
     print_transaction_json(&final_tx, "Final Signed Transaction", true)?;
 
-    println!("\n=== Broadcasting Options ===");
-    println!("Option 1 - Using cosmos-utils:");
-    println!("cargo run -- broadcast \"{}\"", STANDARD.encode(&final_tx));
+    info!("\n=== Broadcasting Options ===");
+    info!("Option 1 - Using cosmos-utils:");
+    info!("cargo run -- broadcast \"{}\"", STANDARD.encode(&final_tx));
 
-    println!("\nOption 2 - Direct HTTP broadcast:");
-    println!("curl -X POST https://rpc.testcosmos.directory/cosmosicsprovidertestnet \\");
-    println!("  -H \"Content-Type: application/json\" \\");
-    println!(
+    info!("\nOption 2 - Direct HTTP broadcast:");
+    info!("curl -X POST https://rpc.testcosmos.directory/cosmosicsprovidertestnet \\");
+    info!("  -H \"Content-Type: application/json\" \\");
+    info!(
         "  -d '{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"broadcast_tx_sync\",\"params\":{{\"tx\":\"{}\"}}}}'",
         STANDARD.encode(&final_tx)
     );
@@ -1018,8 +1616,27 @@ pub fn build_transaction() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-pub fn broadcast_transaction(tx_base64: &str) -> Result<(), Box<dyn Error>> {
-    let client = Client::new();
+/// Decodes signed transaction bytes from either a hex or a base64 string.
+///
+/// Input with a `0x` prefix is always treated as hex. Otherwise, input consisting
+/// only of hex characters with an even length is decoded as hex, and anything else as base64.
+pub fn parse_tx_bytes(input: &str) -> Result<Vec<u8>, ParseError> {
+    let input = input.trim();
+    if let Some(hex_str) = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        return Ok(hex::decode(hex_str)?);
+    }
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if input.len() % 2 == 0 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(hex::decode(input)?);
+    }
+    Ok(STANDARD.decode(input)?)
+}
+
+#[cfg(feature = "native")]
+pub fn broadcast_transaction(tx: &str, config: &CosmosClientConfig) -> Result<(), CosmosError> {
+    let tx_base64 = STANDARD.encode(parse_tx_bytes(tx)?);
     let request = json!({
         "jsonrpc": "2.0",
         "id": 1,
@@ -1028,27 +1645,39 @@ pub fn broadcast_transaction(tx_base64: &str) -> Result<(), Box<dyn Error>> {
             "tx": tx_base64
         }
     });
-    let response = client
-        .post("https://rpc.testcosmos.directory/cosmosicsprovidertestnet")
-        .json(&request)
-        .send()?;
-    let response_json: serde_json::Value = response.json()?;
-    println!("\nTransaction broadcast response:");
-    println!("{}", serde_json::to_string_pretty(&response_json)?);
-    println!(
-        "https://www.mintscan.io/ics-testnet-provider/tx/{}",
-        response_json["result"]["hash"].as_str().unwrap_or("")
-    );
+    let response_json = config.post_json_rpc(&request)?;
+    info!("\nTransaction broadcast response:");
+    info!("{}", serde_json::to_string_pretty(&response_json)?);
+    let hash = response_json["result"]["hash"].as_str().unwrap_or("").to_string();
+    if let Some(url) = build_explorer_url(PROVIDER_TESTNET_CHAIN_ID, ExplorerResource::Transaction(hash)) {
+        info!("{}", url);
+    }
     Ok(())
 }
 
-pub fn print_fund_command() -> Result<(), Box<dyn Error>> {
-    let public_key = get_public_key_from_canister()?;
+#[cfg(feature = "native")]
+pub fn print_fund_command(config: &CosmosClientConfig) -> Result<(), CosmosError> {
+    let public_key = signer_public_key(config)?;
     let cosmos_address = public_key_to_cosmos_address(&public_key)?;
 
-    println!("\nTo fund your wallet, run this command:");
-    println!("gaiad tx bank send <faucet_address> {} 100000uatom --chain-id provider --node https://rpc.testcosmos.directory/cosmosicsprovidertestnet --fees 1000uatom", cosmos_address);
-    println!(
+    match get_account_status(&cosmos_address, None, config)? {
+        AccountStatus::Exists {
+            account_number,
+            sequence,
+        } => info!(
+            "Account {} already exists (account number: {}, sequence: {})",
+            cosmos_address, account_number, sequence
+        ),
+        AccountStatus::NotFound => info!("Account {} has not been funded yet", cosmos_address),
+        AccountStatus::QueryError { code, log } => warn!(
+            "Failed to query account {}: Query error (code {}): {}",
+            cosmos_address, code, log
+        ),
+    }
+
+    info!("\nTo fund your wallet, run this command:");
+    info!("gaiad tx bank send <faucet_address> {} 100000uatom --chain-id provider --node https://rpc.testcosmos.directory/cosmosicsprovidertestnet --fees 1000uatom", cosmos_address);
+    info!(
         "\nThis will send 100,000 uatom from the faucet wallet to your address: {}",
         cosmos_address
     );
@@ -1056,9 +1685,31 @@ pub fn print_fund_command() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-pub fn get_account_info(address: &str) -> Result<(u64, u64), Box<dyn Error>> {
-    let client = Client::new();
+#[cfg(feature = "native")]
+pub fn get_account_info(
+    address: &str,
+    height: Option<u64>,
+    config: &CosmosClientConfig,
+) -> Result<(u64, u64), CosmosError> {
+    match get_account_status(address, height, config)? {
+        AccountStatus::Exists {
+            account_number,
+            sequence,
+        } => Ok((account_number, sequence)),
+        AccountStatus::NotFound => Err(CosmosError::AccountNotFound(address.to_string())),
+        AccountStatus::QueryError { code, log } => Err(CosmosError::RpcError { code, message: log }),
+    }
+}
 
+/// Non-erroring alternative to [`get_account_info`] that distinguishes a new (unfunded)
+/// account from other query failures. Only transport and decoding failures are returned as errors.
+/// The account is read at block `height`, or at the latest block when `None`.
+#[cfg(feature = "native")]
+pub fn get_account_status(
+    address: &str,
+    height: Option<u64>,
+    config: &CosmosClientConfig,
+) -> Result<AccountStatus, CosmosError> {
     // Create the query data - format is: 0a<length><address_string>
     // The address should be encoded as a string, not as raw bytes
     let query_data = format!("0a{:02x}{}", address.len(), hex::encode(address.as_bytes()));
@@ -1070,36 +1721,37 @@ pub fn get_account_info(address: &str) -> Result<(u64, u64), Box<dyn Error>> {
         "params": {
             "path": "/cosmos.auth.v1beta1.Query/Account",
             "data": query_data,
-            "height": "0",
+            "height": height.unwrap_or(0).to_string(),
             "prove": false
         }
     });
 
-    let response = client
-        .post("https://rpc.testcosmos.directory/cosmosicsprovidertestnet")
-        .json(&request)
-        .send()?;
-
-    let response_json: serde_json::Value = response.json()?;
+    let response_json = config.post_json_rpc(&request)?;
 
     // Check for error in response
     if let Some(error) = response_json.get("error") {
-        return Err(format!("RPC error: {}", error).into());
+        return Err(json_rpc_error(error));
     }
 
     // Check for error code in response
     if let Some(code) = response_json["result"]["response"]["code"].as_i64() {
+        if code == ACCOUNT_NOT_FOUND_CODE {
+            return Ok(AccountStatus::NotFound);
+        }
         if code != 0 {
             let log = response_json["result"]["response"]["log"]
                 .as_str()
                 .unwrap_or("Unknown error");
-            return Err(format!("Query error (code {}): {}", code, log).into());
+            return Ok(AccountStatus::QueryError {
+                code,
+                log: log.to_string(),
+            });
         }
     }
 
     let result = response_json["result"]["response"]["value"]
         .as_str()
-        .ok_or_else(|| "Failed to get account info")?;
+        .ok_or_else(|| CosmosError::DecodingError("Failed to get account info".to_string()))?;
 
     let decoded = STANDARD.decode(result)?;
 
@@ -1107,21 +1759,175 @@ pub fn get_account_info(address: &str) -> Result<(u64, u64), Box<dyn Error>> {
     let query_response = QueryAccountResponse::decode(&decoded[..])?;
 
     // Get the account Any message
-    let account_any = query_response.account.ok_or_else(|| "No account found")?;
+    let Some(account_any) = query_response.account else {
+        return Ok(AccountStatus::NotFound);
+    };
 
     // Parse the BaseAccount from the account field
     let account = BaseAccount::decode(account_any.value.as_slice())?;
 
     // If account number is 0, it means the account doesn't exist yet
     if account.account_number == 0 {
-        return Err("Account does not exist yet. Please fund it first.".into());
+        return Ok(AccountStatus::NotFound);
     }
 
-    Ok((account.account_number, account.sequence))
+    Ok(AccountStatus::Exists {
+        account_number: account.account_number,
+        sequence: account.sequence,
+    })
 }
 
-pub fn analyze_account_response(address: &str) -> Result<String, Box<dyn Error>> {
-    let client = Client::new();
+/// Returns the total amount of uatom staked by `address` across all validators,
+/// converting the delegation shares with the exchange rate of each validator.
+#[cfg(feature = "native")]
+pub fn get_total_staked(address: &str, config: &CosmosClientConfig) -> Result<u64, CosmosError> {
+    // Confirm the account exists before querying its delegations
+    get_account_info(address, None, config)?;
+
+    let total: u128 = get_delegations(address, config)?.iter().map(|(_, staked)| staked).sum();
+    Ok(u64::try_from(total)?)
+}
+
+/// Returns the delegations of `address` with their amount of uatom, converting the delegation
+/// shares with the exchange rate of each validator
+#[cfg(feature = "native")]
+fn get_delegations(address: &str, config: &CosmosClientConfig) -> Result<Vec<(Delegation, u128)>, CosmosError> {
+    let mut delegations = Vec::new();
+    let mut key = Vec::new();
+    loop {
+        let request = QueryDelegatorDelegationsRequest {
+            delegator_addr: address.to_string(),
+            pagination: (!key.is_empty()).then(|| PageRequest {
+                key: key.clone(),
+                ..Default::default()
+            }),
+        };
+        let value = abci_query(
+            "/cosmos.staking.v1beta1.Query/DelegatorDelegations",
+            &request.encode_to_vec(),
+            config,
+        )?;
+        let response = QueryDelegatorDelegationsResponse::decode(&value[..])?;
+        delegations.extend(response.delegation_responses.into_iter().filter_map(|r| r.delegation));
+        match response.pagination {
+            Some(page) if !page.next_key.is_empty() => key = page.next_key,
+            _ => break,
+        }
+    }
+
+    let mut exchange_rates = std::collections::BTreeMap::new();
+    let mut staked_delegations = Vec::new();
+    for delegation in delegations {
+        if !exchange_rates.contains_key(&delegation.validator_address) {
+            let request = QueryValidatorRequest {
+                validator_addr: delegation.validator_address.clone(),
+            };
+            let value = abci_query(
+                "/cosmos.staking.v1beta1.Query/Validator",
+                &request.encode_to_vec(),
+                config,
+            )?;
+            let validator = QueryValidatorResponse::decode(&value[..])?.validator.ok_or_else(|| {
+                CosmosError::DecodingError(format!("Validator {} not found", delegation.validator_address))
+            })?;
+            exchange_rates.insert(
+                delegation.validator_address.clone(),
+                (validator.tokens, validator.delegator_shares),
+            );
+        }
+        let (tokens, delegator_shares) = &exchange_rates[&delegation.validator_address];
+        let staked = shares_to_tokens(&delegation.shares, tokens, delegator_shares)?;
+        debug!(
+            "Delegation to {}: {} shares = {} uatom",
+            delegation.validator_address, delegation.shares, staked
+        );
+        staked_delegations.push((delegation, staked));
+    }
+
+    Ok(staked_delegations)
+}
+
+/// Convert delegation `shares` (`Dec` atomics) to tokens with the validator exchange rate
+/// `tokens / delegator_shares`. The shares are truncated to 6 decimals so the product fits into `u128`.
+pub fn shares_to_tokens(shares: &str, tokens: &str, delegator_shares: &str) -> Result<u128, CosmosError> {
+    const SHARES_SCALE_DOWN: u128 = 1_000_000_000_000;
+    let shares = shares.parse::<u128>()? / SHARES_SCALE_DOWN;
+    let tokens = tokens.parse::<u128>()?;
+    let delegator_shares = delegator_shares.parse::<u128>()? / SHARES_SCALE_DOWN;
+    if delegator_shares == 0 {
+        return Ok(0);
+    }
+    let staked = shares
+        .checked_mul(tokens)
+        .ok_or_else(|| CosmosError::EncodingError("Staking amount overflow".to_string()))?;
+    Ok(staked / delegator_shares)
+}
+
+/// Returns the balances of `address` keyed by denom
+#[cfg(feature = "native")]
+pub fn get_all_balances(address: &str, config: &CosmosClientConfig) -> Result<HashMap<String, u64>, CosmosError> {
+    let mut balances = HashMap::new();
+    let mut key = Vec::new();
+    loop {
+        let request = QueryAllBalancesRequest {
+            address: address.to_string(),
+            pagination: (!key.is_empty()).then(|| PageRequest {
+                key: key.clone(),
+                ..Default::default()
+            }),
+        };
+        let value = abci_query(
+            "/cosmos.bank.v1beta1.Query/AllBalances",
+            &request.encode_to_vec(),
+            config,
+        )?;
+        let response = QueryAllBalancesResponse::decode(&value[..])?;
+        for coin in response.balances {
+            balances.insert(coin.denom, coin.amount.parse::<u64>()?);
+        }
+        match response.pagination {
+            Some(page) if !page.next_key.is_empty() => key = page.next_key,
+            _ => break,
+        }
+    }
+    Ok(balances)
+}
+
+/// Runs an ABCI query against the node and returns the decoded response value
+#[cfg(feature = "native")]
+fn abci_query(path: &str, data: &[u8], config: &CosmosClientConfig) -> Result<Vec<u8>, CosmosError> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "abci_query",
+        "params": {
+            "path": path,
+            "data": hex::encode(data),
+            "height": "0",
+            "prove": false
+        }
+    });
+
+    let response_json = config.post_json_rpc(&request)?;
+
+    if let Some(error) = response_json.get("error") {
+        return Err(json_rpc_error(error));
+    }
+
+    let response = &response_json["result"]["response"];
+    if let Some(code) = response["code"].as_i64().filter(|code| *code != 0) {
+        let log = response["log"].as_str().unwrap_or("Unknown error");
+        return Err(CosmosError::RpcError {
+            code,
+            message: log.to_string(),
+        });
+    }
+
+    Ok(STANDARD.decode(response["value"].as_str().unwrap_or(""))?)
+}
+
+#[cfg(feature = "native")]
+pub fn analyze_account_response(address: &str, config: &CosmosClientConfig) -> Result<String, CosmosError> {
     let query_data = format!("0a{:02x}{}", address.len(), hex::encode(address.as_bytes()));
 
     let request = json!({
@@ -1136,12 +1942,7 @@ pub fn analyze_account_response(address: &str) -> Result<String, Box<dyn Error>>
         }
     });
 
-    let response = client
-        .post("https://rpc.testcosmos.directory/cosmosicsprovidertestnet")
-        .json(&request)
-        .send()?;
-
-    let response_json: serde_json::Value = response.json()?;
+    let response_json = config.post_json_rpc(&request)?;
 
     let mut analysis = String::new();
 
@@ -1162,7 +1963,7 @@ pub fn analyze_account_response(address: &str) -> Result<String, Box<dyn Error>>
 
     let result = response_json["result"]["response"]["value"]
         .as_str()
-        .ok_or_else(|| "Failed to get account info")?;
+        .ok_or_else(|| CosmosError::DecodingError("Failed to get account info".to_string()))?;
 
     analysis.push_str(&format!("Raw response value (base64): {}\n", result));
 
@@ -1215,7 +2016,7 @@ pub fn analyze_account_response(address: &str) -> Result<String, Box<dyn Error>>
 }
 
 /// Analyze gas usage from a transaction result to improve estimates
-pub fn analyze_gas_usage_from_result(tx_result_json: &str) -> Result<(), Box<dyn Error>> {
+pub fn analyze_gas_usage_from_result(tx_result_json: &str) -> Result<(), CosmosError> {
     let result: serde_json::Value = serde_json::from_str(tx_result_json)?;
 
     let gas_wanted = result["data"]["gas_wanted"].as_str().unwrap_or("0").parse::<u64>()?;
@@ -1227,48 +2028,45 @@ pub fn analyze_gas_usage_from_result(tx_result_json: &str) -> Result<(), Box<dyn
         .as_str()
         .unwrap_or("unknown");
 
-    println!("=== Gas Usage Analysis ===");
-    println!("Message Type: {}", message_type);
-    println!("Gas Wanted: {}", gas_wanted);
-    println!("Gas Used: {}", gas_used);
-    println!("Result: {}", if code == 0 { "SUCCESS" } else { "FAILED" });
+    info!("=== Gas Usage Analysis ===");
+    info!("Message Type: {}", message_type);
+    info!("Gas Wanted: {}", gas_wanted);
+    info!("Gas Used: {}", gas_used);
+    info!("Result: {}", if code == 0 { "SUCCESS" } else { "FAILED" });
 
     if gas_used > 0 {
         let efficiency = (gas_used as f64 / gas_wanted as f64) * 100.0;
-        println!("Gas Efficiency: {:.1}% ({} / {})", efficiency, gas_used, gas_wanted);
+        info!("Gas Efficiency: {:.1}% ({} / {})", efficiency, gas_used, gas_wanted);
 
         if code != 0 && gas_used >= gas_wanted {
-            println!("⚠️  OUT OF GAS: Need at least {} gas", gas_used + 1);
-            println!("💡 Recommended gas limit: {}", ((gas_used as f64) * 1.2) as u64);
+            warn!("⚠️  OUT OF GAS: Need at least {} gas", gas_used + 1);
+            warn!("💡 Recommended gas limit: {}", ((gas_used as f64) * 1.2) as u64);
         } else if efficiency < 70.0 {
-            println!(
+            info!(
                 "💰 OVER-PROVISIONED: Could reduce gas limit to {}",
                 ((gas_used as f64) * 1.15) as u64
             );
         } else if efficiency > 95.0 {
-            println!(
+            warn!(
                 "⚠️  CLOSE CALL: Consider increasing buffer to {}",
                 ((gas_used as f64) * 1.2) as u64
             );
         } else {
-            println!("✅ OPTIMAL: Gas allocation is reasonable");
+            info!("✅ OPTIMAL: Gas allocation is reasonable");
         }
 
         // Suggest improvements to our estimates
-        match message_type {
-            "/cosmos.staking.v1beta1.MsgDelegate" => {
-                let current_estimate = 324000; // Current estimate from our function
-                let recommended = ((gas_used as f64) * 1.2) as u64;
-                if recommended != current_estimate {
-                    println!("📊 Code Update Suggestion:");
-                    println!(
-                        "   Update MsgDelegate base gas from 270,000 to {}",
-                        ((gas_used as f64) * 1.0) as u64
-                    );
-                    println!("   This would give final estimate: {}", recommended);
-                }
+        if message_type == "/cosmos.staking.v1beta1.MsgDelegate" {
+            let current_estimate = 324000; // Current estimate from our function
+            let recommended = ((gas_used as f64) * 1.2) as u64;
+            if recommended != current_estimate {
+                info!("📊 Code Update Suggestion:");
+                info!(
+                    "   Update MsgDelegate base gas from 270,000 to {}",
+                    ((gas_used as f64) * 1.0) as u64
+                );
+                info!("   This would give final estimate: {}", recommended);
             }
-            _ => {}
         }
     }
 
@@ -1287,4 +2085,153 @@ mod tests {
         assert!(addr.starts_with("cosmos1"));
         assert_eq!(addr.len(), 45); // cosmos1 + 39 chars
     }
+
+    #[test]
+    fn test_gas_buffer_multiplier() {
+        let message = |type_url: &str| CosmosMessage {
+            type_url: type_url.to_string(),
+            value: json!({}),
+        };
+        let send = message("/cosmos.bank.v1beta1.MsgSend");
+        let delegate = message("/cosmos.staking.v1beta1.MsgDelegate");
+        assert_eq!(gas_buffer_multiplier(&[delegate.clone()]), 1.15);
+        assert!((gas_buffer_multiplier(&[delegate.clone(), delegate.clone()]) - 1.2).abs() < 1e-9);
+        assert!((gas_buffer_multiplier(&[send.clone(), delegate.clone()]) - 1.3).abs() < 1e-9);
+        let delegates: Vec<_> = std::iter::repeat(delegate.clone()).take(10).collect();
+        assert_eq!(gas_buffer_multiplier(&delegates), MAX_GAS_BUFFER_MULTIPLIER);
+        assert_eq!(fallback_gas_for_messages(&[send, delegate]), 478_100);
+    }
+
+    #[test]
+    fn test_convert_address() {
+        let cosmos_address = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+        assert_eq!(
+            convert_address_prefix(cosmos_address, "stride").unwrap(),
+            "stride1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmca8lyk"
+        );
+
+        let hex_address = bech32_to_hex(cosmos_address).unwrap();
+        assert_eq!(hex_address.len(), 40);
+        assert_eq!(hex_to_bech32(&hex_address, "cosmos").unwrap(), cosmos_address);
+        assert!(hex_to_bech32("0a0b", "cosmos").is_err());
+        assert!(convert_address_prefix("not-an-address", "osmo").is_err());
+    }
+
+    #[test]
+    fn test_stride_message_json() {
+        let cosmos_address = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+        let message_type = MessageType::LiquidUnstake {
+            host_zone: "cosmoshub-4".to_string(),
+            receiver: None,
+        };
+        let message = stride_message_json(&message_type, cosmos_address, "1000000").unwrap();
+        assert_eq!(message["creator"], "stride1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmca8lyk");
+        assert_eq!(message["receiver"], cosmos_address);
+        assert_eq!(message["host_zone"], "cosmoshub-4");
+    }
+
+    #[test]
+    fn test_decode_signature_blob() {
+        // Signature bytes that the former escape parser misread as "\n", "\r" and "\t"
+        let signature: Vec<u8> = (0..64).map(|i| [b'\n', b'\r', b'\t', b'\\', b'"'][i % 5]).collect();
+        let raw_output = hex::encode(candid::encode_one(&signature).unwrap());
+
+        assert_eq!(decode_signature_blob(&format!("{}\n", raw_output)).unwrap(), signature);
+        assert!(decode_signature_blob("not hex").is_err());
+        assert!(decode_signature_blob(&hex::encode(candid::encode_one("text").unwrap())).is_err());
+    }
+
+    #[test]
+    fn test_parse_tx_bytes() {
+        let bytes = vec![0x0a, 0x90, 0x01, 0xff];
+        assert_eq!(parse_tx_bytes("0a9001ff").unwrap(), bytes);
+        assert_eq!(parse_tx_bytes("0x0A9001FF").unwrap(), bytes);
+        assert_eq!(parse_tx_bytes(&STANDARD.encode(&bytes)).unwrap(), bytes);
+        assert!(matches!(parse_tx_bytes(""), Err(ParseError::Empty)));
+        assert!(matches!(parse_tx_bytes("0x0a9"), Err(ParseError::Hex(_))));
+        assert!(matches!(parse_tx_bytes("not base64!"), Err(ParseError::Base64(_))));
+    }
+
+    #[test]
+    fn test_shares_to_tokens() {
+        let shares = "1000000000000000000000"; // 1000 shares
+        assert_eq!(
+            shares_to_tokens(shares, "5000", "5000000000000000000000").unwrap(),
+            1000
+        );
+        assert_eq!(shares_to_tokens(shares, "4500", "5000000000000000000000").unwrap(), 900);
+        assert!(shares_to_tokens("invalid", "1", "1").is_err());
+    }
+
+    #[test]
+    fn test_vote_weighted_message_json() {
+        let voter = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+        let options = |options: &[(&str, &str)]| -> Vec<(String, String)> {
+            options
+                .iter()
+                .map(|(option, weight)| (option.to_string(), weight.to_string()))
+                .collect()
+        };
+        let message = vote_weighted_message_json(voter, 7, &options(&[("yes", "0.7"), ("abstain", "0.3")])).unwrap();
+        assert_eq!(message["proposal_id"], "7");
+        assert_eq!(message["options"][0]["option"], "VOTE_OPTION_YES");
+        assert_eq!(message["options"][1]["weight"], "0.3");
+
+        // Thirds do not sum to exactly 1.0, the wallet rounds the last weight
+        let third = "0.333333333333333333";
+        assert!(vote_weighted_message_json(
+            voter,
+            7,
+            &options(&[("yes", third), ("no", third), ("no_with_veto", third)])
+        )
+        .is_ok());
+
+        for invalid in [
+            options(&[("yes", "0.7"), ("no", "0.2")]),
+            options(&[("yes", "1"), ("no", "0")]),
+            options(&[("maybe", "1")]),
+            options(&[("yes", "1.x")]),
+        ] {
+            assert!(matches!(
+                vote_weighted_message_json(voter, 7, &invalid),
+                Err(CosmosError::EncodingError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cosmos_error() {
+        assert!(matches!(
+            raw_signature(&[0x01; 10]),
+            Err(CosmosError::InsufficientSignatureLength { got: 10, expected: 64 })
+        ));
+        assert!(matches!(
+            raw_signature(&[0x30, 0x02]),
+            Err(CosmosError::DecodingError(_))
+        ));
+        assert!(matches!(
+            vote_weighted_message_json("cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6", 1, &[]),
+            Err(CosmosError::EncodingError(_))
+        ));
+        assert!(matches!(
+            read_varint_at(&[0x80], &mut 0),
+            Err(CosmosError::DecodingError(_))
+        ));
+        assert!(matches!(
+            parse_tx_bytes("0x0a9").map_err(CosmosError::from),
+            Err(CosmosError::DecodingError(_))
+        ));
+
+        let error = json_rpc_error(
+            &json!({ "code": -32603, "message": "Internal error", "data": "height 0 is not available" }),
+        );
+        assert_eq!(
+            error.to_string(),
+            "RPC error (code -32603): Internal error: height 0 is not available"
+        );
+        assert_eq!(
+            CosmosError::AccountNotFound("cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string()).to_string(),
+            "Account cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6 does not exist yet. Please fund it first."
+        );
+    }
 }
@@ -0,0 +1,34 @@
+//! Minimal `log` backend for binaries using cosmos-utils.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+struct ConsoleLogger;
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Info => println!("{}", record.args()),
+            level => eprintln!("[{}] {}", level, record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
+
+/// Install a console logger printing `INFO` records to stdout and every other level to stderr.
+///
+/// Does nothing if the application has already installed its own logger.
+pub fn init_logging(level: LevelFilter) {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
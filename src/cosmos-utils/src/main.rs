@@ -5,33 +5,76 @@ fn print_usage() {
     println!("Usage:");
     println!("  build    - Build a new transaction and output the signing command");
     println!("  raw <message_type> - Generate wallet transaction and output sendCosmosTransaction command");
-    println!("    message_type can be: send, delegate");
-    println!("  broadcast <tx_base64> - Broadcast a signed transaction to the Cosmos Provider testnet");
+    println!("    message_type can be: send [--to <address>], delegate [--validator <address>],");
+    println!("                         vote-weighted <proposal_id> <option=weight>...,");
+    println!("                         community-pool-spend <recipient> <uatom> --title <text> --description <text>,");
+    println!("                         liquid-stake <host_denom>, liquid-unstake <host_zone> [--to <address>]");
+    println!("  broadcast <tx> - Broadcast a signed transaction (base64 or hex) to the Cosmos Provider testnet");
     println!("  fund     - Print Gaia CLI command to fund the wallet from faucet");
+    println!("  addressbook add <name> <address> | remove <name> | list - Manage named addresses");
+    println!("  template save <name> <message_type> | load <name> | list - Manage reusable transactions");
+    println!("  history commission <validator_address> [--from <height>] [--to <height>]");
+    println!("           - List the commission rate changes of a validator, over the last 100 blocks by default");
+    println!("  convert-address --from <address> --to-prefix <hrp> | --from-hex <hex> --to-prefix <hrp>");
+    println!("                  | --to-hex <address> - Convert between chain prefixes, or between hex and bech32");
+    println!("  portfolio <config_file> - Sum the balances, staked tokens and rewards of accounts on several chains");
+    println!("            listed as {{ \"chains\": [{{ \"chain_id\", \"rpc_url\", \"address\", \"hrp\" }}] }}");
+    println!("  check-health --config <config_file> [--max-blocks-behind <blocks>] - Check the health of RPC nodes");
+    println!("               listed as {{ \"endpoints\": [<rpc_url>...], \"max_blocks_behind\": <blocks> }}");
+    println!("  position <address> - Show the delegations, unbonding delegations and pending rewards of a delegator");
+    println!("  analyze-gas <tx_result_json_file> - Analyze the gas used by a transaction and suggest gas limits");
+    println!("              from the last 7 days of analyzed transactions");
+    println!("\nFlags for raw and template: --amount <uatom> (the deposit of a proposal), --memo <text>.");
+    println!("A loaded template can also override its recipient or validator with --to or --validator.");
+    println!("\nAddresses can be given in full or as a @name reference from the address book.");
+    println!("\nNetwork flags: --connect-timeout <secs> (default 5), --request-timeout <secs> (default 30).");
+    println!("\nSigning flag: --keyfile <path> signs with a local secp256k1 private key (PEM, hex or base64)");
+    println!("instead of the wallet canister through dfx. Never commit the keyfile.");
+    println!("\nSet COSMOS_UTILS_LOG=debug to include hex dumps of the encoded bytes.");
     println!("\nExample:");
     println!("  cargo run -- build");
     println!("  cargo run -- raw send");
     println!("  cargo run -- raw delegate");
+    println!("  cargo run -- addressbook add my-validator cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6");
+    println!("  cargo run -- raw delegate --validator @my-validator");
+    println!("  cargo run -- raw vote-weighted 42 yes=0.7 abstain=0.3");
+    println!(
+        "  cargo run -- raw community-pool-spend @grantee 1000000 --title \"Grant\" --description \"Fund the grantee\""
+    );
+    println!("  cargo run -- raw liquid-stake uatom --amount 1000000");
+    println!("  cargo run -- raw liquid-unstake cosmoshub-4 --amount 1000000");
+    println!("  cargo run -- template save weekly delegate --validator @my-validator --amount 5000");
+    println!("  cargo run -- template load weekly --amount 7000");
     println!("  cargo run -- broadcast \"CpABCo0BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5k...\"");
     println!("  cargo run -- fund");
+    println!("  cargo run -- history commission @my-validator --from 1200000 --to 1201000");
+    println!("  cargo run -- convert-address --from cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6 --to-prefix osmo");
+    println!("  cargo run -- portfolio portfolio.json");
+    println!("  cargo run -- check-health --config nodes.json");
+    println!("  cargo run -- position cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6");
+    println!("  cargo run -- analyze-gas tx-result.json");
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let level = env::var("COSMOS_UTILS_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    init_logging(level);
+
     let args: Vec<String> = env::args().collect();
+    let config = client_config(&args)?;
     match args.get(1).map(|s| s.as_str()) {
-        Some("build") => build_transaction()?,
+        Some("build") => build_transaction(&config)?,
         Some("raw") => {
-            if let Some(message_type) = args.get(2) {
-                match message_type.as_str() {
-                    "send" => generate_raw_transaction(MessageType::Send)?,
-                    "delegate" => generate_raw_transaction(MessageType::Delegate)?,
-                    _ => {
-                        println!(
-                            "Error: Unsupported message type '{}'. Supported types: send, delegate",
-                            message_type
-                        );
-                        print_usage();
-                    }
+            if args.get(2).is_some() {
+                if let Some(message_type) = parse_message_type(&args[2..])? {
+                    let template = TransactionTemplate::new(message_type).with_overrides(
+                        amount_flag(&args)?,
+                        flag_value(&args, "--memo")?.map(str::to_string),
+                        None,
+                    );
+                    generate_templated_transaction(&template, &config)?
                 }
             } else {
                 println!("Error: Message type required for raw command");
@@ -39,15 +82,414 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         Some("broadcast") => {
-            if let Some(tx_base64) = args.get(2) {
-                broadcast_transaction(tx_base64)?
+            if let Some(tx) = args.get(2) {
+                broadcast_transaction(tx, &config)?
+            } else {
+                println!("Error: Signed transaction (base64 or hex) required for broadcast command");
+                print_usage();
+            }
+        }
+        Some("fund") => print_fund_command(&config)?,
+        Some("addressbook") => manage_address_book(&args[2..])?,
+        Some("template") => manage_templates(&args[2..], &config)?,
+        Some("history") => print_history(&args[2..], &config)?,
+        Some("convert-address") => convert_address(&args[2..])?,
+        Some("portfolio") => {
+            if let Some(path) = args.get(2) {
+                print_portfolio(path, &config)?
+            } else {
+                println!("Error: Portfolio config file required for portfolio command");
+                print_usage();
+            }
+        }
+        Some("check-health") => {
+            if let Some(path) = flag_value(&args, "--config")? {
+                print_network_health(path, &args, &config)?
+            } else {
+                println!("Error: --config <file> required for check-health command");
+                print_usage();
+            }
+        }
+        Some("position") => {
+            if let Some(address) = args.get(2) {
+                print_staking_position(address, &config)?
             } else {
-                println!("Error: Transaction base64 required for broadcast command");
+                println!("Error: Delegator address required for position command");
+                print_usage();
+            }
+        }
+        Some("analyze-gas") => {
+            if let Some(path) = args.get(2) {
+                analyze_gas(path)?
+            } else {
+                println!("Error: Transaction result JSON file required for analyze-gas command");
                 print_usage();
             }
         }
-        Some("fund") => print_fund_command()?,
         _ => print_usage(),
     }
     Ok(())
 }
+
+/// Parses `<message_type> [args...]`, printing the usage and returning `None` for an unsupported type
+fn parse_message_type(args: &[String]) -> Result<Option<MessageType>, Box<dyn std::error::Error>> {
+    let message_type = match args[0].as_str() {
+        "send" => MessageType::Send {
+            to_address: resolve_address_flag(args, "--to")?,
+        },
+        "delegate" => MessageType::Delegate {
+            validator_address: resolve_address_flag(args, "--validator")?,
+        },
+        "vote-weighted" => {
+            let proposal_id = args.get(1).ok_or("Proposal ID required for vote-weighted")?.parse()?;
+            let options = args[2..]
+                .iter()
+                .take_while(|arg| !arg.starts_with("--"))
+                .map(|arg| {
+                    arg.split_once('=')
+                        .map(|(option, weight)| (option.to_string(), weight.to_string()))
+                        .ok_or_else(|| format!("Invalid vote option '{}', expected <option>=<weight>", arg))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            MessageType::VoteWeighted { proposal_id, options }
+        }
+        "community-pool-spend" => {
+            let recipient = args.get(1).ok_or("Recipient required for community-pool-spend")?;
+            let amount = args.get(2).ok_or("Amount required for community-pool-spend")?.parse()?;
+            MessageType::CommunityPoolSpendProposal {
+                title: flag_value(args, "--title")?
+                    .ok_or("--title required for community-pool-spend")?
+                    .to_string(),
+                description: flag_value(args, "--description")?
+                    .ok_or("--description required for community-pool-spend")?
+                    .to_string(),
+                recipient: AddressBook::open()?.resolve(recipient)?,
+                amount,
+            }
+        }
+        "liquid-stake" => MessageType::LiquidStake {
+            host_denom: args.get(1).ok_or("Host denom required for liquid-stake")?.to_string(),
+        },
+        "liquid-unstake" => MessageType::LiquidUnstake {
+            host_zone: args.get(1).ok_or("Host zone required for liquid-unstake")?.to_string(),
+            receiver: resolve_address_flag(args, "--to")?,
+        },
+        other => {
+            println!(
+                "Error: Unsupported message type '{}'. Supported types: send, delegate, vote-weighted, \
+                 community-pool-spend, liquid-stake, liquid-unstake",
+                other
+            );
+            print_usage();
+            return Ok(None);
+        }
+    };
+    Ok(Some(message_type))
+}
+
+/// Returns the value following `flag`, if the flag is present
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(index) => args
+            .get(index + 1)
+            .map(|value| Some(value.as_str()))
+            .ok_or_else(|| format!("Value required after {}", flag)),
+        None => Ok(None),
+    }
+}
+
+/// Builds the HTTP client configuration, overriding the defaults with the timeout and keyfile flags
+fn client_config(args: &[String]) -> Result<CosmosClientConfig, Box<dyn std::error::Error>> {
+    let mut config = CosmosClientConfig::default();
+    if let Some(secs) = flag_value(args, "--connect-timeout")? {
+        config.connect_timeout_secs = secs.parse()?;
+    }
+    if let Some(secs) = flag_value(args, "--request-timeout")? {
+        config.request_timeout_secs = secs.parse()?;
+    }
+    config.keyfile = flag_value(args, "--keyfile")?.map(std::path::PathBuf::from);
+    Ok(config)
+}
+
+fn amount_flag(args: &[String]) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    Ok(flag_value(args, "--amount")?.map(str::parse::<u64>).transpose()?)
+}
+
+/// Returns the address following `flag`, resolving `@name` references via the address book
+fn resolve_address_flag(args: &[String], flag: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match flag_value(args, flag)? {
+        Some(value) => Ok(Some(AddressBook::open()?.resolve(value)?)),
+        None => Ok(None),
+    }
+}
+
+fn manage_templates(args: &[String], config: &CosmosClientConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = TemplateStore::open()?;
+    let memo = flag_value(args, "--memo")?.map(str::to_string);
+    match (args.first().map(|s| s.as_str()), args.get(1)) {
+        (Some("save"), Some(name)) if args.len() > 2 => {
+            if let Some(message_type) = parse_message_type(&args[2..])? {
+                let template = TransactionTemplate::new(message_type).with_overrides(amount_flag(args)?, memo, None);
+                store.insert(name, template)?;
+                store.save()?;
+                println!("Saved template {}", name);
+            }
+        }
+        (Some("load"), Some(name)) => {
+            let address = match resolve_address_flag(args, "--to")? {
+                Some(address) => Some(address),
+                None => resolve_address_flag(args, "--validator")?,
+            };
+            let template = store.get(name)?.with_overrides(amount_flag(args)?, memo, address);
+            generate_templated_transaction(&template, config)?
+        }
+        (Some("list"), None) => {
+            for (name, template) in store.list() {
+                println!("{}\t{}", name, serde_json::to_string(&template)?);
+            }
+        }
+        _ => {
+            println!("Error: Usage: template save <name> <message_type> [flags] | load <name> [flags] | list");
+            print_usage();
+        }
+    }
+    Ok(())
+}
+
+fn manage_address_book(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut book = AddressBook::open()?;
+    match (args.first().map(|s| s.as_str()), args.get(1), args.get(2)) {
+        (Some("add"), Some(name), Some(address)) => {
+            book.add(name, address)?;
+            book.save()?;
+            println!("Saved {} as @{}", address, name);
+        }
+        (Some("remove"), Some(name), None) => {
+            if book.remove(name) {
+                book.save()?;
+                println!("Removed @{}", name);
+            } else {
+                println!("No address named @{}", name);
+            }
+        }
+        (Some("list"), None, None) => {
+            for (name, address) in book.list() {
+                println!("@{}\t{}", name, address);
+            }
+        }
+        _ => {
+            println!("Error: Usage: addressbook add <name> <address> | remove <name> | list");
+            print_usage();
+        }
+    }
+    Ok(())
+}
+
+fn print_history(args: &[String], config: &CosmosClientConfig) -> Result<(), Box<dyn std::error::Error>> {
+    match (args.first().map(|s| s.as_str()), args.get(1)) {
+        (Some("commission"), Some(validator)) => {
+            let validator_address = AddressBook::open()?.resolve(validator)?;
+            let end_height = match flag_value(args, "--to")? {
+                Some(height) => height.parse()?,
+                None => latest_block_height(config)?,
+            };
+            let start_height = match flag_value(args, "--from")? {
+                Some(height) => height.parse()?,
+                None => end_height.saturating_sub(history::DEFAULT_HISTORY_BLOCKS - 1).max(1),
+            };
+            let changes = get_validator_commission_history(&validator_address, start_height, end_height, config)?;
+            if changes.is_empty() {
+                println!(
+                    "No commission change for {} between heights {} and {}",
+                    validator_address, start_height, end_height
+                );
+            }
+            for change in changes {
+                println!("{}\t{}\t{}", change.height, change.timestamp, change.new_rate);
+            }
+        }
+        _ => {
+            println!("Error: Usage: history commission <validator_address> [--from <height>] [--to <height>]");
+            print_usage();
+        }
+    }
+    Ok(())
+}
+
+/// Prints the holdings of every chain of the portfolio config file and their estimated USD value
+fn print_portfolio(path: &str, config: &CosmosClientConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let portfolio_config = PortfolioConfig::load(path)?;
+    let mut totals = std::collections::BTreeMap::new();
+    for result in get_portfolio(&portfolio_config, config) {
+        let chain = match result {
+            Ok(chain) => chain,
+            Err(e) => {
+                println!("Error: {}", e);
+                continue;
+            }
+        };
+        println!("{} ({})", chain.chain_id, chain.address);
+        for (label, amounts) in [
+            ("balance", &chain.balances),
+            ("staked", &chain.staked),
+            ("rewards", &chain.rewards),
+        ] {
+            let mut amounts: Vec<_> = amounts.iter().collect();
+            amounts.sort();
+            for (denom, amount) in amounts {
+                println!("  {:<8} {:>20} {}", label, amount, denom);
+            }
+        }
+        for (denom, amount) in chain.totals() {
+            *totals.entry(denom).or_insert(0u64) += amount;
+        }
+    }
+
+    let (value, unpriced) = estimate_usd_value(&totals);
+    println!("\nTotal:");
+    for (denom, amount) in &totals {
+        println!("  {:>20} {}", amount, denom);
+    }
+    println!("Estimated value: ${:.2}", value);
+    if !unpriced.is_empty() {
+        println!("Not valued, no known price: {}", unpriced.join(", "));
+    }
+    Ok(())
+}
+
+/// Prints a table of the health of the nodes of the health check config file, flagging the nodes
+/// lagging behind the median height
+fn print_network_health(
+    path: &str,
+    args: &[String],
+    config: &CosmosClientConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut health_config = HealthCheckConfig::load(path)?;
+    if let Some(blocks) = flag_value(args, "--max-blocks-behind")? {
+        health_config.max_blocks_behind = blocks.parse()?;
+    }
+    let nodes = check_network_health(&health_config, config);
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    println!(
+        "{:<56} {:>7} {:>12} {:>10} {:>12} {:<20} {:>8} {:>8}",
+        "endpoint", "healthy", "height", "version", "app version", "chain id", "catching", "ms"
+    );
+    for node in &nodes {
+        println!(
+            "{:<56} {:>7} {:>12} {:>10} {:>12} {:<20} {:>8} {:>8}{}",
+            node.rpc_url,
+            node.is_healthy,
+            optional(node.latest_block_height.map(|height| height.to_string())),
+            optional(node.node_version.clone()),
+            optional(node.app_version.clone()),
+            optional(node.chain_id.clone()),
+            optional(node.catching_up.map(|catching_up| catching_up.to_string())),
+            node.response_time_ms,
+            if node.lagging { "  LAGGING" } else { "" }
+        );
+    }
+    if let Some(median) = health::median_height(&nodes) {
+        println!("\nMedian height: {}", median);
+    }
+    for node in nodes.iter().filter(|node| node.lagging) {
+        println!(
+            "Warning: {} is more than {} blocks behind the median height",
+            node.rpc_url, health_config.max_blocks_behind
+        );
+    }
+    for node in &nodes {
+        for error in &node.errors {
+            println!("Error: {}: {}", node.rpc_url, error);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the staking position of the delegator `address`, which can be a @name reference
+fn print_staking_position(address: &str, config: &CosmosClientConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let address = AddressBook::open()?.resolve(address)?;
+    let summary = get_staking_position_summary(&address, config)?;
+    println!("Delegations of {}:", address);
+    for delegation in &summary.delegations {
+        println!(
+            "  {} {:>20} uatom {:>14} uatom rewards",
+            delegation.validator_address, delegation.amount_uatom, delegation.rewards_uatom
+        );
+    }
+    if !summary.unbonding.is_empty() {
+        println!("Unbonding:");
+    }
+    for unbonding in &summary.unbonding {
+        println!(
+            "  {} {:>20} uatom, height {}, completes at {}",
+            unbonding.validator_address,
+            unbonding.balance_uatom,
+            unbonding.creation_height,
+            unbonding.completion_time_secs
+        );
+    }
+    println!("\nTotal staked:    {:>20} uatom", summary.total_staked_uatom);
+    println!("Total unbonding: {:>20} uatom", summary.total_unbonding_uatom);
+    println!("Total rewards:   {:>20} uatom", summary.total_rewards_uatom);
+    Ok(())
+}
+
+/// Analyzes a transaction result, records its gas used in the gas history, and compares the
+/// fallback estimates with the limits suggested by the history
+fn analyze_gas(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let tx_result_json = std::fs::read_to_string(path)?;
+    analyze_gas_usage_from_result(&tx_result_json)?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let mut history = GasHistory::open()?;
+    match gas_sample_from_result(&serde_json::from_str(&tx_result_json)?) {
+        Some((message_type, gas_used)) => {
+            history.record(&message_type, gas_used, now);
+            history.save()?;
+        }
+        None => println!("Not recorded in the gas history: only successful single message transactions are"),
+    }
+
+    println!("\nGas used over the last 7 days:");
+    println!(
+        "{:<48} {:>7} {:>10} {:>10} {:>10} {:>10}",
+        "message type", "samples", "average", "p95", "suggested", "fallback"
+    );
+    for stats in history.stats(now) {
+        println!(
+            "{:<48} {:>7} {:>10.0} {:>10} {:>10} {:>10}",
+            stats.message_type,
+            stats.samples,
+            stats.average,
+            stats.p95,
+            stats.suggested_limit,
+            fallback_gas_for_message_type(&stats.message_type)
+        );
+    }
+    Ok(())
+}
+
+fn convert_address(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let prefix = flag_value(args, "--to-prefix")?;
+    match (
+        flag_value(args, "--from")?,
+        flag_value(args, "--from-hex")?,
+        flag_value(args, "--to-hex")?,
+        prefix,
+    ) {
+        (Some(address), None, None, Some(prefix)) => println!("{}", convert_address_prefix(address, prefix)?),
+        (None, Some(hex_address), None, Some(prefix)) => println!("{}", hex_to_bech32(hex_address, prefix)?),
+        (None, None, Some(address), None) => println!("{}", bech32_to_hex(address)?),
+        _ => {
+            println!(
+                "Error: Usage: convert-address --from <address> --to-prefix <hrp> | --from-hex <hex> --to-prefix <hrp> \
+                 | --to-hex <address>"
+            );
+            print_usage();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,294 @@
+//! Balances, staking positions and rewards of accounts on several chains, queried in parallel.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use cosmos_sdk_proto::cosmos::{
+    distribution::v1beta1::{QueryDelegationTotalRewardsRequest, QueryDelegationTotalRewardsResponse},
+    staking::v1beta1::{QueryParamsRequest, QueryParamsResponse},
+};
+use ic_cosmos::explorer::{build_explorer_url, ExplorerConfig, ExplorerResource};
+use ic_cosmos::types::{
+    decode_stride_host_zone_response, encode_stride_host_zone_query, StrideHostZone, STRIDE_HOST_ZONE_PATH,
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::{abci_query, fees::usd_price, get_all_balances, get_total_staked, CosmosClientConfig, CosmosError};
+
+/// Number of decimals of the `Dec` amounts of the rewards
+const DEC_COIN_PRECISION: u32 = 18;
+
+/// An account on a chain, as listed in the portfolio config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosConfig {
+    pub chain_id: String,
+    /// CometBFT RPC endpoint of a node of the chain
+    pub rpc_url: String,
+    /// Account address, using the `hrp` of the chain
+    pub address: String,
+    /// Bech32 prefix of the addresses of the chain, e.g. "osmo"
+    pub hrp: String,
+    /// Explorer of the links printed for the chain, the known explorer of `chain_id` when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer: Option<ExplorerConfig>,
+}
+
+impl CosmosConfig {
+    /// Returns the client config of the node of this chain, with the timeouts and limits of `base`
+    pub fn client_config(&self, base: &CosmosClientConfig) -> CosmosClientConfig {
+        CosmosClientConfig {
+            rpc_url: self.rpc_url.clone(),
+            ..base.clone()
+        }
+    }
+
+    /// Returns the URL of `resource` on the explorer of this chain
+    pub fn explorer_url(&self, resource: ExplorerResource) -> Option<String> {
+        match &self.explorer {
+            Some(explorer) => Some(explorer.url(&resource)),
+            None => build_explorer_url(&self.chain_id, resource),
+        }
+    }
+}
+
+/// Contents of a portfolio config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortfolioConfig {
+    pub chains: Vec<CosmosConfig>,
+}
+
+impl PortfolioConfig {
+    /// Reads a JSON config file, e.g. `{ "chains": [{ "chain_id": "osmosis-1", "rpc_url": "...",
+    /// "address": "osmo1...", "hrp": "osmo" }] }`, checking every address against its prefix
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let config: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        for chain in &config.chains {
+            let (hrp, _, _) = bech32::decode(&chain.address)?;
+            if hrp != chain.hrp {
+                return Err(format!(
+                    "Address {} of chain {} does not use the prefix {}",
+                    chain.address, chain.chain_id, chain.hrp
+                )
+                .into());
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Holdings of an account on one chain, amounts in base units keyed by denom
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainPortfolio {
+    pub chain_id: String,
+    pub address: String,
+    pub balances: HashMap<String, u64>,
+    /// Tokens delegated to validators, in the bond denom of the chain
+    pub staked: HashMap<String, u64>,
+    /// Unclaimed staking rewards, truncated to whole base units
+    pub rewards: HashMap<String, u64>,
+}
+
+impl ChainPortfolio {
+    /// Returns the balances, staked tokens and rewards summed by denom
+    pub fn totals(&self) -> BTreeMap<String, u64> {
+        let mut totals = BTreeMap::new();
+        for (denom, amount) in self.balances.iter().chain(&self.staked).chain(&self.rewards) {
+            *totals.entry(denom.clone()).or_default() += amount;
+        }
+        totals
+    }
+}
+
+/// Returns the USD value of `amounts` at the placeholder prices of [`usd_price`],
+/// and the denoms without a known price, which are left out of the value
+pub fn estimate_usd_value<'a>(amounts: impl IntoIterator<Item = (&'a String, &'a u64)>) -> (f64, Vec<String>) {
+    let mut value = 0.0;
+    let mut unpriced = Vec::new();
+    for (denom, amount) in amounts {
+        match usd_price(denom) {
+            Some(price) => value += price * *amount as f64,
+            None if *amount > 0 => unpriced.push(denom.clone()),
+            None => {}
+        }
+    }
+    (value, unpriced)
+}
+
+/// Queries the holdings of every chain of `config` in parallel, one thread per chain.
+/// The results are in the order of the config, a failed chain does not fail the others.
+pub fn get_portfolio(config: &PortfolioConfig, base: &CosmosClientConfig) -> Vec<Result<ChainPortfolio, String>> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = config
+            .chains
+            .iter()
+            .map(|chain| {
+                scope.spawn(move || {
+                    get_chain_portfolio(chain, &chain.client_config(base))
+                        .map_err(|e| format!("{}: {}", chain.chain_id, e))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err("Portfolio query panicked".to_string()))
+            })
+            .collect()
+    })
+}
+
+/// Queries the holdings of the account of `chain` from the node of `config`
+pub fn get_chain_portfolio(
+    chain: &CosmosConfig,
+    config: &CosmosClientConfig,
+) -> Result<ChainPortfolio, Box<dyn Error>> {
+    let balances = get_all_balances(&chain.address, config)?;
+    let staked = get_total_staked(&chain.address, config)?;
+    let staked = if staked > 0 {
+        HashMap::from([(get_bond_denom(config)?, staked)])
+    } else {
+        HashMap::new()
+    };
+    Ok(ChainPortfolio {
+        chain_id: chain.chain_id.clone(),
+        address: chain.address.clone(),
+        balances,
+        staked,
+        rewards: get_delegation_rewards(&chain.address, config)?,
+    })
+}
+
+/// Returns the unclaimed staking rewards of `address` over all its delegations, keyed by denom
+pub fn get_delegation_rewards(
+    address: &str,
+    config: &CosmosClientConfig,
+) -> Result<HashMap<String, u64>, Box<dyn Error>> {
+    let request = QueryDelegationTotalRewardsRequest {
+        delegator_address: address.to_string(),
+    };
+    let value = abci_query(
+        "/cosmos.distribution.v1beta1.Query/DelegationTotalRewards",
+        &request.encode_to_vec(),
+        config,
+    )?;
+    let response = QueryDelegationTotalRewardsResponse::decode(&value[..])?;
+    let mut rewards = HashMap::new();
+    for coin in response.total {
+        rewards.insert(coin.denom, dec_coin_amount(&coin.amount)?);
+    }
+    Ok(rewards)
+}
+
+/// Returns the denom of the tokens staked on the chain of the node
+pub fn get_bond_denom(config: &CosmosClientConfig) -> Result<String, CosmosError> {
+    let value = abci_query(
+        "/cosmos.staking.v1beta1.Query/Params",
+        &QueryParamsRequest {}.encode_to_vec(),
+        config,
+    )?;
+    let params = QueryParamsResponse::decode(&value[..])?
+        .params
+        .ok_or_else(|| CosmosError::DecodingError("Missing staking params".to_string()))?;
+    Ok(params.bond_denom)
+}
+
+/// Returns the Stride host zone of `host_zone`, e.g. "cosmoshub-4", from the Stride node of `config`
+pub fn get_stride_host_zone(config: &CosmosClientConfig, host_zone: &str) -> Result<StrideHostZone, CosmosError> {
+    let data = hex::decode(encode_stride_host_zone_query(host_zone))?;
+    let value = abci_query(STRIDE_HOST_ZONE_PATH, &data, config)?;
+    decode_stride_host_zone_response(&STANDARD.encode(value)).map_err(CosmosError::DecodingError)
+}
+
+/// Returns the host tokens per stToken of `host_zone`, to value stToken holdings in the staked
+/// tokens, e.g. the uatom of stATOM for "cosmoshub-4"
+pub fn get_stride_redemption_rate(config: &CosmosClientConfig, host_zone: &str) -> Result<f64, CosmosError> {
+    let zone = get_stride_host_zone(config, host_zone)?;
+    if zone.halted {
+        return Err(CosmosError::HostZoneHalted(host_zone.to_string()));
+    }
+    Ok(zone.redemption_rate.parse()?)
+}
+
+/// Converts the atomics of a `DecCoin` amount to whole base units, truncating the fraction
+pub(crate) fn dec_coin_amount(atomics: &str) -> Result<u64, Box<dyn Error>> {
+    let amount = atomics.parse::<u128>()? / 10u128.pow(DEC_COIN_PRECISION);
+    Ok(u64::try_from(amount)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ic_cosmos::explorer::Explorer;
+
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    #[test]
+    fn test_load_portfolio_config() {
+        let (_dir, path) = temp_file_path("portfolio.json");
+        let chain = CosmosConfig {
+            chain_id: "cosmoshub-4".to_string(),
+            rpc_url: "https://cosmos-rpc.publicnode.com".to_string(),
+            address: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            hrp: "cosmos".to_string(),
+            explorer: None,
+        };
+        let config = PortfolioConfig {
+            chains: vec![chain.clone()],
+        };
+        fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(PortfolioConfig::load(&path).unwrap(), config);
+
+        let mismatched_prefix = PortfolioConfig {
+            chains: vec![CosmosConfig {
+                hrp: "osmo".to_string(),
+                ..chain.clone()
+            }],
+        };
+        fs::write(&path, serde_json::to_string(&mismatched_prefix).unwrap()).unwrap();
+        assert!(PortfolioConfig::load(&path).is_err());
+
+        let client_config = chain.client_config(&CosmosClientConfig::default());
+        assert_eq!(client_config.rpc_url, chain.rpc_url);
+        assert_eq!(
+            client_config.request_timeout_secs,
+            CosmosClientConfig::default().request_timeout_secs
+        );
+
+        assert_eq!(
+            chain.explorer_url(ExplorerResource::Block(1)).as_deref(),
+            Some("https://www.mintscan.io/cosmos/block/1")
+        );
+        let ping_pub = CosmosConfig {
+            explorer: Some(ExplorerConfig::new(Explorer::PingPub, "cosmoshub")),
+            ..chain.clone()
+        };
+        assert_eq!(
+            ping_pub.explorer_url(ExplorerResource::Block(1)).as_deref(),
+            Some("https://ping.pub/cosmoshub/block/1")
+        );
+    }
+
+    #[test]
+    fn test_portfolio_value() {
+        let portfolio = ChainPortfolio {
+            balances: HashMap::from([("uatom".to_string(), 1_000_000), ("ibc/27394FB0".to_string(), 5)]),
+            staked: HashMap::from([("uatom".to_string(), 2_000_000)]),
+            rewards: HashMap::from([("uatom".to_string(), 1_234)]),
+            ..Default::default()
+        };
+        let totals = portfolio.totals();
+        assert_eq!(totals["uatom"], 3_001_234);
+
+        let (value, unpriced) = estimate_usd_value(&totals);
+        assert!((value - 15.00617).abs() < 1e-9);
+        assert_eq!(unpriced, vec!["ibc/27394FB0".to_string()]);
+
+        assert_eq!(dec_coin_amount("1234567890000000000000").unwrap(), 1_234);
+    }
+}
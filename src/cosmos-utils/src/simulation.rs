@@ -0,0 +1,93 @@
+//! Typed errors of failed transaction simulations, recognized from the `log` of the response.
+
+/// Reason a transaction simulation failed, with the `log` of the response
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SimulationError {
+    /// The gas limit of the simulated transaction is too low
+    #[error("Simulation ran out of gas: {0}")]
+    OutOfGas(String),
+    /// The fee is below the minimum gas prices of the node
+    #[error("Insufficient fee: {0}")]
+    InsufficientFee(String),
+    /// The signer has no account on chain yet
+    #[error("Account not found: {0}")]
+    AccountNotFound(String),
+    /// Usually a wrong account number, sequence or chain id in the sign doc
+    #[error("Signature verification failed: {0}")]
+    SignatureVerificationFailed(String),
+    /// A message or one of its fields is invalid, e.g. a zero amount
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Simulation failed (code {code}): {log}")]
+    Other { code: u32, log: String },
+}
+
+/// Returns the error of a simulation that failed with ABCI `code`, from the patterns of the
+/// Cosmos SDK errors found in its `log`, e.g.
+/// `"invalid request: invalid amount: expect 1, got 0: invalid request"`
+pub fn parse_simulation_error(log: &str, code: u32) -> SimulationError {
+    let lowercase_log = log.to_lowercase();
+    let log = log.to_string();
+    // The generic "invalid request" is checked last, it also ends more specific errors
+    if lowercase_log.contains("out of gas") {
+        SimulationError::OutOfGas(log)
+    } else if lowercase_log.contains("insufficient fee") {
+        SimulationError::InsufficientFee(log)
+    } else if lowercase_log.contains("signature verification failed") {
+        SimulationError::SignatureVerificationFailed(log)
+    } else if lowercase_log.contains("account not found")
+        || (lowercase_log.contains("account") && lowercase_log.contains("not found"))
+    {
+        SimulationError::AccountNotFound(log)
+    } else if lowercase_log.contains("invalid request") {
+        SimulationError::InvalidRequest(log)
+    } else {
+        SimulationError::Other { code, log }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simulation_error() {
+        let cases = [
+            (
+                "out of gas in location: ReadFlat; gasWanted: 1000, gasUsed: 1240: out of gas",
+                11,
+                SimulationError::OutOfGas as fn(String) -> SimulationError,
+            ),
+            (
+                "insufficient fees; got: 1uatom required: 5000uatom: insufficient fee",
+                13,
+                SimulationError::InsufficientFee,
+            ),
+            (
+                "account cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu not found: key not found",
+                22,
+                SimulationError::AccountNotFound,
+            ),
+            (
+                "signature verification failed; please verify account number (12): unauthorized",
+                4,
+                SimulationError::SignatureVerificationFailed,
+            ),
+            (
+                "invalid request: invalid amount: expect 1, got 0: invalid request",
+                18,
+                SimulationError::InvalidRequest,
+            ),
+        ];
+        for (log, code, expected) in cases {
+            assert_eq!(parse_simulation_error(log, code), expected(log.to_string()));
+        }
+        assert_eq!(
+            parse_simulation_error("failed to execute message", 5),
+            SimulationError::Other {
+                code: 5,
+                log: "failed to execute message".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Summary of the staking position of a delegator: delegations, unbondings and pending rewards.
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use cosmos_sdk_proto::cosmos::{
+    base::query::v1beta1::PageRequest,
+    distribution::v1beta1::{QueryDelegationTotalRewardsRequest, QueryDelegationTotalRewardsResponse},
+    staking::v1beta1::{QueryDelegatorUnbondingDelegationsRequest, QueryDelegatorUnbondingDelegationsResponse},
+};
+use ic_cosmos::types::{encode_bulk_withdraw_rewards, CosmosCoin, CosmosMessage, CosmosTransaction, SignMode};
+use prost::Message;
+use serde_json::json;
+
+use crate::{
+    abci_query, calculate_fee_for_gas, fees::node_gas_prices, gas_estimates::GasEstimateTable, get_account_info,
+    get_delegations, history::rpc_result, portfolio::dec_coin_amount, CosmosClientConfig, DEFAULT_GAS_PRICES,
+};
+
+/// Denom of the staked tokens and of the rewards counted in the summary
+const STAKING_DENOM: &str = "uatom";
+
+/// A delegation to one validator
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DelegationInfo {
+    pub validator_address: String,
+    /// Delegation shares, as `Dec` atomics
+    pub shares: String,
+    /// Tokens the shares are worth at the exchange rate of the validator
+    pub amount_uatom: u64,
+    /// Pending rewards of the delegation, truncated to whole uatom
+    pub rewards_uatom: u64,
+}
+
+/// An unbonding entry, returning its balance to the delegator at `completion_time_secs`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnbondingInfo {
+    pub validator_address: String,
+    pub creation_height: i64,
+    /// Unix time in seconds at which the unbonding completes
+    pub completion_time_secs: i64,
+    /// Tokens still unbonding, less any slashing since the unbonding started
+    pub balance_uatom: u64,
+}
+
+/// Staking position of a delegator, with its totals
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StakingPositionSummary {
+    pub delegations: Vec<DelegationInfo>,
+    pub unbonding: Vec<UnbondingInfo>,
+    pub total_staked_uatom: u64,
+    pub total_unbonding_uatom: u64,
+    pub total_rewards_uatom: u64,
+}
+
+impl StakingPositionSummary {
+    /// Builds the summary of `delegations` and `unbonding`, summing their amounts
+    pub fn new(delegations: Vec<DelegationInfo>, unbonding: Vec<UnbondingInfo>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            total_staked_uatom: sum_amounts(delegations.iter().map(|d| d.amount_uatom))?,
+            total_unbonding_uatom: sum_amounts(unbonding.iter().map(|u| u.balance_uatom))?,
+            total_rewards_uatom: sum_amounts(delegations.iter().map(|d| d.rewards_uatom))?,
+            delegations,
+            unbonding,
+        })
+    }
+}
+
+/// Sums uatom amounts, failing on overflow
+fn sum_amounts(amounts: impl Iterator<Item = u64>) -> Result<u64, Box<dyn Error>> {
+    let mut total = 0u64;
+    for amount in amounts {
+        total = total.checked_add(amount).ok_or("Staking amount overflow")?;
+    }
+    Ok(total)
+}
+
+/// Returns the delegations, unbonding delegations and pending rewards of `address` with their totals
+pub fn get_staking_position_summary(
+    address: &str,
+    config: &CosmosClientConfig,
+) -> Result<StakingPositionSummary, Box<dyn Error>> {
+    let mut rewards = get_rewards_by_validator(address, config)?;
+    let delegations = get_delegations(address, config)?
+        .into_iter()
+        .map(|(delegation, staked)| {
+            Ok(DelegationInfo {
+                rewards_uatom: rewards.remove(&delegation.validator_address).unwrap_or(0),
+                validator_address: delegation.validator_address,
+                shares: delegation.shares,
+                amount_uatom: u64::try_from(staked)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    StakingPositionSummary::new(delegations, get_unbonding_delegations(address, config)?)
+}
+
+/// Returns a transaction withdrawing the rewards of `delegator` from each of `validators` and
+/// delegating them, less the fee of the transaction, to the validator among them with the highest
+/// APR. The APR of a validator is compared by its pending rewards per staked token, which accrue
+/// at the rate of its APR since the rewards were last withdrawn.
+///
+/// The fee is paid at the minimum gas price of the node, for the gas estimated by the default
+/// [`GasEstimateTable`]. Fails if the rewards do not cover it.
+pub fn generate_compound_transaction(
+    delegator: &str,
+    validators: &[&str],
+    rpc_url: &str,
+) -> Result<CosmosTransaction, Box<dyn Error>> {
+    let config = CosmosClientConfig {
+        rpc_url: rpc_url.to_string(),
+        ..Default::default()
+    };
+    let mut rewards = get_rewards_by_validator(delegator, &config)?;
+    let delegations = get_delegations(delegator, &config)?
+        .into_iter()
+        .filter(|(delegation, _)| validators.contains(&delegation.validator_address.as_str()))
+        .map(|(delegation, staked)| {
+            Ok(DelegationInfo {
+                rewards_uatom: rewards.remove(&delegation.validator_address).unwrap_or(0),
+                validator_address: delegation.validator_address,
+                shares: delegation.shares,
+                amount_uatom: u64::try_from(staked)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    if let Some(missing) = validators
+        .iter()
+        .find(|validator| !delegations.iter().any(|d| d.validator_address == **validator))
+    {
+        return Err(format!("{} has no delegation to {}", delegator, missing).into());
+    }
+
+    let status = rpc_result("status", json!({}), &config)?;
+    let chain_id = status["node_info"]["network"]
+        .as_str()
+        .ok_or("Missing network in status")?;
+    let (account_number, sequence) = get_account_info(delegator, None, &config)?;
+
+    let mut transaction = build_compound_transaction(delegator, &delegations, staking_gas_price(&config)?)?;
+    transaction.chain_id = chain_id.to_string();
+    transaction.account_number = account_number;
+    transaction.sequence = sequence;
+    Ok(transaction)
+}
+
+/// Builds the compound transaction of `delegations`, see [`generate_compound_transaction`], with
+/// the fee paid at `gas_price` uatom. The chain ID, account number and sequence are left to the caller.
+pub fn build_compound_transaction(
+    delegator: &str,
+    delegations: &[DelegationInfo],
+    gas_price: f64,
+) -> Result<CosmosTransaction, Box<dyn Error>> {
+    // Highest rewards per staked token, compared as `a.rewards / a.amount > b.rewards / b.amount`
+    let target = delegations
+        .iter()
+        .filter(|delegation| delegation.amount_uatom > 0)
+        .max_by(|a, b| {
+            let a_rate = a.rewards_uatom as u128 * b.amount_uatom as u128;
+            let b_rate = b.rewards_uatom as u128 * a.amount_uatom as u128;
+            a_rate.cmp(&b_rate)
+        })
+        .ok_or("No delegation to compound")?;
+
+    let validators: Vec<&str> = delegations.iter().map(|d| d.validator_address.as_str()).collect();
+    let mut messages = encode_bulk_withdraw_rewards(delegator, &validators)?;
+    // The amount is set once the fee is known, it does not change the estimated gas
+    messages.push(delegate_message(delegator, &target.validator_address, 0));
+
+    let gas_limit = GasEstimateTable::default().gas_for_messages(&messages);
+    let fee = calculate_fee_for_gas(gas_limit, gas_price);
+    let total_rewards = sum_amounts(delegations.iter().map(|d| d.rewards_uatom))?;
+    if total_rewards <= fee {
+        return Err(format!(
+            "Rewards of {} {} do not cover the fee of {} {}",
+            total_rewards, STAKING_DENOM, fee, STAKING_DENOM
+        )
+        .into());
+    }
+    *messages.last_mut().expect("delegate message") =
+        delegate_message(delegator, &target.validator_address, total_rewards - fee);
+
+    Ok(CosmosTransaction {
+        messages,
+        fee: vec![CosmosCoin::new(STAKING_DENOM, fee.to_string())],
+        gas_limit,
+        memo: String::new(),
+        chain_id: String::new(),
+        account_number: 0,
+        sequence: 0,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    })
+}
+
+fn delegate_message(delegator: &str, validator: &str, amount: u64) -> CosmosMessage {
+    CosmosMessage {
+        type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+        value: json!({
+            "delegator_address": delegator,
+            "validator_address": validator,
+            "amount": { "denom": STAKING_DENOM, "amount": amount.to_string() }
+        }),
+    }
+}
+
+/// Returns the minimum uatom gas price of the node, or the default gas price if it accepts
+/// transactions without fees
+fn staking_gas_price(config: &CosmosClientConfig) -> Result<f64, Box<dyn Error>> {
+    let gas_prices = node_gas_prices(config)?;
+    let default_price = DEFAULT_GAS_PRICES
+        .iter()
+        .find(|(denom, _)| *denom == STAKING_DENOM)
+        .map(|(_, price)| *price)
+        .unwrap_or_default();
+    Ok(gas_prices
+        .into_iter()
+        .find(|price| price.denom == STAKING_DENOM)
+        .map_or(default_price, |price| price.price))
+}
+
+/// Returns the unbonding entries of `address` over all validators
+pub fn get_unbonding_delegations(
+    address: &str,
+    config: &CosmosClientConfig,
+) -> Result<Vec<UnbondingInfo>, Box<dyn Error>> {
+    let mut unbonding = Vec::new();
+    let mut key = Vec::new();
+    loop {
+        let request = QueryDelegatorUnbondingDelegationsRequest {
+            delegator_addr: address.to_string(),
+            pagination: (!key.is_empty()).then(|| PageRequest {
+                key: key.clone(),
+                ..Default::default()
+            }),
+        };
+        let value = abci_query(
+            "/cosmos.staking.v1beta1.Query/DelegatorUnbondingDelegations",
+            &request.encode_to_vec(),
+            config,
+        )?;
+        let response = QueryDelegatorUnbondingDelegationsResponse::decode(&value[..])?;
+        for delegation in response.unbonding_responses {
+            for entry in delegation.entries {
+                unbonding.push(UnbondingInfo {
+                    validator_address: delegation.validator_address.clone(),
+                    creation_height: entry.creation_height,
+                    completion_time_secs: entry.completion_time.map(|time| time.seconds).unwrap_or(0),
+                    balance_uatom: entry.balance.parse()?,
+                });
+            }
+        }
+        match response.pagination {
+            Some(page) if !page.next_key.is_empty() => key = page.next_key,
+            _ => break,
+        }
+    }
+    Ok(unbonding)
+}
+
+/// Returns the pending uatom rewards of `address` keyed by validator
+fn get_rewards_by_validator(
+    address: &str,
+    config: &CosmosClientConfig,
+) -> Result<HashMap<String, u64>, Box<dyn Error>> {
+    let request = QueryDelegationTotalRewardsRequest {
+        delegator_address: address.to_string(),
+    };
+    let value = abci_query(
+        "/cosmos.distribution.v1beta1.Query/DelegationTotalRewards",
+        &request.encode_to_vec(),
+        config,
+    )?;
+    let response = QueryDelegationTotalRewardsResponse::decode(&value[..])?;
+    let mut rewards = HashMap::new();
+    for delegation in response.rewards {
+        let mut amount = 0;
+        for coin in delegation.reward.iter().filter(|coin| coin.denom == STAKING_DENOM) {
+            amount += dec_coin_amount(&coin.amount)?;
+        }
+        rewards.insert(delegation.validator_address, amount);
+    }
+    Ok(rewards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staking_position_summary() {
+        let delegation = |validator_address: &str, amount_uatom, rewards_uatom| DelegationInfo {
+            validator_address: validator_address.to_string(),
+            shares: format!("{}000000000000000000", amount_uatom),
+            amount_uatom,
+            rewards_uatom,
+        };
+        let unbonding = |balance_uatom| UnbondingInfo {
+            validator_address: "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6".to_string(),
+            creation_height: 1_200_000,
+            completion_time_secs: 1_767_225_600,
+            balance_uatom,
+        };
+        let summary = StakingPositionSummary::new(
+            vec![
+                delegation("cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6", 2_000_000, 1_234),
+                delegation("cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0", 500_000, 66),
+            ],
+            vec![unbonding(300_000), unbonding(25_000)],
+        )
+        .unwrap();
+        assert_eq!(summary.total_staked_uatom, 2_500_000);
+        assert_eq!(summary.total_unbonding_uatom, 325_000);
+        assert_eq!(summary.total_rewards_uatom, 1_300);
+        assert_eq!(summary.delegations.len(), 2);
+
+        assert_eq!(
+            StakingPositionSummary::new(Vec::new(), Vec::new()).unwrap(),
+            StakingPositionSummary::default()
+        );
+        assert!(StakingPositionSummary::new(Vec::new(), vec![unbonding(u64::MAX), unbonding(1)]).is_err());
+    }
+
+    #[test]
+    fn test_build_compound_transaction() {
+        const DELEGATOR: &str = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+        const LOW_APR: &str = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+        const HIGH_APR: &str = "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0";
+        let delegation = |validator_address: &str, amount_uatom, rewards_uatom| DelegationInfo {
+            validator_address: validator_address.to_string(),
+            shares: format!("{}000000000000000000", amount_uatom),
+            amount_uatom,
+            rewards_uatom,
+        };
+        // 500 uatom per 1 ATOM staked against 600 per 1 ATOM
+        let delegations = [
+            delegation(LOW_APR, 10_000_000, 5_000),
+            delegation(HIGH_APR, 1_000_000, 600),
+        ];
+
+        let transaction = build_compound_transaction(DELEGATOR, &delegations, 0.01).unwrap();
+        let type_urls: Vec<_> = transaction.messages.iter().map(|m| m.type_url.as_str()).collect();
+        assert_eq!(
+            type_urls,
+            vec![
+                "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+                "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+                "/cosmos.staking.v1beta1.MsgDelegate",
+            ]
+        );
+        // 2 withdrawals (102_000) and a delegation (352_000) at 0.01 uatom
+        assert_eq!(transaction.gas_limit, 454_000);
+        assert_eq!(transaction.fee, vec![CosmosCoin::new("uatom", "4540")]);
+        let delegate = &transaction.messages[2].value;
+        assert_eq!(delegate["validator_address"], HIGH_APR);
+        assert_eq!(delegate["amount"]["amount"], "1060");
+
+        let error = build_compound_transaction(DELEGATOR, &delegations, 0.02).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Rewards of 5600 uatom do not cover the fee of 9080 uatom"
+        );
+        assert!(build_compound_transaction(DELEGATOR, &[], 0.01).is_err());
+    }
+}
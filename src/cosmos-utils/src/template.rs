@@ -0,0 +1,151 @@
+//! Reusable transaction configurations persisted to `~/.cosmos-utils/templates.json`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    json_store::{validate_name, JsonStore, JsonStoreError},
+    MessageType,
+};
+
+/// Errors returned by the template store
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("No template named '{0}'")]
+    UnknownTemplate(String),
+    #[error("Templates: {0}")]
+    Store(#[from] JsonStoreError),
+}
+
+/// A saved transaction configuration, e.g. a weekly delegation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionTemplate {
+    pub message_type: MessageType,
+    /// Amount in uatom, [`crate::DEFAULT_AMOUNT`] when not set.
+    /// The initial deposit of a proposal, [`crate::DEFAULT_PROPOSAL_DEPOSIT`] when not set.
+    pub base_amount: Option<u64>,
+    /// Transaction memo, a per-message default when not set
+    pub memo: Option<String>,
+}
+
+impl TransactionTemplate {
+    pub fn new(message_type: MessageType) -> Self {
+        Self {
+            message_type,
+            base_amount: None,
+            memo: None,
+        }
+    }
+
+    /// Replaces the fields given on the command line. `address` overrides the recipient
+    /// of a send, community pool spend or liquid unstake, or the validator of a delegation.
+    pub fn with_overrides(mut self, base_amount: Option<u64>, memo: Option<String>, address: Option<String>) -> Self {
+        if base_amount.is_some() {
+            self.base_amount = base_amount;
+        }
+        if memo.is_some() {
+            self.memo = memo;
+        }
+        if address.is_some() {
+            match &mut self.message_type {
+                MessageType::Send { to_address } => *to_address = address,
+                MessageType::Delegate { validator_address } => *validator_address = address,
+                MessageType::CommunityPoolSpendProposal { recipient, .. } => *recipient = address.unwrap_or_default(),
+                MessageType::LiquidUnstake { receiver, .. } => *receiver = address,
+                MessageType::VoteWeighted { .. } | MessageType::LiquidStake { .. } => {}
+            }
+        }
+        self
+    }
+}
+
+/// Named transaction templates backed by a JSON file
+#[derive(Debug)]
+pub struct TemplateStore {
+    store: JsonStore<BTreeMap<String, TransactionTemplate>>,
+}
+
+impl TemplateStore {
+    /// Opens the templates at `~/.cosmos-utils/templates.json`, empty if the file does not exist yet
+    pub fn open() -> Result<Self, TemplateError> {
+        Ok(Self {
+            store: JsonStore::open("templates.json")?,
+        })
+    }
+
+    /// Opens the templates stored at `path`, empty if the file does not exist yet
+    pub fn open_at(path: impl Into<PathBuf>) -> Result<Self, TemplateError> {
+        Ok(Self {
+            store: JsonStore::open_at(path)?,
+        })
+    }
+
+    /// Writes the templates back to their file
+    pub fn save(&self) -> Result<(), TemplateError> {
+        Ok(self.store.save()?)
+    }
+
+    /// Adds or replaces the template saved under `name`
+    pub fn insert(&mut self, name: &str, template: TransactionTemplate) -> Result<(), TemplateError> {
+        validate_name(name)?;
+        self.store.get_mut().insert(name.to_string(), template);
+        Ok(())
+    }
+
+    /// Returns the template saved under `name`
+    pub fn get(&self, name: &str) -> Result<TransactionTemplate, TemplateError> {
+        self.store
+            .get()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| TemplateError::UnknownTemplate(name.to_string()))
+    }
+
+    /// Returns all `(name, template)` entries sorted by name
+    pub fn list(&self) -> Vec<(String, TransactionTemplate)> {
+        self.store
+            .get()
+            .iter()
+            .map(|(name, template)| (name.clone(), template.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_file_path;
+
+    #[test]
+    fn test_template_roundtrip_and_overrides() {
+        let (_dir, path) = temp_file_path("templates.json");
+        let mut store = TemplateStore::open_at(&path).unwrap();
+        let template = TransactionTemplate {
+            message_type: MessageType::Delegate {
+                validator_address: None,
+            },
+            base_amount: Some(5000),
+            memo: Some("weekly delegate".to_string()),
+        };
+        store.insert("weekly", template.clone()).unwrap();
+        assert!(store.insert("bad name", template.clone()).is_err());
+        store.save().unwrap();
+
+        let store = TemplateStore::open_at(&path).unwrap();
+        assert_eq!(store.get("weekly").unwrap(), template);
+        assert!(store.get("daily").is_err());
+
+        let validator = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6".to_string();
+        let overridden = template.with_overrides(Some(7000), None, Some(validator.clone()));
+        assert_eq!(overridden.base_amount, Some(7000));
+        assert_eq!(overridden.memo.as_deref(), Some("weekly delegate"));
+        assert_eq!(
+            overridden.message_type,
+            MessageType::Delegate {
+                validator_address: Some(validator)
+            }
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Helpers shared by the unit tests.
+
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+
+/// Returns the path of `file_name` in a new temporary directory, removed with the returned
+/// [`TempDir`]. The file itself is not created.
+pub fn temp_file_path(file_name: &str) -> (TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(file_name);
+    (dir, path)
+}
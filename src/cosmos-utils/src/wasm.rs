@@ -0,0 +1,114 @@
+//! Browser build of the RPC client and of the signer, enabled by the `wasm` feature: requests go
+//! through the async reqwest client, backed by `fetch`, and signatures are made by a JavaScript
+//! callback of the wallet instead of the canister called through `dfx`.
+
+use ic_cosmos::types::{
+    build_transaction_for_broadcast, create_sign_doc_bytes, decode_simulate_response, encode_simulate_query,
+    CosmosTransaction, SigningBackend, TX_SIMULATE_PATH,
+};
+use js_sys::{Function, Promise, Uint8Array};
+use serde_json::json;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{json_rpc_error, raw_signature, CosmosClientConfig, CosmosError};
+
+impl CosmosClientConfig {
+    /// Posts the JSON-RPC `request` to the node with the async client and returns the JSON
+    /// response, failing if the response exceeds `max_response_bytes`. Timeouts are left to the
+    /// browser, `fetch` does not support them.
+    pub async fn post_json_rpc_async(&self, request: &serde_json::Value) -> Result<serde_json::Value, CosmosError> {
+        let bytes = reqwest::Client::new()
+            .post(&self.rpc_url)
+            .json(request)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+        if bytes.len() > self.max_response_bytes {
+            return Err(CosmosError::DecodingError(format!(
+                "Response of {} bytes exceeds the limit of {} bytes",
+                bytes.len(),
+                self.max_response_bytes
+            )));
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// Signs with a JavaScript callback of a browser wallet, and simulates transactions with the
+/// async reqwest client
+#[derive(Debug, Clone)]
+pub struct JsSigningBackend {
+    pub config: CosmosClientConfig,
+    /// Compressed secp256k1 public key of the signer
+    pub public_key: Vec<u8>,
+    /// Called with the sign doc bytes as a `Uint8Array`, returns the signature or a `Promise` of
+    /// it, as raw `r || s` bytes or a DER sequence
+    sign_callback: Function,
+}
+
+impl JsSigningBackend {
+    pub fn new(config: CosmosClientConfig, public_key: Vec<u8>, sign_callback: Function) -> Self {
+        Self {
+            config,
+            public_key,
+            sign_callback,
+        }
+    }
+
+    /// Returns the raw 64-byte signature of `sign_bytes` made by the callback
+    pub async fn sign(&self, sign_bytes: &[u8]) -> Result<Vec<u8>, CosmosError> {
+        let result = self
+            .sign_callback
+            .call1(&JsValue::NULL, &Uint8Array::from(sign_bytes))
+            .map_err(js_error)?;
+        let signature = JsFuture::from(Promise::resolve(&result)).await.map_err(js_error)?;
+        Ok(raw_signature(&Uint8Array::new(&signature).to_vec())?.to_vec())
+    }
+
+    /// Signs `transaction` and returns it in base64, ready to be broadcast
+    pub async fn sign_transaction(&self, transaction: &CosmosTransaction) -> Result<String, CosmosError> {
+        let sign_bytes = create_sign_doc_bytes(transaction, &self.public_key).map_err(CosmosError::EncodingError)?;
+        let signature = self.sign(&sign_bytes).await?;
+        build_transaction_for_broadcast(transaction, &self.public_key, &signature).map_err(CosmosError::EncodingError)
+    }
+}
+
+impl SigningBackend for JsSigningBackend {
+    async fn simulate(&self, transaction: &CosmosTransaction) -> Result<u64, String> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "abci_query",
+            "params": {
+                "path": TX_SIMULATE_PATH,
+                "data": encode_simulate_query(transaction, &self.public_key)?,
+                "height": "0",
+                "prove": false
+            }
+        });
+        let response_json = self
+            .config
+            .post_json_rpc_async(&request)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(error) = response_json.get("error") {
+            return Err(json_rpc_error(error).to_string());
+        }
+        let response = &response_json["result"]["response"];
+        if response["code"].as_i64().unwrap_or(0) != 0 {
+            return Err(format!(
+                "Simulation failed: {}",
+                response["log"].as_str().unwrap_or("Unknown error")
+            ));
+        }
+        let value = response["value"].as_str().ok_or("Missing simulation response value")?;
+        decode_simulate_response(value)
+    }
+}
+
+fn js_error(error: JsValue) -> CosmosError {
+    CosmosError::EncodingError(format!("Sign callback failed: {:?}", error))
+}
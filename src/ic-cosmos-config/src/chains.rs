@@ -0,0 +1,72 @@
+use ic_cosmos::types::ChainConfig;
+
+use crate::{memory::CHAINS, types::ChainConfigStorable};
+
+/// Validates `config` and adds it, replacing the configuration of the same chain ID
+pub fn insert_chain(config: ChainConfig) -> Result<(), String> {
+    config.validate()?;
+    CHAINS.with_borrow_mut(|chains| chains.insert(config.chain_id.clone(), ChainConfigStorable(config)));
+    Ok(())
+}
+
+/// Returns the configuration of `chain_id`, if registered
+pub fn get_chain(chain_id: &str) -> Option<ChainConfig> {
+    CHAINS.with_borrow(|chains| chains.get(&chain_id.to_string()).map(|config| config.0))
+}
+
+/// Returns all registered chain configurations sorted by chain ID
+pub fn list_chains() -> Vec<ChainConfig> {
+    CHAINS.with_borrow(|chains| chains.iter().map(|(_, config)| config.0).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(chain_id: &str, hrp: &str) -> ChainConfig {
+        ChainConfig {
+            chain_id: chain_id.to_string(),
+            hrp: hrp.to_string(),
+            gas_price: format!("0.025u{}", hrp),
+            rpc_url: format!("https://rpc.{}.example", hrp),
+            gov_v1: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_get_and_list_chains() {
+        insert_chain(chain("osmosis-1", "osmo")).unwrap();
+        insert_chain(chain("cosmoshub-4", "cosmos")).unwrap();
+        assert_eq!(get_chain("osmosis-1"), Some(chain("osmosis-1", "osmo")));
+        assert_eq!(get_chain("juno-1"), None);
+        assert_eq!(
+            list_chains(),
+            vec![chain("cosmoshub-4", "cosmos"), chain("osmosis-1", "osmo")]
+        );
+
+        let mut updated = chain("osmosis-1", "osmo");
+        updated.gas_price = "0.0025uosmo".to_string();
+        insert_chain(updated.clone()).unwrap();
+        assert_eq!(get_chain("osmosis-1"), Some(updated));
+        assert_eq!(list_chains().len(), 2);
+    }
+
+    #[test]
+    fn test_insert_invalid_chain() {
+        let mut insecure = chain("juno-1", "juno");
+        insecure.rpc_url = "http://rpc.juno.example".to_string();
+        assert!(insert_chain(insecure).is_err());
+
+        let mut missing_hrp = chain("juno-1", "juno");
+        missing_hrp.hrp = String::new();
+        assert_eq!(
+            insert_chain(missing_hrp),
+            Err("Missing hrp in chain config".to_string())
+        );
+        assert_eq!(get_chain("juno-1"), None);
+
+        let long_chain_id = "c".repeat(51);
+        assert!(insert_chain(chain(&long_chain_id, "juno")).is_err());
+        assert!(insert_chain(chain(&"c".repeat(50), "juno")).is_ok());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod chains;
+pub mod memory;
+pub mod types;
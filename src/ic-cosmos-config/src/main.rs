@@ -0,0 +1,33 @@
+use candid::candid_method;
+use ic_cdk::{query, update};
+use ic_cosmos::types::ChainConfig;
+use ic_cosmos_config::chains;
+
+/// Adds or replaces the configuration of `config.chain_id`. Only controllers may register chains.
+#[update(name = "registerChain")]
+#[candid_method(rename = "registerChain")]
+pub fn register_chain(config: ChainConfig) -> Result<(), String> {
+    if !ic_cdk::api::is_controller(&ic_cdk::caller()) {
+        return Err("Unauthorized".to_string());
+    }
+    chains::insert_chain(config)
+}
+
+/// Returns the configuration of `chain_id`, if registered.
+#[query(name = "getChain")]
+#[candid_method(query, rename = "getChain")]
+pub fn get_chain(chain_id: String) -> Option<ChainConfig> {
+    chains::get_chain(&chain_id)
+}
+
+/// Returns all registered chain configurations sorted by chain ID.
+#[query(name = "listChains")]
+#[candid_method(query, rename = "listChains")]
+pub fn list_chains() -> Vec<ChainConfig> {
+    chains::list_chains()
+}
+
+fn main() {}
+
+// Order dependent: do not move above any exposed canister method!
+ic_cdk::export_candid!();
@@ -0,0 +1,26 @@
+use std::cell::RefCell;
+
+use ic_stable_structures::{
+    memory_manager::{MemoryId, MemoryManager, VirtualMemory},
+    DefaultMemoryImpl, StableBTreeMap,
+};
+
+use crate::types::ChainConfigStorable;
+
+const CHAINS_MEMORY_ID: MemoryId = MemoryId::new(0);
+
+pub type StableMemory = VirtualMemory<DefaultMemoryImpl>;
+pub type ChainsMemory = StableBTreeMap<String, ChainConfigStorable, StableMemory>;
+
+thread_local! {
+    // Stable static data: these are preserved when the canister is upgraded.
+    pub static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    /// Chain configurations keyed by chain ID.
+    pub static CHAINS: RefCell<ChainsMemory> = RefCell::new(ChainsMemory::init(get_memory(CHAINS_MEMORY_ID)));
+}
+
+pub fn get_memory(memory_id: MemoryId) -> StableMemory {
+    MEMORY_MANAGER.with_borrow(|m| m.get(memory_id))
+}
@@ -0,0 +1,21 @@
+use std::borrow::Cow;
+
+use candid::{Decode, Encode};
+use ic_cosmos::types::ChainConfig;
+use ic_stable_structures::{storable::Bound, Storable};
+
+/// [`ChainConfig`] stored in stable memory as Candid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainConfigStorable(pub ChainConfig);
+
+impl Storable for ChainConfigStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, ChainConfig).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
@@ -0,0 +1,260 @@
+//! `#[derive(CosmosMessageEncoder)]`, implementing `ic_cosmos::types::CosmosMessageEncoder` from
+//! the field annotations of a struct:
+//!
+//! ```ignore
+//! use ic_cosmos::types::CosmosMessageEncoder;
+//! use ic_cosmos_derive::CosmosMessageEncoder;
+//!
+//! #[derive(CosmosMessageEncoder)]
+//! #[cosmos_type_url = "/cosmos.staking.v1beta1.MsgDelegate"]
+//! struct MsgDelegate {
+//!     #[cosmos_field(tag = 1, wire_type = "string")]
+//!     delegator_address: String,
+//!     #[cosmos_field(tag = 2, wire_type = "string")]
+//!     validator_address: String,
+//!     #[cosmos_field(tag = 3, wire_type = "message")]
+//!     amount: Coin,
+//! }
+//! ```
+//!
+//! Fields equal to their proto3 default are left out, as the Cosmos SDK does, so the
+//! encoding matches the one of the generated protobuf types. `Vec` fields are repeated,
+//! packed for `uint64`, and `Option` messages are only encoded when set.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Lit, LitInt,
+    LitStr, PathArguments, Type,
+};
+
+/// Protobuf types of the `wire_type` annotation
+enum WireType {
+    String,
+    Bytes,
+    Uint64,
+    Bool,
+    Message,
+}
+
+impl WireType {
+    fn parse(value: &LitStr) -> syn::Result<Self> {
+        match value.value().as_str() {
+            "string" => Ok(WireType::String),
+            "bytes" => Ok(WireType::Bytes),
+            "uint64" => Ok(WireType::Uint64),
+            "bool" => Ok(WireType::Bool),
+            "message" => Ok(WireType::Message),
+            other => Err(syn::Error::new_spanned(
+                value,
+                format!(
+                    "Unsupported wire_type \"{}\", expected \"string\", \"bytes\", \"uint64\", \"bool\" or \"message\"",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+#[proc_macro_derive(CosmosMessageEncoder, attributes(cosmos_field, cosmos_type_url))]
+pub fn derive_cosmos_message_encoder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let type_url = parse_type_url(&input)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CosmosMessageEncoder can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CosmosMessageEncoder requires named fields",
+        ));
+    };
+
+    let mut encode_fields = Vec::new();
+    for field in &fields.named {
+        let (tag, wire_type) = parse_field(field)?;
+        let ident = field.ident.as_ref().expect("named field");
+        encode_fields.push(encode_field(quote!(self.#ident), &field.ty, tag, &wire_type));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ic_cosmos::types::CosmosMessageEncoder for #ident #ty_generics #where_clause {
+            fn encode(&self) -> ::std::vec::Vec<u8> {
+                let mut bytes = ::std::vec::Vec::new();
+                #(#encode_fields)*
+                bytes
+            }
+
+            fn type_url() -> &'static str {
+                #type_url
+            }
+        }
+    })
+}
+
+/// Read `#[cosmos_type_url = "..."]` of the struct
+fn parse_type_url(input: &DeriveInput) -> syn::Result<LitStr> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("cosmos_type_url") {
+            continue;
+        }
+        return match &attr.meta.require_name_value()?.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(type_url),
+                ..
+            }) => Ok(type_url.clone()),
+            value => Err(syn::Error::new_spanned(value, "cosmos_type_url must be a string")),
+        };
+    }
+    Err(syn::Error::new_spanned(
+        &input.ident,
+        "Missing #[cosmos_type_url = \"...\"] attribute",
+    ))
+}
+
+/// Read `#[cosmos_field(tag = 1, wire_type = "string")]` of a field
+fn parse_field(field: &syn::Field) -> syn::Result<(u32, WireType)> {
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("cosmos_field"))
+        .ok_or_else(|| syn::Error::new(field.span(), "Missing #[cosmos_field(tag = ..., wire_type = \"...\")]"))?;
+
+    let mut tag = None;
+    let mut wire_type = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("tag") {
+            let value: LitInt = meta.value()?.parse()?;
+            let number = value.base10_parse::<u32>()?;
+            // Field numbers are 29 bit, 19000 to 19999 are reserved by protobuf
+            if number == 0 || number > 0x1FFF_FFFF || (19_000..20_000).contains(&number) {
+                return Err(syn::Error::new_spanned(value, "Invalid protobuf field number"));
+            }
+            tag = Some(number);
+            Ok(())
+        } else if meta.path.is_ident("wire_type") {
+            wire_type = Some(WireType::parse(&meta.value()?.parse()?)?);
+            Ok(())
+        } else {
+            Err(meta.error("Unknown cosmos_field argument, expected tag or wire_type"))
+        }
+    })?;
+
+    match (tag, wire_type) {
+        (Some(tag), Some(wire_type)) => Ok((tag, wire_type)),
+        (None, _) => Err(syn::Error::new_spanned(attr, "Missing tag in cosmos_field")),
+        (_, None) => Err(syn::Error::new_spanned(attr, "Missing wire_type in cosmos_field")),
+    }
+}
+
+/// Returns the type argument of `ty` if it is `wrapper<T>`, e.g. `Vec` or `Option`
+fn type_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Generate the statements appending the field `value` of type `ty` to `bytes`
+fn encode_field(value: TokenStream2, ty: &Type, tag: u32, wire_type: &WireType) -> TokenStream2 {
+    let encoder = quote!(::ic_cosmos::types::cosmos_message_encoder);
+    let repeated = match type_argument(ty, "Vec") {
+        // `Vec<u8>` is a single bytes field, `Vec<Vec<u8>>` a repeated one
+        Some(inner) if matches!(wire_type, WireType::Bytes) => type_argument(inner, "Vec").is_some(),
+        Some(_) => true,
+        None => false,
+    };
+
+    match (wire_type, repeated) {
+        (WireType::String, false) => quote! {
+            if !#value.is_empty() {
+                bytes.extend(#encoder::encode_bytes_field(#tag, #value.as_bytes()));
+            }
+        },
+        (WireType::String, true) => quote! {
+            for item in &#value {
+                bytes.extend(#encoder::encode_bytes_field(#tag, item.as_bytes()));
+            }
+        },
+        (WireType::Bytes, false) => quote! {
+            if !#value.is_empty() {
+                bytes.extend(#encoder::encode_bytes_field(#tag, &#value));
+            }
+        },
+        (WireType::Bytes, true) => quote! {
+            for item in &#value {
+                bytes.extend(#encoder::encode_bytes_field(#tag, item));
+            }
+        },
+        (WireType::Uint64, false) => quote! {
+            if #value != 0 {
+                bytes.extend(#encoder::encode_varint_field(#tag, u64::from(#value)));
+            }
+        },
+        // Repeated scalars are packed in proto3
+        (WireType::Uint64, true) => quote! {
+            if !#value.is_empty() {
+                let mut packed = ::std::vec::Vec::new();
+                for item in &#value {
+                    packed.extend(::ic_cosmos::types::cosmos_protobuf::encode_varint(u64::from(*item)));
+                }
+                bytes.extend(#encoder::encode_bytes_field(#tag, &packed));
+            }
+        },
+        (WireType::Bool, false) => quote! {
+            if #value {
+                bytes.extend(#encoder::encode_varint_field(#tag, 1));
+            }
+        },
+        (WireType::Bool, true) => quote! {
+            if !#value.is_empty() {
+                let packed: ::std::vec::Vec<u8> = #value.iter().map(|item| u8::from(*item)).collect();
+                bytes.extend(#encoder::encode_bytes_field(#tag, &packed));
+            }
+        },
+        (WireType::Message, true) => quote! {
+            for item in &#value {
+                bytes.extend(#encoder::encode_bytes_field(
+                    #tag,
+                    &::ic_cosmos::types::CosmosMessageEncoder::encode(item),
+                ));
+            }
+        },
+        (WireType::Message, false) if type_argument(ty, "Option").is_some() => quote! {
+            if let Some(item) = &#value {
+                bytes.extend(#encoder::encode_bytes_field(
+                    #tag,
+                    &::ic_cosmos::types::CosmosMessageEncoder::encode(item),
+                ));
+            }
+        },
+        // A message field which is not optional is always set
+        (WireType::Message, false) => quote! {
+            bytes.extend(#encoder::encode_bytes_field(
+                #tag,
+                &::ic_cosmos::types::CosmosMessageEncoder::encode(&#value),
+            ));
+        },
+    }
+}
@@ -0,0 +1,150 @@
+use cosmos_sdk_proto::{
+    cosmos::{bank, base::v1beta1::Coin as ProtoCoin, staking},
+    traits::Message,
+    Any,
+};
+use ic_cosmos::types::{encode_msg_send, CosmosMessageEncoder};
+use ic_cosmos_derive::CosmosMessageEncoder;
+use serde_json::json;
+
+#[derive(CosmosMessageEncoder)]
+#[cosmos_type_url = "/cosmos.base.v1beta1.Coin"]
+struct Coin {
+    #[cosmos_field(tag = 1, wire_type = "string")]
+    denom: String,
+    #[cosmos_field(tag = 2, wire_type = "string")]
+    amount: String,
+}
+
+#[derive(CosmosMessageEncoder)]
+#[cosmos_type_url = "/cosmos.bank.v1beta1.MsgSend"]
+struct MsgSend {
+    #[cosmos_field(tag = 1, wire_type = "string")]
+    from_address: String,
+    #[cosmos_field(tag = 2, wire_type = "string")]
+    to_address: String,
+    #[cosmos_field(tag = 3, wire_type = "message")]
+    amount: Vec<Coin>,
+}
+
+#[derive(CosmosMessageEncoder)]
+#[cosmos_type_url = "/cosmos.staking.v1beta1.MsgDelegate"]
+struct MsgDelegate {
+    #[cosmos_field(tag = 1, wire_type = "string")]
+    delegator_address: String,
+    #[cosmos_field(tag = 2, wire_type = "string")]
+    validator_address: String,
+    #[cosmos_field(tag = 3, wire_type = "message")]
+    amount: Option<Coin>,
+}
+
+/// `cosmos.base.query.v1beta1.PageRequest`, covering the scalar wire types
+#[derive(CosmosMessageEncoder)]
+#[cosmos_type_url = "/cosmos.base.query.v1beta1.PageRequest"]
+struct PageRequest {
+    #[cosmos_field(tag = 1, wire_type = "bytes")]
+    key: Vec<u8>,
+    #[cosmos_field(tag = 2, wire_type = "uint64")]
+    offset: u64,
+    #[cosmos_field(tag = 3, wire_type = "uint64")]
+    limit: u64,
+    #[cosmos_field(tag = 4, wire_type = "bool")]
+    count_total: bool,
+    #[cosmos_field(tag = 5, wire_type = "bool")]
+    reverse: bool,
+}
+
+const FROM_ADDRESS: &str = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+const TO_ADDRESS: &str = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+const VALIDATOR_ADDRESS: &str = "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0";
+
+fn coin(denom: &str, amount: &str) -> Coin {
+    Coin {
+        denom: denom.to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+fn proto_coin(denom: &str, amount: &str) -> ProtoCoin {
+    ProtoCoin {
+        denom: denom.to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+#[test]
+fn test_derive_msg_send() {
+    let msg = MsgSend {
+        from_address: FROM_ADDRESS.to_string(),
+        to_address: TO_ADDRESS.to_string(),
+        amount: vec![coin("uatom", "1000"), coin("ibc/27394FB0", "5")],
+    };
+    let expected = bank::v1beta1::MsgSend {
+        from_address: FROM_ADDRESS.to_string(),
+        to_address: TO_ADDRESS.to_string(),
+        amount: vec![proto_coin("uatom", "1000"), proto_coin("ibc/27394FB0", "5")],
+    };
+    assert_eq!(MsgSend::type_url(), "/cosmos.bank.v1beta1.MsgSend");
+    assert_eq!(msg.encode(), expected.encode_to_vec());
+
+    // Same encoding as the JSON encoder for messages without default values
+    let value = json!({
+        "from_address": FROM_ADDRESS,
+        "to_address": TO_ADDRESS,
+        "amount": [{ "denom": "uatom", "amount": "1000" }, { "denom": "ibc/27394FB0", "amount": "5" }]
+    });
+    assert_eq!(msg.encode(), encode_msg_send(&value).unwrap());
+
+    let any = Any {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: expected.encode_to_vec(),
+    };
+    assert_eq!(msg.encode_any(), any.encode_to_vec());
+}
+
+#[test]
+fn test_derive_msg_delegate() {
+    let msg = MsgDelegate {
+        delegator_address: FROM_ADDRESS.to_string(),
+        validator_address: VALIDATOR_ADDRESS.to_string(),
+        amount: Some(coin("uatom", "250000")),
+    };
+    let expected = staking::v1beta1::MsgDelegate {
+        delegator_address: FROM_ADDRESS.to_string(),
+        validator_address: VALIDATOR_ADDRESS.to_string(),
+        amount: Some(proto_coin("uatom", "250000")),
+    };
+    assert_eq!(MsgDelegate::type_url(), "/cosmos.staking.v1beta1.MsgDelegate");
+    assert_eq!(msg.encode(), expected.encode_to_vec());
+
+    let unset = MsgDelegate {
+        delegator_address: FROM_ADDRESS.to_string(),
+        validator_address: String::new(),
+        amount: None,
+    };
+    let expected = staking::v1beta1::MsgDelegate {
+        delegator_address: FROM_ADDRESS.to_string(),
+        validator_address: String::new(),
+        amount: None,
+    };
+    assert_eq!(unset.encode(), expected.encode_to_vec());
+}
+
+#[test]
+fn test_derive_scalar_fields() {
+    let request = PageRequest {
+        key: vec![1, 2, 3],
+        offset: 0,
+        limit: 300,
+        count_total: true,
+        reverse: false,
+    };
+    let expected = cosmos_sdk_proto::cosmos::base::query::v1beta1::PageRequest {
+        key: vec![1, 2, 3],
+        offset: 0,
+        limit: 300,
+        count_total: true,
+        reverse: false,
+    };
+    assert_eq!(request.encode(), expected.encode_to_vec());
+}
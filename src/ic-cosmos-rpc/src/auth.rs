@@ -111,6 +111,16 @@ pub fn require_manage_or_controller() -> Result<(), String> {
     }
 }
 
+pub fn require_controller() -> Result<(), String> {
+    if ic_cdk::api::is_controller(&ic_cdk::caller()) {
+        Ok(())
+    } else {
+        let auth = MetricAuth("controller".to_string());
+        add_metric_entry!(err_unauthorized, auth, 1);
+        Err("Unauthorized".to_string())
+    }
+}
+
 pub fn require_register_provider() -> Result<(), String> {
     if is_authorized(&ic_cdk::caller(), Auth::RegisterProvider) || require_manage_or_controller().is_ok() {
         Ok(())
@@ -0,0 +1,52 @@
+//! Captures the responses of a CometBFT node to the RPC methods covered by the integration tests,
+//! saving them to `tests/fixtures/<method>.json` where the tests load them as mocked outcalls.
+//!
+//! Run with `cargo run -p ic-cosmos-rpc --features generate-fixtures --bin generate_fixtures -- [rpc_url]`,
+//! the provider testnet by default, and commit the refreshed fixtures. `should_decode_captured_fixtures`
+//! is ignored until the fixtures are committed, run it with `cargo test -- --ignored`.
+
+use std::{fs, path::PathBuf};
+
+use ic_cosmos::{request::RpcRequest, types::Cluster};
+use serde_json::{json, Value};
+
+/// Methods captured, with their parameters. Height based methods read the latest block.
+fn fixtures() -> Vec<(RpcRequest, Value)> {
+    vec![
+        (RpcRequest::GetHealth, json!({})),
+        (RpcRequest::GetStatus, json!({})),
+        (RpcRequest::GetAbciInfo, json!({})),
+        (RpcRequest::GetNetInfo, json!({})),
+        (RpcRequest::GetNumUnconfirmedTxs, json!({ "limit": "1" })),
+        (RpcRequest::GetBlock, json!({})),
+        (RpcRequest::GetCommit, json!({})),
+        (RpcRequest::GetHeader, json!({})),
+        (RpcRequest::GetConsensusParams, json!({})),
+    ]
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| Cluster::Testnet.url().to_string());
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fs::create_dir_all(&dir)?;
+
+    let client = reqwest::blocking::Client::new();
+    for (request, params) in fixtures() {
+        let method = request.to_string();
+        let response: Value = client
+            .post(&url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()?
+            .error_for_status()?
+            .json()?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error).into());
+        }
+        let path = dir.join(format!("{}.json", method));
+        fs::write(&path, serde_json::to_string_pretty(&response)? + "\n")?;
+        println!("Saved {}", path.display());
+    }
+    Ok(())
+}
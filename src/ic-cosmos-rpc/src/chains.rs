@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use candid::Principal;
+use ic_canister_log::log;
+use ic_cosmos::{logs::INFO, types::ChainConfig};
+
+use crate::{
+    providers::{ProviderId, RpcProvider},
+    state::mutate_state,
+};
+
+/// Loads the chains of the config canister right after `init` or `post_upgrade`,
+/// which cannot make inter-canister calls themselves.
+pub fn schedule_load_known_chains(config_canister: Principal) {
+    ic_cdk_timers::set_timer(Duration::ZERO, move || {
+        ic_cdk::spawn(async move {
+            if let Err(e) = load_known_chains(config_canister).await {
+                log!(INFO, "[chains] Failed to load chains from {}: {}", config_canister, e);
+            }
+        });
+    });
+}
+
+/// Fetches the chain configurations of `config_canister` and registers a provider named
+/// after the chain ID for every chain that has no provider yet. Invalid chains are logged and
+/// skipped, the others are still loaded.
+pub async fn load_known_chains(config_canister: Principal) -> Result<usize, String> {
+    let (chains,) = ic_cdk::call::<_, (Vec<ChainConfig>,)>(config_canister, "listChains", ())
+        .await
+        .map_err(|(code, message)| format!("{:?}: {}", code, message))?;
+
+    let mut count = 0;
+    mutate_state(|s| {
+        for chain in chains {
+            let id = ProviderId::new(chain.chain_id.clone());
+            let provider = RpcProvider {
+                url: chain.rpc_url.clone(),
+                auth: None,
+                owner: config_canister,
+            };
+            if let Err(e) = chain.validate().and_then(|()| provider.check()) {
+                log!(INFO, "[chains] Skipping chain {:?}: {}", chain.chain_id, e);
+                continue;
+            }
+            if !s.rpc_providers.contains_key(&id) {
+                s.rpc_providers.insert(id, provider);
+            }
+            s.known_chains.insert(chain.chain_id.clone(), chain);
+            count += 1;
+        }
+    });
+    log!(INFO, "[chains] Loaded {} chains from {}", count, config_canister);
+    Ok(count)
+}
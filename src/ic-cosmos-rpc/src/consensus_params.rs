@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use candid::Principal;
+use ic_canister_log::log;
+use ic_cosmos::{
+    logs::INFO,
+    rpc_client::{RpcConfig, RpcError, RpcResult, RpcServices},
+};
+
+use crate::{
+    constants::CONSENSUS_PARAMS_POLL_INTERVAL_SECONDS,
+    http::rpc_client,
+    state::{mutate_state, read_state},
+    types::{CachedConsensusParams, PolledSource, PolledSourceStorable, PrincipalStorable},
+    watcher::latest_block_height,
+};
+
+/// Fetches the consensus parameters at the latest height, caches them and notifies the
+/// consensus params subscribers if they changed since the previous poll.
+pub async fn poll_consensus_params(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<CachedConsensusParams> {
+    let client = rpc_client(source, config);
+    let height = latest_block_height(&client.get_status().await?)?;
+    let params = client.get_consensus_params(height.to_string()).await?;
+
+    let cached = mutate_state(|s| {
+        let changes = s
+            .consensus_params
+            .as_ref()
+            .map(|previous| previous.params.consensus_params.diff(&params.consensus_params))
+            .unwrap_or_default();
+        let cached = CachedConsensusParams {
+            params,
+            updated_at: ic_cdk::api::time(),
+            changes,
+        };
+        s.consensus_params = Some(cached.clone());
+        cached
+    });
+
+    if !cached.changes.is_empty() {
+        for change in &cached.changes {
+            log!(
+                INFO,
+                "[consensus params monitor] {} changed from {} to {} at height {}",
+                change.name,
+                change.previous,
+                change.current,
+                cached.params.block_height
+            );
+        }
+        notify_consensus_params_subscribers(&cached).await;
+    }
+
+    Ok(cached)
+}
+
+/// Starts polling the consensus parameters every `CONSENSUS_PARAMS_POLL_INTERVAL_SECONDS`,
+/// replacing the previously running monitor timer if any.
+/// The source is kept in stable memory to restart the monitor after upgrades.
+pub fn start_consensus_params_monitor(source: RpcServices, config: Option<RpcConfig>) {
+    let monitor = PolledSource {
+        source: source.clone(),
+        config: config.clone(),
+    };
+    let interval = Duration::from_secs(CONSENSUS_PARAMS_POLL_INTERVAL_SECONDS);
+    let timer_id = ic_cdk_timers::set_timer_interval(interval, move || {
+        let source = source.clone();
+        let config = config.clone();
+        ic_cdk::spawn(async move {
+            if let Err(e) = poll_consensus_params(source, config).await {
+                log!(
+                    INFO,
+                    "[consensus params monitor] Failed to poll the consensus params: {}",
+                    e
+                );
+            }
+        });
+    });
+
+    let previous = mutate_state(|s| {
+        s.consensus_params_monitor
+            .set(PolledSourceStorable(Some(monitor)))
+            .expect("Failed to save the consensus params monitor");
+        s.consensus_params_timer.replace(timer_id)
+    });
+    if let Some(previous) = previous {
+        ic_cdk_timers::clear_timer(previous);
+    }
+}
+
+/// Restarts the consensus params monitor with the source it was last started with, as the timers
+/// do not survive upgrades.
+pub fn restart_consensus_params_monitor() {
+    if let Some(monitor) = read_state(|s| s.consensus_params_monitor.get().0.clone()) {
+        start_consensus_params_monitor(monitor.source, monitor.config);
+    }
+}
+
+/// Returns the consensus parameters of the last poll of the monitor.
+pub fn cached_consensus_params() -> RpcResult<CachedConsensusParams> {
+    read_state(|s| s.consensus_params.clone()).ok_or_else(|| {
+        RpcError::ValidationError("Consensus params are not monitored, call cos_monitorConsensusParams".to_string())
+    })
+}
+
+/// Registers the `callback_method` of `canister` to be called with the [`CachedConsensusParams`]
+/// every time the consensus parameters change.
+pub fn do_register_consensus_params_subscriber(canister: Principal, callback_method: String) -> RpcResult<()> {
+    if callback_method.is_empty() {
+        return Err(RpcError::ValidationError(
+            "Callback method must not be empty".to_string(),
+        ));
+    }
+    log!(
+        INFO,
+        "[{}] Registering consensus params subscriber: {} {}",
+        ic_cdk::caller(),
+        canister,
+        callback_method
+    );
+    mutate_state(|s| {
+        s.consensus_params_subscribers
+            .insert(PrincipalStorable(canister), callback_method)
+    });
+    Ok(())
+}
+
+/// Unregisters the consensus params subscriber of `canister`.
+pub fn do_unregister_consensus_params_subscriber(canister: Principal) -> bool {
+    log!(
+        INFO,
+        "[{}] Unregistering consensus params subscriber: {}",
+        ic_cdk::caller(),
+        canister
+    );
+    mutate_state(|s| {
+        s.consensus_params_subscribers
+            .remove(&PrincipalStorable(canister))
+            .is_some()
+    })
+}
+
+async fn notify_consensus_params_subscribers(cached: &CachedConsensusParams) {
+    let subscribers: Vec<(Principal, String)> = read_state(|s| {
+        s.consensus_params_subscribers
+            .iter()
+            .map(|(canister, method)| (canister.0, method))
+            .collect()
+    });
+    for (canister, method) in subscribers {
+        if let Err((code, message)) = ic_cdk::call::<_, ()>(canister, &method, (cached,)).await {
+            log!(
+                INFO,
+                "[consensus params monitor] Failed to call {} {} (height: {}): {:?} {}",
+                canister,
+                method,
+                cached.params.block_height,
+                code,
+                message
+            );
+        }
+    }
+}
@@ -24,5 +24,26 @@ pub const NODES_IN_SUBNET: u32 = 34;
 
 pub const PROVIDER_ID_MAX_SIZE: u32 = 128;
 
+// Maximum size of a Candid encoded provider in stable memory
+pub const RPC_PROVIDER_MAX_SIZE: u32 = 1000;
+
+// Interval between two polls of the new blocks, about the block time of the Cosmos Hub
+pub const BLOCK_POLL_INTERVAL_SECONDS: u64 = 6;
+
+// Maximum number of headers fetched per poll, the subscription catches up over several polls
+pub const MAX_HEADERS_PER_POLL: u64 = 20;
+
+// Time during which the minimum gas prices of a provider are served from the cache
+pub const MIN_GAS_PRICES_TTL_SECONDS: u64 = 60;
+
+// Interval between two polls of the consensus parameters, about 1000 blocks of the Cosmos Hub
+pub const CONSENSUS_PARAMS_POLL_INTERVAL_SECONDS: u64 = 2 * 60 * 60;
+
+// Number of consecutive 5xx responses after which a provider is bypassed
+pub const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 3;
+
+// Time during which a provider is bypassed before a test request is sent to it
+pub const CIRCUIT_BREAKER_COOLDOWN_SECONDS: u64 = 30;
+
 // List of hosts which are not allowed to be used as RPC providers
 pub const RPC_HOSTS_BLOCKLIST: &[&str] = &[];
@@ -0,0 +1,56 @@
+use ic_cosmos::{
+    rpc_client::{RpcConfig, RpcResult, RpcServices},
+    types::{Cluster, GasPrice},
+};
+
+use crate::{
+    constants::MIN_GAS_PRICES_TTL_SECONDS,
+    http::rpc_client,
+    state::{mutate_state, read_state},
+    types::CachedGasPrices,
+};
+
+/// Returns the minimum gas prices of the providers of `source`, queried at most once
+/// every `MIN_GAS_PRICES_TTL_SECONDS` per provider.
+pub async fn get_min_gas_prices(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<Vec<GasPrice>> {
+    let key = provider_key(&source);
+    let now = ic_cdk::api::time();
+    let ttl_nanos = MIN_GAS_PRICES_TTL_SECONDS * 1_000_000_000;
+    let cached = read_state(|s| {
+        s.min_gas_prices
+            .get(&key)
+            .filter(|cached| now.saturating_sub(cached.fetched_at) < ttl_nanos)
+            .map(|cached| cached.prices.clone())
+    });
+    if let Some(prices) = cached {
+        return Ok(prices);
+    }
+
+    let prices = rpc_client(source, config).get_min_gas_prices().await?;
+    mutate_state(|s| {
+        s.min_gas_prices.insert(
+            key,
+            CachedGasPrices {
+                prices: prices.clone(),
+                fetched_at: now,
+            },
+        )
+    });
+    Ok(prices)
+}
+
+/// Returns the key of the providers of `source` in the gas prices cache
+fn provider_key(source: &RpcServices) -> String {
+    match source {
+        RpcServices::Mainnet => Cluster::Mainnet.to_string(),
+        RpcServices::Testnet => Cluster::Testnet.to_string(),
+        RpcServices::Devnet => Cluster::Devnet.to_string(),
+        RpcServices::Localnet => Cluster::Localnet.to_string(),
+        RpcServices::Provider(ids) => ids.join(","),
+        RpcServices::Custom(apis) => apis
+            .iter()
+            .map(|api| api.network.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
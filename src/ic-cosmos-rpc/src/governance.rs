@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use candid::Principal;
+use ic_canister_log::log;
+use ic_cosmos::{
+    logs::INFO,
+    rpc_client::{RpcConfig, RpcError, RpcResult, RpcServices},
+    types::{Pagination, ProposalSummary, ProposalTransition, DEFAULT_PROPOSALS_LIMIT},
+};
+
+use crate::{
+    http::rpc_client,
+    state::{mutate_state, read_state},
+    types::{GovernanceMonitor, GovernanceMonitorStorable, PrincipalStorable, ProposalStorable},
+};
+
+/// Fetches the `limit` most recent governance proposals, stores them in stable memory
+/// and notifies the governance subscribers of the proposals whose status changed.
+/// Proposals seen for the first time are tracked without notification.
+pub async fn poll_governance_proposals(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    limit: Option<u64>,
+) -> RpcResult<Vec<ProposalTransition>> {
+    let pagination = Pagination::first(limit.unwrap_or(DEFAULT_PROPOSALS_LIMIT), true);
+    let proposals = rpc_client(source, config)
+        .get_governance_proposals(None, Some(pagination))
+        .await?
+        .proposals;
+
+    let transitions: Vec<ProposalTransition> = mutate_state(|s| {
+        proposals
+            .into_iter()
+            .filter_map(|proposal| {
+                let previous = s
+                    .monitored_proposals
+                    .insert(proposal.id, ProposalStorable(proposal.clone()))?;
+                (previous.0.status != proposal.status).then_some(ProposalTransition {
+                    previous_status: previous.0.status,
+                    proposal,
+                })
+            })
+            .collect()
+    });
+
+    for transition in &transitions {
+        notify_governance_subscribers(transition).await;
+    }
+
+    Ok(transitions)
+}
+
+/// Starts polling the governance proposals every `interval_seconds`,
+/// replacing the previously running monitor timer if any.
+/// The arguments are kept in stable memory to restart the monitor after upgrades.
+pub fn start_governance_monitor(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    limit: Option<u64>,
+    interval_seconds: u64,
+) {
+    let monitor = GovernanceMonitor {
+        source: source.clone(),
+        config: config.clone(),
+        limit,
+        interval_seconds,
+    };
+    let timer_id = ic_cdk_timers::set_timer_interval(Duration::from_secs(interval_seconds), move || {
+        let source = source.clone();
+        let config = config.clone();
+        ic_cdk::spawn(async move {
+            if let Err(e) = poll_governance_proposals(source, config, limit).await {
+                log!(INFO, "[governance monitor] Failed to poll the proposals: {}", e);
+            }
+        });
+    });
+
+    let previous = mutate_state(|s| {
+        s.governance_monitor
+            .set(GovernanceMonitorStorable(Some(monitor)))
+            .expect("Failed to save the governance monitor");
+        s.governance_monitor_timer.replace(timer_id)
+    });
+    if let Some(previous) = previous {
+        ic_cdk_timers::clear_timer(previous);
+    }
+}
+
+/// Restarts the governance monitor with the arguments it was last started with, as the timers
+/// do not survive upgrades.
+pub fn restart_governance_monitor() {
+    if let Some(monitor) = read_state(|s| s.governance_monitor.get().0.clone()) {
+        start_governance_monitor(monitor.source, monitor.config, monitor.limit, monitor.interval_seconds);
+    }
+}
+
+/// Registers the `callback_method` of `canister` to be called with a [`ProposalTransition`]
+/// every time a monitored proposal changes status.
+pub fn do_register_governance_subscriber(canister: Principal, callback_method: String) -> RpcResult<()> {
+    if callback_method.is_empty() {
+        return Err(RpcError::ValidationError(
+            "Callback method must not be empty".to_string(),
+        ));
+    }
+    log!(
+        INFO,
+        "[{}] Registering governance subscriber: {} {}",
+        ic_cdk::caller(),
+        canister,
+        callback_method
+    );
+    mutate_state(|s| {
+        s.governance_subscribers
+            .insert(PrincipalStorable(canister), callback_method)
+    });
+    Ok(())
+}
+
+/// Unregisters the governance subscriber of `canister`.
+pub fn do_unregister_governance_subscriber(canister: Principal) -> bool {
+    log!(
+        INFO,
+        "[{}] Unregistering governance subscriber: {}",
+        ic_cdk::caller(),
+        canister
+    );
+    mutate_state(|s| s.governance_subscribers.remove(&PrincipalStorable(canister)).is_some())
+}
+
+/// Returns the governance subscribers with their callback method.
+pub fn governance_subscribers() -> Vec<(Principal, String)> {
+    read_state(|s| {
+        s.governance_subscribers
+            .iter()
+            .map(|(canister, method)| (canister.0, method))
+            .collect()
+    })
+}
+
+/// Returns the proposals tracked by the governance monitor, by ascending ID.
+pub fn monitored_proposals() -> Vec<ProposalSummary> {
+    read_state(|s| s.monitored_proposals.iter().map(|(_, proposal)| proposal.0).collect())
+}
+
+async fn notify_governance_subscribers(transition: &ProposalTransition) {
+    let subscribers = governance_subscribers();
+    for (canister, method) in subscribers {
+        if let Err((code, message)) = ic_cdk::call::<_, ()>(canister, &method, (transition,)).await {
+            log!(
+                INFO,
+                "[governance monitor] Failed to call {} {} (proposal: {}): {:?} {}",
+                canister,
+                method,
+                transition.proposal.id,
+                code,
+                message
+            );
+        }
+    }
+}
@@ -3,19 +3,20 @@ use ic_cdk::api::management_canister::http_request::TransformContext;
 use ic_cosmos::{
     constants::HTTP_MAX_SIZE,
     logs::{Log, Priority, Sort},
-    rpc_client::{RpcApi, RpcClient, RpcClientConfig, RpcConfig, RpcServices},
+    rpc_client::{CircuitBreaker, RpcApi, RpcClient, RpcClientConfig, RpcConfig, RpcServices},
     types::Cluster,
 };
 
 use crate::{
     constants::{
-        CANISTER_OVERHEAD, COLLATERAL_CYCLES_PER_NODE, HTTP_OUTCALL_REQUEST_BASE_COST,
-        HTTP_OUTCALL_REQUEST_COST_PER_BYTE, HTTP_OUTCALL_REQUEST_PER_NODE_COST, HTTP_OUTCALL_RESPONSE_COST_PER_BYTE,
-        INGRESS_MESSAGE_BYTE_RECEIVED_COST, INGRESS_MESSAGE_RECEIVED_COST, INGRESS_OVERHEAD_BYTES, NODES_IN_SUBNET,
-        RPC_URL_COST_BYTES,
+        CANISTER_OVERHEAD, CIRCUIT_BREAKER_COOLDOWN_SECONDS, CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+        COLLATERAL_CYCLES_PER_NODE, HTTP_OUTCALL_REQUEST_BASE_COST, HTTP_OUTCALL_REQUEST_COST_PER_BYTE,
+        HTTP_OUTCALL_REQUEST_PER_NODE_COST, HTTP_OUTCALL_RESPONSE_COST_PER_BYTE, INGRESS_MESSAGE_BYTE_RECEIVED_COST,
+        INGRESS_MESSAGE_RECEIVED_COST, INGRESS_OVERHEAD_BYTES, NODES_IN_SUBNET, RPC_URL_COST_BYTES,
     },
     providers::find_provider,
-    state::read_state,
+    state::{mutate_state, read_state},
+    types::CircuitBreakerStorable,
     utils::validate_hostname,
 };
 
@@ -53,6 +54,19 @@ pub fn rpc_client(source: RpcServices, config: Option<RpcConfig>) -> RpcClient {
             transform_context: Some(TransformContext::from_name("__transform_json_rpc".to_owned(), vec![])),
             is_demo_active: s.is_demo_active,
             use_compression: false,
+            chain_version: config.chain_version,
+            rate_limiter: s.rate_limit,
+            circuit_breaker: Some(CircuitBreaker {
+                failure_threshold: CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+                cooldown_seconds: CIRCUIT_BREAKER_COOLDOWN_SECONDS,
+                load: |url| read_state(|s| s.circuit_breakers.get(&url.to_string()).map(|breaker| breaker.0)),
+                store: |url, breaker| {
+                    mutate_state(|s| {
+                        s.circuit_breakers
+                            .insert(url.to_string(), CircuitBreakerStorable(breaker))
+                    });
+                },
+            }),
         };
         RpcClient::new(providers, Some(config))
     })
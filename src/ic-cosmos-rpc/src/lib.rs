@@ -1,8 +1,14 @@
 pub mod auth;
+pub mod chains;
+pub mod consensus_params;
 pub mod constants;
+pub mod gas_prices;
+pub mod governance;
 pub mod http;
 pub mod memory;
 pub mod providers;
+pub mod response_filters;
 pub mod state;
 pub mod types;
 pub mod utils;
+pub mod watcher;
@@ -1,3 +1,6 @@
+// The endpoints forward the results of the client as `Ok(client.method().await?)`
+#![allow(clippy::needless_question_mark)]
+
 use candid::{candid_method, Principal};
 use ic_canisters_http_types::{
     HttpRequest as AssetHttpRequest, HttpResponse as AssetHttpResponse, HttpResponseBuilder,
@@ -7,22 +10,46 @@ use ic_cdk::{
     query, update,
 };
 use ic_cosmos::{
+    analytics::FinalityInfo,
     metrics::{encode_metrics, read_metrics, Metrics},
     request::RpcRequest,
-    rpc_client::{RpcConfig, RpcResult, RpcServices},
+    rpc_client::{RpcConfig, RpcError, RpcResult, RpcServices},
+    schema_registry::{FieldDescriptor, ProtobufSchemaRegistry},
     types::{
-        ABCIQueryResult, AbciInfo, BlockComplete, BlockResults, Blockchain, BroadcastTxResult, CandidValue,
-        CheckTxResult, CommitResult, ConsensusParamsResult, ConsensusState, DumpConsensusState, HeaderResult, NetInfo,
-        NumUnconfirmedTransactionsResult, Status, Tx, ValidatorsResult,
+        ABCIQueryResult, AbciInfo, BlockComplete, BlockEventData, BlockHeight, BlockResults, Blockchain, BondStatus,
+        BroadcastTxCommitResult, BroadcastTxResult, CandidValue, CheckTxResult, CommitResult, ConsensusParamsResult,
+        ConsensusState, ConsumerChain, ConsumerChainStop, CosmosCoin, DumpConsensusState, GasPrice, HeaderResult,
+        IBCConnection, IBCConnectionsResult, NetInfo, NumUnconfirmedTransactionsResult, Pagination, ProposalStatus,
+        ProposalSummary, ProposalTransition, ProposalsPage, Status, SupplyInfo, Tx, ValidatorsResult,
     },
 };
 use ic_cosmos_rpc::{
-    auth::{do_authorize, do_deauthorize, require_manage_or_controller, require_register_provider, Auth},
+    auth::{
+        do_authorize, do_deauthorize, require_controller, require_manage_or_controller, require_register_provider, Auth,
+    },
+    chains::schedule_load_known_chains,
+    consensus_params::{
+        cached_consensus_params, do_register_consensus_params_subscriber, do_unregister_consensus_params_subscriber,
+        poll_consensus_params, restart_consensus_params_monitor, start_consensus_params_monitor,
+    },
     constants::NODES_IN_SUBNET,
+    gas_prices::get_min_gas_prices,
+    governance::{
+        do_register_governance_subscriber, do_unregister_governance_subscriber, governance_subscribers,
+        monitored_proposals, poll_governance_proposals, restart_governance_monitor, start_governance_monitor,
+    },
     http::{get_http_request_cost, rpc_client, serve_logs, serve_metrics},
     providers::{do_register_provider, do_unregister_provider, do_update_provider},
+    response_filters::{
+        apply_response_filters, do_set_response_filters, response_filters, NonDeterministicFieldFilter,
+    },
     state::{read_state, replace_state, InitArgs},
-    types::{RegisterProviderArgs, UpdateProviderArgs},
+    types::{CachedConsensusParams, RegisterProviderArgs, UpdateProviderArgs},
+    watcher::{
+        do_register_height_watcher, do_subscribe_to_new_blocks, do_unregister_height_watcher,
+        do_unsubscribe_from_new_blocks, poll_latest_height, restart_block_subscriptions, restart_height_watcher,
+        start_height_watcher,
+    },
 };
 
 /// Returns the current health of the node.
@@ -102,6 +129,20 @@ pub async fn cos_get_block_results(
     Ok(client.get_block_results(height).await?)
 }
 
+/// Returns the events of type `event_type` of the block at `height`, from its begin, end and
+/// finalize block events and from the events of each transaction.
+#[update(name = "cos_getBlockEventsByType")]
+#[candid_method(rename = "cos_getBlockEventsByType")]
+pub async fn cos_get_block_events_by_type(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    height: String,
+    event_type: String,
+) -> RpcResult<Vec<BlockEventData>> {
+    let client = rpc_client(source, config);
+    Ok(client.get_block_events_by_type(height, event_type).await?)
+}
+
 #[update(name = "cos_getBlockchain")]
 #[candid_method(rename = "cos_getBlockchain")]
 pub async fn cos_get_blockchain(
@@ -121,6 +162,38 @@ pub async fn cos_get_commit(source: RpcServices, config: Option<RpcConfig>, heig
     Ok(client.get_commit(height).await?)
 }
 
+/// Returns the fraction of the blocks of `start_height..=end_height` signed by the validator
+/// with the hex consensus address `validator_address`, at most `MAX_UPTIME_BLOCK_RANGE` blocks.
+#[update(name = "cos_getValidatorUptime")]
+#[candid_method(rename = "cos_getValidatorUptime")]
+pub async fn cos_get_validator_uptime(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    validator_address: String,
+    start_height: u64,
+    end_height: u64,
+) -> RpcResult<f64> {
+    let client = rpc_client(source, config);
+    Ok(client
+        .get_validator_uptime(validator_address, start_height, end_height)
+        .await?)
+}
+
+/// Waits until the block at `height` is final, confirmed by validators with more than 2/3 of the
+/// voting power signing the commit of the next block. Fails after `timeout_secs`, at most
+/// `MAX_FINALITY_TIMEOUT_SECS`.
+#[update(name = "cos_waitForBlockFinality")]
+#[candid_method(rename = "cos_waitForBlockFinality")]
+pub async fn cos_wait_for_block_finality(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    height: u64,
+    timeout_secs: u64,
+) -> RpcResult<FinalityInfo> {
+    let client = rpc_client(source, config);
+    Ok(client.wait_for_block_finality(height, timeout_secs).await?)
+}
+
 #[update(name = "cos_getConsensusParams")]
 #[candid_method(rename = "cos_getConsensusParams")]
 pub async fn cos_get_consensus_params(
@@ -174,11 +247,32 @@ pub async fn cos_get_abci_query(
     config: Option<RpcConfig>,
     path: String,
     data: String,
-    height: String,
+    height: BlockHeight,
     prove: bool,
 ) -> RpcResult<ABCIQueryResult> {
     let client = rpc_client(source, config);
-    Ok(client.get_abci_query(path, data, height, prove).await?)
+    Ok(client
+        .get_abci_query(path, data, height.to_query_param(), prove)
+        .await?)
+}
+
+/// Queries a `/store/` path with `prove: true` and verifies the proof of the value against
+/// `app_hash`, the hex app hash of the header of the block after `height`. The app hash must be
+/// obtained from a trusted source, not from the queried provider.
+#[update(name = "cos_getVerifiedAbciQuery")]
+#[candid_method(rename = "cos_getVerifiedAbciQuery")]
+pub async fn cos_get_verified_abci_query(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    path: String,
+    data: String,
+    height: BlockHeight,
+    app_hash: String,
+) -> RpcResult<ABCIQueryResult> {
+    let client = rpc_client(source, config);
+    Ok(client
+        .get_verified_abci_query(path, data, height.to_query_param(), &app_hash)
+        .await?)
 }
 
 #[update(name = "cos_getCheckTx")]
@@ -210,17 +304,410 @@ pub async fn cos_get_broadcast_tx_sync(
     Ok(client.get_broadcast_tx_sync(tx).await?)
 }
 
+/// Submits a transaction and waits for it to be included in a block.
+#[update(name = "cos_getBroadcastTxCommit")]
+#[candid_method(rename = "cos_getBroadcastTxCommit")]
+pub async fn cos_get_broadcast_tx_commit(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    tx: String,
+) -> RpcResult<BroadcastTxCommitResult> {
+    let client = rpc_client(source, config);
+    Ok(client.get_broadcast_tx_commit(tx).await?)
+}
+
+/// Submits a transaction in sync mode through the `Custom` providers with an `LcdRest`
+/// endpoint, for the nodes which only serve the Cosmos REST API.
+#[update(name = "cos_getLcdBroadcastTxSync")]
+#[candid_method(rename = "cos_getLcdBroadcastTxSync")]
+pub async fn cos_get_lcd_broadcast_tx_sync(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    tx: String,
+) -> RpcResult<BroadcastTxResult> {
+    let client = rpc_client(source, config);
+    Ok(client.get_lcd_broadcast_tx_sync(tx).await?)
+}
+
 #[update(name = "cos_getValidators")]
 #[candid_method(rename = "cos_getValidators")]
 pub async fn cos_get_validators(
     source: RpcServices,
     config: Option<RpcConfig>,
     height: String,
-    page: String,
-    per_page: String,
+    pagination: Option<Pagination>,
+    bond_status_filter: Option<BondStatus>,
 ) -> RpcResult<ValidatorsResult> {
     let client = rpc_client(source, config);
-    Ok(client.get_validators(height, page, per_page).await?)
+    Ok(client.get_validators(height, pagination, bond_status_filter).await?)
+}
+
+/// Queries the smart state of a CosmWasm contract.
+/// `query_msg` is the JSON `QueryMsg` of the contract, the JSON response of the contract is
+/// returned.
+#[update(name = "cos_queryContract")]
+#[candid_method(rename = "cos_queryContract")]
+pub async fn cos_query_contract(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    contract_address: String,
+    query_msg: CandidValue,
+) -> RpcResult<CandidValue> {
+    // The query message may be passed as JSON text
+    let query_msg = match query_msg.0 {
+        serde_json::Value::String(s) => serde_json::from_str(&s)?,
+        value => value,
+    };
+    let client = rpc_client(source, config);
+    Ok(client.query_contract_smart(contract_address, query_msg).await?.into())
+}
+
+/// Reads the raw value stored under `key` in the storage of a CosmWasm contract.
+#[update(name = "cos_queryContractRaw")]
+#[candid_method(rename = "cos_queryContractRaw")]
+pub async fn cos_query_contract_raw(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    contract_address: String,
+    key: Vec<u8>,
+) -> RpcResult<Vec<u8>> {
+    let client = rpc_client(source, config);
+    Ok(client.query_contract_raw(contract_address, key).await?)
+}
+
+/// Reads the value stored under `storage_key` in the storage of a CosmWasm contract, through the
+/// `/store/wasm/key` ABCI query. `storage_key` is hex or base64 encoded, the value is returned in
+/// base64.
+#[update(name = "cos_getContractStateRaw")]
+#[candid_method(rename = "cos_getContractStateRaw")]
+pub async fn cos_get_contract_state_raw(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    contract_address: String,
+    storage_key: String,
+) -> RpcResult<String> {
+    let client = rpc_client(source, config);
+    Ok(client.get_contract_state_raw(contract_address, storage_key).await?)
+}
+
+/// Queries the smart state of a CosmWasm contract with the JSON `QueryMsg` given as text, and
+/// returns the JSON response of the contract.
+#[update(name = "cos_queryContractState")]
+#[candid_method(rename = "cos_queryContractState")]
+pub async fn cos_query_contract_state(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    contract_address: String,
+    query_msg_json: String,
+) -> RpcResult<CandidValue> {
+    let query_msg = serde_json::from_str(&query_msg_json)?;
+    let client = rpc_client(source, config);
+    Ok(client.query_contract_smart(contract_address, query_msg).await?.into())
+}
+
+/// Returns the total amount of tokens staked by `address` across all validators.
+#[update(name = "cos_getTotalStaked")]
+#[candid_method(rename = "cos_getTotalStaked")]
+pub async fn cos_get_total_staked(source: RpcServices, config: Option<RpcConfig>, address: String) -> RpcResult<u64> {
+    let client = rpc_client(source, config);
+    Ok(client.get_total_staked(address).await?)
+}
+
+/// Returns the total supply of `denom` together with its circulating supply, community pool
+/// balance and, for the staking denom, its bonded and not bonded tokens where available.
+#[update(name = "cos_getTokenSupply")]
+#[candid_method(rename = "cos_getTokenSupply")]
+pub async fn cos_get_token_supply(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    denom: String,
+) -> RpcResult<SupplyInfo> {
+    let client = rpc_client(source, config);
+    Ok(client.get_token_supply(denom).await?)
+}
+
+/// Returns the balance of `address` in `denom` at the block `height`.
+#[update(name = "cos_getHistoricalBalance")]
+#[candid_method(rename = "cos_getHistoricalBalance")]
+pub async fn cos_get_historical_balance(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    address: String,
+    denom: String,
+    height: BlockHeight,
+) -> RpcResult<String> {
+    let client = rpc_client(source, config);
+    Ok(client.get_balance(address, denom, height).await?)
+}
+
+/// Returns the balances of `address` from the `Custom` providers with an `LcdRest` endpoint.
+#[update(name = "cos_getLcdBalances")]
+#[candid_method(rename = "cos_getLcdBalances")]
+pub async fn cos_get_lcd_balances(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    address: String,
+) -> RpcResult<Vec<CosmosCoin>> {
+    let client = rpc_client(source, config);
+    Ok(client.get_lcd_balances(address).await?)
+}
+
+/// Returns a page of governance proposals with `status`, or with any status if not set.
+/// Defaults to the most recent proposals, pass the `next_key` of a page to fetch the following one.
+#[update(name = "cos_getGovernanceProposals")]
+#[candid_method(rename = "cos_getGovernanceProposals")]
+pub async fn cos_get_governance_proposals(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    status: Option<ProposalStatus>,
+    pagination: Option<Pagination>,
+) -> RpcResult<ProposalsPage> {
+    let client = rpc_client(source, config);
+    Ok(client.get_governance_proposals(status, pagination).await?)
+}
+
+/// Returns a page of the IBC connections of the chain, pass the `next_key` of a page to fetch the following one.
+#[update(name = "cos_getIBCConnections")]
+#[candid_method(rename = "cos_getIBCConnections")]
+pub async fn cos_get_ibc_connections(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    pagination: Option<Pagination>,
+) -> RpcResult<IBCConnectionsResult> {
+    let client = rpc_client(source, config);
+    Ok(client.get_ibc_connections(pagination).await?)
+}
+
+/// Returns the IBC connection `connection_id`, e.g. "connection-0".
+#[update(name = "cos_getIBCConnection")]
+#[candid_method(rename = "cos_getIBCConnection")]
+pub async fn cos_get_ibc_connection(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    connection_id: String,
+) -> RpcResult<IBCConnection> {
+    let client = rpc_client(source, config);
+    Ok(client.get_ibc_connection(connection_id).await?)
+}
+
+/// Returns the consumer chains secured by the provider chain through Interchain Security,
+/// e.g. the Cosmos Hub or the ICS provider testnet.
+#[update(name = "cos_getConsumerChains")]
+#[candid_method(rename = "cos_getConsumerChains")]
+pub async fn cos_get_consumer_chains(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<Vec<ConsumerChain>> {
+    let client = rpc_client(source, config);
+    Ok(client.get_consumer_chains().await?)
+}
+
+/// Returns the consumer chains being removed from the provider chain, with the time at which
+/// they stop being secured by its validators.
+#[update(name = "cos_getConsumerChainStops")]
+#[candid_method(rename = "cos_getConsumerChainStops")]
+pub async fn cos_get_consumer_chain_stops(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+) -> RpcResult<Vec<ConsumerChainStop>> {
+    let client = rpc_client(source, config);
+    Ok(client.get_consumer_chain_stops().await?)
+}
+
+/// Returns the minimum gas prices accepted by the provider, cached for 60 seconds per provider.
+/// An empty list means that the provider accepts transactions without fees.
+#[update(name = "cos_getMinGasPrices")]
+#[candid_method(rename = "cos_getMinGasPrices")]
+pub async fn cos_get_min_gas_prices(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<Vec<GasPrice>> {
+    get_min_gas_prices(source, config).await
+}
+
+/// Returns the state of an Osmosis GAMM liquidity pool.
+/// The provider must be connected to an Osmosis network.
+#[cfg(feature = "osmosis")]
+#[update(name = "cos_getOsmosisPool")]
+#[candid_method(rename = "cos_getOsmosisPool")]
+pub async fn cos_get_osmosis_pool(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    pool_id: u64,
+) -> RpcResult<ic_cosmos::types::OsmosisPool> {
+    let client = rpc_client(source, config);
+    Ok(client.get_osmosis_pool(pool_id).await?)
+}
+
+/// Estimates the output of swapping `token_in` for `token_out_denom` in an Osmosis balancer pool,
+/// at the spot price of the pool after its swap fee. Used to derive the `token_out_min_amount` of swaps.
+#[cfg(feature = "osmosis")]
+#[update(name = "cos_estimateSwapOutput")]
+#[candid_method(rename = "cos_estimateSwapOutput")]
+pub async fn cos_estimate_swap_output(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    pool_id: u64,
+    token_in: ic_cosmos::types::CosmosCoin,
+    token_out_denom: String,
+) -> RpcResult<u64> {
+    let client = rpc_client(source, config);
+    Ok(client.estimate_swap_output(pool_id, token_in, token_out_denom).await?)
+}
+
+/// Returns the Stride host zone of `host_zone_id`, e.g. "cosmoshub-4" for stATOM, with its
+/// current redemption rate. The provider must be connected to a Stride network.
+#[cfg(feature = "stride")]
+#[update(name = "cos_getStrideHostZone")]
+#[candid_method(rename = "cos_getStrideHostZone")]
+pub async fn cos_get_stride_host_zone(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    host_zone_id: String,
+) -> RpcResult<ic_cosmos::types::StrideHostZone> {
+    let client = rpc_client(source, config);
+    Ok(client.get_stride_host_zone(host_zone_id).await?)
+}
+
+/// Returns the fields of the `type_url` message written by the built-in encoders,
+/// or `None` if the message type is not supported.
+#[query(name = "cos_describeMessageType")]
+#[candid_method(query, rename = "cos_describeMessageType")]
+fn cos_describe_message_type(type_url: String) -> Option<Vec<FieldDescriptor>> {
+    ProtobufSchemaRegistry::default()
+        .describe_message(&type_url)
+        .map(<[FieldDescriptor]>::to_vec)
+}
+
+/// Polls `cos_getStatus` every `interval_seconds` and notifies the registered
+/// height watchers when the latest block height advances.
+/// Returns the latest block height at the time of the call.
+#[update(name = "cos_watchLatestHeight", guard = "require_manage_or_controller")]
+#[candid_method(rename = "cos_watchLatestHeight")]
+pub async fn cos_watch_latest_height(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    interval_seconds: u64,
+) -> RpcResult<u64> {
+    if interval_seconds == 0 {
+        return Err(RpcError::ValidationError(
+            "interval_seconds must be greater than zero".to_string(),
+        ));
+    }
+    let height = poll_latest_height(source.clone(), config.clone()).await?;
+    start_height_watcher(source, config, interval_seconds);
+    Ok(height)
+}
+
+#[query(name = "getLatestHeight")]
+#[candid_method(query, rename = "getLatestHeight")]
+fn get_latest_height() -> u64 {
+    read_state(|s| s.latest_height)
+}
+
+#[update(name = "registerHeightWatcher", guard = "require_manage_or_controller")]
+#[candid_method(rename = "registerHeightWatcher")]
+fn register_height_watcher(canister: Principal, method: String) -> RpcResult<()> {
+    do_register_height_watcher(canister, method)
+}
+
+#[update(name = "unregisterHeightWatcher", guard = "require_manage_or_controller")]
+#[candid_method(rename = "unregisterHeightWatcher")]
+fn unregister_height_watcher(canister: Principal) -> bool {
+    do_unregister_height_watcher(canister)
+}
+
+/// Calls `callback_method` of `subscriber` with the headers of the new blocks, polled from `source`
+/// every 6 seconds and batched by up to `max_headers_per_call` headers.
+/// Fails if the new blocks are already polled from another source for other subscribers.
+#[update(name = "subscribeToNewBlocks", guard = "require_manage_or_controller")]
+#[candid_method(rename = "subscribeToNewBlocks")]
+fn subscribe_to_new_blocks(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    subscriber: Principal,
+    callback_method: String,
+    max_headers_per_call: u32,
+) -> RpcResult<()> {
+    do_subscribe_to_new_blocks(source, config, subscriber, callback_method, max_headers_per_call)
+}
+
+#[update(name = "unsubscribeFromNewBlocks", guard = "require_manage_or_controller")]
+#[candid_method(rename = "unsubscribeFromNewBlocks")]
+fn unsubscribe_from_new_blocks(subscriber: Principal) -> bool {
+    do_unsubscribe_from_new_blocks(subscriber)
+}
+
+/// Polls the `limit` most recent governance proposals every `interval_seconds` and calls the
+/// registered governance subscribers when a proposal changes status.
+/// Returns the transitions detected at the time of the call.
+#[update(name = "cos_monitorGovernanceProposals", guard = "require_manage_or_controller")]
+#[candid_method(rename = "cos_monitorGovernanceProposals")]
+pub async fn cos_monitor_governance_proposals(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    limit: Option<u64>,
+    interval_seconds: u64,
+) -> RpcResult<Vec<ProposalTransition>> {
+    if interval_seconds == 0 {
+        return Err(RpcError::ValidationError(
+            "interval_seconds must be greater than zero".to_string(),
+        ));
+    }
+    let transitions = poll_governance_proposals(source.clone(), config.clone(), limit).await?;
+    start_governance_monitor(source, config, limit, interval_seconds);
+    Ok(transitions)
+}
+
+#[query(name = "getMonitoredProposals")]
+#[candid_method(query, rename = "getMonitoredProposals")]
+fn get_monitored_proposals() -> Vec<ProposalSummary> {
+    monitored_proposals()
+}
+
+#[update(name = "registerGovernanceSubscriber", guard = "require_manage_or_controller")]
+#[candid_method(rename = "registerGovernanceSubscriber")]
+fn register_governance_subscriber(canister: Principal, callback_method: String) -> RpcResult<()> {
+    do_register_governance_subscriber(canister, callback_method)
+}
+
+#[update(name = "unregisterGovernanceSubscriber", guard = "require_manage_or_controller")]
+#[candid_method(rename = "unregisterGovernanceSubscriber")]
+fn unregister_governance_subscriber(canister: Principal) -> bool {
+    do_unregister_governance_subscriber(canister)
+}
+
+#[query(name = "getGovernanceSubscribers")]
+#[candid_method(query, rename = "getGovernanceSubscribers")]
+fn get_governance_subscribers() -> Vec<(Principal, String)> {
+    governance_subscribers()
+}
+
+/// Polls the consensus parameters of `source` at the latest height every 2 hours, about 1000
+/// blocks, and calls the registered consensus params subscribers when they change.
+/// Returns the parameters at the time of the call.
+#[update(name = "cos_monitorConsensusParams", guard = "require_manage_or_controller")]
+#[candid_method(rename = "cos_monitorConsensusParams")]
+pub async fn cos_monitor_consensus_params(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+) -> RpcResult<CachedConsensusParams> {
+    let cached = poll_consensus_params(source.clone(), config.clone()).await?;
+    start_consensus_params_monitor(source, config);
+    Ok(cached)
+}
+
+/// Returns the consensus parameters of the last poll of the consensus params monitor.
+#[query(name = "cos_getCachedConsensusParams")]
+#[candid_method(query, rename = "cos_getCachedConsensusParams")]
+fn cos_get_cached_consensus_params() -> RpcResult<CachedConsensusParams> {
+    cached_consensus_params()
+}
+
+#[update(name = "registerConsensusParamsSubscriber", guard = "require_manage_or_controller")]
+#[candid_method(rename = "registerConsensusParamsSubscriber")]
+fn register_consensus_params_subscriber(canister: Principal, callback_method: String) -> RpcResult<()> {
+    do_register_consensus_params_subscriber(canister, callback_method)
+}
+
+#[update(name = "unregisterConsensusParamsSubscriber", guard = "require_manage_or_controller")]
+#[candid_method(rename = "unregisterConsensusParamsSubscriber")]
+fn unregister_consensus_params_subscriber(canister: Principal) -> bool {
+    do_unregister_consensus_params_subscriber(canister)
 }
 
 /// Sends a JSON-RPC request to a specified Cosmos node provider,
@@ -307,6 +794,18 @@ fn deauthorize(principal: Principal, auth: Auth) -> bool {
     do_deauthorize(principal, auth)
 }
 
+#[query(name = "getResponseFilters")]
+#[candid_method(query, rename = "getResponseFilters")]
+fn get_response_filters() -> Vec<NonDeterministicFieldFilter> {
+    response_filters()
+}
+
+#[update(name = "setResponseFilters", guard = "require_controller")]
+#[candid_method(rename = "setResponseFilters")]
+fn set_response_filters(filters: Vec<NonDeterministicFieldFilter>) {
+    do_set_response_filters(ic_cdk::caller(), filters)
+}
+
 #[query]
 fn http_request(request: AssetHttpRequest) -> AssetHttpResponse {
     match request.path() {
@@ -322,7 +821,7 @@ fn get_metrics() -> Metrics {
     read_metrics(|m| m.to_owned())
 }
 
-/// Cleans up the HTTP response headers to make them deterministic.
+/// Cleans up the HTTP response headers and the filtered fields of the body to make them deterministic.
 ///
 /// # Arguments
 ///
@@ -333,6 +832,8 @@ fn __transform_json_rpc(mut args: TransformArgs) -> HttpResponse {
     // consensus! Errors seem deterministic and do not contain data that can break consensus.
     // Clear non-deterministic fields from the response headers.
     args.response.headers.clear();
+    // Some fields of the body depend on the time of the request, see the response filters.
+    args.response.body = apply_response_filters(args.response.body, &response_filters());
     args.response
 }
 
@@ -343,7 +844,15 @@ fn init(args: InitArgs) {
 
 #[ic_cdk::post_upgrade]
 fn post_upgrade(args: InitArgs) {
+    let config_canister = args.config_canister;
     replace_state(args.into());
+    restart_governance_monitor();
+    restart_height_watcher();
+    restart_block_subscriptions();
+    restart_consensus_params_monitor();
+    if let Some(config_canister) = config_canister {
+        schedule_load_known_chains(config_canister);
+    }
 }
 
 fn main() {}
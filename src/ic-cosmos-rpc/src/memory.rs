@@ -2,21 +2,58 @@ use std::cell::RefCell;
 
 use ic_stable_structures::{
     memory_manager::{MemoryId, MemoryManager, VirtualMemory},
-    DefaultMemoryImpl, StableBTreeMap,
+    Cell, DefaultMemoryImpl, StableBTreeMap,
 };
 
 use crate::{
     auth::AuthSet,
     providers::{ProviderId, RpcProvider},
-    types::PrincipalStorable,
+    response_filters::default_response_filters,
+    types::{
+        BlockSubscription, CircuitBreakerStorable, GovernanceMonitorStorable, HeightWatcherStorable,
+        PolledSourceStorable, PrincipalStorable, ProposalStorable, ResponseFiltersStorable,
+    },
 };
 
 const AUTH_MEMORY_ID: MemoryId = MemoryId::new(2);
 const PROVIDERS_MEMORY_ID: MemoryId = MemoryId::new(3);
+const PROPOSALS_MEMORY_ID: MemoryId = MemoryId::new(4);
+const CIRCUIT_BREAKERS_MEMORY_ID: MemoryId = MemoryId::new(5);
+const RESPONSE_FILTERS_MEMORY_ID: MemoryId = MemoryId::new(6);
+const GOVERNANCE_SUBSCRIBERS_MEMORY_ID: MemoryId = MemoryId::new(7);
+const GOVERNANCE_MONITOR_MEMORY_ID: MemoryId = MemoryId::new(8);
+const HEIGHT_WATCHERS_MEMORY_ID: MemoryId = MemoryId::new(9);
+const HEIGHT_WATCHER_MEMORY_ID: MemoryId = MemoryId::new(10);
+const BLOCK_SUBSCRIBERS_MEMORY_ID: MemoryId = MemoryId::new(11);
+const BLOCK_SUBSCRIPTION_SOURCE_MEMORY_ID: MemoryId = MemoryId::new(12);
+const CONSENSUS_PARAMS_SUBSCRIBERS_MEMORY_ID: MemoryId = MemoryId::new(13);
+const CONSENSUS_PARAMS_MONITOR_MEMORY_ID: MemoryId = MemoryId::new(14);
 
 pub type StableMemory = VirtualMemory<DefaultMemoryImpl>;
 pub type AuthMemory = StableBTreeMap<PrincipalStorable, AuthSet, StableMemory>;
+/// Registered providers, stored directly in stable memory: they survive upgrades without a `pre_upgrade` hook.
 pub type ProvidersMemory = StableBTreeMap<ProviderId, RpcProvider, StableMemory>;
+pub type ProposalsMemory = StableBTreeMap<u64, ProposalStorable, StableMemory>;
+/// Circuit breakers of the providers which returned 5xx responses, keyed by provider URL.
+pub type CircuitBreakersMemory = StableBTreeMap<String, CircuitBreakerStorable, StableMemory>;
+/// Filters of the non-deterministic fields of the responses, the default filters until controllers set them.
+pub type ResponseFiltersMemory = Cell<ResponseFiltersStorable, StableMemory>;
+/// Canisters notified of proposal status transitions, mapped to the callback method.
+pub type GovernanceSubscribersMemory = StableBTreeMap<PrincipalStorable, String, StableMemory>;
+/// Arguments of the running governance monitor, which is restarted with them after upgrades.
+pub type GovernanceMonitorMemory = Cell<GovernanceMonitorStorable, StableMemory>;
+/// Canisters notified when the latest height advances, mapped to the callback method.
+pub type HeightWatchersMemory = StableBTreeMap<PrincipalStorable, String, StableMemory>;
+/// Arguments of the running height watcher, which is restarted with them after upgrades.
+pub type HeightWatcherMemory = Cell<HeightWatcherStorable, StableMemory>;
+/// Canisters called with the headers of new blocks.
+pub type BlockSubscribersMemory = StableBTreeMap<PrincipalStorable, BlockSubscription, StableMemory>;
+/// Source polled for the new blocks while there are block subscribers, restarted after upgrades.
+pub type BlockSubscriptionSourceMemory = Cell<PolledSourceStorable, StableMemory>;
+/// Canisters notified of consensus parameter changes, mapped to the callback method.
+pub type ConsensusParamsSubscribersMemory = StableBTreeMap<PrincipalStorable, String, StableMemory>;
+/// Source of the running consensus params monitor, which is restarted with it after upgrades.
+pub type ConsensusParamsMonitorMemory = Cell<PolledSourceStorable, StableMemory>;
 
 thread_local! {
     // Stable static data: these are preserved when the canister is upgraded.
@@ -35,3 +72,64 @@ pub fn init_auth_memory() -> AuthMemory {
 pub fn init_providers_memory() -> ProvidersMemory {
     ProvidersMemory::init(get_memory(PROVIDERS_MEMORY_ID))
 }
+
+pub fn init_proposals_memory() -> ProposalsMemory {
+    ProposalsMemory::init(get_memory(PROPOSALS_MEMORY_ID))
+}
+
+pub fn init_circuit_breakers_memory() -> CircuitBreakersMemory {
+    CircuitBreakersMemory::init(get_memory(CIRCUIT_BREAKERS_MEMORY_ID))
+}
+
+pub fn init_response_filters_memory() -> ResponseFiltersMemory {
+    ResponseFiltersMemory::init(
+        get_memory(RESPONSE_FILTERS_MEMORY_ID),
+        ResponseFiltersStorable(default_response_filters()),
+    )
+    .expect("Failed to initialize the response filters memory")
+}
+
+pub fn init_governance_subscribers_memory() -> GovernanceSubscribersMemory {
+    GovernanceSubscribersMemory::init(get_memory(GOVERNANCE_SUBSCRIBERS_MEMORY_ID))
+}
+
+pub fn init_governance_monitor_memory() -> GovernanceMonitorMemory {
+    GovernanceMonitorMemory::init(
+        get_memory(GOVERNANCE_MONITOR_MEMORY_ID),
+        GovernanceMonitorStorable(None),
+    )
+    .expect("Failed to initialize the governance monitor memory")
+}
+
+pub fn init_height_watchers_memory() -> HeightWatchersMemory {
+    HeightWatchersMemory::init(get_memory(HEIGHT_WATCHERS_MEMORY_ID))
+}
+
+pub fn init_height_watcher_memory() -> HeightWatcherMemory {
+    HeightWatcherMemory::init(get_memory(HEIGHT_WATCHER_MEMORY_ID), HeightWatcherStorable(None))
+        .expect("Failed to initialize the height watcher memory")
+}
+
+pub fn init_block_subscribers_memory() -> BlockSubscribersMemory {
+    BlockSubscribersMemory::init(get_memory(BLOCK_SUBSCRIBERS_MEMORY_ID))
+}
+
+pub fn init_block_subscription_source_memory() -> BlockSubscriptionSourceMemory {
+    BlockSubscriptionSourceMemory::init(
+        get_memory(BLOCK_SUBSCRIPTION_SOURCE_MEMORY_ID),
+        PolledSourceStorable(None),
+    )
+    .expect("Failed to initialize the block subscription source memory")
+}
+
+pub fn init_consensus_params_subscribers_memory() -> ConsensusParamsSubscribersMemory {
+    ConsensusParamsSubscribersMemory::init(get_memory(CONSENSUS_PARAMS_SUBSCRIBERS_MEMORY_ID))
+}
+
+pub fn init_consensus_params_monitor_memory() -> ConsensusParamsMonitorMemory {
+    ConsensusParamsMonitorMemory::init(
+        get_memory(CONSENSUS_PARAMS_MONITOR_MEMORY_ID),
+        PolledSourceStorable(None),
+    )
+    .expect("Failed to initialize the consensus params monitor memory")
+}
@@ -9,7 +9,7 @@ use serde::Serialize;
 
 use crate::{
     auth::{do_deauthorize, is_authorized, Auth},
-    constants::PROVIDER_ID_MAX_SIZE,
+    constants::{PROVIDER_ID_MAX_SIZE, RPC_PROVIDER_MAX_SIZE},
     state::{mutate_state, read_state},
     types::{RegisterProviderArgs, RpcAuth, UpdateProviderArgs},
     utils::{hostname_from_url, validate_hostname},
@@ -59,16 +59,29 @@ impl RpcProvider {
             }
         }
 
-        RpcApi { network: url, headers }
+        RpcApi {
+            network: url,
+            headers,
+            endpoint_type: None,
+        }
     }
 
     pub fn validate(&self) {
+        if let Err(e) = self.check() {
+            ic_cdk::trap(&e);
+        }
+    }
+
+    /// Checks the hostname of the provider and that it fits in its stable memory bound
+    pub fn check(&self) -> Result<(), String> {
         match hostname_from_url(&self.url) {
-            Some(hostname) => validate_hostname(&hostname).unwrap(),
-            None => {
-                ic_cdk::trap(&format!("Invalid RPC URL: {}", self.url));
-            }
+            Some(hostname) => validate_hostname(&hostname)?,
+            None => return Err(format!("Invalid RPC URL: {}", self.url)),
         }
+        if self.to_bytes().len() > RPC_PROVIDER_MAX_SIZE as usize {
+            return Err(format!("Provider larger than {} bytes", RPC_PROVIDER_MAX_SIZE));
+        }
+        Ok(())
     }
 }
 
@@ -81,7 +94,7 @@ impl Storable for RpcProvider {
     }
 
     const BOUND: Bound = Bound::Bounded {
-        max_size: 1000,
+        max_size: RPC_PROVIDER_MAX_SIZE,
         is_fixed_size: false,
     };
 }
@@ -180,3 +193,30 @@ pub fn do_update_provider(caller: Principal, args: UpdateProviderArgs) {
         None => ic_cdk::trap("Provider not found"),
     });
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_provider() {
+        let provider = RpcProvider {
+            url: "https://rpc.cosmos.network".to_string(),
+            auth: None,
+            owner: Principal::anonymous(),
+        };
+        assert_eq!(provider.check(), Ok(()));
+
+        let invalid_url = RpcProvider {
+            url: "not a url".to_string(),
+            ..provider.clone()
+        };
+        assert!(invalid_url.check().is_err());
+
+        let too_large = RpcProvider {
+            url: format!("https://rpc.cosmos.network/{}", "a".repeat(1000)),
+            ..provider
+        };
+        assert!(too_large.check().is_err());
+    }
+}
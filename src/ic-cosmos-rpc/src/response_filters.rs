@@ -0,0 +1,247 @@
+use candid::{CandidType, Deserialize, Principal};
+use ic_canister_log::log;
+use ic_cosmos::logs::INFO;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    state::{mutate_state, read_state},
+    types::ResponseFiltersStorable,
+};
+
+/// Maximum number of filters applied to the responses of the RPC providers
+pub const MAX_RESPONSE_FILTERS: usize = 64;
+
+/// What a [`NonDeterministicFieldFilter`] does with the fields it matches
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CandidType, Serialize, Deserialize)]
+pub enum FilterAction {
+    /// Removes the field from its object or array
+    Remove,
+    /// Replaces the field with the zero value of its type: `0`, `""`, `false`, recursively for
+    /// objects and arrays. The response still decodes to the same type.
+    Zero,
+}
+
+/// Filter of a field of the JSON responses which differs between the replicas making the outcall,
+/// applied by `__transform_json_rpc`.
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Serialize, Deserialize)]
+pub struct NonDeterministicFieldFilter {
+    /// Dot-separated path of the field from the root of the response, e.g.
+    /// `result.node_info.moniker`. `*` matches every field of an object or element of an
+    /// array, a number the element at that index.
+    pub path: String,
+    pub action: FilterAction,
+}
+
+impl NonDeterministicFieldFilter {
+    pub fn new(path: &str, action: FilterAction) -> Self {
+        Self {
+            path: path.to_string(),
+            action,
+        }
+    }
+
+    /// Applies the filter to `value`, returns whether a field matched
+    pub fn apply(&self, value: &mut Value) -> bool {
+        let path = self.path.split('.').collect::<Vec<_>>();
+        filter_path(value, &path, self.action)
+    }
+}
+
+/// Filters of the fields of Cosmos node responses which differ between the nodes behind a provider
+/// or depend on the time of the request or on the connections of the node. Signed fields, such as
+/// the `timestamp` of commit signatures, are left untouched, as is the `sync_info` of `status`,
+/// which tells whether the node is catching up.
+pub fn default_response_filters() -> Vec<NonDeterministicFieldFilter> {
+    vec![
+        // `status`
+        NonDeterministicFieldFilter::new("result.node_info.id", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.node_info.listen_addr", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.node_info.moniker", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.node_info.other.rpc_address", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.validator_info", FilterAction::Zero),
+        // `net_info`
+        NonDeterministicFieldFilter::new("result.n_peers", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.peers.*.connection_status", FilterAction::Zero),
+        // `dump_consensus_state` and `consensus_state`
+        NonDeterministicFieldFilter::new("result.round_state.start_time", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.round_state.commit_time", FilterAction::Zero),
+        NonDeterministicFieldFilter::new("result.peers.*.peer_state.round_state.start_time", FilterAction::Zero),
+    ]
+}
+
+/// Returns the response `body` with the `filters` applied, unchanged if it is not JSON or if no
+/// filter matched
+pub fn apply_response_filters(body: Vec<u8>, filters: &[NonDeterministicFieldFilter]) -> Vec<u8> {
+    if filters.is_empty() {
+        return body;
+    }
+    let Ok(mut value) = serde_json::from_slice::<Value>(&body) else {
+        return body;
+    };
+    let mut matched = false;
+    for filter in filters {
+        matched |= filter.apply(&mut value);
+    }
+    if !matched {
+        return body;
+    }
+    serde_json::to_vec(&value).unwrap_or(body)
+}
+
+fn filter_path(value: &mut Value, path: &[&str], action: FilterAction) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        return false;
+    };
+    if !rest.is_empty() {
+        let mut matched = false;
+        for child in children_mut(value, segment) {
+            matched |= filter_path(child, rest, action);
+        }
+        return matched;
+    }
+    match action {
+        FilterAction::Zero => {
+            let children = children_mut(value, segment);
+            let matched = !children.is_empty();
+            children.into_iter().for_each(zero_value);
+            matched
+        }
+        FilterAction::Remove => match value {
+            Value::Object(map) if *segment == "*" => {
+                let matched = !map.is_empty();
+                map.clear();
+                matched
+            }
+            Value::Object(map) => map.remove(*segment).is_some(),
+            Value::Array(array) if *segment == "*" => {
+                let matched = !array.is_empty();
+                array.clear();
+                matched
+            }
+            Value::Array(array) => match segment.parse::<usize>() {
+                Ok(index) if index < array.len() => {
+                    array.remove(index);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        },
+    }
+}
+
+/// Returns the fields of `value` matched by the path `segment`
+fn children_mut<'a>(value: &'a mut Value, segment: &str) -> Vec<&'a mut Value> {
+    match value {
+        Value::Object(map) => match segment {
+            "*" => map.values_mut().collect(),
+            _ => map.get_mut(segment).into_iter().collect(),
+        },
+        Value::Array(array) => match segment {
+            "*" => array.iter_mut().collect(),
+            _ => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| array.get_mut(index))
+                .into_iter()
+                .collect(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn zero_value(value: &mut Value) {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => *b = false,
+        Value::Number(n) => *n = 0u64.into(),
+        Value::String(s) => s.clear(),
+        Value::Array(array) => array.iter_mut().for_each(zero_value),
+        Value::Object(map) => map.values_mut().for_each(zero_value),
+    }
+}
+
+/// Returns the filters applied to the responses of the RPC providers
+pub fn response_filters() -> Vec<NonDeterministicFieldFilter> {
+    read_state(|s| s.response_filters.get().0.clone())
+}
+
+/// Replaces the filters applied to the responses of the RPC providers
+pub fn do_set_response_filters(caller: Principal, filters: Vec<NonDeterministicFieldFilter>) {
+    if filters.len() > MAX_RESPONSE_FILTERS {
+        ic_cdk::trap(&format!(
+            "Too many response filters, the maximum is {}",
+            MAX_RESPONSE_FILTERS
+        ));
+    }
+    if let Some(filter) = filters.iter().find(|filter| filter.path.split('.').any(str::is_empty)) {
+        ic_cdk::trap(&format!("Invalid response filter path: {:?}", filter.path));
+    }
+    log!(INFO, "[{}] Setting {} response filters", caller, filters.len());
+    mutate_state(|s| {
+        s.response_filters
+            .set(ResponseFiltersStorable(filters))
+            .expect("Failed to store the response filters")
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_response_filters() {
+        let status = |moniker: &str, peers: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "node_info": { "network": "cosmoshub-4", "moniker": moniker },
+                    "sync_info": {
+                        "latest_block_height": "100",
+                        "latest_block_time": "2024-01-15T12:34:56Z",
+                        "catching_up": false
+                    },
+                    "n_peers": peers,
+                }
+            })
+        };
+        let filters = default_response_filters();
+        let first = apply_response_filters(serde_json::to_vec(&status("node-a", "40")).unwrap(), &filters);
+        let second = apply_response_filters(serde_json::to_vec(&status("node-b", "41")).unwrap(), &filters);
+        assert_eq!(first, second);
+        // The chain ID and the sync info are kept
+        assert_eq!(serde_json::from_slice::<Value>(&first).unwrap(), status("", ""));
+
+        let mut peers = json!({
+            "peers": [
+                {
+                    "remote_ip": "1.2.3.4",
+                    "connection_status": { "Duration": "12", "SendMonitor": { "Active": true } }
+                },
+                {
+                    "remote_ip": "5.6.7.8",
+                    "connection_status": { "Duration": "34", "SendMonitor": { "Active": true } }
+                },
+            ]
+        });
+        assert!(NonDeterministicFieldFilter::new("peers.*.connection_status", FilterAction::Zero).apply(&mut peers));
+        assert_eq!(
+            peers["peers"][1]["connection_status"],
+            json!({ "Duration": "", "SendMonitor": { "Active": false } })
+        );
+        assert!(NonDeterministicFieldFilter::new("peers.0.remote_ip", FilterAction::Remove).apply(&mut peers));
+        assert_eq!(peers["peers"][0].get("remote_ip"), None);
+        assert_eq!(peers["peers"][1]["remote_ip"], "5.6.7.8");
+        assert!(NonDeterministicFieldFilter::new("peers.*", FilterAction::Remove).apply(&mut peers));
+        assert_eq!(peers, json!({ "peers": [] }));
+        assert!(!NonDeterministicFieldFilter::new("result.n_peers", FilterAction::Zero).apply(&mut peers));
+
+        let body = br#"{"result":"ok","id":1}"#.to_vec();
+        assert_eq!(apply_response_filters(body.clone(), &filters), body);
+        assert_eq!(apply_response_filters(b"not json".to_vec(), &filters), b"not json");
+    }
+}
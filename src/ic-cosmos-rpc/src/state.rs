@@ -1,13 +1,26 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::BTreeMap};
 
 use candid::{CandidType, Deserialize, Principal};
-use ic_cosmos::types::Cluster;
+use ic_cdk_timers::TimerId;
+use ic_cosmos::{
+    rpc_client::RateLimiter,
+    types::{ChainConfig, Cluster},
+};
 
 use crate::{
     auth::{Auth, AuthSet},
-    memory::{init_auth_memory, init_providers_memory, AuthMemory, ProvidersMemory},
+    memory::{
+        init_auth_memory, init_block_subscribers_memory, init_block_subscription_source_memory,
+        init_circuit_breakers_memory, init_consensus_params_monitor_memory, init_consensus_params_subscribers_memory,
+        init_governance_monitor_memory, init_governance_subscribers_memory, init_height_watcher_memory,
+        init_height_watchers_memory, init_proposals_memory, init_providers_memory, init_response_filters_memory,
+        AuthMemory, BlockSubscribersMemory, BlockSubscriptionSourceMemory, CircuitBreakersMemory,
+        ConsensusParamsMonitorMemory, ConsensusParamsSubscribersMemory, GovernanceMonitorMemory,
+        GovernanceSubscribersMemory, HeightWatcherMemory, HeightWatchersMemory, ProposalsMemory, ProvidersMemory,
+        ResponseFiltersMemory,
+    },
     providers::{ProviderId, RpcProvider},
-    types::PrincipalStorable,
+    types::{CachedConsensusParams, CachedGasPrices, PrincipalStorable},
 };
 
 thread_local! {
@@ -15,6 +28,27 @@ thread_local! {
         auth: init_auth_memory(),
         rpc_providers: init_providers_memory(),
         is_demo_active: false,
+        latest_height: 0,
+        height_watchers: init_height_watchers_memory(),
+        height_watcher: init_height_watcher_memory(),
+        height_watch_timer: None,
+        known_chains: BTreeMap::new(),
+        monitored_proposals: init_proposals_memory(),
+        governance_subscribers: init_governance_subscribers_memory(),
+        governance_monitor: init_governance_monitor_memory(),
+        governance_monitor_timer: None,
+        block_subscribers: init_block_subscribers_memory(),
+        block_subscription_source: init_block_subscription_source_memory(),
+        block_subscription_height: 0,
+        block_subscription_timer: None,
+        rate_limit: None,
+        min_gas_prices: BTreeMap::new(),
+        circuit_breakers: init_circuit_breakers_memory(),
+        consensus_params: None,
+        consensus_params_subscribers: init_consensus_params_subscribers_memory(),
+        consensus_params_monitor: init_consensus_params_monitor_memory(),
+        consensus_params_timer: None,
+        response_filters: init_response_filters_memory(),
     }));
 }
 
@@ -23,12 +57,59 @@ thread_local! {
 pub struct InitArgs {
     pub demo: Option<bool>,
     pub managers: Option<Vec<Principal>>,
+    /// Config canister whose chains are registered as providers on init and upgrade.
+    pub config_canister: Option<Principal>,
+    /// Limits of the HTTP outcalls to the RPC providers, unlimited if not set.
+    pub rate_limit: Option<RateLimiter>,
 }
 
 pub struct State {
     pub auth: AuthMemory,
     pub rpc_providers: ProvidersMemory,
     pub is_demo_active: bool,
+    /// The latest block height seen by the height watcher.
+    pub latest_height: u64,
+    /// Canisters notified when the latest height advances, mapped to the callback method.
+    pub height_watchers: HeightWatchersMemory,
+    /// Arguments of the height watcher, if it was started.
+    pub height_watcher: HeightWatcherMemory,
+    /// The timer polling `cos_getStatus`, if the height watcher is running.
+    pub height_watch_timer: Option<TimerId>,
+    /// Chain configurations loaded from the config canister, keyed by chain ID.
+    pub known_chains: BTreeMap<String, ChainConfig>,
+    /// Governance proposals seen by the governance monitor, keyed by proposal ID.
+    pub monitored_proposals: ProposalsMemory,
+    /// Canisters notified of proposal status transitions, mapped to the callback method.
+    pub governance_subscribers: GovernanceSubscribersMemory,
+    /// Arguments of the governance monitor, if it was started.
+    pub governance_monitor: GovernanceMonitorMemory,
+    /// The timer polling the governance proposals, if the governance monitor is running.
+    pub governance_monitor_timer: Option<TimerId>,
+    /// Canisters called with the headers of new blocks.
+    pub block_subscribers: BlockSubscribersMemory,
+    /// Source polled for the new blocks, set while there are block subscribers.
+    pub block_subscription_source: BlockSubscriptionSourceMemory,
+    /// The height of the last block whose header was sent to the block subscribers,
+    /// reset on upgrade: the first poll after an upgrade starts at the latest block.
+    pub block_subscription_height: u64,
+    /// The timer polling the new blocks, if there are block subscribers.
+    pub block_subscription_timer: Option<TimerId>,
+    /// Limits of the HTTP outcalls to the RPC providers, unlimited if not set.
+    pub rate_limit: Option<RateLimiter>,
+    /// Minimum gas prices of the providers, keyed by provider.
+    pub min_gas_prices: BTreeMap<String, CachedGasPrices>,
+    /// Circuit breakers of the providers, keyed by provider URL.
+    pub circuit_breakers: CircuitBreakersMemory,
+    /// Consensus parameters of the last poll of the consensus params monitor.
+    pub consensus_params: Option<CachedConsensusParams>,
+    /// Canisters notified of consensus parameter changes, mapped to the callback method.
+    pub consensus_params_subscribers: ConsensusParamsSubscribersMemory,
+    /// Source of the consensus params monitor, if it was started.
+    pub consensus_params_monitor: ConsensusParamsMonitorMemory,
+    /// The timer polling the consensus parameters, if the consensus params monitor is running.
+    pub consensus_params_timer: Option<TimerId>,
+    /// Filters of the non-deterministic fields of the responses, applied by `__transform_json_rpc`.
+    pub response_filters: ResponseFiltersMemory,
     // pub hosts_blocklist: Vec<String>,
 }
 
@@ -71,6 +152,27 @@ impl From<InitArgs> for State {
                 auth,
                 rpc_providers,
                 is_demo_active: value.demo.unwrap_or(false),
+                latest_height: s.latest_height,
+                height_watchers: s.height_watchers,
+                height_watcher: s.height_watcher,
+                height_watch_timer: s.height_watch_timer,
+                known_chains: s.known_chains,
+                monitored_proposals: s.monitored_proposals,
+                governance_subscribers: s.governance_subscribers,
+                governance_monitor: s.governance_monitor,
+                governance_monitor_timer: s.governance_monitor_timer,
+                block_subscribers: s.block_subscribers,
+                block_subscription_source: s.block_subscription_source,
+                block_subscription_height: s.block_subscription_height,
+                block_subscription_timer: s.block_subscription_timer,
+                rate_limit: value.rate_limit,
+                min_gas_prices: s.min_gas_prices,
+                circuit_breakers: s.circuit_breakers,
+                consensus_params: s.consensus_params,
+                consensus_params_subscribers: s.consensus_params_subscribers,
+                consensus_params_monitor: s.consensus_params_monitor,
+                consensus_params_timer: s.consensus_params_timer,
+                response_filters: s.response_filters,
                 // hosts_blocklist: value.hosts_blocklist.unwrap_or_default(),
             }
         })
@@ -80,6 +182,7 @@ impl From<InitArgs> for State {
 impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Demo active: {:?}", self.is_demo_active)?;
+        writeln!(f, "Latest height: {}", self.latest_height)?;
         writeln!(f, "Auth:")?;
         for (principal, auth) in self.auth.iter() {
             writeln!(f, "  - {}: {:?}", principal.0, auth)?;
@@ -1,9 +1,15 @@
 use std::borrow::Cow;
 
-use candid::{CandidType, Deserialize, Principal};
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+use ic_cosmos::{
+    rpc_client::{ProviderCircuitBreaker, RpcConfig, RpcServices},
+    types::{ConsensusParamChange, ConsensusParamsResult, GasPrice, ProposalSummary},
+};
 use ic_stable_structures::{storable::Bound, Storable};
 use serde::Serialize;
 
+use crate::response_filters::NonDeterministicFieldFilter;
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PrincipalStorable(pub Principal);
 
@@ -22,6 +28,163 @@ impl Storable for PrincipalStorable {
     };
 }
 
+/// Governance proposal tracked by the governance monitor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProposalStorable(pub ProposalSummary);
+
+impl Storable for ProposalStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, ProposalSummary).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+pub struct CircuitBreakerStorable(pub ProviderCircuitBreaker);
+
+impl Storable for CircuitBreakerStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, ProviderCircuitBreaker).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Filters of the non-deterministic fields of the responses of the RPC providers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseFiltersStorable(pub Vec<NonDeterministicFieldFilter>);
+
+impl Storable for ResponseFiltersStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, Vec<NonDeterministicFieldFilter>).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Arguments of `cos_monitorGovernanceProposals`, kept to restart the governance monitor after upgrades
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Deserialize)]
+pub struct GovernanceMonitor {
+    pub source: RpcServices,
+    pub config: Option<RpcConfig>,
+    pub limit: Option<u64>,
+    pub interval_seconds: u64,
+}
+
+/// The running governance monitor, `None` if it was never started
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceMonitorStorable(pub Option<GovernanceMonitor>);
+
+impl Storable for GovernanceMonitorStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, Option<GovernanceMonitor>).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Arguments of `cos_watchLatestHeight`, kept to restart the height watcher after upgrades
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Deserialize)]
+pub struct HeightWatcher {
+    pub source: RpcServices,
+    pub config: Option<RpcConfig>,
+    pub interval_seconds: u64,
+}
+
+/// The running height watcher, `None` if it was never started
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeightWatcherStorable(pub Option<HeightWatcher>);
+
+impl Storable for HeightWatcherStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, Option<HeightWatcher>).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Source polled by the block subscriptions or the consensus params monitor,
+/// kept to restart their timer after upgrades
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Deserialize)]
+pub struct PolledSource {
+    pub source: RpcServices,
+    pub config: Option<RpcConfig>,
+}
+
+/// The polled source, `None` if the polling is stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolledSourceStorable(pub Option<PolledSource>);
+
+impl Storable for PolledSourceStorable {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(&self.0).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(Decode!(&bytes, Option<PolledSource>).unwrap())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Subscription of a canister to the headers of new blocks
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Deserialize)]
+pub struct BlockSubscription {
+    pub callback_method: String,
+    /// Maximum number of headers passed to a single callback
+    pub max_headers_per_call: u32,
+}
+
+impl Storable for BlockSubscription {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        Cow::Owned(Encode!(self).unwrap())
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Decode!(&bytes, BlockSubscription).unwrap()
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Minimum gas prices of a provider, as cached by `cos_getMinGasPrices`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedGasPrices {
+    pub prices: Vec<GasPrice>,
+    /// Time of the query, in nanoseconds since the Unix epoch
+    pub fetched_at: u64,
+}
+
+/// Consensus parameters cached by the consensus params monitor
+#[derive(Clone, Debug, PartialEq, Eq, CandidType, Deserialize, Serialize)]
+pub struct CachedConsensusParams {
+    pub params: ConsensusParamsResult,
+    /// Time of the last poll, in nanoseconds since the Unix epoch
+    pub updated_at: u64,
+    /// Parameters changed since the previous poll, empty on the first poll
+    pub changes: Vec<ConsensusParamChange>,
+}
+
 #[derive(Debug, CandidType, Deserialize, Serialize)]
 pub struct SendTransactionRequest {
     pub instructions: Vec<String>,
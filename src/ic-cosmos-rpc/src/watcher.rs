@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use candid::Principal;
+use ic_canister_log::log;
+use ic_cosmos::{
+    logs::INFO,
+    rpc_client::{RpcConfig, RpcError, RpcResult, RpcServices},
+    types::{HeaderResult, Status},
+};
+
+use crate::{
+    constants::{BLOCK_POLL_INTERVAL_SECONDS, MAX_HEADERS_PER_POLL},
+    http::rpc_client,
+    state::{mutate_state, read_state},
+    types::{
+        BlockSubscription, HeightWatcher, HeightWatcherStorable, PolledSource, PolledSourceStorable, PrincipalStorable,
+    },
+};
+
+/// Fetches the latest block height via `status`, stores it in the canister state
+/// and notifies the registered watchers if the height has advanced.
+pub async fn poll_latest_height(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<u64> {
+    let status = rpc_client(source, config).get_status().await?;
+    let height = latest_block_height(&status)?;
+
+    let advanced = mutate_state(|s| {
+        if height > s.latest_height {
+            s.latest_height = height;
+            true
+        } else {
+            false
+        }
+    });
+
+    if advanced {
+        notify_height_watchers(height);
+    }
+
+    Ok(height)
+}
+
+/// Starts polling the latest block height every `interval_seconds`,
+/// replacing the previously running watcher timer if any.
+/// The arguments are kept in stable memory to restart the watcher after upgrades.
+pub fn start_height_watcher(source: RpcServices, config: Option<RpcConfig>, interval_seconds: u64) {
+    let watcher = HeightWatcher {
+        source: source.clone(),
+        config: config.clone(),
+        interval_seconds,
+    };
+    let timer_id = ic_cdk_timers::set_timer_interval(Duration::from_secs(interval_seconds), move || {
+        let source = source.clone();
+        let config = config.clone();
+        ic_cdk::spawn(async move {
+            if let Err(e) = poll_latest_height(source, config).await {
+                log!(INFO, "[height watcher] Failed to poll the latest height: {}", e);
+            }
+        });
+    });
+
+    let previous = mutate_state(|s| {
+        s.height_watcher
+            .set(HeightWatcherStorable(Some(watcher)))
+            .expect("Failed to save the height watcher");
+        s.height_watch_timer.replace(timer_id)
+    });
+    if let Some(previous) = previous {
+        ic_cdk_timers::clear_timer(previous);
+    }
+}
+
+/// Restarts the height watcher with the arguments it was last started with, as the timers
+/// do not survive upgrades.
+pub fn restart_height_watcher() {
+    if let Some(watcher) = read_state(|s| s.height_watcher.get().0.clone()) {
+        start_height_watcher(watcher.source, watcher.config, watcher.interval_seconds);
+    }
+}
+
+/// Registers the `method` of `canister` to be notified with the new height
+/// every time the latest block height advances.
+pub fn do_register_height_watcher(canister: Principal, method: String) -> RpcResult<()> {
+    if method.is_empty() {
+        return Err(RpcError::ValidationError(
+            "Callback method must not be empty".to_string(),
+        ));
+    }
+    log!(
+        INFO,
+        "[{}] Registering height watcher: {} {}",
+        ic_cdk::caller(),
+        canister,
+        method
+    );
+    mutate_state(|s| s.height_watchers.insert(PrincipalStorable(canister), method));
+    Ok(())
+}
+
+/// Unregisters the height watcher of `canister`.
+pub fn do_unregister_height_watcher(canister: Principal) -> bool {
+    log!(
+        INFO,
+        "[{}] Unregistering height watcher: {}",
+        ic_cdk::caller(),
+        canister
+    );
+    mutate_state(|s| s.height_watchers.remove(&PrincipalStorable(canister)).is_some())
+}
+
+fn notify_height_watchers(height: u64) {
+    let watchers: Vec<(Principal, String)> = read_state(|s| {
+        s.height_watchers
+            .iter()
+            .map(|(canister, method)| (canister.0, method))
+            .collect()
+    });
+    for (canister, method) in watchers {
+        if let Err(code) = ic_cdk::notify(canister, &method, (height,)) {
+            log!(
+                INFO,
+                "[height watcher] Failed to notify {} {} (height: {}): {:?}",
+                canister,
+                method,
+                height,
+                code
+            );
+        }
+    }
+}
+
+/// Subscribes the `callback_method` of `subscriber` to the headers of new blocks, polled from
+/// `source` every [`BLOCK_POLL_INTERVAL_SECONDS`]. The callback receives a `Vec<HeaderResult>`
+/// of at most `max_headers_per_call` headers, by ascending height.
+///
+/// All the subscribers share the polled source: subscribing with another `source` or `config`
+/// fails until every subscriber has unsubscribed.
+pub fn do_subscribe_to_new_blocks(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    subscriber: Principal,
+    callback_method: String,
+    max_headers_per_call: u32,
+) -> RpcResult<()> {
+    if callback_method.is_empty() {
+        return Err(RpcError::ValidationError(
+            "Callback method must not be empty".to_string(),
+        ));
+    }
+    if max_headers_per_call == 0 {
+        return Err(RpcError::ValidationError(
+            "max_headers_per_call must be greater than zero".to_string(),
+        ));
+    }
+    let polled = PolledSource { source, config };
+    if let Some(current) = read_state(|s| s.block_subscription_source.get().0.clone()) {
+        if current != polled {
+            return Err(RpcError::ValidationError(
+                "New blocks are already polled from another source".to_string(),
+            ));
+        }
+    }
+    log!(
+        INFO,
+        "[{}] Subscribing to new blocks: {} {}",
+        ic_cdk::caller(),
+        subscriber,
+        callback_method
+    );
+    mutate_state(|s| {
+        s.block_subscribers.insert(
+            PrincipalStorable(subscriber),
+            BlockSubscription {
+                callback_method,
+                max_headers_per_call,
+            },
+        )
+    });
+    start_block_subscription_timer(polled);
+    Ok(())
+}
+
+/// Unsubscribes `subscriber` from the headers of new blocks,
+/// stopping the polling once there are no subscribers left.
+pub fn do_unsubscribe_from_new_blocks(subscriber: Principal) -> bool {
+    log!(
+        INFO,
+        "[{}] Unsubscribing from new blocks: {}",
+        ic_cdk::caller(),
+        subscriber
+    );
+    let (removed, timer_id) = mutate_state(|s| {
+        let removed = s.block_subscribers.remove(&PrincipalStorable(subscriber)).is_some();
+        let timer_id = if s.block_subscribers.is_empty() {
+            s.block_subscription_height = 0;
+            s.block_subscription_source
+                .set(PolledSourceStorable(None))
+                .expect("Failed to clear the block subscription source");
+            s.block_subscription_timer.take()
+        } else {
+            None
+        };
+        (removed, timer_id)
+    });
+    if let Some(timer_id) = timer_id {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+    removed
+}
+
+/// Fetches the headers of the blocks produced since the previous poll, at most
+/// [`MAX_HEADERS_PER_POLL`], and sends them to the block subscribers.
+/// The first poll starts at the latest block.
+pub async fn poll_new_blocks(source: RpcServices, config: Option<RpcConfig>) -> RpcResult<()> {
+    let client = rpc_client(source, config);
+    let latest_height = latest_block_height(&client.get_status().await?)?;
+
+    // Reserve the range before fetching, so that overlapping polls do not send a header twice
+    let Some((previous_height, start, end)) = mutate_state(|s| {
+        let previous_height = s.block_subscription_height;
+        if latest_height <= previous_height {
+            return None;
+        }
+        let start = if previous_height == 0 {
+            latest_height
+        } else {
+            previous_height + 1
+        };
+        let end = latest_height.min(start + MAX_HEADERS_PER_POLL - 1);
+        s.block_subscription_height = end;
+        Some((previous_height, start, end))
+    }) else {
+        return Ok(());
+    };
+
+    let headers = futures::future::join_all((start..=end).map(|height| client.get_header(height.to_string())))
+        .await
+        .into_iter()
+        .collect::<RpcResult<Vec<HeaderResult>>>();
+    let headers = match headers {
+        Ok(headers) => headers,
+        Err(e) => {
+            mutate_state(|s| {
+                if s.block_subscription_height == end {
+                    s.block_subscription_height = previous_height;
+                }
+            });
+            return Err(e);
+        }
+    };
+
+    notify_block_subscribers(&headers).await;
+    Ok(())
+}
+
+/// Restarts polling the new blocks for the block subscribers, as the timers do not survive upgrades.
+pub fn restart_block_subscriptions() {
+    if let Some(polled) = read_state(|s| s.block_subscription_source.get().0.clone()) {
+        start_block_subscription_timer(polled);
+    }
+}
+
+fn start_block_subscription_timer(polled: PolledSource) {
+    let saved = polled.clone();
+    let timer_id = ic_cdk_timers::set_timer_interval(Duration::from_secs(BLOCK_POLL_INTERVAL_SECONDS), move || {
+        let source = polled.source.clone();
+        let config = polled.config.clone();
+        ic_cdk::spawn(async move {
+            if let Err(e) = poll_new_blocks(source, config).await {
+                log!(INFO, "[block subscription] Failed to poll the new blocks: {}", e);
+            }
+        });
+    });
+
+    let previous = mutate_state(|s| {
+        s.block_subscription_source
+            .set(PolledSourceStorable(Some(saved)))
+            .expect("Failed to save the block subscription source");
+        s.block_subscription_timer.replace(timer_id)
+    });
+    if let Some(previous) = previous {
+        ic_cdk_timers::clear_timer(previous);
+    }
+}
+
+async fn notify_block_subscribers(headers: &[HeaderResult]) {
+    let subscribers: Vec<(Principal, BlockSubscription)> = read_state(|s| {
+        s.block_subscribers
+            .iter()
+            .map(|(subscriber, subscription)| (subscriber.0, subscription))
+            .collect()
+    });
+    for (subscriber, subscription) in subscribers {
+        for batch in headers.chunks(subscription.max_headers_per_call as usize) {
+            let method = &subscription.callback_method;
+            if let Err((code, message)) = ic_cdk::call::<_, ()>(subscriber, method, (batch.to_vec(),)).await {
+                log!(
+                    INFO,
+                    "[block subscription] Failed to call {} {} ({} headers): {:?} {}",
+                    subscriber,
+                    method,
+                    batch.len(),
+                    code,
+                    message
+                );
+            }
+        }
+    }
+}
+
+pub fn latest_block_height(status: &Status) -> RpcResult<u64> {
+    status
+        .sync_info
+        .latest_block_height
+        .parse::<u64>()
+        .map_err(|e| RpcError::ParseError(format!("Invalid latest block height: {}", e)))
+}
@@ -20,6 +20,13 @@ thread_local! {
      static WASM: Vec<u8> = load_wasm(env!("CARGO_MANIFEST_DIR"), env!("CARGO_PKG_NAME"), &[]);
 }
 
+/// Returns the response captured by the `generate_fixtures` binary for the RPC `method`,
+/// `None` if the fixtures were not generated yet.
+pub fn load_fixture(method: &str) -> Option<String> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/fixtures/{}.json", method));
+    std::fs::read_to_string(path).ok()
+}
+
 /// Creates a mock update call.
 pub fn mock_update<A: ArgumentEncoder, R: DeserializeOwned + CandidType>(
     method: &str,
@@ -42,6 +49,8 @@ impl Default for CosmosRpcSetup {
         Self::new(InitArgs {
             demo: Some(true),
             managers: Some(vec![TestSetup::controller_id()]),
+            config_canister: None,
+            rate_limit: None,
         })
     }
 }
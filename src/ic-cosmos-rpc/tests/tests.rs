@@ -2,16 +2,27 @@ mod setup;
 
 use std::collections::HashMap;
 
+use candid::{utils::ArgumentEncoder, CandidType, Principal};
 use ic_cosmos::{
     metrics::{MetricRpcHost, Metrics},
     request::RpcRequest,
-    rpc_client::RpcServices,
-    types::Cluster,
+    rpc_client::{RpcApi, RpcError, RpcResult, RpcServices},
+    types::{
+        AbciInfo, BlockComplete, Cluster, CommitResult, ConsensusParamsResult, CosmosCoin, HeaderResult, NetInfo,
+        NumUnconfirmedTransactionsResult, ProposalStatus, ProposalTransition, Status,
+    },
 };
-use ic_cosmos_rpc::{auth::Auth, state::InitArgs, types::RegisterProviderArgs};
+use ic_cosmos_rpc::{
+    auth::Auth,
+    response_filters::{default_response_filters, FilterAction, NonDeterministicFieldFilter},
+    state::InitArgs,
+    types::RegisterProviderArgs,
+};
+use pocket_ic::common::rest::CanisterHttpMethod;
+use serde::de::DeserializeOwned;
 use test_utils::{MockOutcallBuilder, TestSetup};
 
-use crate::setup::{mock_update, CosmosRpcSetup, MOCK_RAW_TX};
+use crate::setup::{load_fixture, mock_update, CosmosRpcSetup, MOCK_RAW_TX};
 
 #[test]
 fn should_canonicalize_json_response() {
@@ -32,6 +43,58 @@ fn should_canonicalize_json_response() {
     assert!(responses.windows(2).all(|w| w[0] == w[1]));
 }
 
+#[test]
+fn should_filter_non_deterministic_fields() {
+    let setup = CosmosRpcSetup::default();
+    let status = r#"{"id":1,"jsonrpc":"2.0","result":{"node_info":{"moniker":"node-a"},"sync_info":{"latest_block_time":"2024-01-15T12:34:56Z"}}}"#;
+    let request = |setup: &CosmosRpcSetup| {
+        let response = setup
+            .request(RpcServices::Mainnet, "status", "", 1000)
+            .mock_http(MockOutcallBuilder::new(200, status))
+            .wait()
+            .unwrap();
+        serde_json::from_str::<serde_json::Value>(&response).unwrap()["result"].clone()
+    };
+    assert_eq!(
+        setup.call_query::<_, Vec<NonDeterministicFieldFilter>>("getResponseFilters", ()),
+        default_response_filters()
+    );
+    assert_eq!(
+        request(&setup),
+        serde_json::json!({ "node_info": { "moniker": "" }, "sync_info": { "latest_block_time": "2024-01-15T12:34:56Z" } })
+    );
+
+    let filters = vec![NonDeterministicFieldFilter::new(
+        "result.sync_info",
+        FilterAction::Remove,
+    )];
+    setup
+        .clone()
+        .as_controller()
+        .call_update::<_, ()>("setResponseFilters", (filters.clone(),))
+        .wait();
+    setup.upgrade_canister(InitArgs {
+        demo: Some(true),
+        ..Default::default()
+    });
+    assert_eq!(
+        setup.call_query::<_, Vec<NonDeterministicFieldFilter>>("getResponseFilters", ()),
+        filters
+    );
+    assert_eq!(
+        request(&setup),
+        serde_json::json!({ "node_info": { "moniker": "node-a" } })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn should_not_allow_caller_without_access_to_set_response_filters() {
+    CosmosRpcSetup::default()
+        .call_update::<_, ()>("setResponseFilters", (Vec::<NonDeterministicFieldFilter>::new(),))
+        .wait();
+}
+
 #[test]
 fn test_get_health() {
     assert_eq!(
@@ -52,6 +115,49 @@ fn test_get_health() {
     .is_err());
 }
 
+#[test]
+fn should_get_lcd_balances() {
+    let source = RpcServices::Custom(vec![RpcApi::lcd("https://lcd.cosmos.network")]);
+    let balances = CosmosRpcSetup::default()
+        .call_update::<_, RpcResult<Vec<CosmosCoin>>>("cos_getLcdBalances", (source, (), "cosmos1abc".to_string()))
+        .mock_http(
+            MockOutcallBuilder::new(
+                200,
+                r#"{"balances":[{"denom":"uatom","amount":"1000"}],"pagination":{"next_key":null,"total":"1"}}"#,
+            )
+            .with_method(CanisterHttpMethod::GET)
+            .with_url("https://lcd.cosmos.network/cosmos/bank/v1beta1/balances/cosmos1abc"),
+        )
+        .wait()
+        .unwrap();
+    assert_eq!(balances, vec![CosmosCoin::new("uatom", "1000")]);
+}
+
+/// Mocks `cos_method` with the captured response of the RPC `method`
+fn check_fixture<A: ArgumentEncoder, R: DeserializeOwned + CandidType>(method: &str, cos_method: &str, args: A) {
+    let response = load_fixture(method)
+        .unwrap_or_else(|| panic!("No {} fixture, run the generate_fixtures binary to capture it", method));
+    if let Err(e) = mock_update::<A, R>(cos_method, args, &response) {
+        panic!("Failed to decode the {} fixture: {:?}", method, e);
+    }
+}
+
+#[test]
+#[ignore = "needs the fixtures captured by the generate_fixtures binary in tests/fixtures"]
+fn should_decode_captured_fixtures() {
+    let source = || (RpcServices::Testnet, ());
+    let latest = || (RpcServices::Testnet, (), String::new());
+    check_fixture::<_, bool>("health", "cos_getHealth", source());
+    check_fixture::<_, Status>("status", "cos_getStatus", source());
+    check_fixture::<_, AbciInfo>("abci_info", "cos_getAbciInfo", source());
+    check_fixture::<_, NetInfo>("net_info", "cos_getNetInfo", source());
+    check_fixture::<_, NumUnconfirmedTransactionsResult>("num_unconfirmed_txs", "cos_getNumUnconfirmedTxs", source());
+    check_fixture::<_, BlockComplete>("block", "cos_getBlock", latest());
+    check_fixture::<_, CommitResult>("commit", "cos_getCommit", latest());
+    check_fixture::<_, HeaderResult>("header", "cos_getHeader", latest());
+    check_fixture::<_, ConsensusParamsResult>("consensus_params", "cos_getConsensusParams", latest());
+}
+
 #[test]
 fn should_get_valid_request_cost() {
     assert_eq!(
@@ -278,3 +384,97 @@ fn upgrade_should_keep_state() {
     let providers = setup.get_providers();
     assert!(providers.contains(&"test_mainnet1".to_string()));
 }
+
+#[test]
+fn should_notify_governance_subscribers() {
+    let setup = CosmosRpcSetup::default().as_controller();
+    let subscriber = TestSetup::principal(5);
+    // `QueryProposalsResponse` of the proposal 1 "t", in voting period then passed
+    let proposals_response = |value: &str| {
+        format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":{{"response":{{"code":0,"log":"","index":"0","key":null,"value":"{}","height":"1000","codespace":""}}}}}}"#,
+            value
+        )
+    };
+    let monitor = |value: &str| {
+        setup
+            .call_update::<_, RpcResult<Vec<ProposalTransition>>>(
+                "cos_monitorGovernanceProposals",
+                (RpcServices::Mainnet, (), None::<u64>, 3600u64),
+            )
+            .mock_http(MockOutcallBuilder::new(200, proposals_response(value)))
+            .wait()
+            .unwrap()
+    };
+
+    setup
+        .call_update::<_, RpcResult<()>>("registerGovernanceSubscriber", (subscriber, "onProposalTransition"))
+        .wait()
+        .unwrap();
+    assert_eq!(monitor("CgcIARgCWgF0"), vec![]);
+
+    // The subscribers and the proposals survive upgrades
+    setup.upgrade_canister(InitArgs {
+        demo: Some(true),
+        ..Default::default()
+    });
+    assert_eq!(
+        setup.call_query::<_, Vec<(Principal, String)>>("getGovernanceSubscribers", ()),
+        vec![(subscriber, "onProposalTransition".to_string())]
+    );
+
+    let transitions = monitor("CgcIARgDWgF0");
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].previous_status, ProposalStatus::VotingPeriod);
+    assert_eq!(transitions[0].proposal.status, ProposalStatus::Passed);
+    // The subscriber is not a canister, the failed notification is logged
+    assert!(setup
+        .http_get_logs("INFO")
+        .iter()
+        .any(|entry| entry.message.contains(&format!(
+            "Failed to call {} onProposalTransition (proposal: 1)",
+            subscriber
+        ))));
+
+    assert!(setup
+        .call_update::<_, bool>("unregisterGovernanceSubscriber", (subscriber,))
+        .wait());
+    assert!(!setup
+        .call_update::<_, bool>("unregisterGovernanceSubscriber", (subscriber,))
+        .wait());
+    assert_eq!(
+        setup.call_query::<_, Vec<(Principal, String)>>("getGovernanceSubscribers", ()),
+        vec![]
+    );
+}
+
+#[test]
+fn should_keep_block_subscriptions_on_upgrade() {
+    let setup = CosmosRpcSetup::default().as_controller();
+    let subscriber = TestSetup::principal(6);
+    let subscribe = |source: RpcServices| {
+        setup
+            .call_update::<_, RpcResult<()>>("subscribeToNewBlocks", (source, (), subscriber, "onNewBlocks", 10u32))
+            .wait()
+    };
+
+    subscribe(RpcServices::Mainnet).unwrap();
+    // The subscribers share the polled source
+    assert!(matches!(
+        subscribe(RpcServices::Testnet),
+        Err(RpcError::ValidationError(_))
+    ));
+
+    // The subscription and its source survive upgrades
+    setup.upgrade_canister(InitArgs::default());
+    assert!(matches!(
+        subscribe(RpcServices::Testnet),
+        Err(RpcError::ValidationError(_))
+    ));
+    assert!(setup
+        .call_update::<_, bool>("unsubscribeFromNewBlocks", (subscriber,))
+        .wait());
+
+    // Without subscribers, another source can be polled
+    subscribe(RpcServices::Testnet).unwrap();
+}
@@ -0,0 +1,56 @@
+use ic_cdk::api::management_canister::main::CanisterId;
+use ic_cosmos::{
+    rpc_client::{RpcConfig, RpcResult, RpcServices},
+    types::{
+        decode_simulate_response, encode_simulate_query, ABCIQueryResult, BlockHeight, CosmosTransaction,
+        SigningBackend, TX_SIMULATE_PATH,
+    },
+};
+
+use crate::simulation_cache::{cache_simulation, get_cached_simulation, simulation_cache_key};
+
+/// [`SigningBackend`] simulating transactions through the `cos_getAbciQuery` endpoint of the RPC canister.
+/// The gas used is cached for about a block, see [`crate::simulation_cache`].
+pub struct RpcCanisterBackend {
+    pub cos_canister: CanisterId,
+    pub source: RpcServices,
+    pub config: Option<RpcConfig>,
+    /// Compressed secp256k1 public key of the signer
+    pub public_key: Vec<u8>,
+}
+
+impl SigningBackend for RpcCanisterBackend {
+    async fn simulate(&self, transaction: &CosmosTransaction) -> Result<u64, String> {
+        let key = simulation_cache_key(transaction, &self.public_key)?;
+        if let Some(gas_used) = get_cached_simulation(&key, ic_cdk::api::time()) {
+            return Ok(gas_used);
+        }
+
+        let query_data = encode_simulate_query(transaction, &self.public_key)?;
+        let (result,) = ic_cdk::call::<_, (RpcResult<ABCIQueryResult>,)>(
+            self.cos_canister,
+            "cos_getAbciQuery",
+            (
+                &self.source,
+                self.config.clone(),
+                TX_SIMULATE_PATH.to_string(),
+                query_data,
+                BlockHeight::Latest,
+                false,
+            ),
+        )
+        .await
+        .map_err(|e| format!("Failed to call abci_query: {:?}", e))?;
+
+        let result = result.map_err(|e| e.to_string())?;
+        if result.response.code != 0 {
+            return Err(format!(
+                "Simulation failed with code {}: {}",
+                result.response.code, result.response.log
+            ));
+        }
+        let gas_used = decode_simulate_response(&result.response.value)?;
+        cache_simulation(key, gas_used, ic_cdk::api::time());
+        Ok(gas_used)
+    }
+}
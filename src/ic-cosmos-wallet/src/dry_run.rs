@@ -0,0 +1,61 @@
+use candid::{CandidType, Deserialize};
+use ic_cosmos::types::{CosmosCoin, CosmosTransaction, DEFAULT_GAS_ADJUSTMENT, DEFAULT_GAS_PRICE};
+use serde::Serialize;
+
+/// Outcome of `sendCosmosTransactionDryRun`: whether the transaction would be accepted, and the
+/// gas and fee it needs.
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct DryRunResult {
+    /// Whether the transaction encodes, fits into a block and passes the simulation
+    pub valid: bool,
+    /// Gas used by the simulation times [`DEFAULT_GAS_ADJUSTMENT`], 0 if the simulation failed
+    pub estimated_gas: u64,
+    /// Fee of `estimated_gas` at the gas price of the transaction fee,
+    /// or at the price of [`DEFAULT_GAS_PRICE`] for a transaction without fee or gas limit
+    pub estimated_fee: CosmosCoin,
+    /// Reasons the transaction is invalid, and issues that do not prevent broadcasting it
+    /// but may make it fail, such as a gas limit below the estimate
+    pub warnings: Vec<String>,
+}
+
+impl DryRunResult {
+    /// Builds the result of `transaction` from the gas used by its simulation and the `errors`
+    /// found before simulating it
+    pub fn new(transaction: &CosmosTransaction, simulation: Result<u64, String>, errors: Vec<String>) -> Self {
+        let mut warnings = errors;
+        let estimated_gas = match simulation {
+            Ok(gas_used) => (gas_used as f64 * DEFAULT_GAS_ADJUSTMENT).ceil() as u64,
+            Err(e) => {
+                warnings.push(format!("Simulation failed: {}", e));
+                0
+            }
+        };
+        let valid = warnings.is_empty();
+
+        if estimated_gas > transaction.gas_limit {
+            warnings.push(format!(
+                "Gas limit {} is below the estimated gas {}",
+                transaction.gas_limit, estimated_gas
+            ));
+        }
+        let (denom, gas_price) = match transaction.fee.first() {
+            Some(fee) if transaction.gas_limit > 0 => {
+                let amount = fee.amount.parse::<u64>().unwrap_or_default();
+                (fee.denom.clone(), amount as f64 / transaction.gas_limit as f64)
+            }
+            Some(fee) => (fee.denom.clone(), DEFAULT_GAS_PRICE.1),
+            None => {
+                warnings.push("Transaction has no fee".to_string());
+                (DEFAULT_GAS_PRICE.0.to_string(), DEFAULT_GAS_PRICE.1)
+            }
+        };
+        let fee_amount = (estimated_gas as f64 * gas_price).ceil() as u64;
+
+        Self {
+            valid,
+            estimated_gas,
+            estimated_fee: CosmosCoin::new(denom, fee_amount.to_string()),
+            warnings,
+        }
+    }
+}
@@ -52,7 +52,7 @@ pub async fn ecdsa_public_key(key: EcdsaKey, derivation_path: Vec<Vec<u8>>) -> V
     let res: Result<(EcdsaPublicKeyResponse,), _> =
         ic_cdk::api::management_canister::ecdsa::ecdsa_public_key(EcdsaPublicKeyArgument {
             canister_id: None,
-            derivation_path: derivation_path,
+            derivation_path,
             key_id: EcdsaKeyId {
                 curve: EcdsaCurve::Secp256k1,
                 name: key.to_string(),
@@ -70,7 +70,7 @@ pub async fn sign_with_ecdsa(key: EcdsaKey, derivation_path: Vec<Vec<u8>>, messa
     let res: Result<(SignWithEcdsaResponse,), _> =
         ic_cdk::api::management_canister::ecdsa::sign_with_ecdsa(SignWithEcdsaArgument {
             message_hash: sha256(&message).to_vec(),
-            derivation_path: derivation_path,
+            derivation_path,
             key_id: EcdsaKeyId {
                 curve: EcdsaCurve::Secp256k1,
                 name: key.to_string(),
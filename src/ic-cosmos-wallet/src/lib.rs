@@ -1,3 +1,8 @@
+pub mod backend;
+pub mod dry_run;
 pub mod eddsa;
+pub mod receipt;
+pub mod send_config;
+pub mod simulation_cache;
 pub mod state;
 pub mod utils;
@@ -1,21 +1,38 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use candid::candid_method;
-use ic_cdk::update;
+use ic_canister_log::log;
+use ic_cdk::{api::management_canister::main::CanisterId, update};
+#[cfg(feature = "osmosis")]
+use ic_cosmos::types::{calculate_min_out_amount, DEFAULT_SLIPPAGE_BPS, OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL};
 use ic_cosmos::{
+    backoff::Backoff,
+    logs::INFO,
     rpc_client::{RpcConfig, RpcResult, RpcServices},
     types::{
-        build_transaction_for_broadcast, create_sign_doc_bytes, extract_signer_address_from_message,
-        parse_account_info_from_abci, public_key_to_cosmos_address, BlockHash, CosmosCoin, CosmosMessage,
-        CosmosTransaction, Pubkey, RpcSendTransactionConfig, Transaction,
+        build_transaction_for_broadcast, check_tx_size, create_sign_doc_bytes, extract_signer_address_from_message,
+        normalize_ecdsa_signature, parse_account_info_from_abci, parse_transaction_receipt, public_key_to_address,
+        public_key_to_cosmos_address, validate_transaction_json, BlockHash, BlockHeight, BroadcastTxResult,
+        ChainConfig, ConsensusParamsResult, CosmosCoin, CosmosMessage, CosmosTransaction, CosmosTransactionReceipt,
+        HeaderResult, MaxTxBytes, Pubkey, RpcSendTransactionConfig, SignMode, SigningBackend, Status, Transaction, Tx,
     },
 };
 use ic_cosmos_wallet::{
-    eddsa::{ecdsa_public_key, sign_with_ecdsa},
-    state::{read_state, InitArgs, State},
+    backend::RpcCanisterBackend,
+    dry_run::DryRunResult,
+    eddsa::{ecdsa_public_key, sign_with_ecdsa, EcdsaKey},
+    receipt::{
+        TransactionWithReceipt, DEFAULT_RECEIPT_POLL_ATTEMPTS, MAX_CONFIRMATION_POLL_ATTEMPTS, MAX_RECEIPT_POLL_DELAY,
+        RECEIPT_POLL_INITIAL_DELAY,
+    },
+    send_config::SendCosmosTransactionConfig,
+    state::{mutate_state, read_state, InitArgs, RotateEcdsaKey, State},
     utils::validate_caller_not_anonymous,
 };
 
+/// Cosmos SDK `ErrWrongSequence` error code, returned when the account sequence is out of date.
+const SDK_ERR_WRONG_SEQUENCE: i32 = 32;
+
 /// Returns the public key of the Cosmos wallet associated with the caller.
 ///
 /// # Returns
@@ -136,7 +153,17 @@ pub async fn cosmos_address() -> RpcResult<String> {
 /// - `source` (`RpcServices`): The Cosmos RPC provider ID.
 /// - `config` (`Option<RpcConfig>`): Optional configuration for the RPC call.
 /// - `raw_transaction` (`String`): The serialized unsigned Cosmos transaction in JSON format.
-/// - `chain_id` (`String`): The chain ID for the Cosmos network.
+///   Each message of `body.messages` carries its type URL in `@type`. A split vote is given as
+///   `{ "@type": "/cosmos.gov.v1beta1.MsgVoteWeighted", "proposal_id": "123", "voter": "cosmos1...",
+///   "options": [{ "option": "VOTE_OPTION_YES", "weight": "0.7" }, { "option": "VOTE_OPTION_ABSTAIN",
+///   "weight": "0.3" }] }`, with weights summing to 1.0.
+///   An Osmosis swap (`/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn`) may leave out
+///   `token_out_min_amount`, it is then set from the spot price of its pools minus `slippage_bps`
+///   basis points (1% by default).
+/// - `chain_id` (`String`): The chain ID for the Cosmos network. Must match the chain ID reported by the
+///   node, otherwise the transaction is rejected before signing.
+/// - `send_config` (`Option<SendCosmosTransactionConfig>`): Simulation, sequence retries and
+///   confirmation of this call. The config of the canister is used if not given.
 ///
 /// # Returns
 ///
@@ -148,13 +175,350 @@ pub async fn send_cosmos_transaction(
     config: Option<RpcConfig>,
     chain_id: String,
     raw_transaction: String,
+    send_config: Option<SendCosmosTransactionConfig>,
 ) -> RpcResult<String> {
-    let caller = validate_caller_not_anonymous();
+    let (cos_canister, send_config) = read_state(|s| (s.cos_canister, s.send_config(send_config)));
+    let result = broadcast_cosmos_transaction(
+        cos_canister,
+        &source,
+        config.clone(),
+        chain_id,
+        &raw_transaction,
+        &send_config,
+    )
+    .await?;
+
+    // A transaction rejected by `CheckTx` is never included in a block
+    if send_config.wait_for_confirmation && result.code == 0 {
+        let receipt = await_transaction_receipt(
+            cos_canister,
+            &source,
+            config,
+            &result.hash,
+            send_config.confirmation_timeout_secs,
+        )
+        .await?
+        .ok_or_else(|| {
+            ic_cosmos::rpc_client::RpcError::ValidationError(format!(
+                "Transaction {} was not confirmed within {} seconds",
+                result.hash, send_config.confirmation_timeout_secs
+            ))
+        })?;
+        if receipt.code != 0 {
+            return Err(ic_cosmos::rpc_client::RpcError::ValidationError(format!(
+                "Transaction {} failed with code {}: {}",
+                result.hash, receipt.code, receipt.raw_log
+            )));
+        }
+    }
+    Ok(result.hash)
+}
+
+/// Signs and sends a Cosmos transaction like `sendCosmosTransaction`, then polls `cos_getTx` until
+/// the transaction is included in a block and returns its receipt.
+///
+/// # Parameters
+///
+/// Those of `sendCosmosTransaction`, and:
+///
+/// - `max_poll_attempts` (`Option<u32>`): Number of `cos_getTx` calls made for the receipt,
+///   [`DEFAULT_RECEIPT_POLL_ATTEMPTS`] by default and [`MAX_CONFIRMATION_POLL_ATTEMPTS`] at most,
+///   with an exponential backoff between them. 0 returns right after the broadcast.
+///
+/// # Returns
+///
+/// - `RpcResult<TransactionWithReceipt>`: The broadcast result and the receipt, if the transaction
+///   was found in a block, or an `RpcError` if it could not be broadcast.
+#[update(name = "sendCosmosTransactionWithReceipt")]
+#[candid_method(update, rename = "sendCosmosTransactionWithReceipt")]
+pub async fn send_cosmos_transaction_with_receipt(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    chain_id: String,
+    raw_transaction: String,
+    max_poll_attempts: Option<u32>,
+) -> RpcResult<TransactionWithReceipt> {
+    let (cos_canister, send_config) = read_state(|s| (s.cos_canister, s.send_config(None)));
+    let broadcast = broadcast_cosmos_transaction(
+        cos_canister,
+        &source,
+        config.clone(),
+        chain_id,
+        &raw_transaction,
+        &send_config,
+    )
+    .await?;
+
+    // A transaction rejected by `CheckTx` is never included in a block
+    let receipt = if broadcast.code == 0 {
+        let attempts = max_poll_attempts
+            .unwrap_or(DEFAULT_RECEIPT_POLL_ATTEMPTS)
+            .min(MAX_CONFIRMATION_POLL_ATTEMPTS);
+        poll_transaction_receipt(cos_canister, &source, config, &broadcast.hash, attempts).await?
+    } else {
+        None
+    };
+    Ok(TransactionWithReceipt { broadcast, receipt })
+}
+
+/// Prepares, signs and broadcasts `raw_transaction`, simulating it first and retrying with the
+/// sequence of the chain if the one used is out of date, as set by `send_config`. Shared by
+/// `sendCosmosTransaction` and `sendCosmosTransactionWithReceipt`.
+async fn broadcast_cosmos_transaction(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    chain_id: String,
+    raw_transaction: &str,
+    send_config: &SendCosmosTransactionConfig,
+) -> RpcResult<BroadcastTxResult> {
+    let PreparedTransaction {
+        mut transaction,
+        key_name,
+        derived_path,
+        pk,
+        address: our_cosmos_address,
+    } = prepare_cosmos_transaction(cos_canister, source, config.clone(), chain_id, raw_transaction, true).await?;
+
+    if send_config.simulate_before_send {
+        let backend = RpcCanisterBackend {
+            cos_canister,
+            source: source.clone(),
+            config: config.clone(),
+            public_key: pk.clone(),
+        };
+        let simulation = backend
+            .simulate(&transaction)
+            .await
+            .map_err(|e| ic_cosmos::rpc_client::RpcError::ValidationError(format!("Simulation failed: {}", e)))
+            .and_then(|gas_used| {
+                if gas_used > transaction.gas_limit {
+                    return Err(ic_cosmos::rpc_client::RpcError::ValidationError(format!(
+                        "Gas limit {} is below the simulated gas {}",
+                        transaction.gas_limit, gas_used
+                    )));
+                }
+                Ok(())
+            });
+        if let Err(e) = simulation {
+            mutate_state(|s| s.release_sequence(&our_cosmos_address, transaction.sequence));
+            return Err(e);
+        }
+    }
+
+    let result = async {
+        let mut result = sign_and_broadcast(
+            cos_canister,
+            source,
+            config.clone(),
+            &transaction,
+            &key_name,
+            &derived_path,
+            &pk,
+        )
+        .await?;
+
+        // The sequence is out of sync with the chain, re-fetch it and retry
+        let mut retries = 0;
+        while send_config.auto_retry_sequence && retries < send_config.max_retries && is_sequence_mismatch(&result) {
+            retries += 1;
+            mutate_state(|s| s.invalidate_account_info(&our_cosmos_address));
+            let fetched = fetch_account_info(cos_canister, source, config.clone(), &our_cosmos_address).await?;
+            let (account_number, sequence) =
+                take_account_info(&our_cosmos_address, Some(fetched), true, ic_cdk::api::time()).unwrap_or(fetched);
+            transaction.account_number = account_number;
+            transaction.sequence = sequence;
+            result = sign_and_broadcast(
+                cos_canister,
+                source,
+                config.clone(),
+                &transaction,
+                &key_name,
+                &derived_path,
+                &pk,
+            )
+            .await?;
+        }
+        Ok(result)
+    }
+    .await;
+
+    // The reserved sequence is kept once the node accepted the transaction, given back if it
+    // rejected it without consuming the sequence, and re-fetched from the chain otherwise
+    match &result {
+        Ok(result) if result.code == 0 => {}
+        Ok(result) if !is_sequence_mismatch(result) => {
+            mutate_state(|s| s.release_sequence(&our_cosmos_address, transaction.sequence))
+        }
+        _ => mutate_state(|s| s.invalidate_account_info(&our_cosmos_address)),
+    }
+
+    result
+}
+
+/// Looks up the transaction `hash` with `cos_getTx` up to `attempts` times, until it is found in a
+/// block, and sets the block time of its receipt from the header of the block. The calls are
+/// spaced with an exponential backoff.
+async fn poll_transaction_receipt(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    hash: &str,
+    attempts: u32,
+) -> RpcResult<Option<CosmosTransactionReceipt>> {
+    if attempts == 0 {
+        return Ok(None);
+    }
+    let mut backoff = Backoff::new(RECEIPT_POLL_INITIAL_DELAY, MAX_RECEIPT_POLL_DELAY, attempts);
+    loop {
+        // The node answers with an error until the transaction is in a block
+        let Ok((Ok(tx),)) =
+            ic_cdk::call::<_, (RpcResult<Tx>,)>(cos_canister, "cos_getTx", (source, config.clone(), hash, false)).await
+        else {
+            if backoff.wait().await {
+                continue;
+            }
+            return Ok(None);
+        };
+        let mut receipt = parse_transaction_receipt(&tx)
+            .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to parse receipt: {}", e)))?;
+        if let Ok((Ok(header),)) = ic_cdk::call::<_, (RpcResult<HeaderResult>,)>(
+            cos_canister,
+            "cos_getHeader",
+            (source, config, tx.height.clone()),
+        )
+        .await
+        {
+            receipt.block_time = header.header.time;
+        }
+        return Ok(Some(receipt));
+    }
+}
+
+/// Polls `cos_getTx` for the transaction `hash` until it is included in a block, or
+/// `timeout_secs` have passed since the first attempt. The calls are spaced with an exponential
+/// backoff, and at most [`MAX_CONFIRMATION_POLL_ATTEMPTS`].
+async fn await_transaction_receipt(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    hash: &str,
+    timeout_secs: u64,
+) -> RpcResult<Option<CosmosTransactionReceipt>> {
+    let deadline = ic_cdk::api::time().saturating_add(timeout_secs.saturating_mul(1_000_000_000));
+    let mut backoff = Backoff::new(
+        RECEIPT_POLL_INITIAL_DELAY,
+        MAX_RECEIPT_POLL_DELAY,
+        MAX_CONFIRMATION_POLL_ATTEMPTS,
+    );
+    loop {
+        let receipt = poll_transaction_receipt(cos_canister, source, config.clone(), hash, 1).await?;
+        if receipt.is_some() || ic_cdk::api::time() >= deadline || !backoff.wait().await {
+            return Ok(receipt);
+        }
+    }
+}
+
+/// Checks a Cosmos transaction without signing or broadcasting it, so that callers can make sure
+/// it is well-formed before paying for a threshold ECDSA signature.
+///
+/// The transaction goes through the same checks as in `sendCosmosTransaction`, then its sign doc
+/// is built and it is simulated by the node, with a zero placeholder signature.
+///
+/// # Parameters
+///
+/// Those of `sendCosmosTransaction`.
+///
+/// # Returns
+///
+/// - `RpcResult<DryRunResult>`: Whether the transaction is valid, with its estimated gas and fee and
+///   the issues found, or an `RpcError` if it is rejected before the simulation.
+#[update(name = "sendCosmosTransactionDryRun")]
+#[candid_method(update, rename = "sendCosmosTransactionDryRun")]
+pub async fn send_cosmos_transaction_dry_run(
+    source: RpcServices,
+    config: Option<RpcConfig>,
+    chain_id: String,
+    raw_transaction: String,
+) -> RpcResult<DryRunResult> {
     let cos_canister = read_state(|s| s.cos_canister);
+    let PreparedTransaction { transaction, pk, .. } =
+        prepare_cosmos_transaction(cos_canister, &source, config.clone(), chain_id, &raw_transaction, false).await?;
+
+    let mut errors = Vec::new();
+    let max_tx_bytes = fetch_max_tx_bytes(cos_canister, &source, config.clone()).await;
+    if let Err(e) = check_tx_size(&transaction, max_tx_bytes) {
+        errors.push(e.to_string());
+    }
+    if let Err(e) = create_sign_doc_bytes(&transaction, &pk) {
+        errors.push(format!("Failed to build the sign doc: {}", e));
+    }
+
+    let backend = RpcCanisterBackend {
+        cos_canister,
+        source,
+        config,
+        public_key: pk,
+    };
+    let simulation = backend.simulate(&transaction).await;
+    Ok(DryRunResult::new(&transaction, simulation, errors))
+}
+
+/// Rotates the ECDSA key of the wallet to `args.new_key_name`. Only controllers may rotate the key.
+///
+/// During `args.transition_period_nanos`, `address`, `cosmosAddress` and `signMessage` use the new
+/// key, while `sendCosmosTransaction` still accepts messages signed by the caller's address of the
+/// previous key, and signs them with it. The previous key is retired afterwards.
+///
+/// # Returns
+///
+/// - `Result<(), String>`: An error if the caller is not a controller or a rotation is in progress.
+#[update(name = "rotateEcdsaKey")]
+#[candid_method(update, rename = "rotateEcdsaKey")]
+pub fn rotate_ecdsa_key(args: RotateEcdsaKey) -> Result<(), String> {
+    if !ic_cdk::api::is_controller(&ic_cdk::caller()) {
+        return Err("Unauthorized".to_string());
+    }
+    mutate_state(|s| s.rotate_ecdsa_key(args, ic_cdk::api::time()))?;
+    schedule_key_retirement();
+    Ok(())
+}
+
+/// A transaction of the caller parsed from its JSON, with its account number and sequence
+struct PreparedTransaction {
+    transaction: CosmosTransaction,
+    /// Key of the signer, the previous key during a rotation if the messages are signed by its address
+    key_name: EcdsaKey,
+    derived_path: Vec<Vec<u8>>,
+    pk: Vec<u8>,
+    /// Cosmos address of the signer
+    address: String,
+}
+
+/// Parses and validates `raw_transaction`, checks that the caller owns the signer addresses of
+/// its messages and that the node behind `source` is synced on `chain_id`, and sets its account number
+/// and sequence. Shared by `sendCosmosTransaction`, `sendCosmosTransactionWithReceipt` and
+/// `sendCosmosTransactionDryRun`.
+///
+/// With `reserve_sequence`, the sequence is reserved in the cache for the transaction, and must
+/// be released with [`State::release_sequence`] if the transaction is not broadcast.
+async fn prepare_cosmos_transaction(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    chain_id: String,
+    raw_transaction: &str,
+    reserve_sequence: bool,
+) -> RpcResult<PreparedTransaction> {
+    let caller = validate_caller_not_anonymous();
 
     // Parse the raw JSON transaction
-    let tx_json: serde_json::Value = serde_json::from_str(&raw_transaction)
+    let tx_json: serde_json::Value = serde_json::from_str(raw_transaction)
         .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to parse transaction: {}", e)))?;
+    validate_transaction_json(&tx_json).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        ic_cosmos::rpc_client::RpcError::ParseError(format!("Invalid transaction: {}", errors.join("; ")))
+    })?;
 
     // Parse messages from the transaction
     let messages_array = tx_json["body"]["messages"].as_array().ok_or_else(|| {
@@ -186,42 +550,275 @@ pub async fn send_cosmos_transaction(
         });
     }
 
+    // Reject the gov messages of a module version the chain does not have, when it is known
+    if let Some(chain) = read_state(|s| s.get_known_chain(&chain_id)) {
+        for message in &cosmos_messages {
+            chain
+                .gov_version()
+                .check_type_url(&message.type_url)
+                .map_err(ic_cosmos::rpc_client::RpcError::ValidationError)?;
+        }
+    }
+
     // Get our public key and derive the Cosmos address
-    let key_name = read_state(|s| s.ecdsa_key.to_owned());
+    let mut key_name = read_state(|s| s.ecdsa_key.to_owned());
     let derived_path = vec![caller.as_slice().to_vec()];
-    let pk = ecdsa_public_key(key_name.clone(), derived_path.clone()).await;
+    let mut pk = ecdsa_public_key(key_name.clone(), derived_path.clone()).await;
 
-    let our_cosmos_address = public_key_to_cosmos_address(&bs58::encode(&pk).into_string())
-        .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e))?;
+    let mut our_cosmos_address = chain_address(&pk, &chain_id)?;
 
     // Verify that we own all the signer addresses in the messages
+    let mut signer_addresses = Vec::new();
     for message in &cosmos_messages {
-        let signer_address =
-            extract_signer_address_from_message(message).map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e))?;
+        signer_addresses
+            .push(extract_signer_address_from_message(message).map_err(ic_cosmos::rpc_client::RpcError::ParseError)?);
+    }
 
-        if signer_address != our_cosmos_address {
-            return Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
-                "Message signer address '{}' does not match our wallet address '{}'",
-                signer_address, our_cosmos_address
-            )));
+    // During a key rotation the address of the previous key is still accepted, and signed with it
+    if let Some(previous_key) = read_state(|s| s.previous_ecdsa_key(ic_cdk::api::time()))
+        .filter(|_| signer_addresses.iter().any(|address| *address != our_cosmos_address))
+    {
+        let previous_pk = ecdsa_public_key(previous_key.clone(), derived_path.clone()).await;
+        let previous_address = chain_address(&previous_pk, &chain_id)?;
+        if signer_addresses.iter().all(|address| *address == previous_address) {
+            key_name = previous_key;
+            pk = previous_pk;
+            our_cosmos_address = previous_address;
         }
     }
 
-    // Get account info (account_number and sequence) via abci_query
-    let query_data = format!(
-        "0a{:02x}{}",
-        our_cosmos_address.len(),
-        hex::encode(our_cosmos_address.as_bytes())
-    );
+    if let Some(signer_address) = signer_addresses.iter().find(|address| **address != our_cosmos_address) {
+        return Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
+            "Message signer address '{}' does not match our wallet address '{}'",
+            signer_address, our_cosmos_address
+        )));
+    }
+
+    // Make sure the transaction is not signed for a different chain than the node's
+    ensure_chain_id(cos_canister, source, config.clone(), &chain_id).await?;
+
+    #[cfg(feature = "osmosis")]
+    let cosmos_messages = fill_swap_min_out_amounts(cos_canister, source, config.clone(), cosmos_messages).await?;
+
+    // Parse fee and convert to CosmosCoin
+    let fee_array = tx_json["auth_info"]["fee"]["amount"]
+        .as_array()
+        .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee amount".to_string()))?;
+
+    let mut fees = Vec::new();
+    for fee_coin in fee_array {
+        let denom = fee_coin["denom"]
+            .as_str()
+            .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee denom".to_string()))?;
+        let amount_str = fee_coin["amount"]
+            .as_str()
+            .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee amount".to_string()))?;
+
+        fees.push(CosmosCoin::new(denom, amount_str));
+    }
+
+    let gas_limit = tx_json["auth_info"]["fee"]["gas_limit"]
+        .as_str()
+        .unwrap_or_default()
+        .parse::<u64>()
+        .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Invalid gas limit: {}", e)))?;
+
+    let memo = tx_json["body"]["memo"].as_str().unwrap_or("");
+
+    // Get account info (account_number and sequence), from the cache while it is fresh. The
+    // sequence is reserved without awaiting in between, see `take_account_info`.
+    let now = ic_cdk::api::time();
+    let (account_number, sequence) = match take_account_info(&our_cosmos_address, None, reserve_sequence, now) {
+        Some(info) => info,
+        None => {
+            let fetched = fetch_account_info(cos_canister, source, config, &our_cosmos_address).await?;
+            take_account_info(&our_cosmos_address, Some(fetched), reserve_sequence, now).unwrap_or(fetched)
+        }
+    };
+
+    let transaction = CosmosTransaction {
+        messages: cosmos_messages,
+        fee: fees,
+        gas_limit,
+        memo: memo.to_string(),
+        chain_id,
+        account_number,
+        sequence,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    };
+
+    Ok(PreparedTransaction {
+        transaction,
+        key_name,
+        derived_path,
+        pk,
+        address: our_cosmos_address,
+    })
+}
+
+/// Returns the address of the public key `pk` on `chain_id`, with the bech32 prefix of the chain
+/// if the config canister knows it, the `cosmos` prefix otherwise.
+fn chain_address(pk: &[u8], chain_id: &str) -> RpcResult<String> {
+    let hrp = read_state(|s| s.get_known_chain(chain_id)).map_or_else(|| "cosmos".to_string(), |chain| chain.hrp);
+    public_key_to_address(&bs58::encode(pk).into_string(), &hrp).map_err(ic_cosmos::rpc_client::RpcError::ParseError)
+}
+
+/// Returns the account number and sequence of `address` from the cache, reserving the sequence
+/// if `reserve`. The `fetched` account info is cached first, unless a concurrent call cached it
+/// meanwhile, the sequences it reserved are kept.
+fn take_account_info(address: &str, fetched: Option<(u64, u64)>, reserve: bool, now: u64) -> Option<(u64, u64)> {
+    mutate_state(|s| {
+        if let Some((account_number, sequence)) = fetched.filter(|_| s.get_account_info(address, now).is_none()) {
+            s.cache_account_info(address, account_number, sequence, now);
+        }
+        if reserve {
+            s.reserve_sequence(address, now)
+        } else {
+            s.get_account_info(address, now)
+                .map(|info| (info.account_number, info.current_sequence))
+        }
+    })
+}
+
+/// Verifies that the node behind `source` reports `chain_id` via `cos_getStatus`, and that it is
+/// not catching up, which would leave its account sequences and simulations behind the chain.
+/// The status is fetched on every call, as a node can fall behind at any time.
+async fn ensure_chain_id(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    chain_id: &str,
+) -> RpcResult<()> {
+    let status = ic_cdk::call::<_, (RpcResult<Status>,)>(cos_canister, "cos_getStatus", (source, config))
+        .await
+        .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to call cos_getStatus: {:?}", e)))?
+        .0?;
+
+    if status.sync_info.is_catching_up() {
+        return Err(ic_cosmos::rpc_client::RpcError::ValidationError(format!(
+            "Node is catching up, its latest block is {} at {}",
+            status.sync_info.latest_block_height, status.sync_info.latest_block_time
+        )));
+    }
+
+    let node_chain_id = status.node_info.network;
+    if node_chain_id != chain_id {
+        return Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
+            "Chain ID mismatch: expected {}, got {}",
+            chain_id, node_chain_id
+        )));
+    }
+    Ok(())
+}
+
+/// Sets the `token_out_min_amount` of the Osmosis swaps of `messages` that leave it out, from the
+/// output estimated along their route minus their `slippage_bps` (`DEFAULT_SLIPPAGE_BPS` if not given).
+#[cfg(feature = "osmosis")]
+async fn fill_swap_min_out_amounts(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    mut messages: Vec<CosmosMessage>,
+) -> RpcResult<Vec<CosmosMessage>> {
+    let swaps = messages
+        .iter_mut()
+        .filter(|message| message.type_url == OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL);
+    for message in swaps {
+        // `slippage_bps` is not part of the message, it must not be encoded
+        let slippage_bps = message
+            .value
+            .as_object_mut()
+            .and_then(|value| value.remove("slippage_bps"));
+        if !message.value["token_out_min_amount"].is_null() {
+            continue;
+        }
+        let slippage_bps = match slippage_bps {
+            None => DEFAULT_SLIPPAGE_BPS,
+            Some(slippage_bps) => slippage_bps
+                .as_u64()
+                .or_else(|| slippage_bps.as_str().and_then(|bps| bps.parse().ok()))
+                .filter(|bps| *bps <= 10_000)
+                .ok_or_else(|| {
+                    ic_cosmos::rpc_client::RpcError::ParseError(format!("Invalid slippage_bps: {}", slippage_bps))
+                })? as u16,
+        };
+
+        let mut token_in: CosmosCoin = serde_json::from_value(message.value["token_in"].clone())
+            .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Invalid token_in in swap: {}", e)))?;
+        let routes = message.value["routes"].as_array().cloned().unwrap_or_default();
+        for route in routes {
+            let pool_id = route["pool_id"]
+                .as_str()
+                .and_then(|id| id.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    ic_cosmos::rpc_client::RpcError::ParseError("Invalid pool_id in swap route".to_string())
+                })?;
+            let token_out_denom = route["token_out_denom"].as_str().unwrap_or_default().to_string();
+            let expected_out = ic_cdk::call::<_, (RpcResult<u64>,)>(
+                cos_canister,
+                "cos_estimateSwapOutput",
+                (source, config.clone(), pool_id, token_in, token_out_denom.clone()),
+            )
+            .await
+            .map_err(|e| {
+                ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to call cos_estimateSwapOutput: {:?}", e))
+            })?
+            .0?;
+            token_in = CosmosCoin::new(token_out_denom, expected_out.to_string());
+        }
+
+        let expected_out = token_in.amount.parse::<u64>().unwrap_or_default();
+        message.value["token_out_min_amount"] = calculate_min_out_amount(expected_out, slippage_bps).to_string().into();
+    }
+    Ok(messages)
+}
+
+/// Returns the `block.max_bytes` consensus param of the chain behind `source`, cached per source.
+/// `None` if the consensus params are not available.
+async fn fetch_max_tx_bytes(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+) -> Option<MaxTxBytes> {
+    if let Some(max_bytes) = read_state(|s| s.get_max_tx_bytes(source)) {
+        return MaxTxBytes::from_param(max_bytes);
+    }
+
+    let params = ic_cdk::call::<_, (RpcResult<ConsensusParamsResult>,)>(
+        cos_canister,
+        "cos_getConsensusParams",
+        (source, config, String::new()),
+    )
+    .await
+    .ok()?
+    .0
+    .ok()?;
+
+    let max_bytes = params.consensus_params.block.max_bytes.parse::<i64>().ok()?;
+    let max_tx_bytes = MaxTxBytes::from_param(max_bytes)?;
+    mutate_state(|s| s.cache_max_tx_bytes(source, max_bytes));
+    Some(max_tx_bytes)
+}
+
+/// Fetches the account number and sequence of `address` via abci_query.
+async fn fetch_account_info(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    address: &str,
+) -> RpcResult<(u64, u64)> {
+    let query_data = format!("0a{:02x}{}", address.len(), hex::encode(address.as_bytes()));
     let account_info_result = ic_cdk::call::<_, (RpcResult<ic_cosmos::types::ABCIQueryResult>,)>(
         cos_canister,
         "cos_getAbciQuery",
         (
-            &source,
-            config.clone(),
+            source,
+            config,
             "/cosmos.auth.v1beta1.Query/Account".to_string(),
             query_data,
-            "0".to_string(),
+            BlockHeight::Latest,
             false,
         ),
     )
@@ -231,7 +828,7 @@ pub async fn send_cosmos_transaction(
     let abci_result = account_info_result.0?;
 
     // Parse the ABCI response to get account info
-    let (account_number, sequence) = if abci_result.response.code == 0 {
+    if abci_result.response.code == 0 {
         // Success case - check if we have a value
         if abci_result.response.value.is_empty() {
             return Err(ic_cosmos::rpc_client::RpcError::ParseError(
@@ -241,7 +838,7 @@ pub async fn send_cosmos_transaction(
 
         // Parse the protobuf response
         parse_account_info_from_abci(&abci_result.response.value)
-            .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to parse account info: {}", e)))?
+            .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to parse account info: {}", e)))
     } else {
         // Error case
         let log_msg = if abci_result.response.log.is_empty() {
@@ -250,85 +847,89 @@ pub async fn send_cosmos_transaction(
             abci_result.response.log.clone()
         };
 
-        return Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
+        Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
             "ABCI query failed with code {}: {}",
             abci_result.response.code, log_msg
-        )));
-    };
-
-    // Parse fee and convert to CosmosCoin
-    let fee_array = tx_json["auth_info"]["fee"]["amount"]
-        .as_array()
-        .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee amount".to_string()))?;
-
-    let mut fees = Vec::new();
-    for fee_coin in fee_array {
-        let denom = fee_coin["denom"]
-            .as_str()
-            .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee denom".to_string()))?;
-        let amount_str = fee_coin["amount"]
-            .as_str()
-            .ok_or_else(|| ic_cosmos::rpc_client::RpcError::ParseError("Missing fee amount".to_string()))?;
-
-        fees.push(CosmosCoin::new(denom, amount_str));
+        )))
     }
+}
 
-    let gas_limit = tx_json["auth_info"]["fee"]["gas_limit"]
-        .as_str()
-        .unwrap_or("200000")
-        .parse::<u64>()
-        .unwrap_or(200000);
-
-    let memo = tx_json["body"]["memo"].as_str().unwrap_or("");
-
-    // Create transaction structure
-    let transaction = CosmosTransaction {
-        messages: cosmos_messages,
-        fee: fees,
-        gas_limit,
-        memo: memo.to_string(),
-        chain_id: chain_id.clone(),
-        account_number,
-        sequence,
-    };
+/// Signs the transaction with the caller's key and broadcasts it via `broadcast_tx_sync`.
+async fn sign_and_broadcast(
+    cos_canister: CanisterId,
+    source: &RpcServices,
+    config: Option<RpcConfig>,
+    transaction: &CosmosTransaction,
+    key_name: &EcdsaKey,
+    derived_path: &[Vec<u8>],
+    pk: &[u8],
+) -> RpcResult<BroadcastTxResult> {
+    // Reject transactions the node would refuse before spending cycles on signing
+    let max_tx_bytes = fetch_max_tx_bytes(cos_canister, source, config.clone()).await;
+    check_tx_size(transaction, max_tx_bytes).map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e.to_string()))?;
 
     // Create sign doc for signing
-    let sign_bytes =
-        create_sign_doc_bytes(&transaction, &pk).map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e))?;
+    let sign_bytes = create_sign_doc_bytes(transaction, pk).map_err(ic_cosmos::rpc_client::RpcError::ParseError)?;
 
     // Sign the transaction
-    let signature = sign_with_ecdsa(key_name, derived_path, sign_bytes).await;
+    let signature = sign_with_ecdsa(key_name.to_owned(), derived_path.to_vec(), sign_bytes).await;
 
-    // Ensure signature is 64 bytes (truncate if longer)
-    let signature = if signature.len() >= 64 {
-        signature[..64].to_vec()
-    } else {
-        return Err(ic_cosmos::rpc_client::RpcError::ParseError(format!(
-            "Signature too short: got {} bytes, expected at least 64",
-            signature.len()
-        )));
-    };
+    let signature = normalize_ecdsa_signature(&signature)
+        .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e.to_string()))?
+        .to_vec();
 
     // Build final transaction for broadcast
-    let tx_base64 = build_transaction_for_broadcast(&transaction, &pk, &signature)
-        .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(e))?;
+    let tx_base64 = build_transaction_for_broadcast(transaction, pk, &signature)
+        .map_err(ic_cosmos::rpc_client::RpcError::ParseError)?;
 
     // Broadcast the transaction
-    let broadcast_result = ic_cdk::call::<_, (RpcResult<ic_cosmos::types::BroadcastTxResult>,)>(
+    let broadcast_result = ic_cdk::call::<_, (RpcResult<BroadcastTxResult>,)>(
         cos_canister,
         "cos_getBroadcastTxSync",
-        (&source, config, tx_base64),
+        (source, config, tx_base64),
     )
     .await
     .map_err(|e| ic_cosmos::rpc_client::RpcError::ParseError(format!("Failed to broadcast transaction: {:?}", e)))?;
 
-    let result = broadcast_result.0?;
-    Ok(result.hash)
+    broadcast_result.0
+}
+
+/// Returns true if the transaction was rejected because of an account sequence mismatch.
+fn is_sequence_mismatch(result: &BroadcastTxResult) -> bool {
+    result.code == SDK_ERR_WRONG_SEQUENCE && result.codespace == "sdk"
+}
+
+/// Loads the chains of the config canister, if configured, right after `init` or
+/// `post_upgrade`, which cannot make inter-canister calls themselves.
+fn schedule_load_known_chains() {
+    let Some(config_canister) = read_state(|s| s.config_canister) else {
+        return;
+    };
+    ic_cdk_timers::set_timer(Duration::ZERO, move || {
+        ic_cdk::spawn(async move {
+            match ic_cdk::call::<_, (Vec<ChainConfig>,)>(config_canister, "listChains", ()).await {
+                Ok((chains,)) => mutate_state(|s| s.set_known_chains(chains)),
+                Err((code, message)) => log!(INFO, "Failed to load chains: {:?} {}", code, message),
+            }
+        });
+    });
+}
+
+/// Retires the previous ECDSA key at the end of its transition period, if a rotation is in
+/// progress. Timers do not survive upgrades, `post_upgrade` schedules the retirement again.
+fn schedule_key_retirement() {
+    let Some(retires_at_nanos) = read_state(|s| s.key_rotation.as_ref().map(|rotation| rotation.retires_at_nanos))
+    else {
+        return;
+    };
+    let delay = Duration::from_nanos(retires_at_nanos.saturating_sub(ic_cdk::api::time()));
+    ic_cdk_timers::set_timer(delay, || mutate_state(|s| s.retire_previous_key(ic_cdk::api::time())));
 }
 
 #[ic_cdk::init]
 fn init(args: InitArgs) {
-    State::init(args)
+    State::init(args);
+    schedule_load_known_chains();
 }
 
 #[ic_cdk::pre_upgrade]
@@ -338,7 +939,9 @@ fn pre_upgrade() {
 
 #[ic_cdk::post_upgrade]
 fn post_upgrade(args: Option<InitArgs>) {
-    State::post_upgrade(args)
+    State::post_upgrade(args);
+    schedule_load_known_chains();
+    schedule_key_retirement();
 }
 
 fn main() {}
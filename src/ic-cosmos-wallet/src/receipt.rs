@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use candid::{CandidType, Deserialize};
+use ic_cosmos::types::{BroadcastTxResult, CosmosTransactionReceipt};
+use serde::Serialize;
+
+/// Number of `cos_getTx` calls made for the receipt when the caller does not set one. Each call is
+/// an HTTPS outcall of a few seconds, and the calls are spaced by the backoff delays, so the
+/// attempts cover about two blocks.
+pub const DEFAULT_RECEIPT_POLL_ATTEMPTS: u32 = 3;
+
+/// Delay between the first two `cos_getTx` calls of a poll for a receipt, doubled after each
+/// call up to [`MAX_RECEIPT_POLL_DELAY`].
+pub const RECEIPT_POLL_INITIAL_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_RECEIPT_POLL_DELAY: Duration = Duration::from_secs(8);
+
+/// Number of `cos_getTx` calls made at most for the receipt of a transaction, whatever the
+/// confirmation timeout or the poll attempts asked by the caller.
+pub const MAX_CONFIRMATION_POLL_ATTEMPTS: u32 = 10;
+
+/// Outcome of `sendCosmosTransactionWithReceipt`: the `CheckTx` result of the broadcast, and the
+/// receipt of the transaction once it is included in a block.
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TransactionWithReceipt {
+    pub broadcast: BroadcastTxResult,
+    /// `None` if the transaction was rejected by `CheckTx`, or not found in a block within the
+    /// poll attempts. It can then be looked up later with `cos_getTx` and `broadcast.hash`.
+    pub receipt: Option<CosmosTransactionReceipt>,
+}
@@ -0,0 +1,37 @@
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+/// Time waited for the receipt of a transaction when the caller does not set one, about five
+/// blocks of the Cosmos Hub.
+pub const DEFAULT_CONFIRMATION_TIMEOUT_SECS: u64 = 30;
+
+/// How `sendCosmosTransaction` checks, retries and follows a transaction. Given per call, or set
+/// for the whole canister in the `InitArgs`; [`Default`] keeps the behavior of the canister
+/// without configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SendCosmosTransactionConfig {
+    /// Simulates the transaction before signing it, rejecting it if the simulation fails or
+    /// uses more gas than its gas limit
+    pub simulate_before_send: bool,
+    /// Re-fetches the account sequence and signs the transaction again when the chain rejects
+    /// its sequence
+    pub auto_retry_sequence: bool,
+    /// Number of broadcasts retried with a re-fetched sequence, if `auto_retry_sequence` is set
+    pub max_retries: u32,
+    /// Polls `cos_getTx` after the broadcast until the transaction is included in a block,
+    /// failing if it is not within `confirmation_timeout_secs` or fails in the block
+    pub wait_for_confirmation: bool,
+    pub confirmation_timeout_secs: u64,
+}
+
+impl Default for SendCosmosTransactionConfig {
+    fn default() -> Self {
+        Self {
+            simulate_before_send: false,
+            auto_retry_sequence: true,
+            max_retries: 1,
+            wait_for_confirmation: false,
+            confirmation_timeout_secs: DEFAULT_CONFIRMATION_TIMEOUT_SECS,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use ic_cosmos::types::{encode_message_bytes, CosmosTransaction};
+use sha2::{Digest, Sha256};
+
+/// Time during which a simulated gas is reused, about one block of the Cosmos Hub. The gas used
+/// depends on the state of the chain, which may change with every block.
+pub const SIMULATION_CACHE_TTL_NANOS: u64 = 6_000_000_000;
+
+thread_local! {
+    /// Gas used by the recent simulations, keyed by [`simulation_cache_key`]. Kept on the heap
+    /// only, the results are outdated long before the next upgrade.
+    static SIMULATION_CACHE: RefCell<BTreeMap<[u8; 32], CachedSimulation>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CachedSimulation {
+    gas_used: u64,
+    simulated_at_nanos: u64,
+}
+
+/// Returns the SHA-256 of the messages and chain ID of `transaction` and of the signer `public_key`.
+///
+/// The fee, the gas limit and the sequence are left out, transactions only differing by them
+/// use the same gas.
+pub fn simulation_cache_key(transaction: &CosmosTransaction, public_key: &[u8]) -> Result<[u8; 32], String> {
+    let mut hasher = Sha256::new();
+    hasher.update(encode_message_bytes(&transaction.messages)?);
+    hasher.update(public_key);
+    hasher.update(transaction.chain_id.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Returns the gas used by the simulation of `key` if it ran within [`SIMULATION_CACHE_TTL_NANOS`].
+pub fn get_cached_simulation(key: &[u8; 32], now_nanos: u64) -> Option<u64> {
+    SIMULATION_CACHE.with(|cache| {
+        cache
+            .borrow()
+            .get(key)
+            .filter(|cached| now_nanos.saturating_sub(cached.simulated_at_nanos) < SIMULATION_CACHE_TTL_NANOS)
+            .map(|cached| cached.gas_used)
+    })
+}
+
+/// Stores the gas used by the simulation of `key`, dropping the expired simulations.
+pub fn cache_simulation(key: [u8; 32], gas_used: u64, now_nanos: u64) {
+    SIMULATION_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.retain(|_, cached| now_nanos.saturating_sub(cached.simulated_at_nanos) < SIMULATION_CACHE_TTL_NANOS);
+        cache.insert(
+            key,
+            CachedSimulation {
+                gas_used,
+                simulated_at_nanos: now_nanos,
+            },
+        );
+    });
+}
@@ -1,13 +1,17 @@
-use std::{cell::RefCell, str::FromStr};
+use std::{cell::RefCell, collections::BTreeMap, str::FromStr};
 
 use candid::{CandidType, Deserialize};
 use ic_cdk::{
     api::management_canister::main::CanisterId,
     storage::{stable_restore, stable_save},
 };
+use ic_cosmos::{rpc_client::RpcServices, types::ChainConfig};
 use serde::Serialize;
 
-use crate::eddsa::EcdsaKey;
+use crate::{eddsa::EcdsaKey, send_config::SendCosmosTransactionConfig};
+
+/// Default time after which the cached account number and sequence are re-fetched from the chain.
+pub const DEFAULT_ACCOUNT_INFO_STALENESS_NANOS: u64 = 30_000_000_000;
 
 thread_local! {
     pub static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
@@ -17,12 +21,53 @@ thread_local! {
 pub struct InitArgs {
     pub cos_canister: Option<CanisterId>,
     pub ecdsa_key: Option<String>,
+    pub account_info_staleness_nanos: Option<u64>,
+    /// Config canister to load the known chains from on init and upgrade.
+    pub config_canister: Option<CanisterId>,
+    /// Config of the `sendCosmosTransaction` calls that do not give one.
+    pub send_config: Option<SendCosmosTransactionConfig>,
+}
+
+/// Arguments of `rotateEcdsaKey`.
+#[derive(CandidType, Clone, Debug, Deserialize)]
+pub struct RotateEcdsaKey {
+    pub new_key_name: String,
+    /// Time during which the addresses of the previous key are still accepted.
+    pub transition_period_nanos: u64,
+}
+
+/// ECDSA key replaced by a rotation, retired at the end of the transition period.
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct KeyRotation {
+    pub previous_key: EcdsaKey,
+    pub retires_at_nanos: u64,
+}
+
+/// Cached account number and sequence of a Cosmos address.
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AccountInfoCache {
+    pub account_number: u64,
+    /// The sequence to use for the next transaction, reserved by each transaction before it is signed.
+    pub current_sequence: u64,
+    pub last_fetched_at_nanos: u64,
 }
 
 #[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
 pub struct State {
     pub cos_canister: CanisterId,
     pub ecdsa_key: EcdsaKey,
+    pub account_info_staleness_nanos: Option<u64>,
+    pub account_info_cache: Option<BTreeMap<String, AccountInfoCache>>,
+    /// `block.max_bytes` consensus param of each RPC source, -1 if unlimited, keyed by
+    /// [`source_cache_key`].
+    pub max_tx_bytes_cache: Option<BTreeMap<String, i64>>,
+    pub config_canister: Option<CanisterId>,
+    /// Chain configurations loaded from the config canister, keyed by chain ID.
+    pub known_chains: Option<BTreeMap<String, ChainConfig>>,
+    /// Key replaced by the last rotation, while its transition period runs.
+    pub key_rotation: Option<KeyRotation>,
+    /// Config of the `sendCosmosTransaction` calls that do not give one.
+    pub send_config: Option<SendCosmosTransactionConfig>,
 }
 
 impl State {
@@ -33,7 +78,140 @@ impl State {
                 .ecdsa_key
                 .and_then(|s| EcdsaKey::from_str(&s).ok())
                 .unwrap_or(EcdsaKey::TestKey1),
+            account_info_staleness_nanos: args.account_info_staleness_nanos,
+            account_info_cache: None,
+            max_tx_bytes_cache: None,
+            config_canister: args.config_canister,
+            known_chains: None,
+            key_rotation: None,
+            send_config: args.send_config,
+        });
+    }
+
+    /// Returns the cached account info of `address` if it was fetched within the staleness threshold.
+    pub fn get_account_info(&self, address: &str, now_nanos: u64) -> Option<AccountInfoCache> {
+        let staleness = self
+            .account_info_staleness_nanos
+            .unwrap_or(DEFAULT_ACCOUNT_INFO_STALENESS_NANOS);
+        self.account_info_cache
+            .as_ref()?
+            .get(address)
+            .filter(|info| now_nanos.saturating_sub(info.last_fetched_at_nanos) < staleness)
+            .cloned()
+    }
+
+    /// Stores the account info of `address` freshly fetched from the chain.
+    pub fn cache_account_info(&mut self, address: &str, account_number: u64, sequence: u64, now_nanos: u64) {
+        self.account_info_cache.get_or_insert_with(BTreeMap::new).insert(
+            address.to_string(),
+            AccountInfoCache {
+                account_number,
+                current_sequence: sequence,
+                last_fetched_at_nanos: now_nanos,
+            },
+        );
+    }
+
+    /// Reserves the cached sequence of `address` for a transaction and advances it, so that the
+    /// concurrent transactions of `address` are signed with different sequences. Returns the
+    /// account number and the reserved sequence, or `None` if the cache is stale.
+    pub fn reserve_sequence(&mut self, address: &str, now_nanos: u64) -> Option<(u64, u64)> {
+        self.get_account_info(address, now_nanos)?;
+        let info = self.account_info_cache.as_mut()?.get_mut(address)?;
+        info.current_sequence += 1;
+        Some((info.account_number, info.current_sequence - 1))
+    }
+
+    /// Gives back the `sequence` reserved for a transaction of `address` that was not broadcast.
+    /// The cache is dropped if a later sequence was reserved meanwhile, the following
+    /// transactions would not match the chain.
+    pub fn release_sequence(&mut self, address: &str, sequence: u64) {
+        match self.account_info_cache.as_mut().and_then(|c| c.get_mut(address)) {
+            Some(info) if info.current_sequence == sequence + 1 => info.current_sequence = sequence,
+            Some(_) => self.invalidate_account_info(address),
+            None => {}
+        }
+    }
+
+    /// Drops the cached account info of `address`, forcing a re-fetch on the next transaction.
+    pub fn invalidate_account_info(&mut self, address: &str) {
+        if let Some(cache) = self.account_info_cache.as_mut() {
+            cache.remove(address);
+        }
+    }
+
+    /// Returns the cached maximum transaction size of the RPC `source`.
+    pub fn get_max_tx_bytes(&self, source: &RpcServices) -> Option<i64> {
+        self.max_tx_bytes_cache
+            .as_ref()?
+            .get(&source_cache_key(source))
+            .copied()
+    }
+
+    /// Stores the maximum transaction size reported by the node behind the RPC `source`.
+    pub fn cache_max_tx_bytes(&mut self, source: &RpcServices, max_bytes: i64) {
+        self.max_tx_bytes_cache
+            .get_or_insert_with(BTreeMap::new)
+            .insert(source_cache_key(source), max_bytes);
+    }
+
+    /// Returns the configuration of `chain_id` loaded from the config canister.
+    pub fn get_known_chain(&self, chain_id: &str) -> Option<ChainConfig> {
+        self.known_chains.as_ref()?.get(chain_id).cloned()
+    }
+
+    /// Replaces the known chains with the configurations loaded from the config canister.
+    pub fn set_known_chains(&mut self, chains: Vec<ChainConfig>) {
+        self.known_chains = Some(
+            chains
+                .into_iter()
+                .map(|chain| (chain.chain_id.clone(), chain))
+                .collect(),
+        );
+    }
+
+    /// Returns the config of the `sendCosmosTransaction` calls, `call_config` if the caller gave
+    /// one, the config of the canister otherwise.
+    pub fn send_config(&self, call_config: Option<SendCosmosTransactionConfig>) -> SendCosmosTransactionConfig {
+        call_config.or(self.send_config).unwrap_or_default()
+    }
+
+    /// Replaces the ECDSA key with `args.new_key_name`, keeping the current key as the previous
+    /// key until `now_nanos + args.transition_period_nanos`.
+    pub fn rotate_ecdsa_key(&mut self, args: RotateEcdsaKey, now_nanos: u64) -> Result<(), String> {
+        if self.key_rotation.is_some() {
+            return Err("A key rotation is already in progress".to_string());
+        }
+        let new_key = EcdsaKey::from_str(&args.new_key_name)?;
+        if new_key.to_string() == self.ecdsa_key.to_string() {
+            return Err(format!("{} is already the current key", new_key));
+        }
+        self.key_rotation = Some(KeyRotation {
+            previous_key: std::mem::replace(&mut self.ecdsa_key, new_key),
+            retires_at_nanos: now_nanos.saturating_add(args.transition_period_nanos),
         });
+        Ok(())
+    }
+
+    /// Returns the previous key while its transition period runs at `now_nanos`.
+    pub fn previous_ecdsa_key(&self, now_nanos: u64) -> Option<EcdsaKey> {
+        self.key_rotation
+            .as_ref()
+            .filter(|rotation| now_nanos < rotation.retires_at_nanos)
+            .map(|rotation| rotation.previous_key.clone())
+    }
+
+    /// Retires the previous key once its transition period is over at `now_nanos`.
+    /// The cached account info is dropped with it, it may belong to addresses of the previous key.
+    pub fn retire_previous_key(&mut self, now_nanos: u64) {
+        if self
+            .key_rotation
+            .as_ref()
+            .is_some_and(|rotation| now_nanos >= rotation.retires_at_nanos)
+        {
+            self.key_rotation = None;
+            self.account_info_cache = None;
+        }
     }
 
     pub fn pre_upgrade() {
@@ -49,6 +227,15 @@ impl State {
             if let Some(ecdsa_key) = args.ecdsa_key {
                 state.ecdsa_key = EcdsaKey::from_str(&ecdsa_key).expect("Invalid ecdsa key");
             }
+            if let Some(staleness) = args.account_info_staleness_nanos {
+                state.account_info_staleness_nanos = Some(staleness);
+            }
+            if let Some(config_canister) = args.config_canister {
+                state.config_canister = Some(config_canister);
+            }
+            if let Some(send_config) = args.send_config {
+                state.send_config = Some(send_config);
+            }
         }
         replace_state(state);
     }
@@ -58,10 +245,37 @@ impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Cosmos canister: {:?}", self.cos_canister)?;
         writeln!(f, "ECDSA key: {:?}", self.ecdsa_key)?;
+        if let Some(rotation) = &self.key_rotation {
+            writeln!(
+                f,
+                "Previous ECDSA key: {:?}, retired at {}",
+                rotation.previous_key, rotation.retires_at_nanos
+            )?;
+        }
+        writeln!(f, "Account info staleness: {:?}", self.account_info_staleness_nanos)?;
+        writeln!(f, "Send config: {:?}", self.send_config)?;
         Ok(())
     }
 }
 
+/// Returns a stable key identifying the RPC `source` in the state caches.
+pub fn source_cache_key(source: &RpcServices) -> String {
+    match source {
+        RpcServices::Mainnet => "mainnet".to_string(),
+        RpcServices::Testnet => "testnet".to_string(),
+        RpcServices::Devnet => "devnet".to_string(),
+        RpcServices::Localnet => "localnet".to_string(),
+        RpcServices::Provider(ids) => format!("provider:{}", ids.join(",")),
+        RpcServices::Custom(apis) => format!(
+            "custom:{}",
+            apis.iter()
+                .map(|api| api.network.as_str())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
 /// Take the current state.
 ///
 /// After calling this function, the state won't be initialized anymore.
@@ -27,26 +27,30 @@ impl Default for CosmosWalletSetup {
 
 impl CosmosWalletSetup {
     pub fn new() -> Self {
-        let rpc_setup = TestSetup::new(
+        // The wallet is created first, its canister ID is part of the derivation of its addresses
+        let setup = TestSetup::create();
+        let rpc_setup = setup.create_canister();
+        rpc_setup.install_code(
             RPC_WASM.with(|wasm| wasm.clone()),
             ic_cosmos_rpc::state::InitArgs {
                 demo: Some(true),
                 managers: Some(vec![TestSetup::controller_id()]),
+                config_canister: None,
+                rate_limit: None,
+            },
+        );
+        setup.install_code(
+            WASM.with(|wasm| wasm.clone()),
+            InitArgs {
+                cos_canister: Some(rpc_setup.canister_id),
+                ecdsa_key: None,
+                account_info_staleness_nanos: None,
+                config_canister: None,
+                send_config: None,
             },
         );
 
-        let cos_canister = Some(rpc_setup.canister_id);
-
-        Self {
-            rpc_setup,
-            setup: TestSetup::new(
-                WASM.with(|wasm| wasm.clone()),
-                InitArgs {
-                    cos_canister: cos_canister,
-                    ecdsa_key: None,
-                },
-            ),
-        }
+        Self { rpc_setup, setup }
     }
 
     #[allow(clippy::wrong_self_convention)]
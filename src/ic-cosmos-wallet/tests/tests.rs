@@ -1,19 +1,51 @@
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use cosmrs::bank::MsgSend;
-use cosmrs::crypto::secp256k1::VerifyingKey;
+use cosmrs::crypto::secp256k1::{Signature, VerifyingKey};
 use cosmrs::crypto::PublicKey;
+use cosmrs::proto::cosmos::{
+    auth::v1beta1::{BaseAccount, QueryAccountResponse},
+    bank::v1beta1::MsgSend as ProtoMsgSend,
+    base::{abci::v1beta1::GasInfo, v1beta1::Coin as ProtoCoin},
+    distribution::v1beta1::MsgSetWithdrawAddress as ProtoMsgSetWithdrawAddress,
+    staking::v1beta1::MsgDelegate as ProtoMsgDelegate,
+    tx::v1beta1::{AuthInfo, Fee, SignDoc as ProtoSignDoc, SimulateResponse, TxBody, TxRaw},
+};
+use cosmrs::proto::traits::Message;
 use cosmrs::tendermint::{block, chain};
 use cosmrs::tx::{self, Msg, Raw, SignDoc};
 use cosmrs::Coin;
-use ic_cosmos::{rpc_client::RpcServices, types::Pubkey};
-use test_utils::MockOutcallBuilder;
+use ic_cosmos::{
+    rpc_client::{RpcResult, RpcServices},
+    types::{
+        build_transaction_for_broadcast, create_sign_doc_bytes, extract_signer_address_from_message, format_block_time,
+        normalize_ecdsa_signature, public_key_to_cosmos_address, CosmosCoin, CosmosMessage, CosmosTransaction, Pubkey,
+        SignMode,
+    },
+};
+use ic_cosmos_wallet::{
+    dry_run::DryRunResult,
+    receipt::{TransactionWithReceipt, RECEIPT_POLL_INITIAL_DELAY},
+    send_config::SendCosmosTransactionConfig,
+    state::RotateEcdsaKey,
+};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use test_utils::{MockJsonRequestBody, MockOutcallBuilder};
 
 mod setup;
 
 use crate::setup::CosmosWalletSetup;
 
+const CHAIN_ID: &str = "pion-1";
+const ACCOUNT_NUMBER: u64 = 577723;
+const VALIDATOR_ADDRESS: &str = "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0";
+
 #[test]
 fn test_address() {
     let setup = CosmosWalletSetup::new();
@@ -26,6 +58,43 @@ fn test_address() {
     assert_eq!(addr, "tpjAAg3YphZd7zytYuCbFojxC5RDTFTrbSJTj1iM2zNZ");
 }
 
+#[test]
+fn test_rotate_ecdsa_key() {
+    let setup = CosmosWalletSetup::new();
+    let rotation = RotateEcdsaKey {
+        new_key_name: "dfx_test_key".to_string(),
+        transition_period_nanos: 3_600_000_000_000,
+    };
+
+    let previous_address = setup.call_update::<_, String>("address", ()).wait();
+    let result = setup
+        .call_update::<_, Result<(), String>>("rotateEcdsaKey", (rotation.clone(),))
+        .wait();
+    assert_eq!(result, Err("Unauthorized".to_string()));
+
+    let controller = setup.clone().as_controller();
+    let result = controller
+        .call_update::<_, Result<(), String>>("rotateEcdsaKey", (rotation.clone(),))
+        .wait();
+    assert_eq!(result, Ok(()));
+
+    // The new key is used for the address and the signatures right away
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    assert_ne!(address, previous_address);
+    let message = b"test123".to_vec();
+    let signature = setup
+        .call_update::<_, Vec<u8>>("signMessage", (message.clone(),))
+        .wait();
+    assert!(Pubkey::from_str(&address)
+        .unwrap()
+        .verify_signature(&message, &signature));
+
+    let result = controller
+        .call_update::<_, Result<(), String>>("rotateEcdsaKey", (rotation,))
+        .wait();
+    assert_eq!(result, Err("A key rotation is already in progress".to_string()));
+}
+
 #[test]
 fn test_sign_message() {
     let setup = CosmosWalletSetup::new();
@@ -61,9 +130,785 @@ fn test_sign_cosmos_message() {
     println!("signature: {:?}", signature);
     println!("message(base64): {:?}", STANDARD.encode(&message));
     println!("signature(base64): {:?}", STANDARD.encode(&signature));
-    let signed_tx = get_signed_tx_bytes(address, signature).unwrap();
+    let signed_tx = get_signed_tx_bytes(address.clone(), signature).unwrap();
     println!("signed_tx(base64): {:?}", STANDARD.encode(&signed_tx));
+
+    let public_key_bytes = bs58::decode(address).into_vec().unwrap();
+    assert!(verify_cosmos_tx_signature(&signed_tx, &public_key_bytes));
+}
+#[test]
+fn test_multi_message_cosmos_transaction() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let public_key_bytes = bs58::decode(&address).into_vec().unwrap();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let transaction = CosmosTransaction {
+        messages: multi_messages(&cosmos_address),
+        fee: vec![CosmosCoin::new("uatom", "5000")],
+        gas_limit: 300_000,
+        memo: "multi".to_string(),
+        chain_id: CHAIN_ID.to_string(),
+        account_number: ACCOUNT_NUMBER,
+        sequence: 4,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    };
+
+    let sign_bytes = create_sign_doc_bytes(&transaction, &public_key_bytes).unwrap();
+    let sign_doc = ProtoSignDoc::decode(&sign_bytes[..]).unwrap();
+    assert_eq!(sign_doc.chain_id, CHAIN_ID);
+    assert_eq!(sign_doc.account_number, ACCOUNT_NUMBER);
+
+    let body = TxBody::decode(&sign_doc.body_bytes[..]).unwrap();
+    assert_eq!(body.memo, "multi");
+    assert_eq!(body.messages.len(), 2);
+    assert_eq!(body.messages[0].type_url, "/cosmos.bank.v1beta1.MsgSend");
+    let msg_send = ProtoMsgSend::decode(&body.messages[0].value[..]).unwrap();
+    assert_eq!(msg_send.from_address, cosmos_address);
+    assert_eq!(msg_send.amount[0].amount, "1000");
+    assert_eq!(body.messages[1].type_url, "/cosmos.staking.v1beta1.MsgDelegate");
+    let msg_delegate = ProtoMsgDelegate::decode(&body.messages[1].value[..]).unwrap();
+    assert_eq!(msg_delegate.delegator_address, cosmos_address);
+    assert_eq!(msg_delegate.validator_address, VALIDATOR_ADDRESS);
+
+    let signature = setup
+        .call_update::<_, Vec<u8>>("signMessage", (sign_bytes.clone(),))
+        .wait();
+    assert!(Pubkey::from_str(&address)
+        .unwrap()
+        .verify_signature(&sign_bytes, &signature));
+
+    let signature = normalize_ecdsa_signature(&signature).unwrap();
+    let signed_tx = build_transaction_for_broadcast(&transaction, &public_key_bytes, &signature).unwrap();
+    assert!(verify_cosmos_tx_signature(
+        &STANDARD.decode(signed_tx).unwrap(),
+        &public_key_bytes
+    ));
+}
+
+#[test]
+fn test_set_withdraw_address_transaction() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let public_key_bytes = bs58::decode(&address).into_vec().unwrap();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let withdraw_address = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+    let message = CosmosMessage {
+        type_url: "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress".to_string(),
+        value: json!({
+            "delegator_address": cosmos_address,
+            "withdraw_address": withdraw_address
+        }),
+    };
+    assert_eq!(extract_signer_address_from_message(&message).unwrap(), cosmos_address);
+
+    let transaction = CosmosTransaction {
+        messages: vec![message],
+        fee: vec![CosmosCoin::new("uatom", "5000")],
+        gas_limit: 200_000,
+        memo: String::new(),
+        chain_id: CHAIN_ID.to_string(),
+        account_number: ACCOUNT_NUMBER,
+        sequence: 4,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    };
+    let sign_bytes = create_sign_doc_bytes(&transaction, &public_key_bytes).unwrap();
+    let sign_doc = ProtoSignDoc::decode(&sign_bytes[..]).unwrap();
+    let body = TxBody::decode(&sign_doc.body_bytes[..]).unwrap();
+    assert_eq!(
+        body.messages[0].type_url,
+        "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress"
+    );
+    let msg = ProtoMsgSetWithdrawAddress::decode(&body.messages[0].value[..]).unwrap();
+    assert_eq!(msg.delegator_address, cosmos_address);
+    assert_eq!(msg.withdraw_address, withdraw_address);
+
+    let signature = setup
+        .call_update::<_, Vec<u8>>("signMessage", (sign_bytes.clone(),))
+        .wait();
+    let signature = normalize_ecdsa_signature(&signature).unwrap();
+    let signed_tx = build_transaction_for_broadcast(&transaction, &public_key_bytes, &signature).unwrap();
+    assert!(verify_cosmos_tx_signature(
+        &STANDARD.decode(signed_tx).unwrap(),
+        &public_key_bytes
+    ));
+}
+
+#[test]
+fn test_send_cosmos_transaction_with_multiple_messages() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let raw_transaction = multi_message_raw_transaction(&cosmos_address);
+
+    let result = setup
+        .call_update::<_, RpcResult<String>>(
+            "sendCosmosTransaction",
+            (
+                RpcServices::Mainnet,
+                (),
+                CHAIN_ID.to_string(),
+                raw_transaction,
+                None::<SendCosmosTransactionConfig>,
+            ),
+        )
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        // Without the consensus params, the size of the transaction is not checked
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"9A4B5E2C1F3D"},"id":1}"#,
+        ))
+        .wait();
+    assert_eq!(result.unwrap(), "9A4B5E2C1F3D");
+}
+
+#[test]
+fn test_send_cosmos_transaction_end_to_end() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let public_key_bytes = bs58::decode(&address).into_vec().unwrap();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let raw_transaction = json!({
+        "body": {
+            "messages": [{
+                "@type": "/cosmos.bank.v1beta1.MsgSend",
+                "from_address": cosmos_address,
+                "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                "amount": [{ "denom": "uatom", "amount": "1000" }]
+            }],
+            "memo": "e2e"
+        },
+        "auth_info": { "fee": { "amount": [{ "denom": "uatom", "amount": "5000" }], "gas_limit": "200000" } }
+    })
+    .to_string();
+    let consensus_params_response = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "block_height": "1000",
+            "consensus_params": {
+                "block": { "max_bytes": "22020096", "max_gas": "-1" },
+                "evidence": {
+                    "max_age_num_blocks": "100000",
+                    "max_age_duration": "172800000000000",
+                    "max_bytes": "1048576"
+                },
+                "validator": { "pub_key_types": ["ed25519"] }
+            }
+        }
+    });
+
+    let mut account_query = None;
+    let mut broadcast_request = None;
+    let result = setup
+        .call_update::<_, RpcResult<String>>(
+            "sendCosmosTransaction",
+            (
+                RpcServices::Mainnet,
+                (),
+                CHAIN_ID.to_string(),
+                raw_transaction,
+                None::<SendCosmosTransactionConfig>,
+            ),
+        )
+        .mock_http_once(
+            MockOutcallBuilder::new(200, status_response(CHAIN_ID))
+                .with_request_body(MockJsonRequestBody::new("status")),
+        )
+        .inspect_http_request(|request| account_query = Some(request.body.clone()))
+        .mock_http_once(
+            MockOutcallBuilder::new(200, abci_query_response(&account_response(&cosmos_address)))
+                .with_request_body(MockJsonRequestBody::new("abci_query")),
+        )
+        .mock_http_once(
+            MockOutcallBuilder::new(200, consensus_params_response.to_string())
+                .with_request_body(MockJsonRequestBody::new("consensus_params")),
+        )
+        .inspect_http_request(|request| broadcast_request = Some(request.body.clone()))
+        .mock_http_once(
+            MockOutcallBuilder::new(
+                200,
+                r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"5C3F0E7A9B1D"},"id":1}"#,
+            )
+            .with_request_body(MockJsonRequestBody::new("broadcast_tx_sync")),
+        )
+        .wait();
+    assert_eq!(result.unwrap(), "5C3F0E7A9B1D");
+
+    // The account of the signer is queried through the RPC canister
+    let account_query: serde_json::Value = serde_json::from_slice(&account_query.unwrap()).unwrap();
+    assert_eq!(account_query["params"][0], "/cosmos.auth.v1beta1.Query/Account");
+    let query_data = hex::decode(account_query["params"][1].as_str().unwrap()).unwrap();
+    assert!(query_data.ends_with(cosmos_address.as_bytes()));
+
+    // The broadcast transaction is signed by the wallet with the sequence of the account
+    let broadcast_request: serde_json::Value = serde_json::from_slice(&broadcast_request.unwrap()).unwrap();
+    let tx_bytes = STANDARD
+        .decode(broadcast_request["params"][0].as_str().unwrap())
+        .unwrap();
+    assert!(verify_cosmos_tx_signature(&tx_bytes, &public_key_bytes));
+    let tx_raw = TxRaw::decode(&tx_bytes[..]).unwrap();
+    let body = TxBody::decode(&tx_raw.body_bytes[..]).unwrap();
+    assert_eq!(body.memo, "e2e");
+    assert_eq!(body.messages.len(), 1);
+    let msg_send = ProtoMsgSend::decode(&body.messages[0].value[..]).unwrap();
+    assert_eq!(msg_send.from_address, cosmos_address);
+    assert_eq!(msg_send.amount[0].amount, "1000");
+    let auth_info = AuthInfo::decode(&tx_raw.auth_info_bytes[..]).unwrap();
+    assert_eq!(auth_info.signer_infos[0].sequence, 4);
+    let fee = auth_info.fee.unwrap();
+    assert_eq!(fee.gas_limit, 200_000);
+    assert_eq!(fee.amount[0].amount, "5000");
+}
+
+#[test]
+fn test_send_cosmos_transaction_dry_run() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let simulate_response = SimulateResponse {
+        gas_info: Some(GasInfo {
+            gas_wanted: 0,
+            gas_used: 100_000,
+        }),
+        result: None,
+    };
+
+    // Nothing is signed, the simulation is the last call
+    let result = setup
+        .call_update::<_, RpcResult<DryRunResult>>(
+            "sendCosmosTransactionDryRun",
+            (
+                RpcServices::Mainnet,
+                (),
+                CHAIN_ID.to_string(),
+                multi_message_raw_transaction(&cosmos_address),
+            ),
+        )
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&simulate_response.encode_to_vec()),
+        ))
+        .wait()
+        .unwrap();
+
+    assert!(result.valid);
+    assert_eq!(result.estimated_gas, 130_000);
+    // At the price of the transaction fee, 5000uatom for 300000 gas
+    assert_eq!(result.estimated_fee.denom, "uatom");
+    assert_eq!(result.estimated_fee.amount, "2167");
+    assert!(result.warnings.is_empty());
 }
+
+#[test]
+fn test_send_cosmos_transaction_dry_run_reuses_simulation() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let simulate_response = SimulateResponse {
+        gas_info: Some(GasInfo {
+            gas_wanted: 0,
+            gas_used: 100_000,
+        }),
+        result: None,
+    };
+    let args = (
+        RpcServices::Mainnet,
+        (),
+        CHAIN_ID.to_string(),
+        multi_message_raw_transaction(&cosmos_address),
+    );
+
+    let first = setup
+        .call_update::<_, RpcResult<DryRunResult>>("sendCosmosTransactionDryRun", args.clone())
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&simulate_response.encode_to_vec()),
+        ))
+        .wait()
+        .unwrap();
+
+    // The account is cached, the simulation within the same block as well: only the status of
+    // the node and the consensus params, which failed to be fetched, are requested again
+    let second = setup
+        .call_update::<_, RpcResult<DryRunResult>>("sendCosmosTransactionDryRun", args)
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .wait()
+        .unwrap();
+
+    assert_eq!(second.estimated_gas, first.estimated_gas);
+    assert_eq!(second.estimated_fee, first.estimated_fee);
+}
+
+#[test]
+fn test_send_cosmos_transaction_with_receipt() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let result = setup
+        .call_update::<_, RpcResult<TransactionWithReceipt>>(
+            "sendCosmosTransactionWithReceipt",
+            (
+                RpcServices::Mainnet,
+                (),
+                CHAIN_ID.to_string(),
+                multi_message_raw_transaction(&cosmos_address),
+                Some(2u32),
+            ),
+        )
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"9A4B5E2C1F3D"},"id":1}"#,
+        ))
+        // Not in a block yet on the first attempt
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":"tx (9A4B5E2C1F3D) not found"},"id":1}"#,
+        ))
+        .advance_time(RECEIPT_POLL_INITIAL_DELAY)
+        .mock_http_once(MockOutcallBuilder::new(200, tx_response("9A4B5E2C1F3D", 0)))
+        .mock_http_once(MockOutcallBuilder::new(200, header_response()))
+        .wait()
+        .unwrap();
+
+    assert_eq!(result.broadcast.hash, "9A4B5E2C1F3D");
+    let receipt = result.receipt.unwrap();
+    assert_eq!(receipt.height, 1001);
+    assert_eq!(receipt.block_time, "2024-01-01T00:00:06Z");
+    assert_eq!(receipt.gas_used, 123_456);
+    assert_eq!(receipt.events[0].r#type, "transfer");
+    assert_eq!(receipt.fee, vec![CosmosCoin::new("uatom", "5000")]);
+}
+
+#[test]
+fn test_send_cosmos_transaction_waits_for_confirmation() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let send_config = SendCosmosTransactionConfig {
+        wait_for_confirmation: true,
+        ..Default::default()
+    };
+    let consensus_params_error =
+        r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#;
+    let broadcast_response =
+        r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"9A4B5E2C1F3D"},"id":1}"#;
+    let args = (
+        RpcServices::Mainnet,
+        (),
+        CHAIN_ID.to_string(),
+        multi_message_raw_transaction(&cosmos_address),
+        Some(send_config),
+    );
+
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args.clone())
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(200, consensus_params_error))
+        .mock_http_once(MockOutcallBuilder::new(200, broadcast_response))
+        // Not in a block yet on the first attempt
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":"tx (9A4B5E2C1F3D) not found"},"id":1}"#,
+        ))
+        .advance_time(RECEIPT_POLL_INITIAL_DELAY)
+        .mock_http_once(MockOutcallBuilder::new(200, tx_response("9A4B5E2C1F3D", 0)))
+        .mock_http_once(MockOutcallBuilder::new(200, header_response()))
+        .wait();
+    assert_eq!(result.unwrap(), "9A4B5E2C1F3D");
+
+    // The broadcast succeeds but the transaction fails in the block. The account is cached by
+    // the first call.
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args)
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(200, consensus_params_error))
+        .mock_http_once(MockOutcallBuilder::new(200, broadcast_response))
+        .mock_http_once(MockOutcallBuilder::new(200, tx_response("9A4B5E2C1F3D", 11)))
+        .mock_http_once(MockOutcallBuilder::new(200, header_response()))
+        .wait();
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("failed with code 11"), "{}", error);
+}
+
+#[test]
+fn test_send_cosmos_transaction_without_sequence_retry() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let send_config = SendCosmosTransactionConfig {
+        auto_retry_sequence: false,
+        ..Default::default()
+    };
+
+    // The sequence mismatch is returned as is, without re-fetching the account
+    let result = setup
+        .call_update::<_, RpcResult<String>>(
+            "sendCosmosTransaction",
+            (
+                RpcServices::Mainnet,
+                (),
+                CHAIN_ID.to_string(),
+                multi_message_raw_transaction(&cosmos_address),
+                Some(send_config),
+            ),
+        )
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":32,"data":"","log":"account sequence mismatch","codespace":"sdk","hash":"7D2E4F6A8C0B"},"id":1}"#,
+        ))
+        .wait();
+    assert_eq!(result.unwrap(), "7D2E4F6A8C0B");
+}
+
+#[test]
+fn test_send_cosmos_transaction_releases_rejected_sequence() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let consensus_params_error =
+        r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#;
+    let args = (
+        RpcServices::Mainnet,
+        (),
+        CHAIN_ID.to_string(),
+        multi_message_raw_transaction(&cosmos_address),
+        None::<SendCosmosTransactionConfig>,
+    );
+
+    // Rejected by `CheckTx`, the sequence reserved for the transaction is not consumed
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args.clone())
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(200, consensus_params_error))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":5,"data":"","log":"insufficient funds","codespace":"sdk","hash":"1B3D5F7A9C2E"},"id":1}"#,
+        ))
+        .wait();
+    assert_eq!(result.unwrap(), "1B3D5F7A9C2E");
+
+    // The next transaction is signed with the same sequence, from the cache
+    let mut broadcast_request = None;
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args)
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(200, consensus_params_error))
+        .inspect_http_request(|request| broadcast_request = Some(request.body.clone()))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"2C4E6A8B0D1F"},"id":1}"#,
+        ))
+        .wait();
+    assert_eq!(result.unwrap(), "2C4E6A8B0D1F");
+    let broadcast_request: serde_json::Value = serde_json::from_slice(&broadcast_request.unwrap()).unwrap();
+    let tx_bytes = STANDARD
+        .decode(broadcast_request["params"][0].as_str().unwrap())
+        .unwrap();
+    let tx_raw = TxRaw::decode(&tx_bytes[..]).unwrap();
+    let auth_info = AuthInfo::decode(&tx_raw.auth_info_bytes[..]).unwrap();
+    assert_eq!(auth_info.signer_infos[0].sequence, 4);
+}
+
+#[test]
+fn test_send_cosmos_transaction_rejects_catching_up_node() {
+    let setup = CosmosWalletSetup::new();
+
+    let address = setup.call_update::<_, String>("address", ()).wait();
+    let cosmos_address = public_key_to_cosmos_address(&address).unwrap();
+    let args = (
+        RpcServices::Mainnet,
+        (),
+        CHAIN_ID.to_string(),
+        multi_message_raw_transaction(&cosmos_address),
+        None::<SendCosmosTransactionConfig>,
+    );
+
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args.clone())
+        .mock_http_once(MockOutcallBuilder::new(200, status_response(CHAIN_ID)))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            abci_query_response(&account_response(&cosmos_address)),
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"Internal error","data":""},"id":1}"#,
+        ))
+        .mock_http_once(MockOutcallBuilder::new(
+            200,
+            r#"{"jsonrpc":"2.0","result":{"code":0,"data":"","log":"[]","codespace":"","hash":"9A4B5E2C1F3D"},"id":1}"#,
+        ))
+        .wait();
+    assert_eq!(result.unwrap(), "9A4B5E2C1F3D");
+
+    // The node fell behind since the first transaction
+    let result = setup
+        .call_update::<_, RpcResult<String>>("sendCosmosTransaction", args)
+        .mock_http_once(MockOutcallBuilder::new(200, node_status_response(CHAIN_ID, true)))
+        .wait();
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("Node is catching up"), "{}", error);
+}
+
+/// JSON of a transaction of the `multi_messages` of `address`, as given to `sendCosmosTransaction`
+fn multi_message_raw_transaction(address: &str) -> String {
+    let messages: Vec<_> = multi_messages(address)
+        .into_iter()
+        .map(|message| {
+            let mut value = message.value;
+            value["@type"] = message.type_url.into();
+            value
+        })
+        .collect();
+    json!({
+        "body": { "messages": messages, "memo": "multi" },
+        "auth_info": { "fee": { "amount": [{ "denom": "uatom", "amount": "5000" }], "gas_limit": "300000" } }
+    })
+    .to_string()
+}
+
+/// `QueryAccountResponse` of the base account `address`
+fn account_response(address: &str) -> Vec<u8> {
+    let account = BaseAccount {
+        address: address.to_string(),
+        pub_key: None,
+        account_number: ACCOUNT_NUMBER,
+        sequence: 4,
+    };
+    QueryAccountResponse {
+        account: Some(cosmrs::Any {
+            type_url: "/cosmos.auth.v1beta1.BaseAccount".to_string(),
+            value: account.encode_to_vec(),
+        }),
+    }
+    .encode_to_vec()
+}
+
+/// `abci_query` response of a successful query returning `value`
+fn abci_query_response(value: &[u8]) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": { "response": {
+            "code": 0,
+            "log": "",
+            "index": "0",
+            "key": null,
+            "value": STANDARD.encode(value),
+            "height": "1000",
+            "codespace": ""
+        } }
+    })
+    .to_string()
+}
+
+/// A `MsgSend` and a `MsgDelegate` signed by `address`
+fn multi_messages(address: &str) -> Vec<CosmosMessage> {
+    vec![
+        CosmosMessage {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: json!({
+                "from_address": address,
+                "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                "amount": [{ "denom": "uatom", "amount": "1000" }]
+            }),
+        },
+        CosmosMessage {
+            type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+            value: json!({
+                "delegator_address": address,
+                "validator_address": VALIDATOR_ADDRESS,
+                "amount": { "denom": "uatom", "amount": "250000" }
+            }),
+        },
+    ]
+}
+
+/// `tx` response of the transaction `hash` in block 1001, with a 5000uatom fee
+fn tx_response(hash: &str, code: u32) -> String {
+    let auth_info = AuthInfo {
+        signer_infos: Vec::new(),
+        fee: Some(Fee {
+            amount: vec![ProtoCoin {
+                denom: "uatom".to_string(),
+                amount: "5000".to_string(),
+            }],
+            gas_limit: 300_000,
+            payer: String::new(),
+            granter: String::new(),
+        }),
+        ..Default::default()
+    };
+    let tx_raw = TxRaw {
+        body_bytes: Vec::new(),
+        auth_info_bytes: auth_info.encode_to_vec(),
+        signatures: Vec::new(),
+    };
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "hash": hash,
+            "height": "1001",
+            "index": 0,
+            "tx_result": {
+                "code": code,
+                "data": "",
+                "log": if code == 0 { "" } else { "out of gas" },
+                "gas_wanted": "300000",
+                "gas_used": "123456",
+                "events": [{
+                    "type": "transfer",
+                    "attributes": [{ "key": "amount", "value": "1000uatom", "index": true }]
+                }]
+            },
+            "tx": STANDARD.encode(tx_raw.encode_to_vec())
+        }
+    })
+    .to_string()
+}
+
+/// `header` response of block 1001
+fn header_response() -> String {
+    let block_id = json!({ "hash": "", "parts": { "total": 1, "hash": "" } });
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "header": {
+                "version": { "block": "11" },
+                "chain_id": CHAIN_ID,
+                "height": "1001",
+                "time": "2024-01-01T00:00:06Z",
+                "last_block_id": block_id,
+                "last_commit_hash": "",
+                "data_hash": "",
+                "validators_hash": "",
+                "next_validators_hash": "",
+                "consensus_hash": "",
+                "app_hash": "",
+                "last_results_hash": "",
+                "evidence_hash": "",
+                "proposer_address": ""
+            }
+        }
+    })
+    .to_string()
+}
+
+/// `status` response of a synced node of `chain_id`
+fn status_response(chain_id: &str) -> String {
+    node_status_response(chain_id, false)
+}
+
+/// `status` response of a node of `chain_id`, with a latest block produced now
+fn node_status_response(chain_id: &str, catching_up: bool) -> String {
+    let now_nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64;
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "node_info": {
+                "protocol_version": { "p2p": "8", "block": "11", "app": "0" },
+                "id": "3f2a9c1e5b7d4f6a8c0e2b4d6f8a0c2e4b6d8f0a",
+                "listen_addr": "tcp://0.0.0.0:26656",
+                "network": chain_id,
+                "version": "0.38.12",
+                "channels": "40202122233038606100",
+                "moniker": "node",
+                "other": { "tx_index": "on", "rpc_address": "tcp://0.0.0.0:26657" }
+            },
+            "sync_info": {
+                "latest_block_hash": "",
+                "latest_app_hash": "",
+                "latest_block_height": "1000",
+                "latest_block_time": format_block_time(now_nanos),
+                "earliest_block_hash": "",
+                "earliest_app_hash": "",
+                "earliest_block_height": "1",
+                "earliest_block_time": "2023-01-01T00:00:00Z",
+                "catching_up": catching_up
+            },
+            "validator_info": {
+                "address": "",
+                "pub_key": { "type": "tendermint/PubKeyEd25519", "value": "" },
+                "voting_power": "0"
+            }
+        }
+    })
+    .to_string()
+}
+
 // common transaction data
 fn sign_doc_data(address: String) -> SignDoc {
     let public_key = get_pubkey(address);
@@ -100,9 +945,37 @@ fn sign_doc_data(address: String) -> SignDoc {
         },
     };
 
-    let chain_id = chain::Id::try_from("pion-1").unwrap();
+    let chain_id = chain::Id::try_from(CHAIN_ID).unwrap();
+
+    SignDoc::new(&tx_body, &auth_info, &chain_id, ACCOUNT_NUMBER).unwrap()
+}
+
+/// Verifies the first signature of an encoded `TxRaw` the way a Cosmos node does:
+/// the `SignDoc` is rebuilt from the body and auth info bytes, hashed with SHA256
+/// and checked against the signer's public key.
+fn verify_cosmos_tx_signature(raw_tx_bytes: &[u8], public_key_bytes: &[u8]) -> bool {
+    let Ok(tx_raw) = TxRaw::decode(raw_tx_bytes) else {
+        return false;
+    };
+    let Some(signature) = tx_raw.signatures.first() else {
+        return false;
+    };
+    let (Ok(verifying_key), Ok(signature)) = (
+        VerifyingKey::from_sec1_bytes(public_key_bytes),
+        Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+
+    let sign_doc = ProtoSignDoc {
+        body_bytes: tx_raw.body_bytes,
+        auth_info_bytes: tx_raw.auth_info_bytes,
+        chain_id: CHAIN_ID.to_string(),
+        account_number: ACCOUNT_NUMBER,
+    };
+    let hash = Sha256::digest(sign_doc.encode_to_vec());
 
-    SignDoc::new(&tx_body, &auth_info, &chain_id, 577723).unwrap()
+    verifying_key.verify_prehash(&hash, &signature).is_ok()
 }
 
 fn get_pubkey(address: String) -> PublicKey {
@@ -0,0 +1,178 @@
+//! Amino JSON helpers used by the `SIGN_MODE_LEGACY_AMINO_JSON` sign mode.
+
+use serde_json::{json, Value};
+
+use crate::types::{CosmosTransaction, SignError};
+
+/// Fields encoded as (u)int64 in Amino, which Amino JSON represents as strings
+const AMINO_INT64_FIELDS: &[&str] = &[
+    "account_number",
+    "sequence",
+    "gas",
+    "proposal_id",
+    "timeout_height",
+    "timeout_timestamp",
+    "revision_number",
+    "revision_height",
+    "height",
+    "code_id",
+];
+
+/// Returns the Amino name of the message with the given protobuf type URL, e.g.
+/// `cosmos-sdk/MsgSend`, for the messages which can be signed in Amino JSON
+pub fn amino_message_type(type_url: &str) -> Option<&'static str> {
+    match type_url {
+        "/cosmos.bank.v1beta1.MsgSend" => Some("cosmos-sdk/MsgSend"),
+        "/cosmos.staking.v1beta1.MsgDelegate" => Some("cosmos-sdk/MsgDelegate"),
+        "/cosmos.staking.v1beta1.MsgUndelegate" => Some("cosmos-sdk/MsgUndelegate"),
+        "/cosmos.staking.v1beta1.MsgBeginRedelegate" => Some("cosmos-sdk/MsgBeginRedelegate"),
+        "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => Some("cosmos-sdk/MsgWithdrawDelegationReward"),
+        "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => Some("cosmos-sdk/MsgModifyWithdrawAddress"),
+        "/cosmos.gov.v1beta1.MsgVote" => Some("cosmos-sdk/MsgVote"),
+        "/ibc.applications.transfer.v1.MsgTransfer" => Some("cosmos-sdk/MsgTransfer"),
+        _ => None,
+    }
+}
+
+/// Returns the Amino JSON `StdSignDoc` of `transaction`, signed in `SIGN_MODE_LEGACY_AMINO_JSON`
+/// once serialized with [`canonical_json_serialize`]
+pub fn amino_sign_doc(transaction: &CosmosTransaction) -> Result<Value, SignError> {
+    if transaction.messages.is_empty() {
+        return Err(SignError::NoMessages);
+    }
+    let msgs = transaction
+        .messages
+        .iter()
+        .map(|message| {
+            let amino_type = amino_message_type(&message.type_url)
+                .ok_or_else(|| SignError::UnsupportedMessage(message.type_url.clone()))?;
+            let mut value = message.value.clone();
+            if let Some(fields) = value.as_object_mut() {
+                fields.remove("@type");
+            }
+            Ok(json!({ "type": amino_type, "value": value }))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(json!({
+        "account_number": transaction.account_number.to_string(),
+        "chain_id": transaction.chain_id,
+        "fee": { "amount": transaction.fee, "gas": transaction.gas_limit.to_string() },
+        "memo": transaction.memo,
+        "msgs": msgs,
+        "sequence": transaction.sequence.to_string()
+    }))
+}
+
+/// Serialize `value` as canonical Amino JSON: object keys are sorted at every nesting level,
+/// null values are stripped, 64-bit integer fields are written as strings and `<`, `>`, `&`
+/// are escaped like Go's `encoding/json` (as done by the Cosmos SDK `MustSortJSON`).
+pub fn canonical_json_serialize(value: &Value) -> String {
+    let mut output = String::new();
+    write_canonical(value, None, &mut output);
+    output
+}
+
+fn write_canonical(value: &Value, key: Option<&str>, output: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().filter(|(_, value)| !value.is_null()).collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            output.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_string(key, output);
+                output.push(':');
+                write_canonical(value, Some(key), output);
+            }
+            output.push('}');
+        }
+        Value::Array(items) => {
+            output.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                // Elements of an int64 array inherit the field name
+                write_canonical(item, key, output);
+            }
+            output.push(']');
+        }
+        Value::Number(number) if key.is_some_and(|key| AMINO_INT64_FIELDS.contains(&key)) => {
+            write_string(&number.to_string(), output);
+        }
+        Value::String(s) => write_string(s, output),
+        other => output.push_str(&other.to_string()),
+    }
+}
+
+fn write_string(s: &str, output: &mut String) {
+    let escaped = Value::String(s.to_string()).to_string();
+    output.push_str(
+        &escaped
+            .replace('<', "\\u003c")
+            .replace('>', "\\u003e")
+            .replace('&', "\\u0026"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_json_send_sign_doc() {
+        // Sign doc of a MsgSend as signed by Keplr in Amino JSON mode
+        let sign_doc = json!({
+            "sequence": 7,
+            "msgs": [{
+                "value": {
+                    "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                },
+                "type": "cosmos-sdk/MsgSend"
+            }],
+            "memo": "",
+            "fee": {
+                "gas": "200000",
+                "amount": [{ "denom": "uatom", "amount": "5000" }],
+                "granter": null
+            },
+            "chain_id": "cosmoshub-4",
+            "account_number": 577723
+        });
+
+        assert_eq!(
+            canonical_json_serialize(&sign_doc),
+            r#"{"account_number":"577723","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"5000","denom":"uatom"}],"gas":"200000"},"memo":"","msgs":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"1000","denom":"uatom"}],"from_address":"cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6","to_address":"cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573"}}],"sequence":"7"}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_vote_sign_doc() {
+        // The vote option is an int32 enum and stays a number, the proposal id is a uint64
+        let msg = json!({
+            "type": "cosmos-sdk/MsgVote",
+            "value": { "voter": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6", "proposal_id": 42, "option": 1 }
+        });
+
+        assert_eq!(
+            canonical_json_serialize(&msg),
+            r#"{"type":"cosmos-sdk/MsgVote","value":{"option":1,"proposal_id":"42","voter":"cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"}}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_escapes_html() {
+        assert_eq!(
+            canonical_json_serialize(&json!({ "memo": "a<b>&c" })),
+            r#"{"memo":"a\u003cb\u003e\u0026c"}"#
+        );
+    }
+}
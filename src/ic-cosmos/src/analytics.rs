@@ -0,0 +1,313 @@
+//! Metrics computed from ranges of blocks, such as validator uptime, and filters of block events.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockEvent, BlockEventData, BlockResults, CommitResult, EventSource, ValidatorWithPriority};
+
+/// Maximum number of blocks of an uptime query, each block costing one `commit` call
+pub const MAX_UPTIME_BLOCK_RANGE: u64 = 100;
+
+/// Number of `commit` calls of an uptime query made in parallel
+pub const UPTIME_COMMIT_PAGE_SIZE: usize = 10;
+
+/// Longest wait for the finality of a block, each poll costing one `commit` call
+pub const MAX_FINALITY_TIMEOUT_SECS: u64 = 120;
+
+/// Delay between the first two polls of the finality of a block, doubled after each poll up to
+/// [`MAX_FINALITY_POLL_DELAY`], about one block
+pub const FINALITY_POLL_INITIAL_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_FINALITY_POLL_DELAY: Duration = Duration::from_secs(6);
+
+/// Number of polls of the finality of a block made at most, whatever its timeout
+pub const MAX_FINALITY_POLL_ATTEMPTS: u32 = 30;
+
+/// Number of validators per page of the `validators` calls, the maximum of CometBFT
+pub const VALIDATOR_SET_PAGE_LIMIT: u64 = 100;
+
+/// CometBFT `BlockIDFlagAbsent`: the validator did not vote for the block
+const BLOCK_ID_FLAG_ABSENT: i32 = 1;
+
+/// CometBFT `BlockIDFlagCommit`: the validator voted for the block
+const BLOCK_ID_FLAG_COMMIT: i32 = 2;
+
+/// Finality of a block, confirmed by the commit of the next block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, CandidType)]
+pub struct FinalityInfo {
+    /// Height of the commit which confirmed the finality
+    pub confirmed_at_height: u64,
+    /// Fraction of the voting power of the validator set which signed the commit, above 2/3
+    pub signing_power_fraction: f64,
+}
+
+/// Returns the finality confirmed by `commit` when the `validators` which voted for its block hold
+/// more than 2/3 of their voting power, as required by IBC light clients. `validators` is the
+/// validator set at the height of the commit. Nil votes do not count.
+pub fn block_finality(commit: &CommitResult, validators: &[ValidatorWithPriority]) -> Option<FinalityInfo> {
+    let voting_power: HashMap<String, u128> = validators
+        .iter()
+        .filter_map(|validator| {
+            let power = validator.voting_power.parse::<u128>().ok()?;
+            Some((validator.address.to_ascii_uppercase(), power))
+        })
+        .collect();
+    let total_power: u128 = voting_power.values().sum();
+    let commit = &commit.signed_header.commit;
+    let signed_power: u128 = commit
+        .signatures
+        .iter()
+        .filter(|signature| signature.block_id_flag == BLOCK_ID_FLAG_COMMIT)
+        .map(|signature| signature.validator_address.to_ascii_uppercase())
+        .collect::<BTreeSet<_>>()
+        .iter()
+        .filter_map(|address| voting_power.get(address))
+        .sum();
+
+    if total_power == 0 || signed_power * 3 <= total_power * 2 {
+        return None;
+    }
+    Some(FinalityInfo {
+        confirmed_at_height: commit.height.parse().ok()?,
+        signing_power_fraction: signed_power as f64 / total_power as f64,
+    })
+}
+
+/// Returns the fraction of the blocks of `start_height..=end_height` whose commit was signed by
+/// `validator_address`, the hex consensus address. Commits outside the range are ignored and
+/// blocks without a commit count as missed. Nil votes count as signed, as done by `x/slashing`.
+pub fn calculate_validator_uptime(
+    validator_address: &str,
+    commits: &[CommitResult],
+    start_height: u64,
+    end_height: u64,
+) -> f64 {
+    if end_height < start_height {
+        return 0.0;
+    }
+    let signed_heights: BTreeSet<u64> = commits
+        .iter()
+        .filter_map(|commit| {
+            let commit = &commit.signed_header.commit;
+            let height = commit.height.parse::<u64>().ok()?;
+            let signed = commit.signatures.iter().any(|signature| {
+                signature.block_id_flag != BLOCK_ID_FLAG_ABSENT
+                    && signature.validator_address.eq_ignore_ascii_case(validator_address)
+            });
+            signed.then_some(height)
+        })
+        .filter(|height| (start_height..=end_height).contains(height))
+        .collect();
+
+    signed_heights.len() as f64 / (end_height - start_height + 1) as f64
+}
+
+/// Returns the events of type `event_type` of the block, in the order of execution: begin block,
+/// transactions, end block, then finalize block events
+pub fn filter_block_events(results: &BlockResults, event_type: &str) -> Vec<BlockEventData> {
+    let events_of = |events: &Option<Vec<BlockEvent>>| events.as_deref().unwrap_or(&[]).to_vec();
+    let mut sources = vec![(EventSource::BeginBlock, events_of(&results.begin_block_events))];
+    for (index, tx) in results.txs_results.iter().flatten().enumerate() {
+        sources.push((EventSource::Tx(index as u32), events_of(&tx.events)));
+    }
+    sources.push((EventSource::EndBlock, events_of(&results.end_block_events)));
+    sources.push((EventSource::FinalizeBlock, events_of(&results.finalize_block_events)));
+
+    sources
+        .into_iter()
+        .flat_map(|(source, events)| {
+            events
+                .into_iter()
+                .filter(|event| event.r#type == event_type)
+                .map(move |event| BlockEventData {
+                    source,
+                    event_type: event.r#type,
+                    attributes: event
+                        .attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.key, attribute.value))
+                        .collect(),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const VALIDATOR: &str = "2A77ADE8C222C5C7D00A0E4D1F4D4A7E2E5B2C35";
+
+    fn commit(height: u64, signature: Option<(&str, i32)>) -> CommitResult {
+        let block_id = json!({ "hash": "", "parts": { "total": 1, "hash": "" } });
+        let signatures: Vec<_> = signature
+            .into_iter()
+            .map(|(validator_address, block_id_flag)| {
+                json!({
+                    "block_id_flag": block_id_flag,
+                    "validator_address": validator_address,
+                    "timestamp": "2024-01-01T00:00:00Z",
+                    "signature": null
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "signed_header": {
+                "header": {
+                    "version": { "block": "11", "app": null },
+                    "chain_id": "cosmoshub-4",
+                    "height": height.to_string(),
+                    "time": "2024-01-01T00:00:00Z",
+                    "last_block_id": block_id,
+                    "last_commit_hash": "",
+                    "data_hash": "",
+                    "validators_hash": "",
+                    "next_validators_hash": "",
+                    "consensus_hash": "",
+                    "app_hash": "",
+                    "last_results_hash": "",
+                    "evidence_hash": "",
+                    "proposer_address": ""
+                },
+                "commit": {
+                    "height": height.to_string(),
+                    "round": 0,
+                    "block_id": block_id,
+                    "signatures": signatures
+                }
+            },
+            "canonical": true
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_calculate_validator_uptime() {
+        let commits = vec![
+            commit(10, Some((VALIDATOR, 2))),
+            commit(11, Some((&VALIDATOR.to_lowercase(), 3))),
+            commit(12, Some((VALIDATOR, BLOCK_ID_FLAG_ABSENT))),
+            commit(13, Some(("B1C6D6A4A6B9E4A2C4B1E1C3D0E9F8A7B6C5D4E3", 2))),
+            commit(14, None),
+            // Outside of the range
+            commit(20, Some((VALIDATOR, 2))),
+        ];
+
+        assert_eq!(calculate_validator_uptime(VALIDATOR, &commits, 10, 14), 0.4);
+        assert_eq!(calculate_validator_uptime(VALIDATOR, &commits, 10, 11), 1.0);
+        assert_eq!(calculate_validator_uptime(VALIDATOR, &commits, 14, 10), 0.0);
+    }
+
+    #[test]
+    fn test_block_finality() {
+        let validator = |address: &str, voting_power: &str| ValidatorWithPriority {
+            address: address.to_string(),
+            pub_key: crate::types::PubKey {
+                type_field: "tendermint/PubKeyEd25519".to_string(),
+                value: String::new(),
+            },
+            voting_power: voting_power.to_string(),
+            proposer_priority: "0".to_string(),
+            commission_rate: None,
+        };
+        let validators = vec![validator("AA", "40"), validator("BB", "30"), validator("CC", "30")];
+        let signed = |signers: &[(&str, i32)]| {
+            let mut commit = commit(101, None);
+            commit.signed_header.commit.signatures = signers
+                .iter()
+                .map(|(validator_address, block_id_flag)| crate::types::CommitSignature {
+                    block_id_flag: *block_id_flag,
+                    validator_address: validator_address.to_string(),
+                    timestamp: "2024-01-01T00:00:00Z".to_string(),
+                    signature: None,
+                })
+                .collect();
+            commit
+        };
+
+        assert_eq!(
+            block_finality(&signed(&[("AA", 2), ("bb", 2), ("CC", 3)]), &validators),
+            Some(FinalityInfo {
+                confirmed_at_height: 101,
+                signing_power_fraction: 0.7,
+            })
+        );
+        // Exactly 2/3 of the voting power, or signatures of unknown and duplicate validators
+        let two_thirds = vec![validator("AA", "40"), validator("BB", "20"), validator("CC", "30")];
+        assert_eq!(block_finality(&signed(&[("AA", 2), ("BB", 2)]), &two_thirds), None);
+        assert_eq!(
+            block_finality(&signed(&[("AA", 2), ("AA", 2), ("DD", 2)]), &validators),
+            None
+        );
+        assert_eq!(block_finality(&signed(&[("AA", 2)]), &[]), None);
+    }
+
+    fn event(event_type: &str, key: &str, value: &str) -> serde_json::Value {
+        json!({ "type": event_type, "attributes": [{ "key": key, "value": value, "index": true }] })
+    }
+
+    fn tx_result(events: Vec<serde_json::Value>) -> serde_json::Value {
+        json!({
+            "code": 0,
+            "data": "",
+            "log": "",
+            "info": "",
+            "gas_wanted": "200000",
+            "gas_used": "94771",
+            "events": events,
+            "codespace": ""
+        })
+    }
+
+    #[test]
+    fn test_filter_block_events() {
+        let results: BlockResults = serde_json::from_value(json!({
+            "height": "1200000",
+            "txs_results": [
+                tx_result(vec![event("transfer", "amount", "1000uatom"), event("message", "module", "bank")]),
+                tx_result(vec![]),
+                tx_result(vec![event("transfer", "amount", "5uatom")]),
+            ],
+            "finalize_block_events": [event("transfer", "amount", "250uatom"), event("mint", "amount", "7")],
+            "validator_updates": null,
+            "consensus_param_updates": null
+        }))
+        .unwrap();
+
+        let transfers = filter_block_events(&results, "transfer");
+        let sources: Vec<_> = transfers.iter().map(|event| event.source).collect();
+        assert_eq!(
+            sources,
+            vec![EventSource::Tx(0), EventSource::Tx(2), EventSource::FinalizeBlock]
+        );
+        assert_eq!(transfers[0].event_type, "transfer");
+        assert_eq!(
+            transfers[1].attributes,
+            vec![("amount".to_string(), "5uatom".to_string())]
+        );
+        assert!(filter_block_events(&results, "slash").is_empty());
+
+        // Blocks of CometBFT 0.37 emit begin and end block events instead
+        let results: BlockResults = serde_json::from_value(json!({
+            "height": "1200000",
+            "txs_results": null,
+            "begin_block_events": [event("mint", "amount", "7")],
+            "end_block_events": [event("complete_unbonding", "amount", "300uatom"), event("mint", "amount", "1")],
+            "finalize_block_events": null,
+            "validator_updates": null,
+            "consensus_param_updates": null
+        }))
+        .unwrap();
+        let sources: Vec<_> = filter_block_events(&results, "mint")
+            .iter()
+            .map(|event| event.source)
+            .collect();
+        assert_eq!(sources, vec![EventSource::BeginBlock, EventSource::EndBlock]);
+    }
+}
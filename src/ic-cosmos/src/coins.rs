@@ -0,0 +1,186 @@
+//! Formatting and parsing of Cosmos SDK coins, and the display names of the common denoms.
+
+use crate::types::CosmosCoin;
+
+/// Display name of a base denom, e.g. `ATOM` for `uatom`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DenomAlias {
+    /// Lowercase name used by users, e.g. `atom`
+    pub display: &'static str,
+    /// Denom of the coins on chain, e.g. `uatom`
+    pub base: &'static str,
+    /// Number of decimals of the display unit in base units
+    pub exponent: u32,
+}
+
+/// Display names of the native denoms of the chains supported by the canisters
+pub const DENOM_ALIASES: &[DenomAlias] = &[
+    DenomAlias {
+        display: "atom",
+        base: "uatom",
+        exponent: 6,
+    },
+    DenomAlias {
+        display: "osmo",
+        base: "uosmo",
+        exponent: 6,
+    },
+    DenomAlias {
+        display: "ntrn",
+        base: "untrn",
+        exponent: 6,
+    },
+    DenomAlias {
+        display: "strd",
+        base: "ustrd",
+        exponent: 6,
+    },
+    DenomAlias {
+        display: "inj",
+        base: "inj",
+        exponent: 18,
+    },
+    DenomAlias {
+        display: "evmos",
+        base: "aevmos",
+        exponent: 18,
+    },
+];
+
+/// Returns the alias whose display name or base denom is `denom`, ignoring case
+pub fn denom_alias(denom: &str) -> Option<&'static DenomAlias> {
+    DENOM_ALIASES
+        .iter()
+        .find(|alias| alias.display.eq_ignore_ascii_case(denom) || alias.base.eq_ignore_ascii_case(denom))
+}
+
+/// Returns the base denom of a denom given by its display name, e.g. `uatom` for `atom` or `ATOM`.
+/// Other denoms are returned unchanged.
+pub fn resolve_denom(denom: &str) -> String {
+    match denom_alias(denom) {
+        Some(alias) => alias.base.to_string(),
+        None => denom.to_string(),
+    }
+}
+
+/// Errors returned by [`parse_coin_string`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CoinParseError {
+    #[error("Missing amount in coin {0}")]
+    MissingAmount(String),
+    #[error("Missing denom in coin {0}")]
+    MissingDenom(String),
+    /// Denoms start with a letter, followed by 2 to 127 letters, digits or `/:._-`
+    #[error("Invalid denom {0}")]
+    InvalidDenom(String),
+}
+
+/// Formats an amount of base units in the display unit of its denom, e.g. `1 ATOM` for
+/// 1000000 `uatom`. Denoms without an alias are kept, e.g. `1000 ibc/27394FB0`.
+pub fn format_coin(amount: u64, denom: &str) -> String {
+    let Some(alias) = DENOM_ALIASES.iter().find(|alias| alias.base == denom) else {
+        return format!("{} {}", amount, denom);
+    };
+    let scale = 10u128.pow(alias.exponent);
+    let (whole, fraction) = (amount as u128 / scale, amount as u128 % scale);
+    let display = alias.display.to_ascii_uppercase();
+    if fraction == 0 {
+        return format!("{} {}", whole, display);
+    }
+    let fraction = format!("{:0width$}", fraction, width = alias.exponent as usize);
+    format!("{}.{} {}", whole, fraction.trim_end_matches('0'), display)
+}
+
+/// Parses a coin such as `1000uatom`, with the denom right after the amount
+pub fn parse_coin_string(s: &str) -> Result<CosmosCoin, CoinParseError> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (amount, denom) = s.split_at(split);
+    if amount.is_empty() {
+        return Err(CoinParseError::MissingAmount(s.to_string()));
+    }
+    if denom.is_empty() {
+        return Err(CoinParseError::MissingDenom(s.to_string()));
+    }
+    if !is_valid_denom(denom) {
+        return Err(CoinParseError::InvalidDenom(denom.to_string()));
+    }
+    Ok(CosmosCoin::new(denom, amount))
+}
+
+/// Returns whether two denoms designate the same coins: denoms are compared without case after
+/// resolving their aliases, and IBC denoms by their hash
+pub fn denoms_are_equivalent(a: &str, b: &str) -> bool {
+    match (ibc_hash(a), ibc_hash(b)) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        (None, None) => resolve_denom(a).eq_ignore_ascii_case(&resolve_denom(b)),
+        _ => false,
+    }
+}
+
+fn ibc_hash(denom: &str) -> Option<&str> {
+    denom
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("ibc/"))
+        .map(|_| &denom[4..])
+}
+
+/// Checks a denom against the `[a-zA-Z][a-zA-Z0-9/:._-]{2,127}` expression of the Cosmos SDK
+fn is_valid_denom(denom: &str) -> bool {
+    let mut chars = denom.chars();
+    (3..=128).contains(&denom.len())
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_coin() {
+        assert_eq!(format_coin(1_000_000, "uatom"), "1 ATOM");
+        assert_eq!(format_coin(1_250_000, "uatom"), "1.25 ATOM");
+        assert_eq!(format_coin(5, "uosmo"), "0.000005 OSMO");
+        assert_eq!(format_coin(1_500_000_000_000_000_000, "aevmos"), "1.5 EVMOS");
+        assert_eq!(format_coin(1000, "ibc/27394FB0"), "1000 ibc/27394FB0");
+    }
+
+    #[test]
+    fn test_parse_coin_string() {
+        assert_eq!(parse_coin_string("1000uatom"), Ok(CosmosCoin::new("uatom", "1000")));
+        assert_eq!(
+            parse_coin_string("5ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"),
+            Ok(CosmosCoin::new(
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+                "5"
+            ))
+        );
+        assert_eq!(
+            parse_coin_string("uatom"),
+            Err(CoinParseError::MissingAmount("uatom".to_string()))
+        );
+        assert_eq!(
+            parse_coin_string("1000"),
+            Err(CoinParseError::MissingDenom("1000".to_string()))
+        );
+        assert_eq!(
+            parse_coin_string("1000 uatom"),
+            Err(CoinParseError::InvalidDenom(" uatom".to_string()))
+        );
+        assert_eq!(
+            parse_coin_string("10u"),
+            Err(CoinParseError::InvalidDenom("u".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_denoms_are_equivalent() {
+        assert!(denoms_are_equivalent("uatom", "UATOM"));
+        assert!(denoms_are_equivalent("atom", "uatom"));
+        assert!(denoms_are_equivalent("ibc/27394fb0", "IBC/27394FB0"));
+        assert!(!denoms_are_equivalent("ibc/27394FB0", "ibc/14F9BC3E"));
+        assert!(!denoms_are_equivalent("uatom", "uosmo"));
+        assert_eq!(resolve_denom("ATOM"), "uatom");
+        assert_eq!(resolve_denom("ujuno"), "ujuno");
+    }
+}
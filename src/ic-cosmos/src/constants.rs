@@ -48,5 +48,10 @@ pub const COSMOS_BLOCK_RESULTS_SIZE_ESTIMATE: u64 = 1024 * 1024;
 pub const COSMOS_BLOCKCHAIN_SIZE_ESTIMATE: u64 = 1024 * 1024;
 pub const COSMOS_COMMIT_SIZE_ESTIMATE: u64 = 1024 * 1024;
 pub const COSMOS_TX_SIZE_ESTIMATE: u64 = 1024 * 1024;
+/// The commit result includes all the events emitted by the transaction
+pub const COSMOS_BROADCAST_TX_COMMIT_SIZE_ESTIMATE: u64 = 1024 * 1024;
 pub const COSMOS_ABCI_QUERY_SIZE_ESTIMATE: u64 = 1024 * 1024;
 pub const COSMOS_VALIDATORS_SIZE_ESTIMATE: u64 = 100000;
+/// Cosmos REST API (LCD) response size estimates
+pub const LCD_BALANCES_SIZE_ESTIMATE: u64 = 16 * 1024;
+pub const LCD_BROADCAST_TX_SIZE_ESTIMATE: u64 = 4 * 1024;
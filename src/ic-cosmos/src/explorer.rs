@@ -0,0 +1,145 @@
+//! Links to transactions, accounts, blocks and validators on the block explorers of the chains.
+
+use serde::{Deserialize, Serialize};
+
+/// Page of a block explorer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplorerResource {
+    /// Transaction of the given hash, in hex
+    Transaction(String),
+    Address(String),
+    /// Block at the given height
+    Block(u64),
+    /// Validator of the given operator address, e.g. `cosmosvaloper1...`
+    Validator(String),
+}
+
+/// Block explorer, each with its own URL format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Explorer {
+    /// `https://www.mintscan.io/{chain}/tx/{hash}`
+    Mintscan,
+    /// `https://ping.pub/{chain}/tx/{hash}`
+    PingPub,
+    /// `https://{chain}.aneka.io/txs/{hash}`
+    Aneka,
+}
+
+/// Explorer of a chain, with the name of the chain in the URLs of the explorer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplorerConfig {
+    pub explorer: Explorer,
+    /// Name of the chain on the explorer, e.g. `cosmos` for `cosmoshub-4` on Mintscan
+    pub chain_name: String,
+}
+
+impl ExplorerConfig {
+    pub fn new(explorer: Explorer, chain_name: impl Into<String>) -> Self {
+        Self {
+            explorer,
+            chain_name: chain_name.into(),
+        }
+    }
+
+    /// Returns the URL of the page of `resource` on the explorer
+    pub fn url(&self, resource: &ExplorerResource) -> String {
+        let chain = &self.chain_name;
+        match (self.explorer, resource) {
+            (Explorer::Mintscan, ExplorerResource::Transaction(hash)) => {
+                format!("https://www.mintscan.io/{}/tx/{}", chain, hash)
+            }
+            (Explorer::Mintscan, ExplorerResource::Address(address)) => {
+                format!("https://www.mintscan.io/{}/address/{}", chain, address)
+            }
+            (Explorer::Mintscan, ExplorerResource::Block(height)) => {
+                format!("https://www.mintscan.io/{}/block/{}", chain, height)
+            }
+            (Explorer::Mintscan, ExplorerResource::Validator(operator)) => {
+                format!("https://www.mintscan.io/{}/validators/{}", chain, operator)
+            }
+            (Explorer::PingPub, ExplorerResource::Transaction(hash)) => {
+                format!("https://ping.pub/{}/tx/{}", chain, hash)
+            }
+            (Explorer::PingPub, ExplorerResource::Address(address)) => {
+                format!("https://ping.pub/{}/account/{}", chain, address)
+            }
+            (Explorer::PingPub, ExplorerResource::Block(height)) => {
+                format!("https://ping.pub/{}/block/{}", chain, height)
+            }
+            (Explorer::PingPub, ExplorerResource::Validator(operator)) => {
+                format!("https://ping.pub/{}/staking/{}", chain, operator)
+            }
+            (Explorer::Aneka, ExplorerResource::Transaction(hash)) => {
+                format!("https://{}.aneka.io/txs/{}", chain, hash)
+            }
+            (Explorer::Aneka, ExplorerResource::Address(address)) => {
+                format!("https://{}.aneka.io/accounts/{}", chain, address)
+            }
+            (Explorer::Aneka, ExplorerResource::Block(height)) => {
+                format!("https://{}.aneka.io/blocks/{}", chain, height)
+            }
+            (Explorer::Aneka, ExplorerResource::Validator(operator)) => {
+                format!("https://{}.aneka.io/validators/{}", chain, operator)
+            }
+        }
+    }
+}
+
+/// Explorers of the known chains, by chain id
+const KNOWN_EXPLORERS: &[(&str, Explorer, &str)] = &[
+    ("cosmoshub-4", Explorer::Mintscan, "cosmos"),
+    ("provider", Explorer::Mintscan, "ics-testnet-provider"),
+    ("osmosis-1", Explorer::Mintscan, "osmosis"),
+    ("neutron-1", Explorer::Mintscan, "neutron"),
+    ("stride-1", Explorer::Mintscan, "stride"),
+    ("injective-1", Explorer::Mintscan, "injective"),
+    ("evmos_9001-2", Explorer::Mintscan, "evmos"),
+    ("juno-1", Explorer::PingPub, "juno"),
+];
+
+/// Returns the explorer of a known chain
+pub fn explorer_config(chain_id: &str) -> Option<ExplorerConfig> {
+    KNOWN_EXPLORERS
+        .iter()
+        .find(|(known, _, _)| *known == chain_id)
+        .map(|(_, explorer, chain_name)| ExplorerConfig::new(*explorer, *chain_name))
+}
+
+/// Returns the URL of `resource` on the explorer of `chain_id`, `None` for unknown chains
+pub fn build_explorer_url(chain_id: &str, resource: ExplorerResource) -> Option<String> {
+    explorer_config(chain_id).map(|config| config.url(&resource))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_explorer_url() {
+        assert_eq!(
+            build_explorer_url("cosmoshub-4", ExplorerResource::Transaction("ABCD".to_string())).as_deref(),
+            Some("https://www.mintscan.io/cosmos/tx/ABCD")
+        );
+        assert_eq!(
+            build_explorer_url("provider", ExplorerResource::Block(42)).as_deref(),
+            Some("https://www.mintscan.io/ics-testnet-provider/block/42")
+        );
+        assert_eq!(build_explorer_url("unknown-1", ExplorerResource::Block(1)), None);
+
+        let address = ExplorerResource::Address("osmo1abc".to_string());
+        let validator = ExplorerResource::Validator("osmovaloper1abc".to_string());
+        let ping_pub = ExplorerConfig::new(Explorer::PingPub, "osmosis");
+        assert_eq!(ping_pub.url(&address), "https://ping.pub/osmosis/account/osmo1abc");
+        assert_eq!(
+            ping_pub.url(&validator),
+            "https://ping.pub/osmosis/staking/osmovaloper1abc"
+        );
+        let aneka = ExplorerConfig::new(Explorer::Aneka, "osmosis");
+        assert_eq!(aneka.url(&address), "https://osmosis.aneka.io/accounts/osmo1abc");
+        assert_eq!(
+            aneka.url(&validator),
+            "https://osmosis.aneka.io/validators/osmovaloper1abc"
+        );
+    }
+}
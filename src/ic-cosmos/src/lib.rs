@@ -1,8 +1,14 @@
+pub mod amino;
+pub mod analytics;
+pub mod coins;
 pub mod constants;
+pub mod explorer;
 pub mod logs;
 pub mod metrics;
+pub mod proof;
 pub mod request;
 pub mod rpc_client;
+pub mod schema_registry;
 pub mod types;
 pub mod utils;
 
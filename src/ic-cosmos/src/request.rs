@@ -1,7 +1,10 @@
 use std::fmt;
 
+use ic_cdk::api::management_canister::http_request::HttpMethod;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
+
+use crate::rpc_client::EndpointType;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum RpcRequest {
@@ -26,6 +29,7 @@ pub enum RpcRequest {
     GetCheckTx,
     GetBroadcastTxAsync,
     GetBroadcastTxSync,
+    GetBroadcastTxCommit,
     GetValidators,
 }
 
@@ -53,6 +57,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetCheckTx => "check_tx",
             RpcRequest::GetBroadcastTxAsync => "broadcast_tx_async",
             RpcRequest::GetBroadcastTxSync => "broadcast_tx_sync",
+            RpcRequest::GetBroadcastTxCommit => "broadcast_tx_commit",
             RpcRequest::GetValidators => "validators",
         };
 
@@ -94,9 +99,133 @@ impl<P: Serialize> JsonRpcRequest<P> {
     }
 }
 
+/// Request of the Cosmos REST API (LCD), the counterpart of [`RpcRequest`] for the providers
+/// with an [`EndpointType::LcdRest`] endpoint
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum LcdRequest {
+    /// Balances of a bech32 address, built with [`LcdRequest::get_balances`]
+    GetBalances { address: String },
+    /// Broadcast of the base64 `tx_bytes` in sync mode
+    BroadcastTx { tx_bytes: String },
+}
+
+impl fmt::Display for LcdRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = match self {
+            LcdRequest::GetBalances { .. } => "lcd_balances",
+            LcdRequest::BroadcastTx { .. } => "lcd_broadcast_tx",
+        };
+
+        write!(f, "{method}")
+    }
+}
+
+impl LcdRequest {
+    /// Returns the request of the balances of `address`, which must be a bech32 address as it is
+    /// a segment of the URL path
+    pub fn get_balances(address: String) -> Result<Self, String> {
+        bech32::decode(&address).map_err(|e| format!("Invalid address {}: {}", address, e))?;
+        Ok(LcdRequest::GetBalances { address })
+    }
+
+    pub fn http_method(&self) -> HttpMethod {
+        match self {
+            LcdRequest::BroadcastTx { .. } => HttpMethod::POST,
+            _ => HttpMethod::GET,
+        }
+    }
+
+    /// Returns the path of the request, with its query string
+    pub fn path(&self) -> String {
+        match self {
+            LcdRequest::GetBalances { address } => format!("/cosmos/bank/v1beta1/balances/{}", address),
+            LcdRequest::BroadcastTx { .. } => "/cosmos/tx/v1beta1/txs".to_string(),
+        }
+    }
+
+    /// Returns the JSON body of the POST requests
+    pub fn body(&self) -> Option<Value> {
+        match self {
+            LcdRequest::BroadcastTx { tx_bytes } => Some(json!({
+                "tx_bytes": tx_bytes,
+                "mode": "BROADCAST_MODE_SYNC"
+            })),
+            _ => None,
+        }
+    }
+
+    /// Returns the URL of the request on the provider at `base_url`, keeping the query
+    /// parameters of the provider, e.g. an API key
+    pub fn build_url(&self, base_url: &str) -> String {
+        let (base, provider_query) = match base_url.split_once('?') {
+            Some((base, query)) => (base, Some(query)),
+            None => (base_url, None),
+        };
+        let mut url = format!("{}{}", base.trim_end_matches('/'), self.path());
+        if let Some(query) = provider_query {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(query);
+        }
+        url
+    }
+}
+
+/// Request sent to the providers, in the format of their endpoint type
+#[derive(Debug, Clone)]
+pub enum ProviderRequest {
+    /// JSON-RPC payload, a single request or a batch
+    JsonRpc(Value),
+    Lcd(LcdRequest),
+}
+
+impl ProviderRequest {
+    pub fn endpoint_type(&self) -> EndpointType {
+        match self {
+            ProviderRequest::JsonRpc(_) => EndpointType::JsonRpc,
+            ProviderRequest::Lcd(_) => EndpointType::LcdRest,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
 
+    #[test]
+    fn test_build_lcd_request() {
+        let address = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+        let request = LcdRequest::get_balances(address.to_string()).unwrap();
+        assert_eq!(request.http_method(), HttpMethod::GET);
+        assert_eq!(request.body(), None);
+        assert_eq!(
+            request.build_url("https://lcd.cosmos.network/"),
+            format!("https://lcd.cosmos.network/cosmos/bank/v1beta1/balances/{}", address)
+        );
+        assert_eq!(
+            request.build_url("https://lcd.example.com?key=secret"),
+            format!(
+                "https://lcd.example.com/cosmos/bank/v1beta1/balances/{}?key=secret",
+                address
+            )
+        );
+        for address in [
+            "cosmos1abc",
+            "../../tx/v1beta1/txs",
+            "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6?denom=x",
+        ] {
+            assert!(LcdRequest::get_balances(address.to_string()).is_err());
+        }
+
+        let request = LcdRequest::BroadcastTx {
+            tx_bytes: "CpIBCo8B".to_string(),
+        };
+        assert_eq!(request.http_method(), HttpMethod::POST);
+        assert_eq!(
+            request.build_url("https://lcd.example.com/v1/key?x=1"),
+            "https://lcd.example.com/v1/key/cosmos/tx/v1beta1/txs?x=1"
+        );
+        assert_eq!(request.body().unwrap()["mode"], "BROADCAST_MODE_SYNC");
+    }
 
     // TODO: add tests for all requests
     //     #[test]
@@ -1,4 +1,7 @@
-use std::{cell::RefCell, collections::BTreeSet};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+};
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use hex;
@@ -9,22 +12,60 @@ use ic_cdk::api::management_canister::http_request::{
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 
+#[cfg(feature = "osmosis")]
+use crate::types::{
+    decode_osmosis_pool_response, encode_osmosis_pool_query, estimate_swap_output, is_osmosis_chain_id, OsmosisPool,
+    OSMOSIS_GAMM_POOL_PATH,
+};
+#[cfg(feature = "stride")]
+use crate::types::{
+    decode_stride_host_zone_response, encode_stride_host_zone_query, is_stride_chain_id, StrideHostZone,
+    STRIDE_HOST_ZONE_PATH,
+};
 use crate::{
     add_metric_entry,
+    analytics::{
+        block_finality, calculate_validator_uptime, filter_block_events, FinalityInfo, FINALITY_POLL_INITIAL_DELAY,
+        MAX_FINALITY_POLL_ATTEMPTS, MAX_FINALITY_POLL_DELAY, MAX_FINALITY_TIMEOUT_SECS, MAX_UPTIME_BLOCK_RANGE,
+        UPTIME_COMMIT_PAGE_SIZE, VALIDATOR_SET_PAGE_LIMIT,
+    },
+    backoff::Backoff,
     constants::*,
-    request::RpcRequest,
+    proof,
+    request::{LcdRequest, ProviderRequest, RpcRequest},
     rpc_client::multi_call::{MultiCallError, MultiCallResults},
     types::{
-        ABCIQueryResult, AbciInfo, BlockComplete, BlockResults, Blockchain, BroadcastTxResult, CheckTxResult,
-        CommitResult, ConsensusParamsResult, ConsensusState, DumpConsensusState, HeaderResult, NetInfo,
-        NumUnconfirmedTransactionsResult, Status, Tx, ValidatorsResult,
+        circulating_supply, decode_balance_response, decode_community_pool_response, decode_connection_response,
+        decode_connections_response, decode_consumer_chain_stops_response, decode_consumer_chains_response,
+        decode_contract_state_response, decode_delegator_delegations_response, decode_node_config_response,
+        decode_proposals_response, decode_staking_bond_denom, decode_staking_pool_response, decode_storage_key,
+        decode_supply_of_response, decode_validator_exchange_rate, default_proposals_pagination, encode_balance_query,
+        encode_connection_query, encode_connections_query, encode_contract_store_key,
+        encode_delegator_delegations_query, encode_proposals_query, encode_raw_contract_state_query,
+        encode_smart_contract_state_query, encode_supply_of_query, encode_validator_query, format_sdk_dec,
+        normalize_response, shares_to_tokens, ABCIQueryResult, AbciInfo, BlockComplete, BlockEventData, BlockHeight,
+        BlockResults, Blockchain, BondStatus, BroadcastTxCommitResult, BroadcastTxResult, ChainVersion, CheckTxResult,
+        CommitResult, ConsensusParamsResult, ConsensusState, ConsumerChain, ConsumerChainStop, CosmosCoin,
+        DumpConsensusState, GasPrice, HeaderResult, IBCConnection, IBCConnectionsResult, LcdBalancesResponse,
+        LcdBroadcastTxResponse, LcdError, NetInfo, NumUnconfirmedTransactionsResult, Pagination, ProposalStatus,
+        ProposalsPage, Status, SupplyInfo, Tx, ValidatorWithPriority, ValidatorsResult, BANK_BALANCE_PATH,
+        BANK_SUPPLY_OF_PATH, DEFAULT_PAGE_LIMIT, DISTRIBUTION_COMMUNITY_POOL_PATH, GOV_PROPOSALS_PATH,
+        IBC_CONNECTIONS_PATH, IBC_CONNECTION_PATH, ICS_CONSUMER_CHAINS_PATH, ICS_CONSUMER_CHAIN_STOPS_PATH,
+        NODE_CONFIG_PATH, STAKING_DELEGATOR_DELEGATIONS_PATH, STAKING_PARAMS_PATH, STAKING_POOL_PATH,
+        STAKING_VALIDATOR_PATH, WASM_RAW_CONTRACT_STATE_PATH, WASM_SMART_CONTRACT_STATE_PATH, WASM_STORE_KEY_PATH,
     },
 };
 
+mod circuit_breaker;
 mod compression;
 mod multi_call;
+mod rate_limiter;
 mod types;
 
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerLoader, CircuitBreakerStore, CircuitState, ProviderCircuitBreaker,
+};
+pub use rate_limiter::RateLimiter;
 pub use types::*;
 
 use crate::{
@@ -46,6 +87,9 @@ pub struct RpcClientConfig {
     pub transform_context: Option<TransformContext>,
     pub use_compression: bool,
     pub is_demo_active: bool,
+    pub chain_version: Option<ChainVersion>,
+    pub rate_limiter: Option<RateLimiter>,
+    pub circuit_breaker: Option<CircuitBreaker>,
 }
 
 #[derive(Clone, Debug)]
@@ -82,7 +126,7 @@ impl RpcClient {
         })
     }
 
-    /// Asynchronously sends an HTTP POST request to the specified URL with the given payload and
+    /// Asynchronously sends an HTTP request to the specified provider with the given request and
     /// maximum response bytes and returns the response as a string.
     /// This function calculates the required cycles for the HTTP request and logs the request
     /// details and response status. It uses a transformation named "cleanup_response" for the
@@ -91,22 +135,61 @@ impl RpcClient {
     /// # Arguments
     ///
     /// * `provider` - RPC API provider.
-    /// * `payload` - JSON payload to be sent in the HTTP request.
+    /// * `request` - Request to be sent, a JSON-RPC payload posted to the URL of the provider, or a
+    ///   REST request to a path of the URL for the providers with an [`EndpointType::LcdRest`] endpoint.
     /// * `max_response_bytes` - The maximal size of the response in bytes. If None, 2MiB will be
     ///   the limit.
     ///
     /// # Returns
     ///
-    /// * `RpcResult<Vec<u8>>` - The response body as a vector of bytes.
+    /// * `RpcResult<Vec<u8>>` - The response body as a vector of bytes,
+    ///   [`RpcError::ProviderUnavailable`] if the circuit breaker of the config bypasses the
+    ///   provider, or [`RpcError::RateLimited`] if the rate limiter of the config has no call available.
     async fn call_internal(
         &self,
         provider: &RpcApi,
-        payload: &Value,
+        request: &ProviderRequest,
         max_response_bytes: Option<u64>,
     ) -> RpcResult<Vec<u8>> {
         let cluster = provider.cluster();
         let url = cluster.url();
 
+        let (method, request_url, body, method_name) = match (provider.endpoint_type(), request) {
+            (EndpointType::JsonRpc, ProviderRequest::JsonRpc(payload)) => (
+                HttpMethod::POST,
+                url.to_string(),
+                Some(serde_json::to_vec(payload).map_err(|e| RpcError::ParseError(e.to_string()))?),
+                Self::find_rpc_method_name(payload).to_string(),
+            ),
+            (EndpointType::LcdRest, ProviderRequest::Lcd(lcd_request)) => (
+                lcd_request.http_method(),
+                lcd_request.build_url(url),
+                lcd_request
+                    .body()
+                    .map(|body| serde_json::to_vec(&body))
+                    .transpose()
+                    .map_err(|e| RpcError::ParseError(e.to_string()))?,
+                lcd_request.to_string(),
+            ),
+            (endpoint_type, request) => {
+                return Err(RpcError::ValidationError(format!(
+                    "Provider with a {:?} endpoint cannot serve {:?} requests",
+                    endpoint_type,
+                    request.endpoint_type()
+                )))
+            }
+        };
+
+        if let Some(circuit_breaker) = &self.config.circuit_breaker {
+            if !circuit_breaker.try_acquire(url, ic_cdk::api::time()) {
+                return Err(RpcError::ProviderUnavailable(url.to_string()));
+            }
+        }
+
+        if let Some(rate_limiter) = &self.config.rate_limiter {
+            rate_limiter.try_acquire()?;
+        }
+
         // Ensure "Content-Type: application/json" is present
         let mut headers = provider.headers.clone().unwrap_or_default();
         if !headers
@@ -126,14 +209,18 @@ impl RpcClient {
             });
         }
 
-        let body = serde_json::to_vec(payload).map_err(|e| RpcError::ParseError(e.to_string()))?;
+        let payload = body
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default()
+            .into_owned();
 
         let request = CanisterHttpRequestArgument {
-            url: url.to_string(),
+            url: request_url.clone(),
             max_response_bytes,
-            method: HttpMethod::POST,
+            method,
             headers,
-            body: Some(body),
+            body,
             transform: self.config.transform_context.clone(),
         };
 
@@ -151,7 +238,7 @@ impl RpcClient {
             .ok_or_else(|| RpcError::ParseError(format!("Error parsing hostname from URL: {}", url)))?;
 
         let rpc_host = MetricRpcHost(host.to_string());
-        let rpc_method = MetricRpcMethod(Self::find_rpc_method_name(payload).to_string());
+        let rpc_method = MetricRpcMethod(method_name);
 
         if let Some(is_allowed) = self.config.host_validator {
             if !is_allowed(host) {
@@ -175,7 +262,7 @@ impl RpcClient {
 
         log!(
             DEBUG,
-            "Calling url: {url} with payload: {payload}. Cycles: {cycles_cost}"
+            "Calling url: {request_url} with payload: {payload}. Cycles: {cycles_cost}"
         );
 
         add_metric_entry!(requests, (rpc_method.clone(), rpc_host.clone()), 1);
@@ -209,6 +296,9 @@ impl RpcClient {
                 // }
 
                 add_metric_entry!(responses, (rpc_method, rpc_host, http_status.into()), 1);
+                if let Some(circuit_breaker) = &self.config.circuit_breaker {
+                    circuit_breaker.record_response(url, http_status, ic_cdk::api::time());
+                }
 
                 Ok(bytes)
             }
@@ -219,13 +309,31 @@ impl RpcClient {
         }
     }
 
-    /// Calls multiple providers in parallel and returns the results.
-    async fn parallel_call(&self, payload: &Value, max_response_bytes: Option<u64>) -> Vec<RpcResult<Vec<u8>>> {
-        futures::future::join_all(self.providers.iter().map(|provider| {
+    /// Calls in parallel the providers with the endpoint type of `request` and returns their
+    /// results, or an error if no provider has such an endpoint.
+    async fn parallel_call(
+        &self,
+        request: &ProviderRequest,
+        max_response_bytes: Option<u64>,
+    ) -> RpcResult<Vec<(RpcApi, RpcResult<Vec<u8>>)>> {
+        let providers: Vec<&RpcApi> = self
+            .providers
+            .iter()
+            .filter(|provider| provider.endpoint_type() == request.endpoint_type())
+            .collect();
+        if providers.is_empty() {
+            return Err(RpcError::ValidationError(format!(
+                "No provider with a {:?} endpoint",
+                request.endpoint_type()
+            )));
+        }
+
+        let results = futures::future::join_all(providers.iter().map(|provider| {
             log!(DEBUG, "[parallel_call]: will call provider: {:?}", provider);
-            async { self.call_internal(provider, payload, max_response_bytes).await }
+            async { self.call_internal(provider, request, max_response_bytes).await }
         }))
-        .await
+        .await;
+        Ok(providers.into_iter().cloned().zip(results).collect())
     }
 
     /// Makes a single JSON-RPC call.
@@ -238,16 +346,15 @@ impl RpcClient {
         let payload = method.build_json(self.next_request_id(), params);
         let results = self
             .parallel_call(
-                &payload,
+                &ProviderRequest::JsonRpc(payload),
                 max_response_bytes.map(|estimate| self.response_size_estimate(estimate)),
             )
-            .await;
+            .await?;
         let bytes = Self::process_result(
             method,
-            MultiCallResults::from_non_empty_iter(self.providers.iter().cloned().zip(results.into_iter()))
-                .reduce(self.consensus_strategy()),
+            MultiCallResults::from_non_empty_iter(results).reduce(self.consensus_strategy()),
         )?;
-        Ok(serde_json::from_slice(&bytes)?)
+        self.parse_response(&bytes)
     }
 
     /// Makes multiple JSON-RPC calls in a single batch request.
@@ -263,20 +370,56 @@ impl RpcClient {
                 .collect(),
         );
 
+        let method = Self::find_rpc_method_name(&payload).to_string();
+
         let results = self
             .parallel_call(
-                &payload,
+                &ProviderRequest::JsonRpc(payload),
                 max_response_bytes.map(|estimate| self.response_size_estimate(estimate)),
             )
-            .await;
+            .await?;
+
+        let bytes = Self::process_result(
+            method,
+            MultiCallResults::from_non_empty_iter(results).reduce(self.consensus_strategy()),
+        )?;
 
+        self.parse_response(&bytes)
+    }
+
+    /// Makes a single call to the providers of the Cosmos REST API (LCD).
+    pub async fn lcd_call<R: DeserializeOwned>(
+        &self,
+        request: LcdRequest,
+        max_response_bytes: Option<u64>,
+    ) -> RpcResult<R> {
+        let method = request.to_string();
+        let results = self
+            .parallel_call(
+                &ProviderRequest::Lcd(request),
+                max_response_bytes.map(|estimate| self.response_size_estimate(estimate)),
+            )
+            .await?;
         let bytes = Self::process_result(
-            Self::find_rpc_method_name(&payload),
-            MultiCallResults::from_non_empty_iter(self.providers.iter().cloned().zip(results.into_iter()))
-                .reduce(self.consensus_strategy()),
+            method,
+            MultiCallResults::from_non_empty_iter(results).reduce(self.consensus_strategy()),
         )?;
 
-        Ok(serde_json::from_slice(&bytes)?)
+        let value: Value = serde_json::from_slice(&bytes)?;
+        if let Some(error) = LcdError::from_response(&value) {
+            return Err(RpcError::Text(format!(
+                "LCD error (code: {}): {}",
+                error.code, error.message
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Deserializes a response after normalizing the fields that differ between chain versions.
+    fn parse_response<R: DeserializeOwned>(&self, bytes: &[u8]) -> RpcResult<R> {
+        let value: Value = serde_json::from_slice(bytes)?;
+        let chain_version = self.config.chain_version.unwrap_or_default();
+        Ok(serde_json::from_value(normalize_response(value, chain_version))?)
     }
 
     /// Returns the current health of the node.
@@ -366,6 +509,13 @@ impl RpcClient {
         response.into_rpc_result()
     }
 
+    /// Returns the events of type `event_type` of the block at `height`, with the block phase or
+    /// transaction which emitted them
+    pub async fn get_block_events_by_type(&self, height: String, event_type: String) -> RpcResult<Vec<BlockEventData>> {
+        let results = self.get_block_results(height).await?;
+        Ok(filter_block_events(&results, &event_type))
+    }
+
     pub async fn get_blockchain(&self, min_height: String, max_height: String) -> RpcResult<Blockchain> {
         let response: JsonRpcResponse<Blockchain> = self
             .call(
@@ -384,6 +534,124 @@ impl RpcClient {
         response.into_rpc_result()
     }
 
+    /// Returns the fraction of the blocks of `start_height..=end_height` signed by `validator_address`,
+    /// fetching the commits in pages of [`UPTIME_COMMIT_PAGE_SIZE`] parallel calls.
+    pub async fn get_validator_uptime(
+        &self,
+        validator_address: String,
+        start_height: u64,
+        end_height: u64,
+    ) -> RpcResult<f64> {
+        if start_height == 0 || end_height < start_height {
+            return Err(RpcError::ValidationError(format!(
+                "Invalid block range {}..={}",
+                start_height, end_height
+            )));
+        }
+        if end_height - start_height + 1 > MAX_UPTIME_BLOCK_RANGE {
+            return Err(RpcError::ValidationError(format!(
+                "Block range exceeds the maximum of {} blocks",
+                MAX_UPTIME_BLOCK_RANGE
+            )));
+        }
+
+        let heights: Vec<u64> = (start_height..=end_height).collect();
+        let mut commits = Vec::with_capacity(heights.len());
+        for page in heights.chunks(UPTIME_COMMIT_PAGE_SIZE) {
+            let results =
+                futures::future::join_all(page.iter().map(|height| self.get_commit(height.to_string()))).await;
+            for result in results {
+                commits.push(result?);
+            }
+        }
+        Ok(calculate_validator_uptime(
+            &validator_address,
+            &commits,
+            start_height,
+            end_height,
+        ))
+    }
+
+    /// Returns the complete validator set at `height`, fetched in pages of [`VALIDATOR_SET_PAGE_LIMIT`].
+    pub async fn get_validator_set(&self, height: u64) -> RpcResult<Vec<ValidatorWithPriority>> {
+        let mut validators = Vec::new();
+        loop {
+            let pagination = Pagination::Offset {
+                offset: validators.len() as u64,
+                limit: VALIDATOR_SET_PAGE_LIMIT,
+                count_total: false,
+                reverse: false,
+            };
+            let page = self.get_validators(height.to_string(), Some(pagination), None).await?;
+            let total: usize = page
+                .total
+                .parse()
+                .map_err(|_| RpcError::ParseError(format!("Invalid validator set total: {}", page.total)))?;
+            if page.validators.is_empty() {
+                break;
+            }
+            validators.extend(page.validators);
+            if validators.len() >= total {
+                break;
+            }
+        }
+        Ok(validators)
+    }
+
+    /// Waits until `height` is final, polling the commit of the next block, which carries the votes
+    /// of the validators for `height`, until validators with more than 2/3 of the voting power signed
+    /// it. The polls are spaced with an exponential backoff, also when the rate limiter has no call
+    /// available. Gives up after `timeout_secs`, at most [`MAX_FINALITY_TIMEOUT_SECS`], or
+    /// [`MAX_FINALITY_POLL_ATTEMPTS`] polls.
+    pub async fn wait_for_block_finality(&self, height: u64, timeout_secs: u64) -> RpcResult<FinalityInfo> {
+        if height == 0 {
+            return Err(RpcError::ValidationError("Invalid block height 0".to_string()));
+        }
+        if timeout_secs > MAX_FINALITY_TIMEOUT_SECS {
+            return Err(RpcError::ValidationError(format!(
+                "Timeout exceeds the maximum of {} seconds",
+                MAX_FINALITY_TIMEOUT_SECS
+            )));
+        }
+
+        let deadline = ic_cdk::api::time().saturating_add(timeout_secs.saturating_mul(1_000_000_000));
+        let mut backoff = Backoff::new(
+            FINALITY_POLL_INITIAL_DELAY,
+            MAX_FINALITY_POLL_DELAY,
+            MAX_FINALITY_POLL_ATTEMPTS,
+        );
+        let mut validators = None;
+        loop {
+            match self.get_commit((height + 1).to_string()).await {
+                Ok(commit) => {
+                    if validators.is_none() {
+                        match self.get_validator_set(height + 1).await {
+                            Ok(validator_set) => validators = Some(validator_set),
+                            Err(RpcError::RateLimited) => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    if let Some(finality) = validators
+                        .as_deref()
+                        .and_then(|validators| block_finality(&commit, validators))
+                    {
+                        return Ok(finality);
+                    }
+                }
+                // The next block is not committed yet, or there is no call available until the
+                // rate limiter refills
+                Err(RpcError::JsonRpcError(_) | RpcError::InconsistentResponse(_) | RpcError::RateLimited) => {}
+                Err(e) => return Err(e),
+            }
+            if ic_cdk::api::time() >= deadline || !backoff.wait().await {
+                return Err(RpcError::Text(format!(
+                    "Block {} is not final after {} seconds",
+                    height, timeout_secs
+                )));
+            }
+        }
+    }
+
     pub async fn get_consensus_params(&self, height: String) -> RpcResult<ConsensusParamsResult> {
         let response: JsonRpcResponse<ConsensusParamsResult> =
             self.call(RpcRequest::GetConsensusParams, (height,), Some(128)).await?;
@@ -434,6 +702,32 @@ impl RpcClient {
         response.into_rpc_result()
     }
 
+    /// Queries `path` with `prove: true` and checks the proof of the value against `app_hash`,
+    /// the hex app hash of the block after the response height. The app hash must come from a
+    /// trusted source, e.g. a light client, not from the provider queried: the proof only shows
+    /// that the value is committed to by this app hash. Only the `/store/` paths return proofs.
+    /// Responses with a non-zero code hold no value and are returned as is.
+    pub async fn get_verified_abci_query(
+        &self,
+        path: String,
+        data: String,
+        height: String,
+        app_hash: &str,
+    ) -> RpcResult<ABCIQueryResult> {
+        if !path.starts_with("/store/") {
+            return Err(RpcError::ValidationError(format!(
+                "ABCI query path {} has no proof: only the /store/ paths can be verified",
+                path
+            )));
+        }
+        let result = self.get_abci_query(path, data, height, true).await?;
+        if result.response.code == 0 {
+            proof::verify_abci_query_proof(&result.response, app_hash)
+                .map_err(|e| RpcError::ValidationError(format!("Invalid ABCI query proof: {}", e)))?;
+        }
+        Ok(result)
+    }
+
     pub async fn get_check_tx(&self, tx: String) -> RpcResult<CheckTxResult> {
         let response: JsonRpcResponse<CheckTxResult> = self.call(RpcRequest::GetCheckTx, (tx,), Some(128)).await?;
         response.into_rpc_result()
@@ -451,7 +745,34 @@ impl RpcClient {
         response.into_rpc_result()
     }
 
-    pub async fn get_validators(&self, height: String, page: String, per_page: String) -> RpcResult<ValidatorsResult> {
+    /// Submits a transaction and waits until it is included in a block.
+    /// The node may take several seconds to respond, up to its `timeout_broadcast_tx_commit`.
+    pub async fn get_broadcast_tx_commit(&self, tx: String) -> RpcResult<BroadcastTxCommitResult> {
+        let response: JsonRpcResponse<BroadcastTxCommitResult> = self
+            .call(
+                RpcRequest::GetBroadcastTxCommit,
+                (tx,),
+                Some(COSMOS_BROADCAST_TX_COMMIT_SIZE_ESTIMATE),
+            )
+            .await?;
+        response.into_rpc_result()
+    }
+
+    /// Returns the validator set at `height` together with its bonded statistics.
+    /// Only offset pagination is supported, the first page of `DEFAULT_PAGE_LIMIT` validators is
+    /// returned if `None`.
+    /// The CometBFT validator set only contains bonded validators, so any `bond_status_filter`
+    /// other than [`BondStatus::Bonded`] returns an empty list.
+    pub async fn get_validators(
+        &self,
+        height: String,
+        pagination: Option<Pagination>,
+        bond_status_filter: Option<BondStatus>,
+    ) -> RpcResult<ValidatorsResult> {
+        let (page, per_page) = pagination
+            .unwrap_or(Pagination::first(DEFAULT_PAGE_LIMIT, false))
+            .to_page_params()
+            .map_err(RpcError::ValidationError)?;
         let response: JsonRpcResponse<ValidatorsResult> = self
             .call(
                 RpcRequest::GetValidators,
@@ -459,7 +780,326 @@ impl RpcClient {
                 Some(COSMOS_VALIDATORS_SIZE_ESTIMATE),
             )
             .await?;
-        response.into_rpc_result()
+        let mut result = response.into_rpc_result()?;
+        if bond_status_filter.is_some_and(|status| status != BondStatus::Bonded) {
+            result.validators.clear();
+            result.count = "0".to_string();
+        }
+        result.compute_bonded_stats();
+        Ok(result)
+    }
+
+    /// Queries the smart state of a CosmWasm contract with a JSON `QueryMsg`
+    /// and returns the JSON response of the contract.
+    pub async fn query_contract_smart(&self, contract_address: String, query_msg: Value) -> RpcResult<Value> {
+        let data =
+            encode_smart_contract_state_query(&contract_address, &query_msg).map_err(RpcError::ValidationError)?;
+        let result = self
+            .get_abci_query(WASM_SMART_CONTRACT_STATE_PATH.to_string(), data, "0".to_string(), false)
+            .await?;
+        let data = decode_contract_state_response(&abci_query_value(result)?).map_err(RpcError::ParseError)?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Reads the raw value stored under `key` in the storage of a CosmWasm contract.
+    /// Returns an empty value if the key does not exist.
+    pub async fn query_contract_raw(&self, contract_address: String, key: Vec<u8>) -> RpcResult<Vec<u8>> {
+        let data = encode_raw_contract_state_query(&contract_address, &key);
+        let result = self
+            .get_abci_query(WASM_RAW_CONTRACT_STATE_PATH.to_string(), data, "0".to_string(), false)
+            .await?;
+        decode_contract_state_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Reads the value stored under `storage_key` in the storage of a CosmWasm contract directly from
+    /// the store of the `wasm` module. `storage_key` is hex or base64 encoded, the value is returned
+    /// in base64 and is empty if the key does not exist.
+    pub async fn get_contract_state_raw(&self, contract_address: String, storage_key: String) -> RpcResult<String> {
+        let key = decode_storage_key(&storage_key).map_err(RpcError::ValidationError)?;
+        let data = encode_contract_store_key(&contract_address, &key).map_err(RpcError::ValidationError)?;
+        let result = self
+            .get_abci_query(WASM_STORE_KEY_PATH.to_string(), data, "0".to_string(), false)
+            .await?;
+        let value = STANDARD
+            .decode(abci_query_value(result)?)
+            .map_err(|e| RpcError::ParseError(format!("Failed to decode base64 response: {}", e)))?;
+        Ok(STANDARD.encode(value))
+    }
+
+    /// Returns the total amount of tokens staked by `delegator_address` across all validators.
+    /// Delegation shares are converted to tokens with the exchange rate of each validator.
+    pub async fn get_total_staked(&self, delegator_address: String) -> RpcResult<u64> {
+        let mut delegations = Vec::new();
+        let mut key = Vec::new();
+        loop {
+            let result = self
+                .get_abci_query(
+                    STAKING_DELEGATOR_DELEGATIONS_PATH.to_string(),
+                    encode_delegator_delegations_query(&delegator_address, &key),
+                    "0".to_string(),
+                    false,
+                )
+                .await?;
+            let page =
+                decode_delegator_delegations_response(&abci_query_value(result)?).map_err(RpcError::ParseError)?;
+            delegations.extend(page.delegations);
+            if page.next_key.is_empty() {
+                break;
+            }
+            key = page.next_key;
+        }
+
+        let mut exchange_rates = BTreeMap::new();
+        let mut total = 0u128;
+        for delegation in delegations {
+            if !exchange_rates.contains_key(&delegation.validator_address) {
+                let result = self
+                    .get_abci_query(
+                        STAKING_VALIDATOR_PATH.to_string(),
+                        encode_validator_query(&delegation.validator_address),
+                        "0".to_string(),
+                        false,
+                    )
+                    .await?;
+                let rate = decode_validator_exchange_rate(&abci_query_value(result)?).map_err(RpcError::ParseError)?;
+                exchange_rates.insert(delegation.validator_address.clone(), rate);
+            }
+            let (tokens, delegator_shares) = &exchange_rates[&delegation.validator_address];
+            total += shares_to_tokens(&delegation.shares, tokens, delegator_shares).map_err(RpcError::ParseError)?;
+        }
+
+        u64::try_from(total).map_err(|_| RpcError::ParseError("Total staked amount exceeds u64".to_string()))
+    }
+
+    /// Returns the total supply of `denom`. The community pool, the circulating supply and, for the
+    /// staking denom, the bonded and not bonded tokens are best effort and `None` when their query fails.
+    pub async fn get_token_supply(&self, denom: String) -> RpcResult<SupplyInfo> {
+        let result = self
+            .get_abci_query(
+                BANK_SUPPLY_OF_PATH.to_string(),
+                encode_supply_of_query(&denom),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        let total_supply = decode_supply_of_response(&abci_query_value(result)?).map_err(RpcError::ParseError)?;
+
+        let community_pool = self
+            .get_abci_query(
+                DISTRIBUTION_COMMUNITY_POOL_PATH.to_string(),
+                String::new(),
+                "0".to_string(),
+                false,
+            )
+            .await
+            .and_then(abci_query_value)
+            .ok()
+            .and_then(|value| decode_community_pool_response(&value, &denom).ok());
+        let circulating_supply = community_pool
+            .as_ref()
+            .and_then(|atomics| circulating_supply(&total_supply, atomics).ok());
+        let community_pool = community_pool.and_then(|atomics| format_sdk_dec(&atomics).ok());
+
+        let bond_denom = self
+            .get_abci_query(STAKING_PARAMS_PATH.to_string(), String::new(), "0".to_string(), false)
+            .await
+            .and_then(abci_query_value)
+            .ok()
+            .and_then(|value| decode_staking_bond_denom(&value).ok());
+        let (bonded_tokens, not_bonded_tokens) = if bond_denom.as_deref() == Some(denom.as_str()) {
+            self.get_abci_query(STAKING_POOL_PATH.to_string(), String::new(), "0".to_string(), false)
+                .await
+                .and_then(abci_query_value)
+                .ok()
+                .and_then(|value| decode_staking_pool_response(&value).ok())
+                .map_or((None, None), |(bonded, not_bonded)| (Some(bonded), Some(not_bonded)))
+        } else {
+            (None, None)
+        };
+
+        Ok(SupplyInfo {
+            total_supply,
+            circulating_supply,
+            community_pool,
+            bonded_tokens,
+            not_bonded_tokens,
+        })
+    }
+
+    /// Returns the balance of `address` in `denom` at the block `height`.
+    /// Historical heights fail once the node has pruned their state.
+    pub async fn get_balance(&self, address: String, denom: String, height: BlockHeight) -> RpcResult<String> {
+        let result = self
+            .get_abci_query(
+                BANK_BALANCE_PATH.to_string(),
+                encode_balance_query(&address, &denom),
+                height.to_query_param(),
+                false,
+            )
+            .await?;
+        decode_balance_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the balances of `address` from the providers of the Cosmos REST API (LCD), the
+    /// first page of up to 100 denoms.
+    pub async fn get_lcd_balances(&self, address: String) -> RpcResult<Vec<CosmosCoin>> {
+        let response: LcdBalancesResponse = self
+            .lcd_call(
+                LcdRequest::get_balances(address).map_err(RpcError::ValidationError)?,
+                Some(LCD_BALANCES_SIZE_ESTIMATE),
+            )
+            .await?;
+        Ok(response.balances)
+    }
+
+    /// Broadcasts the base64 transaction `tx` in sync mode through the providers of the Cosmos
+    /// REST API (LCD).
+    pub async fn get_lcd_broadcast_tx_sync(&self, tx: String) -> RpcResult<BroadcastTxResult> {
+        let response: LcdBroadcastTxResponse = self
+            .lcd_call(
+                LcdRequest::BroadcastTx { tx_bytes: tx },
+                Some(LCD_BROADCAST_TX_SIZE_ESTIMATE),
+            )
+            .await?;
+        Ok(response.tx_response.into())
+    }
+
+    /// Returns a page of governance proposals with `status`, or with any status if `None`.
+    /// Defaults to the `DEFAULT_PROPOSALS_LIMIT` most recent proposals.
+    pub async fn get_governance_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+        pagination: Option<Pagination>,
+    ) -> RpcResult<ProposalsPage> {
+        let pagination = pagination.unwrap_or_else(default_proposals_pagination);
+        let result = self
+            .get_abci_query(
+                GOV_PROPOSALS_PATH.to_string(),
+                encode_proposals_query(status, &pagination),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_proposals_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns a page of the IBC connections of the chain, the default page of the node if `pagination` is `None`
+    pub async fn get_ibc_connections(&self, pagination: Option<Pagination>) -> RpcResult<IBCConnectionsResult> {
+        let result = self
+            .get_abci_query(
+                IBC_CONNECTIONS_PATH.to_string(),
+                encode_connections_query(pagination.as_ref()),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_connections_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the IBC connection `connection_id`, e.g. "connection-0"
+    pub async fn get_ibc_connection(&self, connection_id: String) -> RpcResult<IBCConnection> {
+        let result = self
+            .get_abci_query(
+                IBC_CONNECTION_PATH.to_string(),
+                encode_connection_query(&connection_id),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_connection_response(&connection_id, &abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the consumer chains secured by the provider chain through Interchain Security
+    pub async fn get_consumer_chains(&self) -> RpcResult<Vec<ConsumerChain>> {
+        let result = self
+            .get_abci_query(
+                ICS_CONSUMER_CHAINS_PATH.to_string(),
+                String::new(),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_consumer_chains_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the consumer chains whose removal from the provider chain was approved, with the
+    /// time at which they stop being secured by its validators
+    pub async fn get_consumer_chain_stops(&self) -> RpcResult<Vec<ConsumerChainStop>> {
+        let result = self
+            .get_abci_query(
+                ICS_CONSUMER_CHAIN_STOPS_PATH.to_string(),
+                String::new(),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_consumer_chain_stops_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the minimum gas prices accepted by the node, empty if it accepts transactions without fees
+    pub async fn get_min_gas_prices(&self) -> RpcResult<Vec<GasPrice>> {
+        let result = self
+            .get_abci_query(NODE_CONFIG_PATH.to_string(), String::new(), "0".to_string(), false)
+            .await?;
+        decode_node_config_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Returns the state of an Osmosis GAMM liquidity pool.
+    /// Fails with a validation error if the provider is not connected to an Osmosis network.
+    #[cfg(feature = "osmosis")]
+    pub async fn get_osmosis_pool(&self, pool_id: u64) -> RpcResult<OsmosisPool> {
+        let network = self.get_status().await?.node_info.network;
+        if !is_osmosis_chain_id(&network) {
+            return Err(RpcError::ValidationError(format!(
+                "Provider is not configured for Osmosis (network: {})",
+                network
+            )));
+        }
+        let result = self
+            .get_abci_query(
+                OSMOSIS_GAMM_POOL_PATH.to_string(),
+                encode_osmosis_pool_query(pool_id),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_osmosis_pool_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
+    }
+
+    /// Estimates the output of swapping `token_in` for `token_out_denom` in an Osmosis balancer pool,
+    /// at the spot price of the pool after its swap fee.
+    #[cfg(feature = "osmosis")]
+    pub async fn estimate_swap_output(
+        &self,
+        pool_id: u64,
+        token_in: CosmosCoin,
+        token_out_denom: String,
+    ) -> RpcResult<u64> {
+        let pool = self.get_osmosis_pool(pool_id).await?;
+        estimate_swap_output(&pool, &token_in, &token_out_denom).map_err(RpcError::ValidationError)
+    }
+
+    /// Returns the Stride host zone of `host_zone_id`, the chain ID of the host zone (e.g.
+    /// "cosmoshub-4"). Fails with a validation error if the provider is not connected to a Stride
+    /// network.
+    #[cfg(feature = "stride")]
+    pub async fn get_stride_host_zone(&self, host_zone_id: String) -> RpcResult<StrideHostZone> {
+        let network = self.get_status().await?.node_info.network;
+        if !is_stride_chain_id(&network) {
+            return Err(RpcError::ValidationError(format!(
+                "Provider is not configured for Stride (network: {})",
+                network
+            )));
+        }
+        let result = self
+            .get_abci_query(
+                STRIDE_HOST_ZONE_PATH.to_string(),
+                encode_stride_host_zone_query(&host_zone_id),
+                "0".to_string(),
+                false,
+            )
+            .await?;
+        decode_stride_host_zone_response(&abci_query_value(result)?).map_err(RpcError::ParseError)
     }
 
     /// Processes the result of an RPC method call by handling consistent and inconsistent responses
@@ -517,11 +1157,22 @@ impl RpcClient {
     }
 }
 
+/// Returns the base64 value of a successful ABCI query, or an error with the query log.
+fn abci_query_value(result: ABCIQueryResult) -> RpcResult<String> {
+    let response = result.response;
+    if response.code != 0 {
+        return Err(RpcError::Text(format!(
+            "ABCI query failed with code {}: {}",
+            response.code, response.log
+        )));
+    }
+    Ok(response.value)
+}
+
 fn remove_0x_prefix(hash: String) -> String {
-    if hash.starts_with("0x") {
-        hash[2..].to_string()
-    } else {
-        hash
+    match hash.strip_prefix("0x") {
+        Some(hash) => hash.to_string(),
+        None => hash,
     }
 }
 
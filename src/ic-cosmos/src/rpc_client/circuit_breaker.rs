@@ -0,0 +1,138 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// Loads the circuit breaker of the provider with the given URL, `None` if it never failed
+pub type CircuitBreakerLoader = fn(&str) -> Option<ProviderCircuitBreaker>;
+/// Persists the circuit breaker of the provider with the given URL
+pub type CircuitBreakerStore = fn(&str, ProviderCircuitBreaker);
+
+/// Bypasses the providers answering with consecutive HTTP 5xx responses for a cooldown period.
+/// The state of each provider is kept by the canister through `load` and `store`, so that it
+/// can live in stable memory. Bypassed calls fail with [`RpcError::ProviderUnavailable`].
+///
+/// [`RpcError::ProviderUnavailable`]: crate::rpc_client::RpcError::ProviderUnavailable
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CircuitBreaker {
+    /// Number of consecutive 5xx responses opening the circuit
+    pub failure_threshold: u32,
+    /// Time the provider is bypassed once the circuit is open
+    pub cooldown_seconds: u64,
+    pub load: CircuitBreakerLoader,
+    pub store: CircuitBreakerStore,
+}
+
+impl CircuitBreaker {
+    /// Returns whether the provider at `url` can be called at `now`, moving an open circuit
+    /// whose cooldown is over to half-open
+    pub fn try_acquire(&self, url: &str, now: u64) -> bool {
+        let Some(mut breaker) = (self.load)(url) else {
+            return true;
+        };
+        let previous = breaker;
+        let allowed = breaker.try_acquire(now, self.cooldown_nanos());
+        if breaker != previous {
+            (self.store)(url, breaker);
+        }
+        allowed
+    }
+
+    /// Records the HTTP status of a response of the provider at `url`
+    pub fn record_response(&self, url: &str, http_status: u16, now: u64) {
+        let breaker = (self.load)(url);
+        if (500..600).contains(&http_status) {
+            let mut breaker = breaker.unwrap_or_default();
+            breaker.record_failure(now, self.failure_threshold);
+            (self.store)(url, breaker);
+        } else if breaker.is_some_and(|breaker| breaker != ProviderCircuitBreaker::default()) {
+            (self.store)(url, ProviderCircuitBreaker::default());
+        }
+    }
+
+    fn cooldown_nanos(&self) -> u64 {
+        self.cooldown_seconds.saturating_mul(NANOS_PER_SECOND)
+    }
+}
+
+/// State of the circuit of a provider
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, CandidType, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// The provider is called
+    #[default]
+    Closed,
+    /// The provider is bypassed until the cooldown is over
+    Open,
+    /// A single test request is sent to the provider, closing or reopening the circuit
+    HalfOpen,
+}
+
+/// Consecutive failures and circuit state of a provider
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, CandidType, Serialize, Deserialize)]
+pub struct ProviderCircuitBreaker {
+    /// Number of consecutive 5xx responses
+    pub failure_count: u32,
+    /// Time of the last failure, or of the test request when half-open, in nanoseconds since the Unix epoch
+    pub last_failure: u64,
+    pub state: CircuitState,
+}
+
+impl ProviderCircuitBreaker {
+    /// Returns whether a request can be sent at `now`. Once the cooldown of an open circuit is
+    /// over, the circuit is half-open and a single test request is allowed per cooldown period,
+    /// so a test request whose response is never recorded does not keep the circuit half-open.
+    fn try_acquire(&mut self, now: u64, cooldown_nanos: u64) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open | CircuitState::HalfOpen if now.saturating_sub(self.last_failure) >= cooldown_nanos => {
+                self.state = CircuitState::HalfOpen;
+                self.last_failure = now;
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => false,
+        }
+    }
+
+    /// Counts a 5xx response, opening the circuit after `failure_threshold` consecutive ones
+    /// or when the test request of a half-open circuit fails
+    fn record_failure(&mut self, now: u64, failure_threshold: u32) {
+        self.failure_count = self.failure_count.saturating_add(1);
+        self.last_failure = now;
+        if self.state == CircuitState::HalfOpen || self.failure_count >= failure_threshold {
+            self.state = CircuitState::Open;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: u64 = 60 * NANOS_PER_SECOND;
+
+    #[test]
+    fn test_provider_circuit_breaker() {
+        let mut breaker = ProviderCircuitBreaker::default();
+        assert!(breaker.try_acquire(0, COOLDOWN));
+
+        breaker.record_failure(1, 3);
+        breaker.record_failure(2, 3);
+        assert_eq!(breaker.state, CircuitState::Closed);
+        breaker.record_failure(3, 3);
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert!(!breaker.try_acquire(COOLDOWN, COOLDOWN));
+
+        // A single test request once the cooldown is over, reopening the circuit on failure
+        assert!(breaker.try_acquire(COOLDOWN + 3, COOLDOWN));
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+        assert!(!breaker.try_acquire(COOLDOWN + 4, COOLDOWN));
+        breaker.record_failure(COOLDOWN + 5, 3);
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert!(!breaker.try_acquire(2 * COOLDOWN, COOLDOWN));
+
+        // A lost test request allows another one after the cooldown
+        assert!(breaker.try_acquire(2 * COOLDOWN + 5, COOLDOWN));
+        assert!(breaker.try_acquire(3 * COOLDOWN + 5, COOLDOWN));
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+    }
+}
@@ -0,0 +1,161 @@
+use std::cell::RefCell;
+
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::rpc_client::{RpcError, RpcResult};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const NANOS_PER_MINUTE: u64 = 60 * NANOS_PER_SECOND;
+
+thread_local! {
+    static BUCKETS: RefCell<Option<RateLimitBuckets>> = const { RefCell::new(None) };
+}
+
+/// Limits of the outbound HTTP outcalls of the canister, shared by all its RPC clients.
+/// Calls over the limits fail with [`RpcError::RateLimited`] without reaching the provider.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, CandidType, Serialize, Deserialize)]
+pub struct RateLimiter {
+    pub max_calls_per_second: u32,
+    pub max_calls_per_minute: u32,
+}
+
+impl RateLimiter {
+    /// Consumes a call from the limits, failing if none is available
+    pub fn try_acquire(&self) -> RpcResult<()> {
+        let now = ic_cdk::api::time();
+        self.with_buckets(|buckets| {
+            if buckets.try_acquire(now) {
+                Ok(())
+            } else {
+                Err(RpcError::RateLimited)
+            }
+        })
+    }
+
+    /// Returns the time to wait before a call is available, 0 if one is available now
+    pub fn waiting_time_nanos(&self) -> u64 {
+        let now = ic_cdk::api::time();
+        self.with_buckets(|buckets| buckets.waiting_time_nanos(now))
+    }
+
+    fn with_buckets<R>(&self, f: impl FnOnce(&mut RateLimitBuckets) -> R) -> R {
+        BUCKETS.with(|buckets| {
+            let mut buckets = buckets.borrow_mut();
+            // Start over with full buckets when the limits change
+            if buckets.as_ref().map(|buckets| buckets.limits) != Some(*self) {
+                *buckets = Some(RateLimitBuckets::new(*self, ic_cdk::api::time()));
+            }
+            f(buckets.as_mut().expect("rate limit buckets are initialized"))
+        })
+    }
+}
+
+/// Token buckets of a [`RateLimiter`], refilled continuously over their window
+#[derive(Debug, Clone)]
+struct RateLimitBuckets {
+    limits: RateLimiter,
+    per_second: TokenBucket,
+    per_minute: TokenBucket,
+}
+
+impl RateLimitBuckets {
+    fn new(limits: RateLimiter, now: u64) -> Self {
+        Self {
+            limits,
+            per_second: TokenBucket::new(limits.max_calls_per_second, NANOS_PER_SECOND, now),
+            per_minute: TokenBucket::new(limits.max_calls_per_minute, NANOS_PER_MINUTE, now),
+        }
+    }
+
+    fn try_acquire(&mut self, now: u64) -> bool {
+        self.per_second.refill(now);
+        self.per_minute.refill(now);
+        if self.per_second.waiting_time_nanos() > 0 || self.per_minute.waiting_time_nanos() > 0 {
+            return false;
+        }
+        self.per_second.consume();
+        self.per_minute.consume();
+        true
+    }
+
+    fn waiting_time_nanos(&mut self, now: u64) -> u64 {
+        self.per_second.refill(now);
+        self.per_minute.refill(now);
+        self.per_second
+            .waiting_time_nanos()
+            .max(self.per_minute.waiting_time_nanos())
+    }
+}
+
+/// Bucket of `capacity` tokens refilled at `capacity` tokens per `window_nanos`.
+/// Tokens are counted in units of `1 / window_nanos` to refill without rounding.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: u128,
+    window_nanos: u128,
+    scaled_tokens: u128,
+    last_refill: u64,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, window_nanos: u64, now: u64) -> Self {
+        let capacity = capacity as u128;
+        let window_nanos = window_nanos as u128;
+        Self {
+            capacity,
+            window_nanos,
+            scaled_tokens: capacity * window_nanos,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: u64) {
+        let elapsed = now.saturating_sub(self.last_refill) as u128;
+        self.scaled_tokens = (self.scaled_tokens + elapsed * self.capacity).min(self.capacity * self.window_nanos);
+        self.last_refill = self.last_refill.max(now);
+    }
+
+    fn consume(&mut self) {
+        self.scaled_tokens -= self.window_nanos;
+    }
+
+    fn waiting_time_nanos(&self) -> u64 {
+        if self.scaled_tokens >= self.window_nanos {
+            return 0;
+        }
+        if self.capacity == 0 {
+            return u64::MAX;
+        }
+        let missing = self.window_nanos - self.scaled_tokens;
+        missing.div_ceil(self.capacity).try_into().unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_buckets() {
+        let limits = RateLimiter {
+            max_calls_per_second: 2,
+            max_calls_per_minute: 3,
+        };
+        let mut buckets = RateLimitBuckets::new(limits, 0);
+
+        assert!(buckets.try_acquire(0));
+        assert!(buckets.try_acquire(0));
+        assert!(!buckets.try_acquire(0));
+        assert_eq!(buckets.waiting_time_nanos(0), NANOS_PER_SECOND / 2);
+
+        // The per second bucket is refilled, the per minute bucket has a single call left
+        assert!(buckets.try_acquire(NANOS_PER_SECOND));
+        assert!(!buckets.try_acquire(NANOS_PER_SECOND));
+        assert_eq!(
+            buckets.waiting_time_nanos(NANOS_PER_SECOND),
+            NANOS_PER_MINUTE / 3 - NANOS_PER_SECOND
+        );
+        assert!(buckets.try_acquire(NANOS_PER_MINUTE / 3));
+    }
+}
@@ -8,7 +8,7 @@ use ic_cdk::api::{
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::types::Cluster;
+use crate::types::{ChainVersion, Cluster};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Deserialize, CandidType)]
 pub enum ConsensusStrategy {
@@ -61,10 +61,22 @@ impl<T> From<JsonRpcResponse<T>> for RpcResult<T> {
 
 pub type RpcResult<T> = Result<T, RpcError>;
 
+/// Protocol of the endpoint of a provider
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, CandidType, Deserialize)]
+pub enum EndpointType {
+    /// Tendermint JSON-RPC, served on port 26657 by default
+    #[default]
+    JsonRpc,
+    /// Cosmos REST API (LCD), served on port 1317 by default
+    LcdRest,
+}
+
 #[derive(Clone, Eq, PartialEq, PartialOrd, Ord, CandidType, Deserialize)]
 pub struct RpcApi {
     pub network: String,
     pub headers: Option<Vec<HttpHeader>>,
+    /// Protocol of the endpoint, JSON-RPC when not set
+    pub endpoint_type: Option<EndpointType>,
 }
 
 impl RpcApi {
@@ -72,8 +84,21 @@ impl RpcApi {
         Self {
             network: network.to_string(),
             headers: None,
+            endpoint_type: None,
+        }
+    }
+
+    /// Returns the provider of the Cosmos REST API (LCD) at `network`, e.g. `https://lcd.cosmos.network`
+    pub fn lcd(network: impl ToString) -> Self {
+        Self {
+            endpoint_type: Some(EndpointType::LcdRest),
+            ..Self::new(network)
         }
     }
+
+    pub fn endpoint_type(&self) -> EndpointType {
+        self.endpoint_type.unwrap_or_default()
+    }
     pub fn cluster(&self) -> Cluster {
         Cluster::from_str(&self.network).expect("Failed to parse cluster url")
     }
@@ -108,6 +133,12 @@ pub enum RpcError {
 
     #[error("{0}")]
     Text(String),
+
+    #[error("Rate limit of outbound RPC calls exceeded")]
+    RateLimited,
+
+    #[error("Provider {0} is bypassed after repeated server errors")]
+    ProviderUnavailable(String),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, CandidType, Serialize, Deserialize, Error)]
@@ -153,4 +184,8 @@ pub struct RpcConfig {
 
     #[serde(rename = "responseConsensus")]
     pub response_consensus: Option<ConsensusStrategy>,
+
+    /// Version of the providers, responses are normalized as for [`ChainVersion::Unknown`] when not set
+    #[serde(rename = "chainVersion")]
+    pub chain_version: Option<ChainVersion>,
 }
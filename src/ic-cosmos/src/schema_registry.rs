@@ -0,0 +1,315 @@
+//! Field descriptions of the message types encoded by [`encode_message_to_protobuf`], so callers
+//! can discover which messages and fields are supported.
+//!
+//! [`encode_message_to_protobuf`]: crate::types::encode_message_to_protobuf
+
+use std::collections::HashMap;
+
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "osmosis")]
+use crate::types::OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL;
+use crate::types::{MSG_STORE_CODE_TYPE_URL, MSG_TRANSFER_TYPE_URL, MSG_UPDATE_CLIENT_TYPE_URL};
+#[cfg(feature = "stride")]
+use crate::types::{STRIDE_MSG_LIQUID_STAKE_TYPE_URL, STRIDE_MSG_REDEEM_STAKE_TYPE_URL};
+
+/// Protobuf type of a message field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum PbFieldType {
+    String,
+    Bytes,
+    Uint64,
+    Bool,
+    /// Enum of the given full name, e.g. `cosmos.gov.v1beta1.VoteOption`
+    Enum(String),
+    /// Message of the given full name, e.g. `cosmos.base.v1beta1.Coin`
+    Message(String),
+    /// Repeated field of the given type
+    Repeated(Box<PbFieldType>),
+}
+
+/// A field of a message type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct FieldDescriptor {
+    /// Field name, also the key of the field in the JSON representation of the message
+    pub name: String,
+    pub field_number: u32,
+    pub field_type: PbFieldType,
+}
+
+/// Field descriptions keyed by type URL, pre-populated with the built-in message types
+#[derive(Debug, Clone)]
+pub struct ProtobufSchemaRegistry {
+    schemas: HashMap<String, Vec<FieldDescriptor>>,
+}
+
+impl Default for ProtobufSchemaRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            schemas: HashMap::new(),
+        };
+        for (type_url, fields) in builtin_schemas() {
+            registry.register(type_url, fields);
+        }
+        registry
+    }
+}
+
+impl ProtobufSchemaRegistry {
+    /// Adds the fields of the `type_url` message, replacing any previous description
+    pub fn register(&mut self, type_url: impl Into<String>, fields: Vec<FieldDescriptor>) {
+        self.schemas.insert(type_url.into(), fields);
+    }
+
+    /// Returns the fields of the `type_url` message, ordered by field number
+    pub fn describe_message(&self, type_url: &str) -> Option<&[FieldDescriptor]> {
+        self.schemas.get(type_url).map(Vec::as_slice)
+    }
+
+    /// Returns the type URLs of the described messages, sorted
+    pub fn type_urls(&self) -> Vec<&str> {
+        let mut type_urls: Vec<&str> = self.schemas.keys().map(String::as_str).collect();
+        type_urls.sort_unstable();
+        type_urls
+    }
+}
+
+fn field(name: &str, field_number: u32, field_type: PbFieldType) -> FieldDescriptor {
+    FieldDescriptor {
+        name: name.to_string(),
+        field_number,
+        field_type,
+    }
+}
+
+fn message(name: &str) -> PbFieldType {
+    PbFieldType::Message(name.to_string())
+}
+
+fn repeated(field_type: PbFieldType) -> PbFieldType {
+    PbFieldType::Repeated(Box::new(field_type))
+}
+
+/// Fields written by the built-in encoders, which may be a subset of the fields of the message
+fn builtin_schemas() -> Vec<(&'static str, Vec<FieldDescriptor>)> {
+    use PbFieldType as Pb;
+
+    let coin = || message("cosmos.base.v1beta1.Coin");
+    let any = || message("google.protobuf.Any");
+    let delegation = || {
+        vec![
+            field("delegator_address", 1, Pb::String),
+            field("validator_address", 2, Pb::String),
+            field("amount", 3, coin()),
+        ]
+    };
+    let vote = |package: &str| {
+        let mut fields = vec![
+            field("proposal_id", 1, Pb::Uint64),
+            field("voter", 2, Pb::String),
+            field("option", 3, Pb::Enum(format!("{}.VoteOption", package))),
+        ];
+        if package == "cosmos.gov.v1" {
+            fields.push(field("metadata", 4, Pb::String));
+        }
+        fields
+    };
+    let vote_weighted = |package: &str| {
+        let mut fields = vec![
+            field("proposal_id", 1, Pb::Uint64),
+            field("voter", 2, Pb::String),
+            field(
+                "options",
+                3,
+                repeated(message(&format!("{}.WeightedVoteOption", package))),
+            ),
+        ];
+        if package == "cosmos.gov.v1" {
+            fields.push(field("metadata", 4, Pb::String));
+        }
+        fields
+    };
+    let deposit = || {
+        vec![
+            field("proposal_id", 1, Pb::Uint64),
+            field("depositor", 2, Pb::String),
+            field("amount", 3, repeated(coin())),
+        ]
+    };
+
+    #[allow(unused_mut)]
+    let mut schemas = vec![
+        (
+            "/cosmos.bank.v1beta1.MsgSend",
+            vec![
+                field("from_address", 1, Pb::String),
+                field("to_address", 2, Pb::String),
+                field("amount", 3, repeated(coin())),
+            ],
+        ),
+        ("/cosmos.staking.v1beta1.MsgDelegate", delegation()),
+        ("/cosmos.staking.v1beta1.MsgUndelegate", delegation()),
+        (
+            "/cosmos.staking.v1beta1.MsgBeginRedelegate",
+            vec![
+                field("delegator_address", 1, Pb::String),
+                field("validator_src_address", 2, Pb::String),
+                field("validator_dst_address", 3, Pb::String),
+                field("amount", 4, coin()),
+            ],
+        ),
+        (
+            "/cosmos.staking.v1beta1.MsgEditValidator",
+            vec![
+                field("description", 1, message("cosmos.staking.v1beta1.Description")),
+                field("validator_address", 2, Pb::String),
+                field("commission_rate", 3, Pb::String),
+                field("min_self_delegation", 4, Pb::String),
+            ],
+        ),
+        (
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+            vec![
+                field("delegator_address", 1, Pb::String),
+                field("validator_address", 2, Pb::String),
+            ],
+        ),
+        (
+            "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress",
+            vec![
+                field("delegator_address", 1, Pb::String),
+                field("withdraw_address", 2, Pb::String),
+            ],
+        ),
+        ("/cosmos.gov.v1beta1.MsgVote", vote("cosmos.gov.v1beta1")),
+        ("/cosmos.gov.v1.MsgVote", vote("cosmos.gov.v1")),
+        (
+            "/cosmos.gov.v1beta1.MsgVoteWeighted",
+            vote_weighted("cosmos.gov.v1beta1"),
+        ),
+        ("/cosmos.gov.v1.MsgVoteWeighted", vote_weighted("cosmos.gov.v1")),
+        ("/cosmos.gov.v1beta1.MsgDeposit", deposit()),
+        ("/cosmos.gov.v1.MsgDeposit", deposit()),
+        (
+            "/cosmos.gov.v1beta1.MsgSubmitProposal",
+            vec![
+                field("content", 1, any()),
+                field("initial_deposit", 2, repeated(coin())),
+                field("proposer", 3, Pb::String),
+            ],
+        ),
+        (
+            "/cosmos.gov.v1.MsgSubmitProposal",
+            vec![
+                field("messages", 1, repeated(any())),
+                field("initial_deposit", 2, repeated(coin())),
+                field("proposer", 3, Pb::String),
+                field("metadata", 4, Pb::String),
+                field("title", 5, Pb::String),
+                field("summary", 6, Pb::String),
+                field("expedited", 7, Pb::Bool),
+            ],
+        ),
+        (
+            MSG_TRANSFER_TYPE_URL,
+            vec![
+                field("source_port", 1, Pb::String),
+                field("source_channel", 2, Pb::String),
+                field("token", 3, coin()),
+                field("sender", 4, Pb::String),
+                field("receiver", 5, Pb::String),
+                field("timeout_height", 6, message("ibc.core.client.v1.Height")),
+                field("timeout_timestamp", 7, Pb::Uint64),
+                field("memo", 8, Pb::String),
+            ],
+        ),
+        (
+            MSG_UPDATE_CLIENT_TYPE_URL,
+            vec![
+                field("client_id", 1, Pb::String),
+                field("client_message", 2, any()),
+                field("signer", 3, Pb::String),
+            ],
+        ),
+        (
+            MSG_STORE_CODE_TYPE_URL,
+            vec![field("sender", 1, Pb::String), field("wasm_byte_code", 2, Pb::Bytes)],
+        ),
+    ];
+    #[cfg(feature = "osmosis")]
+    schemas.push((
+        OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL,
+        vec![
+            field("sender", 1, Pb::String),
+            field(
+                "routes",
+                2,
+                repeated(message("osmosis.poolmanager.v1beta1.SwapAmountInRoute")),
+            ),
+            field("token_in", 3, coin()),
+            field("token_out_min_amount", 4, Pb::String),
+        ],
+    ));
+    #[cfg(feature = "stride")]
+    schemas.extend([
+        (
+            STRIDE_MSG_LIQUID_STAKE_TYPE_URL,
+            vec![
+                field("creator", 1, Pb::String),
+                field("amount", 2, Pb::String),
+                field("host_denom", 3, Pb::String),
+            ],
+        ),
+        (
+            STRIDE_MSG_REDEEM_STAKE_TYPE_URL,
+            vec![
+                field("creator", 1, Pb::String),
+                field("amount", 2, Pb::String),
+                field("host_zone", 3, Pb::String),
+                field("receiver", 4, Pb::String),
+            ],
+        ),
+    ]);
+    schemas
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{encode_message_to_protobuf, CosmosMessage};
+
+    #[test]
+    fn test_describe_message() {
+        let registry = ProtobufSchemaRegistry::default();
+        let fields = registry.describe_message("/cosmos.bank.v1beta1.MsgSend").unwrap();
+        assert_eq!(fields.len(), 3);
+        assert_eq!(
+            fields[2],
+            field("amount", 3, repeated(message("cosmos.base.v1beta1.Coin")))
+        );
+        assert_eq!(
+            registry.describe_message("/cosmos.gov.v1.MsgVote").unwrap()[2].field_type,
+            PbFieldType::Enum("cosmos.gov.v1.VoteOption".to_string())
+        );
+        assert!(registry.describe_message("/cosmos.bank.v1beta1.MsgMultiSend").is_none());
+
+        let mut registry = registry;
+        registry.register("/custom.v1.MsgPing", vec![field("sender", 1, PbFieldType::String)]);
+        assert_eq!(registry.describe_message("/custom.v1.MsgPing").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_described_messages_are_encoded() {
+        for type_url in ProtobufSchemaRegistry::default().type_urls() {
+            let message = CosmosMessage {
+                type_url: type_url.to_string(),
+                value: json!({}),
+            };
+            let error = encode_message_to_protobuf(&message).unwrap_err();
+            assert!(!error.starts_with("Unsupported message type"), "{}", type_url);
+        }
+    }
+}
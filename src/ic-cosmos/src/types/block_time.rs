@@ -0,0 +1,119 @@
+//! Conversions between the RFC 3339 times of the block headers, e.g.
+//! `2024-01-15T12:34:56.789012Z`, and Unix timestamps in nanoseconds as returned by
+//! `ic_cdk::api::time()`.
+
+use super::{parse_timestamp, ParseError};
+
+/// Age above which the latest block of a node shows it is behind the chain, about 10 blocks of
+/// the Cosmos Hub
+pub const MAX_SYNCED_BLOCK_AGE_NANOS: u64 = 60_000_000_000;
+
+/// Parses a block time as nanoseconds since the Unix epoch. Times before the epoch are rejected.
+pub fn parse_block_time(time_str: &str) -> Result<u64, ParseError> {
+    let invalid = || ParseError::InvalidTime(time_str.to_string());
+    let (seconds, nanos) = parse_timestamp(time_str).map_err(|_| invalid())?;
+    u64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| seconds.checked_mul(1_000_000_000))
+        .and_then(|nanos_since_epoch| nanos_since_epoch.checked_add(nanos as u64))
+        .ok_or_else(invalid)
+}
+
+/// Formats nanoseconds since the Unix epoch as a UTC block time, with the shortest fraction of a
+/// second that keeps the precision, e.g. `2024-01-15T12:34:56.789012Z`
+pub fn format_block_time(nanos: u64) -> String {
+    let seconds = nanos / 1_000_000_000;
+    let fraction = nanos % 1_000_000_000;
+    let (year, month, day) = civil_from_days(seconds / 86_400);
+    let time_of_day = seconds % 86_400;
+    let clock = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time_of_day / 3600,
+        time_of_day / 60 % 60,
+        time_of_day % 60
+    );
+    if fraction == 0 {
+        return format!("{}Z", clock);
+    }
+    let fraction = format!("{:09}", fraction);
+    format!("{}.{}Z", clock, fraction.trim_end_matches('0'))
+}
+
+/// Returns how many nanoseconds ago the block of `time_str` was produced, at the current time of
+/// the IC. Blocks timed in the future are 0 nanoseconds old.
+pub fn block_time_age_nanos(time_str: &str) -> Result<u64, ParseError> {
+    block_time_age_nanos_at(time_str, ic_cdk::api::time())
+}
+
+/// Returns how many nanoseconds before `now_nanos` the block of `time_str` was produced
+pub fn block_time_age_nanos_at(time_str: &str, now_nanos: u64) -> Result<u64, ParseError> {
+    Ok(now_nanos.saturating_sub(parse_block_time(time_str)?))
+}
+
+/// Returns whether a node is behind the chain at `now_nanos`: when it reports catching up, or
+/// when its latest block is more than `max_block_age_nanos` old. A node whose latest block time
+/// cannot be parsed is only judged on what it reports.
+pub fn is_catching_up_at(catching_up: bool, latest_block_time: &str, now_nanos: u64, max_block_age_nanos: u64) -> bool {
+    catching_up || block_time_age_nanos_at(latest_block_time, now_nanos).is_ok_and(|age| age > max_block_age_nanos)
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) date of the proleptic Gregorian
+/// calendar, the inverse of `days_from_civil`
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_time_conversions() {
+        let nanos = 1_705_322_096_789_012_000;
+        assert_eq!(parse_block_time("2024-01-15T12:34:56.789012Z"), Ok(nanos));
+        assert_eq!(format_block_time(nanos), "2024-01-15T12:34:56.789012Z");
+        assert_eq!(parse_block_time("1970-01-01T00:00:00Z"), Ok(0));
+        assert_eq!(format_block_time(0), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format_block_time(951_782_400_000_000_001),
+            "2000-02-29T00:00:00.000000001Z"
+        );
+        assert_eq!(
+            parse_block_time("2024-01-15T14:34:56.789012+02:00"),
+            parse_block_time("2024-01-15T12:34:56.789012Z")
+        );
+        assert_eq!(
+            parse_block_time("1969-12-31T23:59:59Z"),
+            Err(ParseError::InvalidTime("1969-12-31T23:59:59Z".to_string()))
+        );
+        assert!(parse_block_time("2024-01-15").is_err());
+
+        assert_eq!(
+            block_time_age_nanos_at("2024-01-15T12:34:56.789012Z", nanos + 5_000),
+            Ok(5_000)
+        );
+        assert_eq!(block_time_age_nanos_at("2024-01-15T12:34:56.789012Z", 0), Ok(0));
+    }
+
+    #[test]
+    fn test_is_catching_up_at() {
+        let time = "2024-01-15T12:34:56.789012Z";
+        let now = parse_block_time(time).unwrap() + MAX_SYNCED_BLOCK_AGE_NANOS;
+        assert!(!is_catching_up_at(false, time, now, MAX_SYNCED_BLOCK_AGE_NANOS));
+        assert!(is_catching_up_at(false, time, now + 1, MAX_SYNCED_BLOCK_AGE_NANOS));
+        assert!(is_catching_up_at(true, time, now, MAX_SYNCED_BLOCK_AGE_NANOS));
+        assert!(!is_catching_up_at(false, "", now, MAX_SYNCED_BLOCK_AGE_NANOS));
+    }
+}
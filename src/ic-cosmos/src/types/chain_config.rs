@@ -0,0 +1,110 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{gov_message, CosmosMessage, GovVersion};
+
+/// Maximum length of a chain ID accepted by CometBFT
+pub const MAX_CHAIN_ID_LEN: usize = 50;
+
+/// Configuration of a Cosmos chain shared between canisters via the config canister.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ChainConfig {
+    /// The chain ID, e.g. "cosmoshub-4"
+    pub chain_id: String,
+    /// The bech32 human readable part of account addresses, e.g. "cosmos"
+    pub hrp: String,
+    /// The minimum gas price with its denom, e.g. "0.025uatom"
+    pub gas_price: String,
+    /// The CometBFT JSON-RPC URL of the chain
+    pub rpc_url: String,
+    /// Whether the chain has `x/gov` v1 enabled, v1beta1 only when not set
+    pub gov_v1: Option<bool>,
+}
+
+impl ChainConfig {
+    /// Checks that all fields are set, `chain_id` is at most [`MAX_CHAIN_ID_LEN`] bytes and `rpc_url`
+    /// uses HTTPS
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("chain_id", &self.chain_id),
+            ("hrp", &self.hrp),
+            ("gas_price", &self.gas_price),
+        ] {
+            if value.is_empty() {
+                return Err(format!("Missing {} in chain config", name));
+            }
+        }
+        if self.chain_id.len() > MAX_CHAIN_ID_LEN {
+            return Err(format!(
+                "Chain ID longer than {} bytes: {}",
+                MAX_CHAIN_ID_LEN, self.chain_id
+            ));
+        }
+        if !self.rpc_url.starts_with("https://") {
+            return Err(format!("Chain RPC URL must use HTTPS: {}", self.rpc_url));
+        }
+        Ok(())
+    }
+
+    /// Returns the version of the `x/gov` module messages of the chain
+    pub fn gov_version(&self) -> GovVersion {
+        GovVersion::from_chain_flag(self.gov_v1.unwrap_or_default())
+    }
+
+    /// Builds the `msg_name` gov message with the type URL of the chain's gov module version
+    pub fn gov_message(&self, msg_name: &str, value: serde_json::Value) -> Result<CosmosMessage, String> {
+        gov_message(self.gov_version(), msg_name, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn cosmoshub() -> ChainConfig {
+        ChainConfig {
+            chain_id: "cosmoshub-4".to_string(),
+            hrp: "cosmos".to_string(),
+            gas_price: "0.025uatom".to_string(),
+            rpc_url: "https://rpc.cosmos.network".to_string(),
+            gov_v1: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_chain_config() {
+        assert_eq!(cosmoshub().validate(), Ok(()));
+
+        let long_chain_id = ChainConfig {
+            chain_id: "c".repeat(MAX_CHAIN_ID_LEN + 1),
+            ..cosmoshub()
+        };
+        assert!(long_chain_id.validate().is_err());
+    }
+
+    #[test]
+    fn test_chain_gov_message() {
+        let vote = json!({ "proposal_id": "1", "voter": "cosmos1voter", "option": "VOTE_OPTION_YES" });
+
+        let v1beta1 = cosmoshub();
+        assert_eq!(v1beta1.gov_version(), GovVersion::V1Beta1);
+        assert_eq!(
+            v1beta1.gov_message("MsgVote", vote.clone()).unwrap().type_url,
+            "/cosmos.gov.v1beta1.MsgVote"
+        );
+        assert!(v1beta1.gov_message("MsgSubmitProposal", json!({})).is_err());
+
+        let v1 = ChainConfig {
+            gov_v1: Some(true),
+            ..cosmoshub()
+        };
+        assert_eq!(v1.gov_version(), GovVersion::V1);
+        assert_eq!(
+            v1.gov_message("MsgVote", vote).unwrap().type_url,
+            "/cosmos.gov.v1.MsgVote"
+        );
+        assert!(v1.gov_message("MsgSubmitProposal", json!({})).is_ok());
+    }
+}
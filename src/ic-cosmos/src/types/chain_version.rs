@@ -0,0 +1,131 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::cosmos_common::NodeInfo;
+
+/// Fields holding an application hash, returned as hex or base64 depending on the endpoint
+const APP_HASH_FIELDS: &[&str] = &[
+    "app_hash",
+    "last_block_app_hash",
+    "latest_app_hash",
+    "earliest_app_hash",
+];
+
+/// Consensus engine version of a node, selecting the normalization of its responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum ChainVersion {
+    /// Tendermint v0.34, used by Cosmos SDK chains before v0.47
+    Tendermint034,
+    /// CometBFT v0.37, used by Cosmos SDK v0.47
+    CometBft037,
+    /// CometBFT v0.38 and later, used by Cosmos SDK v0.50 and later
+    CometBft038,
+    /// Not detected, every normalization rule is applied
+    #[default]
+    Unknown,
+}
+
+impl ChainVersion {
+    /// Detects the version from the `version` of the `status` node info, e.g. "0.37.4"
+    pub fn detect_from_node_info(node_info: &NodeInfo) -> ChainVersion {
+        let mut parts = node_info.version.trim_start_matches('v').split('.');
+        let major = parts.next().and_then(|part| part.parse::<u64>().ok());
+        let minor = parts.next().and_then(|part| part.parse::<u64>().ok());
+        match (major, minor) {
+            (Some(0), Some(34)) => ChainVersion::Tendermint034,
+            (Some(0), Some(37)) => ChainVersion::CometBft037,
+            (Some(0), Some(minor)) if minor >= 38 => ChainVersion::CometBft038,
+            (Some(major), Some(_)) if major >= 1 => ChainVersion::CometBft038,
+            _ => ChainVersion::Unknown,
+        }
+    }
+
+    /// Whether transaction results may be returned as `deliver_tx` instead of `tx_result`
+    fn uses_deliver_tx(&self) -> bool {
+        !matches!(self, ChainVersion::CometBft038)
+    }
+}
+
+/// Rewrites the fields that differ between Cosmos SDK and CometBFT versions to a single form:
+/// `deliver_tx` is renamed to `tx_result`, application hashes are upper case hex and
+/// voting powers are strings.
+pub fn normalize_response(value: Value, chain_version: ChainVersion) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = normalize_response(value, chain_version);
+                    match key.as_str() {
+                        "deliver_tx" if chain_version.uses_deliver_tx() => ("tx_result".to_string(), value),
+                        key if APP_HASH_FIELDS.contains(&key) => {
+                            let value = normalize_app_hash(value);
+                            (key.to_string(), value)
+                        }
+                        "voting_power" => {
+                            let value = match value {
+                                Value::Number(number) => Value::String(number.to_string()),
+                                other => other,
+                            };
+                            (key, value)
+                        }
+                        _ => (key, value),
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| normalize_response(item, chain_version))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn normalize_app_hash(value: Value) -> Value {
+    let Value::String(hash) = value else {
+        return value;
+    };
+    if hash.len() % 2 == 0 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Value::String(hash.to_ascii_uppercase());
+    }
+    match STANDARD.decode(&hash) {
+        Ok(bytes) => Value::String(hex::encode_upper(bytes)),
+        Err(_) => Value::String(hash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_response() {
+        let response = json!({
+            "result": {
+                "deliver_tx": { "code": 0 },
+                "last_block_app_hash": "q83vEjRWeJA=",
+                "header": { "app_hash": "abcdef1234567890" },
+                "validators": [{ "voting_power": 100 }, { "voting_power": "200" }]
+            }
+        });
+
+        assert_eq!(
+            normalize_response(response.clone(), ChainVersion::Tendermint034),
+            json!({
+                "result": {
+                    "tx_result": { "code": 0 },
+                    "last_block_app_hash": "ABCDEF1234567890",
+                    "header": { "app_hash": "ABCDEF1234567890" },
+                    "validators": [{ "voting_power": "100" }, { "voting_power": "200" }]
+                }
+            })
+        );
+        assert!(normalize_response(response, ChainVersion::CometBft038)["result"]["deliver_tx"].is_object());
+    }
+}
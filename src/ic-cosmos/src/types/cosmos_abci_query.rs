@@ -39,8 +39,56 @@ pub struct ABCIResponse {
     pub value: String,
     /// The response proof
     pub proof: Option<String>,
+    /// The Merkle proof of the value, with `prove: true`
+    #[serde(rename = "proofOps", default)]
+    pub proof_ops: Option<ABCIProofOps>,
     /// The response height
     pub height: String,
     /// The response codespace
     pub codespace: String,
 }
+
+/// Represents the Merkle proof operations of an ABCI response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ABCIProofOps {
+    /// The operations, from the proven key to the app hash
+    pub ops: Vec<ABCIProofOp>,
+}
+
+/// Represents a Merkle proof operation of an ABCI response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ABCIProofOp {
+    /// The proof type, e.g. `ics23:iavl`
+    #[serde(rename = "type")]
+    pub op_type: String,
+    /// The base64 key proven by the operation
+    pub key: String,
+    /// The base64 protobuf encoded ICS23 `CommitmentProof`
+    pub data: String,
+}
+
+/// Block height at which an ABCI query reads the chain state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum BlockHeight {
+    /// The state of the latest block
+    #[default]
+    Latest,
+    /// The state at a historical block, which the node must not have pruned yet
+    Height(u64),
+}
+
+impl BlockHeight {
+    /// Returns the `height` parameter of the `abci_query` request, where "0" means the latest block
+    pub fn to_query_param(&self) -> String {
+        match self {
+            BlockHeight::Latest => "0".to_string(),
+            BlockHeight::Height(height) => height.to_string(),
+        }
+    }
+}
+
+impl From<Option<u64>> for BlockHeight {
+    fn from(height: Option<u64>) -> Self {
+        height.map_or(BlockHeight::Latest, BlockHeight::Height)
+    }
+}
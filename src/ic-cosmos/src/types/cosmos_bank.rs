@@ -0,0 +1,45 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::types::cosmos_protobuf::{encode_string, find_field};
+
+/// ABCI query path of the balance of an address in a single denom
+pub const BANK_BALANCE_PATH: &str = "/cosmos.bank.v1beta1.Query/Balance";
+
+/// Encode `QueryBalanceRequest` as hex `abci_query` data
+pub fn encode_balance_query(address: &str, denom: &str) -> String {
+    let mut request_bytes = encode_string(0x0a, address); // address = 1
+    request_bytes.extend(encode_string(0x12, denom)); // denom = 2
+    hex::encode(request_bytes)
+}
+
+/// Decode the amount of a `QueryBalanceResponse` from the base64 ABCI response value,
+/// "0" if the address holds no such coin
+pub fn decode_balance_response(response_value: &str) -> Result<String, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // balance = 1 -> Coin.amount = 2
+    let Some(coin) = find_field(&decoded, 1)?.and_then(|f| f.as_bytes()) else {
+        return Ok("0".to_string());
+    };
+    Ok(find_field(coin, 2)?
+        .and_then(|f| f.as_string())
+        .unwrap_or_else(|| "0".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::cosmos_protobuf::encode_length_delimited;
+
+    #[test]
+    fn test_decode_balance_response() {
+        let mut coin = encode_string(0x0a, "uatom");
+        coin.extend(encode_string(0x12, "123456"));
+        let response = STANDARD.encode(encode_length_delimited(0x0a, &coin));
+
+        assert_eq!(decode_balance_response(&response).unwrap(), "123456");
+        assert_eq!(decode_balance_response("").unwrap(), "0");
+    }
+}
@@ -88,6 +88,10 @@ pub struct BlockResults {
     pub height: String,
     /// The transaction results
     pub txs_results: Option<Vec<TxResult>>,
+    /// The begin block events, replaced by the finalize block events since CometBFT 0.38
+    pub begin_block_events: Option<Vec<BlockEvent>>,
+    /// The end block events, replaced by the finalize block events since CometBFT 0.38
+    pub end_block_events: Option<Vec<BlockEvent>>,
     /// The finalize block events
     pub finalize_block_events: Option<Vec<BlockEvent>>,
     /// The validator updates
@@ -128,6 +132,27 @@ pub struct BlockEvent {
     pub attributes: Vec<Event>,
 }
 
+/// Block phase or transaction which emitted an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum EventSource {
+    BeginBlock,
+    EndBlock,
+    FinalizeBlock,
+    /// Index of the transaction in the block
+    Tx(u32),
+}
+
+/// An event of a block, with the phase or transaction which emitted it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct BlockEventData {
+    /// Where the event was emitted
+    pub source: EventSource,
+    /// Event type
+    pub event_type: String,
+    /// Event attributes as key-value pairs
+    pub attributes: Vec<(String, String)>,
+}
+
 /// Represents a validator update.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 #[serde(rename_all = "snake_case")]
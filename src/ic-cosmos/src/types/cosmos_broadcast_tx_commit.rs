@@ -0,0 +1,31 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{cosmos_block::TxResult, cosmos_check_tx::CheckTxResult};
+
+/// Represents the response from the /broadcast_tx_commit endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+#[serde(rename_all = "snake_case")]
+pub struct BroadcastTxCommitResponse {
+    /// The JSON-RPC version
+    pub jsonrpc: String,
+    /// The request ID
+    pub id: i32,
+    /// The broadcast transaction commit result
+    pub result: BroadcastTxCommitResult,
+}
+
+/// Represents the result of a transaction broadcast that waited for block inclusion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+#[serde(rename_all = "snake_case")]
+pub struct BroadcastTxCommitResult {
+    /// The transaction hash
+    pub hash: String,
+    /// The height of the block including the transaction
+    pub height: String,
+    /// The mempool check result
+    pub check_tx: CheckTxResult,
+    /// The block execution result (`tx_result` since CometBFT v0.38)
+    #[serde(alias = "tx_result")]
+    pub deliver_tx: TxResult,
+}
@@ -24,3 +24,83 @@ pub struct ConsensusParamsResult {
     /// The consensus parameters
     pub consensus_params: ConsensusParams,
 }
+
+/// A consensus parameter whose value changed between two queries, e.g. after a governance
+/// proposal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ConsensusParamChange {
+    /// Path of the parameter, e.g. `block.max_bytes`
+    pub name: String,
+    pub previous: String,
+    pub current: String,
+}
+
+impl ConsensusParams {
+    /// Returns the parameters whose value differs in `current`
+    pub fn diff(&self, current: &ConsensusParams) -> Vec<ConsensusParamChange> {
+        let values = |params: &ConsensusParams| {
+            [
+                ("block.max_bytes", params.block.max_bytes.clone()),
+                ("block.max_gas", params.block.max_gas.clone()),
+                (
+                    "block.time_iota_ms",
+                    params.block.time_iota_ms.clone().unwrap_or_default(),
+                ),
+                (
+                    "evidence.max_age_num_blocks",
+                    params.evidence.max_age_num_blocks.clone(),
+                ),
+                ("evidence.max_age_duration", params.evidence.max_age_duration.clone()),
+                ("evidence.max_bytes", params.evidence.max_bytes.clone()),
+                ("validator.pub_key_types", params.validator.pub_key_types.join(",")),
+            ]
+        };
+        values(self)
+            .into_iter()
+            .zip(values(current))
+            .filter(|((_, previous), (_, current))| previous != current)
+            .map(|((name, previous), (_, current))| ConsensusParamChange {
+                name: name.to_string(),
+                previous,
+                current,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::cosmos_common::{BlockParams, EvidenceParams, ValidatorParams};
+
+    #[test]
+    fn test_consensus_params_diff() {
+        let params = ConsensusParams {
+            block: BlockParams {
+                max_bytes: "22020096".to_string(),
+                max_gas: "-1".to_string(),
+                time_iota_ms: None,
+            },
+            evidence: EvidenceParams {
+                max_age_num_blocks: "100000".to_string(),
+                max_age_duration: "172800000000000".to_string(),
+                max_bytes: "1048576".to_string(),
+            },
+            validator: ValidatorParams {
+                pub_key_types: vec!["ed25519".to_string()],
+            },
+        };
+        assert!(params.diff(&params).is_empty());
+
+        let mut current = params.clone();
+        current.block.max_gas = "75000000".to_string();
+        assert_eq!(
+            params.diff(&current),
+            vec![ConsensusParamChange {
+                name: "block.max_gas".to_string(),
+                previous: "-1".to_string(),
+                current: "75000000".to_string(),
+            }]
+        );
+    }
+}
@@ -34,7 +34,7 @@ pub struct DumpRoundState {
     /// The current round
     pub round: i32,
     /// The current step
-    pub step: i32,
+    pub step: ConsensusStep,
     /// The start time of the round
     pub start_time: String,
     /// The commit time
@@ -47,13 +47,13 @@ pub struct DumpRoundState {
     pub proposal_block: Option<DumpBlock>,
     /// The proposal block parts information
     pub proposal_block_parts: Option<DumpBlockParts>,
-    /// The locked round
+    /// The locked round, -1 if no block is locked
     pub locked_round: i32,
     /// The locked block information
     pub locked_block: Option<DumpBlock>,
     /// The locked block parts information
     pub locked_block_parts: Option<DumpBlockParts>,
-    /// The valid round
+    /// The valid round, -1 if no valid block has been seen
     pub valid_round: i32,
     /// The valid block information
     pub valid_block: Option<DumpBlock>,
@@ -61,7 +61,7 @@ pub struct DumpRoundState {
     pub valid_block_parts: Option<DumpBlockParts>,
     /// The votes information
     pub votes: Vec<DumpVoteSet>,
-    /// The commit round
+    /// The commit round, -1 until a commit is reached
     pub commit_round: i32,
     /// The last commit information
     pub last_commit: Option<DumpLastCommit>,
@@ -71,6 +71,70 @@ pub struct DumpRoundState {
     pub triggered_timeout_precommit: bool,
 }
 
+/// Represents a step of the consensus round (CometBFT `RoundStepType`).
+/// Encoded as `int32` in both JSON and Candid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "i32", into = "i32")]
+pub enum ConsensusStep {
+    /// Waiting for the commit timeout to start the next height
+    NewHeight = 1,
+    /// Setting up a new round
+    NewRound = 2,
+    /// Waiting for the proposal
+    Propose = 3,
+    /// Collecting prevotes
+    Prevote = 4,
+    /// Waiting for more prevotes after +2/3 of any prevotes
+    PrevoteWait = 5,
+    /// Collecting precommits
+    Precommit = 6,
+    /// Waiting for more precommits after +2/3 of any precommits
+    PrecommitWait = 7,
+    /// Committing the block
+    Commit = 8,
+}
+
+impl TryFrom<i32> for ConsensusStep {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ConsensusStep::NewHeight),
+            2 => Ok(ConsensusStep::NewRound),
+            3 => Ok(ConsensusStep::Propose),
+            4 => Ok(ConsensusStep::Prevote),
+            5 => Ok(ConsensusStep::PrevoteWait),
+            6 => Ok(ConsensusStep::Precommit),
+            7 => Ok(ConsensusStep::PrecommitWait),
+            8 => Ok(ConsensusStep::Commit),
+            _ => Err(format!("Invalid consensus step: {}", value)),
+        }
+    }
+}
+
+impl From<ConsensusStep> for i32 {
+    fn from(step: ConsensusStep) -> Self {
+        step as i32
+    }
+}
+
+impl CandidType for ConsensusStep {
+    fn id() -> candid::types::TypeId {
+        i32::id()
+    }
+
+    fn _ty() -> candid::types::Type {
+        i32::_ty()
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        i32::from(*self).idl_serialize(serializer)
+    }
+}
+
 /// Represents a proposal.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 #[serde(rename_all = "snake_case")]
@@ -319,3 +383,23 @@ pub struct PeerStats {
     /// The number of block parts
     pub block_parts: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consensus_step_serde() {
+        let step: ConsensusStep = serde_json::from_str("4").unwrap();
+        assert_eq!(step, ConsensusStep::Prevote);
+        assert_eq!(serde_json::to_string(&ConsensusStep::Commit).unwrap(), "8");
+        assert!(serde_json::from_str::<ConsensusStep>("9").is_err());
+
+        let bytes = candid::encode_one(ConsensusStep::Precommit).unwrap();
+        assert_eq!(candid::decode_one::<i32>(&bytes).unwrap(), 6);
+        assert_eq!(
+            candid::decode_one::<ConsensusStep>(&bytes).unwrap(),
+            ConsensusStep::Precommit
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    cosmos_pagination::{decode_page_response, encode_page_request, Pagination},
+    cosmos_protobuf::{decode_fields, encode_length_delimited, encode_uint64, ProtoField},
+};
+
+/// ABCI query path of the governance proposals
+pub const GOV_PROPOSALS_PATH: &str = "/cosmos.gov.v1.Query/Proposals";
+
+/// Number of proposals returned when no limit is given
+pub const DEFAULT_PROPOSALS_LIMIT: u64 = 50;
+
+/// Status of a governance proposal, as `cosmos.gov.v1.ProposalStatus`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, CandidType)]
+pub enum ProposalStatus {
+    #[default]
+    Unspecified,
+    DepositPeriod,
+    VotingPeriod,
+    Passed,
+    Rejected,
+    Failed,
+}
+
+impl ProposalStatus {
+    pub fn from_proto(value: u64) -> Self {
+        match value {
+            1 => ProposalStatus::DepositPeriod,
+            2 => ProposalStatus::VotingPeriod,
+            3 => ProposalStatus::Passed,
+            4 => ProposalStatus::Rejected,
+            5 => ProposalStatus::Failed,
+            _ => ProposalStatus::Unspecified,
+        }
+    }
+
+    pub fn to_proto(&self) -> u64 {
+        match self {
+            ProposalStatus::Unspecified => 0,
+            ProposalStatus::DepositPeriod => 1,
+            ProposalStatus::VotingPeriod => 2,
+            ProposalStatus::Passed => 3,
+            ProposalStatus::Rejected => 4,
+            ProposalStatus::Failed => 5,
+        }
+    }
+
+    /// Whether the proposal can no longer change status
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            ProposalStatus::Passed | ProposalStatus::Rejected | ProposalStatus::Failed
+        )
+    }
+}
+
+/// A governance proposal without its messages and tally
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ProposalSummary {
+    pub id: u64,
+    pub title: String,
+    pub status: ProposalStatus,
+}
+
+/// Page of proposals returned by `Proposals`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, CandidType)]
+pub struct ProposalsPage {
+    pub proposals: Vec<ProposalSummary>,
+    /// Key of the next page, `None` on the last page
+    pub next_key: Option<Vec<u8>>,
+    /// Total number of proposals, 0 unless `count_total` was requested
+    pub total: u64,
+}
+
+/// Status change of a governance proposal, sent to the governance subscribers of the RPC canister
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ProposalTransition {
+    pub proposal: ProposalSummary,
+    pub previous_status: ProposalStatus,
+}
+
+/// Returns the `DEFAULT_PROPOSALS_LIMIT` most recent proposals
+pub fn default_proposals_pagination() -> Pagination {
+    Pagination::first(DEFAULT_PROPOSALS_LIMIT, true)
+}
+
+/// Encode `QueryProposalsRequest` as hex `abci_query` data, returning the page of proposals
+/// with `status`, or with any status if `None`
+pub fn encode_proposals_query(status: Option<ProposalStatus>, pagination: &Pagination) -> String {
+    let mut request_bytes = Vec::new();
+    if let Some(status) = status.filter(|status| *status != ProposalStatus::Unspecified) {
+        request_bytes.extend(encode_uint64(0x08, status.to_proto())); // proposal_status = 1
+    }
+    request_bytes.extend(encode_length_delimited(0x22, &encode_page_request(pagination))); // pagination = 4
+    hex::encode(request_bytes)
+}
+
+/// Decode a `QueryProposalsResponse` from the base64 ABCI response value
+pub fn decode_proposals_response(response_value: &str) -> Result<ProposalsPage, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    let mut page = ProposalsPage::default();
+    for (number, field) in decode_fields(&decoded)? {
+        let proposal = match (number, field) {
+            // proposals = 1
+            (1, ProtoField::Bytes(proposal)) => proposal,
+            // pagination = 2
+            (2, ProtoField::Bytes(pagination)) => {
+                (page.next_key, page.total) = decode_page_response(pagination)?;
+                continue;
+            }
+            _ => continue,
+        };
+        let mut summary = ProposalSummary {
+            id: 0,
+            title: String::new(),
+            status: ProposalStatus::Unspecified,
+        };
+        for (number, field) in decode_fields(proposal)? {
+            match (number, field) {
+                (1, ProtoField::Varint(id)) => summary.id = id,
+                (3, ProtoField::Varint(status)) => summary.status = ProposalStatus::from_proto(status),
+                (11, field) => summary.title = field.as_string().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        page.proposals.push(summary);
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::{
+        cosmos::{
+            base::query::v1beta1::{PageRequest, PageResponse},
+            gov::v1::{Proposal, QueryProposalsRequest, QueryProposalsResponse},
+        },
+        traits::Message,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_encode_proposals_query() {
+        let expected = QueryProposalsRequest {
+            proposal_status: 2,
+            pagination: Some(PageRequest {
+                limit: 10,
+                reverse: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_proposals_query(Some(ProposalStatus::VotingPeriod), &Pagination::first(10, true)),
+            hex::encode(expected.encode_to_vec())
+        );
+    }
+
+    #[test]
+    fn test_decode_proposals_response() {
+        let response = QueryProposalsResponse {
+            proposals: vec![
+                Proposal {
+                    id: 42,
+                    status: 3,
+                    title: "Signaling proposal".to_string(),
+                    ..Default::default()
+                },
+                Proposal {
+                    id: 43,
+                    status: 1,
+                    ..Default::default()
+                },
+            ],
+            pagination: Some(PageResponse {
+                next_key: vec![0, 0, 0, 0, 0, 0, 0, 41],
+                total: 0,
+            }),
+        };
+        let page = decode_proposals_response(&STANDARD.encode(response.encode_to_vec())).unwrap();
+
+        assert_eq!(page.next_key, Some(vec![0, 0, 0, 0, 0, 0, 0, 41]));
+        assert_eq!(
+            page.proposals,
+            vec![
+                ProposalSummary {
+                    id: 42,
+                    title: "Signaling proposal".to_string(),
+                    status: ProposalStatus::Passed,
+                },
+                ProposalSummary {
+                    id: 43,
+                    title: String::new(),
+                    status: ProposalStatus::DepositPeriod,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Protobuf encoding of typed Cosmos messages, usually implemented with
+//! `#[derive(CosmosMessageEncoder)]` of the `ic-cosmos-derive` crate.
+
+use crate::types::cosmos_protobuf::encode_varint;
+
+/// Wire type of varint fields
+pub const WIRE_TYPE_VARINT: u8 = 0;
+
+/// Wire type of strings, bytes and embedded messages
+pub const WIRE_TYPE_LENGTH_DELIMITED: u8 = 2;
+
+/// A Cosmos message, or a message embedded in one, with its protobuf encoding
+pub trait CosmosMessageEncoder {
+    /// Encode the fields of the message, without the `Any` wrapper
+    fn encode(&self) -> Vec<u8>;
+
+    /// Type URL of the message, e.g. "/cosmos.bank.v1beta1.MsgSend"
+    fn type_url() -> &'static str;
+
+    /// Encode the message as `google.protobuf.Any`
+    fn encode_any(&self) -> Vec<u8> {
+        let mut bytes = encode_bytes_field(1, Self::type_url().as_bytes()); // type_url = 1
+        bytes.extend(encode_bytes_field(2, &self.encode())); // value = 2
+        bytes
+    }
+}
+
+/// Encode the key of field `field_number`, which takes several bytes past field 15
+pub fn encode_field_key(field_number: u32, wire_type: u8) -> Vec<u8> {
+    encode_varint(((field_number as u64) << 3) | wire_type as u64)
+}
+
+/// Encode a length-delimited field: a string, bytes or an embedded message
+pub fn encode_bytes_field(field_number: u32, data: &[u8]) -> Vec<u8> {
+    let mut bytes = encode_field_key(field_number, WIRE_TYPE_LENGTH_DELIMITED);
+    bytes.extend(encode_varint(data.len() as u64));
+    bytes.extend(data);
+    bytes
+}
+
+/// Encode a varint field
+pub fn encode_varint_field(field_number: u32, value: u64) -> Vec<u8> {
+    let mut bytes = encode_field_key(field_number, WIRE_TYPE_VARINT);
+    bytes.extend(encode_varint(value));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::cosmos_protobuf::{encode_string, encode_uint64};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_fields() {
+        assert_eq!(encode_bytes_field(1, b"uatom"), encode_string(0x0a, "uatom"));
+        assert_eq!(encode_varint_field(3, 300), encode_uint64(0x18, 300));
+        // (16 << 3) | 2 does not fit the 7 bits of a one-byte varint
+        assert_eq!(encode_field_key(16, WIRE_TYPE_LENGTH_DELIMITED), vec![0x82, 0x01]);
+    }
+}
@@ -0,0 +1,98 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::cosmos_protobuf::decode_fields;
+
+/// ABCI query path of the operator configuration of the node
+pub const NODE_CONFIG_PATH: &str = "/cosmos.base.node.v1beta1.Service/Config";
+
+/// Minimum price of a unit of gas accepted by a node, in `denom`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, CandidType)]
+pub struct GasPrice {
+    pub denom: String,
+    pub price: f64,
+}
+
+/// Parse a `minimum-gas-prices` setting, e.g. "0.0025uatom,0.001uosmo".
+/// An empty setting, which accepts transactions without fees, returns no price.
+pub fn parse_min_gas_prices(value: &str) -> Result<Vec<GasPrice>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|coin| !coin.is_empty())
+        .map(|coin| {
+            let denom_start = coin
+                .find(|c: char| c.is_ascii_alphabetic())
+                .ok_or_else(|| format!("Missing denom in gas price: {}", coin))?;
+            let (price, denom) = coin.split_at(denom_start);
+            let price = price
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid gas price {}: {}", coin, e))?;
+            Ok(GasPrice {
+                denom: denom.to_string(),
+                price,
+            })
+        })
+        .collect()
+}
+
+/// Decode a `ConfigResponse` from the base64 ABCI response value into its minimum gas prices
+pub fn decode_node_config_response(response_value: &str) -> Result<Vec<GasPrice>, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    for (number, field) in decode_fields(&decoded)? {
+        // minimum_gas_price = 1
+        if number == 1 {
+            return parse_min_gas_prices(&field.as_string().unwrap_or_default());
+        }
+    }
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::cosmos_protobuf::{encode_string, encode_uint64};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_min_gas_prices() {
+        assert_eq!(
+            parse_min_gas_prices(
+                "0.0025uatom, 0.01ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+            )
+            .unwrap(),
+            vec![
+                GasPrice {
+                    denom: "uatom".to_string(),
+                    price: 0.0025,
+                },
+                GasPrice {
+                    denom: "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string(),
+                    price: 0.01,
+                },
+            ]
+        );
+        assert_eq!(parse_min_gas_prices("").unwrap(), vec![]);
+        assert!(parse_min_gas_prices("0.0025").is_err());
+        assert!(parse_min_gas_prices("0.0.1uatom").is_err());
+    }
+
+    #[test]
+    fn test_decode_node_config_response() {
+        // minimum_gas_price = 1, halt_height = 4
+        let mut response = encode_string(0x0a, "0.005uatom");
+        response.extend(encode_uint64(0x20, 0));
+        assert_eq!(
+            decode_node_config_response(&STANDARD.encode(response)).unwrap(),
+            vec![GasPrice {
+                denom: "uatom".to_string(),
+                price: 0.005,
+            }]
+        );
+        assert_eq!(decode_node_config_response("").unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,158 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::cosmos_protobuf::{decode_fields, encode_length_delimited, encode_uint64, ProtoField};
+
+/// Number of items per page of the CometBFT RPC when the pagination does not set a limit
+pub const DEFAULT_PAGE_LIMIT: u64 = 30;
+
+/// Pagination of a list query, by offset or by the `next_key` of the previous page.
+/// Maps to `cosmos.base.query.v1beta1.PageRequest` for ABCI queries and to `page` / `per_page`
+/// for CometBFT RPC methods, which only support offsets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum Pagination {
+    Offset {
+        offset: u64,
+        limit: u64,
+        /// Whether the response includes the total number of items, at an extra cost for the node
+        count_total: bool,
+        reverse: bool,
+    },
+    Key {
+        key: Vec<u8>,
+        limit: u64,
+        reverse: bool,
+    },
+}
+
+impl Pagination {
+    /// The first `limit` items, most recent first when `reverse` is set
+    pub fn first(limit: u64, reverse: bool) -> Self {
+        Pagination::Offset {
+            offset: 0,
+            limit,
+            count_total: false,
+            reverse,
+        }
+    }
+
+    /// The page following the one which returned `next_key`
+    pub fn after(next_key: Vec<u8>) -> Self {
+        Pagination::Key {
+            key: next_key,
+            limit: 0,
+            reverse: false,
+        }
+    }
+
+    /// Returns the CometBFT RPC `page` (starting at 1) and `per_page` of an offset pagination,
+    /// with the offset rounded down to a page boundary
+    pub fn to_page_params(&self) -> Result<(String, String), String> {
+        match self {
+            Pagination::Offset { offset, limit, .. } => {
+                let per_page = if *limit == 0 { DEFAULT_PAGE_LIMIT } else { *limit };
+                Ok(((offset / per_page + 1).to_string(), per_page.to_string()))
+            }
+            Pagination::Key { .. } => Err("Key based pagination is not supported by CometBFT RPC methods".to_string()),
+        }
+    }
+}
+
+/// Encode a `PageRequest`, without the field tag of the enclosing request
+pub fn encode_page_request(pagination: &Pagination) -> Vec<u8> {
+    let (key, offset, limit, count_total, reverse): (&[u8], _, _, _, _) = match pagination {
+        Pagination::Offset {
+            offset,
+            limit,
+            count_total,
+            reverse,
+        } => (&[], *offset, *limit, *count_total, *reverse),
+        Pagination::Key { key, limit, reverse } => (key, 0, *limit, false, *reverse),
+    };
+
+    let mut bytes = Vec::new();
+    if !key.is_empty() {
+        bytes.extend(encode_length_delimited(0x0a, key)); // key = 1
+    }
+    for (tag, value) in [
+        (0x10, offset),             // offset = 2
+        (0x18, limit),              // limit = 3
+        (0x20, count_total as u64), // count_total = 4
+        (0x28, reverse as u64),     // reverse = 5
+    ] {
+        if value != 0 {
+            bytes.extend(encode_uint64(tag, value));
+        }
+    }
+    bytes
+}
+
+/// Decode a `PageResponse` into the key of the next page, `None` on the last page,
+/// and the total number of items, 0 unless `count_total` was requested
+pub fn decode_page_response(bytes: &[u8]) -> Result<(Option<Vec<u8>>, u64), String> {
+    let mut next_key = None;
+    let mut total = 0;
+    for (number, field) in decode_fields(bytes)? {
+        match (number, field) {
+            (1, ProtoField::Bytes(key)) if !key.is_empty() => next_key = Some(key.to_vec()), // next_key = 1
+            (2, ProtoField::Varint(value)) => total = value,                                 // total = 2
+            _ => {}
+        }
+    }
+    Ok((next_key, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::{
+        cosmos::base::query::v1beta1::{PageRequest, PageResponse},
+        traits::Message,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_encode_page_request() {
+        let offset = Pagination::Offset {
+            offset: 20,
+            limit: 10,
+            count_total: true,
+            reverse: true,
+        };
+        let expected = PageRequest {
+            offset: 20,
+            limit: 10,
+            count_total: true,
+            reverse: true,
+            ..Default::default()
+        };
+        assert_eq!(encode_page_request(&offset), expected.encode_to_vec());
+
+        let key = Pagination::Key {
+            key: vec![1, 2, 3],
+            limit: 50,
+            reverse: false,
+        };
+        let expected = PageRequest {
+            key: vec![1, 2, 3],
+            limit: 50,
+            ..Default::default()
+        };
+        assert_eq!(encode_page_request(&key), expected.encode_to_vec());
+        assert_eq!(offset.to_page_params().unwrap(), ("3".to_string(), "10".to_string()));
+        assert!(key.to_page_params().is_err());
+    }
+
+    #[test]
+    fn test_decode_page_response() {
+        let response = PageResponse {
+            next_key: vec![9, 8],
+            total: 42,
+        };
+        assert_eq!(
+            decode_page_response(&response.encode_to_vec()).unwrap(),
+            (Some(vec![9, 8]), 42)
+        );
+        assert_eq!(decode_page_response(&[]).unwrap(), (None, 0));
+    }
+}
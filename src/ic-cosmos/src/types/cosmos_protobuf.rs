@@ -0,0 +1,156 @@
+//! Minimal protobuf wire format helpers used to encode Cosmos messages and
+//! decode ABCI query responses without generated code.
+
+/// A single decoded protobuf field value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtoField<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> ProtoField<'a> {
+    pub fn as_varint(&self) -> Option<u64> {
+        match self {
+            ProtoField::Varint(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            ProtoField::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_string(&self) -> Option<String> {
+        self.as_bytes().map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+}
+
+/// Helper function to encode varint
+pub fn encode_varint(value: u64) -> Vec<u8> {
+    let mut result = Vec::new();
+    let mut val = value;
+    while val >= 0x80 {
+        result.push(((val & 0x7F) | 0x80) as u8);
+        val >>= 7;
+    }
+    result.push(val as u8);
+    result
+}
+
+/// Helper function to encode length-delimited field
+pub fn encode_length_delimited(tag: u8, data: &[u8]) -> Vec<u8> {
+    let mut result = vec![tag];
+    result.extend(encode_varint(data.len() as u64));
+    result.extend(data);
+    result
+}
+
+/// Helper function to encode string field
+pub fn encode_string(tag: u8, value: &str) -> Vec<u8> {
+    encode_length_delimited(tag, value.as_bytes())
+}
+
+/// Helper function to encode uint64 field
+pub fn encode_uint64(tag: u8, value: u64) -> Vec<u8> {
+    let mut result = vec![tag];
+    result.extend(encode_varint(value));
+    result
+}
+
+/// Encode a string field, omitted when empty like the default values of proto3
+pub fn encode_non_empty_string(tag: u8, value: &str) -> Vec<u8> {
+    if value.is_empty() {
+        return Vec::new();
+    }
+    encode_string(tag, value)
+}
+
+/// Encode a uint64 field, omitted when zero like the default values of proto3
+pub fn encode_non_zero_uint64(tag: u8, value: u64) -> Vec<u8> {
+    if value == 0 {
+        return Vec::new();
+    }
+    encode_uint64(tag, value)
+}
+
+/// Read a varint from `data` starting at `pos`, advancing `pos` past it
+pub fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    let mut shift = 0;
+
+    while *pos < data.len() {
+        let byte = data[*pos];
+        *pos += 1;
+
+        value |= ((byte & 0x7F) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+
+        if shift >= 64 {
+            return Err("Varint too long".to_string());
+        }
+    }
+
+    Err("Unexpected end of data while reading varint".to_string())
+}
+
+/// Read a length-delimited field from `data` starting at `pos`, advancing `pos` past it
+pub fn read_length_delimited<'a>(data: &'a [u8], pos: &mut usize) -> Result<&'a [u8], String> {
+    let length = read_varint(data, pos)? as usize;
+    let end = pos
+        .checked_add(length)
+        .filter(|end| *end <= data.len())
+        .ok_or("Length-delimited field exceeds data length")?;
+    let bytes = &data[*pos..end];
+    *pos = end;
+    Ok(bytes)
+}
+
+/// Decode all top-level fields of a protobuf message as `(field_number, value)` pairs
+pub fn decode_fields(data: &[u8]) -> Result<Vec<(u32, ProtoField<'_>)>, String> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let key = read_varint(data, &mut pos)?;
+        let field_number = (key >> 3) as u32;
+        let value = match key & 0x07 {
+            0 => ProtoField::Varint(read_varint(data, &mut pos)?),
+            1 => {
+                let bytes = data
+                    .get(pos..pos + 8)
+                    .ok_or("Unexpected end of data while reading fixed64")?;
+                pos += 8;
+                ProtoField::Fixed64(u64::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            2 => ProtoField::Bytes(read_length_delimited(data, &mut pos)?),
+            5 => {
+                let bytes = data
+                    .get(pos..pos + 4)
+                    .ok_or("Unexpected end of data while reading fixed32")?;
+                pos += 4;
+                ProtoField::Fixed32(u32::from_le_bytes(bytes.try_into().unwrap()))
+            }
+            wire_type => return Err(format!("Unsupported wire type {}", wire_type)),
+        };
+        fields.push((field_number, value));
+    }
+
+    Ok(fields)
+}
+
+/// Find the first occurrence of `field_number` in a protobuf message
+pub fn find_field(data: &[u8], field_number: u32) -> Result<Option<ProtoField<'_>>, String> {
+    Ok(decode_fields(data)?
+        .into_iter()
+        .find(|(number, _)| *number == field_number)
+        .map(|(_, value)| value))
+}
@@ -0,0 +1,169 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::types::{
+    cosmos_pagination::{decode_page_response, encode_page_request, Pagination},
+    cosmos_protobuf::{decode_fields, encode_length_delimited, encode_string, find_field, ProtoField},
+};
+
+/// ABCI query path of the delegations of a delegator
+pub const STAKING_DELEGATOR_DELEGATIONS_PATH: &str = "/cosmos.staking.v1beta1.Query/DelegatorDelegations";
+
+/// ABCI query path of a single validator
+pub const STAKING_VALIDATOR_PATH: &str = "/cosmos.staking.v1beta1.Query/Validator";
+
+/// Number of decimals of delegation shares dropped before converting them to tokens,
+/// so that `shares * tokens` fits into `u128`. Leaves 6 decimals of precision.
+const SHARES_SCALE_DOWN: u128 = 1_000_000_000_000;
+
+/// A delegation of a delegator, with its shares as `Dec` atomics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegationShares {
+    pub validator_address: String,
+    pub shares: String,
+}
+
+/// Page of delegations returned by `DelegatorDelegations`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DelegationsPage {
+    pub delegations: Vec<DelegationShares>,
+    /// Key of the next page, empty on the last page
+    pub next_key: Vec<u8>,
+}
+
+/// Encode `QueryDelegatorDelegationsRequest` as hex `abci_query` data, starting at the page `key`
+pub fn encode_delegator_delegations_query(delegator_address: &str, key: &[u8]) -> String {
+    let mut request_bytes = encode_string(0x0a, delegator_address); // delegator_addr = 1
+    if !key.is_empty() {
+        let page_request = encode_page_request(&Pagination::after(key.to_vec()));
+        request_bytes.extend(encode_length_delimited(0x12, &page_request)); // pagination = 2
+    }
+    hex::encode(request_bytes)
+}
+
+/// Decode a `QueryDelegatorDelegationsResponse` from the base64 ABCI response value
+pub fn decode_delegator_delegations_response(response_value: &str) -> Result<DelegationsPage, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    let mut page = DelegationsPage::default();
+    for (number, field) in decode_fields(&decoded)? {
+        match (number, field) {
+            // delegation_responses = 1 -> delegation = 1
+            (1, ProtoField::Bytes(response)) => {
+                let Some(delegation) = find_field(response, 1)?.and_then(|f| f.as_bytes()) else {
+                    continue;
+                };
+                let mut shares = DelegationShares {
+                    validator_address: String::new(),
+                    shares: "0".to_string(),
+                };
+                for (number, field) in decode_fields(delegation)? {
+                    match (number, field.as_string()) {
+                        (2, Some(validator_address)) => shares.validator_address = validator_address,
+                        (3, Some(value)) => shares.shares = value,
+                        _ => {}
+                    }
+                }
+                page.delegations.push(shares);
+            }
+            // pagination = 2
+            (2, ProtoField::Bytes(pagination)) => {
+                page.next_key = decode_page_response(pagination)?.0.unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+    Ok(page)
+}
+
+/// Encode `QueryValidatorRequest` as hex `abci_query` data
+pub fn encode_validator_query(validator_address: &str) -> String {
+    hex::encode(encode_string(0x0a, validator_address)) // validator_addr = 1
+}
+
+/// Decode the `tokens` and `delegator_shares` of a `QueryValidatorResponse`
+/// from the base64 ABCI response value
+pub fn decode_validator_exchange_rate(response_value: &str) -> Result<(String, String), String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // validator = 1
+    let validator = find_field(&decoded, 1)?
+        .and_then(|f| f.as_bytes())
+        .ok_or("Validator not found in response")?;
+
+    let mut tokens = "0".to_string();
+    let mut delegator_shares = "0".to_string();
+    for (number, field) in decode_fields(validator)? {
+        match (number, field.as_string()) {
+            (5, Some(value)) => tokens = value,
+            (6, Some(value)) => delegator_shares = value,
+            _ => {}
+        }
+    }
+    Ok((tokens, delegator_shares))
+}
+
+/// Convert delegation `shares` to tokens using the validator exchange rate
+/// `tokens / delegator_shares`. Shares are `Dec` atomics, tokens an `Int`.
+pub fn shares_to_tokens(shares: &str, tokens: &str, delegator_shares: &str) -> Result<u128, String> {
+    let parse = |value: &str| {
+        value
+            .parse::<u128>()
+            .map_err(|e| format!("Invalid staking amount {}: {}", value, e))
+    };
+    let shares = parse(shares)? / SHARES_SCALE_DOWN;
+    let tokens = parse(tokens)?;
+    let delegator_shares = parse(delegator_shares)? / SHARES_SCALE_DOWN;
+    if delegator_shares == 0 {
+        return Ok(0);
+    }
+    shares
+        .checked_mul(tokens)
+        .map(|value| value / delegator_shares)
+        .ok_or_else(|| "Staking amount overflow".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shares_to_tokens() {
+        // 1:1 exchange rate
+        assert_eq!(
+            shares_to_tokens("1000000000000000000000", "5000", "5000000000000000000000").unwrap(),
+            1000
+        );
+        // Slashed validator: 10% of the tokens are gone
+        assert_eq!(
+            shares_to_tokens("1000000000000000000000", "4500", "5000000000000000000000").unwrap(),
+            900
+        );
+        assert_eq!(shares_to_tokens("0", "0", "0").unwrap(), 0);
+        assert!(shares_to_tokens("1.5", "0", "1").is_err());
+    }
+
+    #[test]
+    fn test_decode_delegator_delegations_response() {
+        let mut delegation = encode_string(0x0a, "cosmos1delegator");
+        delegation.extend(encode_string(0x12, "cosmosvaloper1validator"));
+        delegation.extend(encode_string(0x1a, "1000000000000000000000"));
+        let response = encode_length_delimited(0x0a, &delegation);
+
+        let mut data = encode_length_delimited(0x0a, &response);
+        data.extend(encode_length_delimited(0x12, &encode_length_delimited(0x0a, b"next")));
+
+        let page = decode_delegator_delegations_response(&STANDARD.encode(data)).unwrap();
+        assert_eq!(
+            page.delegations,
+            vec![DelegationShares {
+                validator_address: "cosmosvaloper1validator".to_string(),
+                shares: "1000000000000000000000".to_string(),
+            }]
+        );
+        assert_eq!(page.next_key, b"next");
+    }
+}
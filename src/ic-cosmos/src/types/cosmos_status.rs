@@ -1,7 +1,10 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
-use super::cosmos_common::{NodeInfo, PubKey};
+use super::{
+    block_time::{is_catching_up_at, MAX_SYNCED_BLOCK_AGE_NANOS},
+    cosmos_common::{NodeInfo, PubKey},
+};
 
 /// Represents the complete status response from a Cosmos node.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
@@ -60,6 +63,25 @@ pub struct SyncInfo {
     pub catching_up: bool,
 }
 
+impl SyncInfo {
+    /// Returns whether the node is behind the chain at `now_nanos`: when it reports catching up,
+    /// or when its latest block is more than `max_block_age_nanos` old
+    pub fn is_catching_up_at(&self, now_nanos: u64, max_block_age_nanos: u64) -> bool {
+        is_catching_up_at(
+            self.catching_up,
+            &self.latest_block_time,
+            now_nanos,
+            max_block_age_nanos,
+        )
+    }
+
+    /// Returns whether the node is behind the chain at the current time of the IC, its latest
+    /// block being more than [`MAX_SYNCED_BLOCK_AGE_NANOS`] old if it does not report it
+    pub fn is_catching_up(&self) -> bool {
+        self.is_catching_up_at(ic_cdk::api::time(), MAX_SYNCED_BLOCK_AGE_NANOS)
+    }
+}
+
 /// Represents information about a validator.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 #[serde(rename_all = "snake_case")]
@@ -72,5 +94,3 @@ pub struct ValidatorInfo {
     #[serde(rename = "voting_power")]
     pub voting_power: String,
 }
-
-
@@ -0,0 +1,169 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::cosmos_protobuf::{decode_fields, encode_string, find_field, ProtoField};
+
+/// ABCI query path of the total supply of a single denom
+pub const BANK_SUPPLY_OF_PATH: &str = "/cosmos.bank.v1beta1.Query/SupplyOf";
+
+/// ABCI query path of the bonded and not bonded tokens of the staking module
+pub const STAKING_POOL_PATH: &str = "/cosmos.staking.v1beta1.Query/Pool";
+
+/// ABCI query path of the staking module parameters
+pub const STAKING_PARAMS_PATH: &str = "/cosmos.staking.v1beta1.Query/Params";
+
+/// ABCI query path of the community pool balance
+pub const DISTRIBUTION_COMMUNITY_POOL_PATH: &str = "/cosmos.distribution.v1beta1.Query/CommunityPool";
+
+/// Number of decimals of the Cosmos SDK `Dec` atomics
+const SDK_DEC_ATOMICS: u128 = 1_000_000_000_000_000_000;
+
+/// Represents the supply of a token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SupplyInfo {
+    /// The total supply of the denom
+    pub total_supply: String,
+    /// The total supply without the community pool, `None` if the community pool is not available
+    pub circulating_supply: Option<String>,
+    /// The community pool balance of the denom as a decimal
+    pub community_pool: Option<String>,
+    /// The bonded tokens, only set for the staking denom
+    pub bonded_tokens: Option<String>,
+    /// The not bonded tokens, only set for the staking denom
+    pub not_bonded_tokens: Option<String>,
+}
+
+/// Encode `QuerySupplyOfRequest` as hex `abci_query` data
+pub fn encode_supply_of_query(denom: &str) -> String {
+    hex::encode(encode_string(0x0a, denom)) // denom = 1
+}
+
+/// Decode the amount of a `QuerySupplyOfResponse` from the base64 ABCI response value
+pub fn decode_supply_of_response(response_value: &str) -> Result<String, String> {
+    let decoded = decode_base64(response_value)?;
+
+    // amount = 1 -> Coin.amount = 2
+    let coin = find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .ok_or("Supply not found in response")?;
+    Ok(find_field(coin, 2)?
+        .and_then(|field| field.as_string())
+        .unwrap_or_else(|| "0".to_string()))
+}
+
+/// Decode the `(bonded_tokens, not_bonded_tokens)` of a `QueryPoolResponse`
+/// from the base64 ABCI response value
+pub fn decode_staking_pool_response(response_value: &str) -> Result<(String, String), String> {
+    let decoded = decode_base64(response_value)?;
+
+    // pool = 1
+    let pool = find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .ok_or("Pool not found in response")?;
+
+    let mut not_bonded_tokens = "0".to_string();
+    let mut bonded_tokens = "0".to_string();
+    for (number, field) in decode_fields(pool)? {
+        match (number, field.as_string()) {
+            (1, Some(value)) => not_bonded_tokens = value,
+            (2, Some(value)) => bonded_tokens = value,
+            _ => {}
+        }
+    }
+    Ok((bonded_tokens, not_bonded_tokens))
+}
+
+/// Decode the `bond_denom` of a staking `QueryParamsResponse` from the base64 ABCI response value
+pub fn decode_staking_bond_denom(response_value: &str) -> Result<String, String> {
+    let decoded = decode_base64(response_value)?;
+
+    // params = 1 -> bond_denom = 5
+    let params = find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .ok_or("Params not found in response")?;
+    find_field(params, 5)?
+        .and_then(|field| field.as_string())
+        .ok_or_else(|| "Bond denom not found in response".to_string())
+}
+
+/// Decode the `Dec` atomics of `denom` in a `QueryCommunityPoolResponse`
+/// from the base64 ABCI response value, "0" if the pool holds no such coin
+pub fn decode_community_pool_response(response_value: &str, denom: &str) -> Result<String, String> {
+    let decoded = decode_base64(response_value)?;
+
+    // pool = 1 (repeated DecCoin)
+    for (number, field) in decode_fields(&decoded)? {
+        let (1, ProtoField::Bytes(coin)) = (number, field) else {
+            continue;
+        };
+        let mut coin_denom = String::new();
+        let mut amount = "0".to_string();
+        for (number, field) in decode_fields(coin)? {
+            match (number, field.as_string()) {
+                (1, Some(value)) => coin_denom = value,
+                (2, Some(value)) => amount = value,
+                _ => {}
+            }
+        }
+        if coin_denom == denom {
+            return Ok(amount);
+        }
+    }
+    Ok("0".to_string())
+}
+
+/// Subtract the integer part of the community pool `Dec` atomics from the total supply
+pub fn circulating_supply(total_supply: &str, community_pool_atomics: &str) -> Result<String, String> {
+    let parse = |value: &str| {
+        value
+            .parse::<u128>()
+            .map_err(|e| format!("Invalid supply amount {}: {}", value, e))
+    };
+    let total_supply = parse(total_supply)?;
+    let community_pool = parse(community_pool_atomics)? / SDK_DEC_ATOMICS;
+    Ok(total_supply.saturating_sub(community_pool).to_string())
+}
+
+fn decode_base64(response_value: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::cosmos_protobuf::encode_length_delimited;
+
+    #[test]
+    fn test_decode_community_pool_response() {
+        let mut data = Vec::new();
+        for (denom, amount) in [
+            ("ibc/27394FB0", "5000000000000000000"),
+            ("uatom", "1500000000000000000000"),
+        ] {
+            let mut coin = encode_string(0x0a, denom);
+            coin.extend(encode_string(0x12, amount));
+            data.extend(encode_length_delimited(0x0a, &coin));
+        }
+        let response = STANDARD.encode(data);
+
+        let amount = decode_community_pool_response(&response, "uatom").unwrap();
+        assert_eq!(amount, "1500000000000000000000");
+        assert_eq!(decode_community_pool_response(&response, "uosmo").unwrap(), "0");
+        assert_eq!(circulating_supply("10000", &amount).unwrap(), "8500");
+    }
+
+    #[test]
+    fn test_decode_staking_pool_response() {
+        let mut pool = encode_string(0x0a, "100");
+        pool.extend(encode_string(0x12, "900"));
+        let response = STANDARD.encode(encode_length_delimited(0x0a, &pool));
+
+        assert_eq!(
+            decode_staking_pool_response(&response).unwrap(),
+            ("900".to_string(), "100".to_string())
+        );
+    }
+}
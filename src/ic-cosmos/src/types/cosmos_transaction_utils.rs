@@ -1,9 +1,32 @@
+use std::str::FromStr;
+
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use bech32::{encode, ToBase32, Variant};
+use bech32::{encode, FromBase32, ToBase32, Variant};
+use candid::CandidType;
 use ripemd::Ripemd160;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use crate::amino::{amino_sign_doc, canonical_json_serialize};
+#[cfg(feature = "osmosis")]
+use crate::types::osmosis::{encode_msg_swap_exact_amount_in, OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL};
+#[cfg(feature = "stride")]
+use crate::types::stride::{
+    encode_msg_liquid_stake, encode_msg_liquid_unstake, STRIDE_MSG_LIQUID_STAKE_TYPE_URL,
+    STRIDE_MSG_REDEEM_STAKE_TYPE_URL,
+};
+use crate::types::{
+    cosmos_protobuf::{
+        encode_length_delimited, encode_non_empty_string, encode_non_zero_uint64, encode_string, encode_uint64,
+        encode_varint, read_length_delimited, read_varint,
+    },
+    cosmos_tx_builder::DEFAULT_GAS_PRICE,
+    cosmos_wasm::{encode_msg_store_code, MSG_STORE_CODE_TYPE_URL},
+    ibc_client::{encode_msg_update_client, MSG_UPDATE_CLIENT_TYPE_URL},
+    ibc_transfer::{encode_msg_transfer, MSG_TRANSFER_TYPE_URL},
+    pubkey::normalize_secp256k1_pubkey,
+};
+
 /// Simple structs for account info
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CosmosAccountInfo {
@@ -21,6 +44,51 @@ pub struct CosmosTransaction {
     pub chain_id: String,
     pub account_number: u64,
     pub sequence: u64,
+    /// `extension_options` of the `TxBody` as `(type_url, value)` pairs, e.g. the
+    /// `ExtensionOptionsEthereumTx` of Evmos. Chains reject transactions with unknown options.
+    #[serde(default)]
+    pub extension_options: Vec<(String, Vec<u8>)>,
+    /// `non_critical_extension_options` of the `TxBody`, ignored by chains which do not know them
+    #[serde(default)]
+    pub non_critical_extension_options: Vec<(String, Vec<u8>)>,
+    /// Sign mode of the signer info, which also selects the type of its public key
+    #[serde(default)]
+    pub sign_mode: SignMode,
+}
+
+/// Sign modes, as encoded in the `ModeInfo` of the `AuthInfo`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignMode {
+    /// `SIGN_MODE_DIRECT`, the signature covers the protobuf `SignDoc`
+    #[default]
+    Direct,
+    /// `SIGN_MODE_LEGACY_AMINO_JSON`, the signature covers the canonical Amino JSON sign doc
+    LegacyAminoJson,
+    /// Evmos EIP-712, the signature covers the typed data of [`create_eip712_sign_doc`].
+    /// Encoded as `SIGN_MODE_LEGACY_AMINO_JSON`, which Evmos verifies as EIP-712 for
+    /// `ethsecp256k1` keys. There are no sign doc bytes in this mode, the typed data is signed
+    /// by an Ethereum signer.
+    ///
+    /// [`create_eip712_sign_doc`]: crate::types::create_eip712_sign_doc
+    Eip712Cw,
+}
+
+impl SignMode {
+    /// Returns the `cosmos.tx.signing.v1beta1.SignMode` value of the mode
+    pub fn as_i32(&self) -> i32 {
+        match self {
+            SignMode::Direct => 1,
+            SignMode::LegacyAminoJson | SignMode::Eip712Cw => 127,
+        }
+    }
+
+    /// Returns the type URL of the public key of the signer info
+    pub fn public_key_type_url(&self) -> &'static str {
+        match self {
+            SignMode::Direct | SignMode::LegacyAminoJson => "/cosmos.crypto.secp256k1.PubKey",
+            SignMode::Eip712Cw => "/ethermint.crypto.v1.ethsecp256k1.PubKey",
+        }
+    }
 }
 
 /// Generic message structure for any Cosmos message type
@@ -31,7 +99,7 @@ pub struct CosmosMessage {
 }
 
 /// Coin structure for Cosmos amounts
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 pub struct CosmosCoin {
     pub denom: String,
     pub amount: String,
@@ -46,14 +114,324 @@ impl CosmosCoin {
     }
 }
 
-/// Utility function to convert a public key to a Cosmos address
+/// Number of decimal places of the Cosmos SDK `Dec` type
+pub const SDK_DEC_PRECISION: usize = 18;
+
+/// Vote options of the `x/gov` module
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoteOption {
+    Unspecified = 0,
+    Yes = 1,
+    Abstain = 2,
+    No = 3,
+    NoWithVeto = 4,
+}
+
+impl FromStr for VoteOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().trim_start_matches("vote_option_") {
+            "unspecified" | "0" => Ok(VoteOption::Unspecified),
+            "yes" | "1" => Ok(VoteOption::Yes),
+            "abstain" | "2" => Ok(VoteOption::Abstain),
+            "no" | "3" => Ok(VoteOption::No),
+            "no_with_veto" | "nowithveto" | "veto" | "4" => Ok(VoteOption::NoWithVeto),
+            _ => Err(format!("Invalid vote option: {}", s)),
+        }
+    }
+}
+
+impl VoteOption {
+    /// Parse a vote option from either its name (`"VOTE_OPTION_YES"`, `"yes"`) or its number
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        match value {
+            serde_json::Value::String(s) => s.parse(),
+            serde_json::Value::Number(n) => n.to_string().parse(),
+            _ => Err(format!("Invalid vote option: {}", value)),
+        }
+    }
+}
+
+/// Version of the `x/gov` module messages supported by a chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GovVersion {
+    /// `cosmos.gov.v1beta1`, supported by every Cosmos SDK chain
+    #[default]
+    V1Beta1,
+    /// `cosmos.gov.v1`, introduced in Cosmos SDK v0.46 and required for message-based proposals
+    V1,
+}
+
+impl GovVersion {
+    /// Returns the gov module version to use given whether the chain has `x/gov` v1 enabled
+    pub fn from_chain_flag(gov_v1_enabled: bool) -> Self {
+        if gov_v1_enabled {
+            GovVersion::V1
+        } else {
+            GovVersion::V1Beta1
+        }
+    }
+
+    /// Returns the type URL of a gov message, e.g. `/cosmos.gov.v1.MsgVote` for `MsgVote`
+    pub fn type_url(&self, msg_name: &str) -> String {
+        match self {
+            GovVersion::V1Beta1 => format!("/cosmos.gov.v1beta1.{}", msg_name),
+            GovVersion::V1 => format!("/cosmos.gov.v1.{}", msg_name),
+        }
+    }
+
+    /// Checks that a chain with this gov module version accepts a message of `type_url`.
+    /// `cosmos.gov.v1` messages are rejected on chains that only have `x/gov` v1beta1.
+    pub fn check_type_url(&self, type_url: &str) -> Result<(), String> {
+        if *self == GovVersion::V1Beta1 && type_url.starts_with("/cosmos.gov.v1.") {
+            return Err(format!(
+                "{} requires x/gov v1, the chain only supports v1beta1",
+                type_url
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Build a gov message using the type URL of the module version enabled on the chain
+pub fn gov_message(version: GovVersion, msg_name: &str, value: serde_json::Value) -> Result<CosmosMessage, String> {
+    if version == GovVersion::V1Beta1 && msg_name == "MsgSubmitProposal" {
+        return Err("Message-based MsgSubmitProposal requires x/gov v1".to_string());
+    }
+    Ok(CosmosMessage {
+        type_url: version.type_url(msg_name),
+        value,
+    })
+}
+
+/// A single option of a split vote, where `weight` is a decimal string like `"0.5"`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WeightedVoteOption {
+    pub option: VoteOption,
+    pub weight: String,
+}
+
+/// Parse a decimal string into the integer representation of a Cosmos SDK `Dec`
+/// (the value multiplied by 10^18)
+pub fn parse_sdk_dec(value: &str) -> Result<u128, String> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if integer.is_empty()
+        || fraction.len() > SDK_DEC_PRECISION
+        || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Invalid decimal: {}", value));
+    }
+    let digits = format!("{}{:0<width$}", integer, fraction, width = SDK_DEC_PRECISION);
+    digits
+        .parse::<u128>()
+        .map_err(|e| format!("Invalid decimal {}: {}", value, e))
+}
+
+/// Format the integer atomics of a Cosmos SDK `Dec` (e.g. "2000000000000000") as a decimal string ("0.002")
+pub fn format_sdk_dec(atomics: &str) -> Result<String, String> {
+    if atomics.is_empty() || !atomics.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid decimal atomics: {}", atomics));
+    }
+    let padded = format!("{:0>width$}", atomics, width = SDK_DEC_PRECISION + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - SDK_DEC_PRECISION);
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        Ok(integer.to_string())
+    } else {
+        Ok(format!("{}.{}", integer, fraction))
+    }
+}
+
+/// Largest difference from 1.0 tolerated in the sum of split vote weights, in `Dec` atomics (1e-9)
+pub const VOTE_WEIGHT_EPSILON: u128 = 1_000_000_000;
+
+/// Validate that every weight of a split vote is positive and that the weights sum to 1.0,
+/// within `VOTE_WEIGHT_EPSILON`. Returns the sum of the weights in `Dec` atomics.
+pub fn validate_vote_weights(options: &[WeightedVoteOption]) -> Result<u128, String> {
+    if options.is_empty() {
+        return Err("Weighted vote requires at least one option".to_string());
+    }
+    let mut total = 0u128;
+    for option in options {
+        let weight = parse_sdk_dec(&option.weight)?;
+        if weight == 0 {
+            return Err(format!("Weight of {:?} must be positive", option.option));
+        }
+        total += weight;
+    }
+    if total.abs_diff(10u128.pow(SDK_DEC_PRECISION as u32)) > VOTE_WEIGHT_EPSILON {
+        return Err(format!(
+            "Weighted vote options must sum to 1.0, got {}",
+            format_sdk_dec(&total.to_string())?
+        ));
+    }
+    Ok(total)
+}
+
+/// Parse the `options` of a `MsgVoteWeighted` JSON message:
+/// `[{ "option": "VOTE_OPTION_YES", "weight": "0.7" }, { "option": "VOTE_OPTION_ABSTAIN", "weight": "0.3" }]`.
+/// Options are given by name or number, weights as decimal strings summing to 1.0. The chain
+/// requires an exact sum, so a rounding difference within `VOTE_WEIGHT_EPSILON` is applied to the
+/// last option.
+pub fn parse_weighted_vote_options(options: &serde_json::Value) -> Result<Vec<WeightedVoteOption>, String> {
+    let mut options = options
+        .as_array()
+        .ok_or("Missing options array in MsgVoteWeighted")?
+        .iter()
+        .map(|option_value| -> Result<WeightedVoteOption, String> {
+            Ok(WeightedVoteOption {
+                option: VoteOption::from_json(&option_value["option"])?,
+                weight: option_value["weight"]
+                    .as_str()
+                    .ok_or("Missing weight in WeightedVoteOption")?
+                    .to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let total = validate_vote_weights(&options)?;
+    let one = 10u128.pow(SDK_DEC_PRECISION as u32);
+    if total != one {
+        let last = options.last_mut().ok_or("Weighted vote requires at least one option")?;
+        let weight = (parse_sdk_dec(&last.weight)? + one)
+            .checked_sub(total)
+            .filter(|weight| *weight > 0)
+            .ok_or_else(|| format!("Weight of {:?} must be positive", last.option))?;
+        last.weight = format_sdk_dec(&weight.to_string())?;
+    }
+    Ok(options)
+}
+
+/// Error found by [`validate_transaction_json`] at the JSON `path`, e.g. `body.messages[0].@type`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{path}: {message}")]
+pub struct JsonValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl JsonValidationError {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Validate the shape of the JSON transaction given to `sendCosmosTransaction`, returning every
+/// error found instead of stopping at the first one:
+/// - `body.messages` is a non-empty array of objects with a string `@type`
+/// - `auth_info.fee.amount` is an array of coins and `auth_info.fee.gas_limit` a numeric string
+/// - every `amount` given as a string, in the messages or the fee, is a numeric string
+pub fn validate_transaction_json(json: &serde_json::Value) -> Result<(), Vec<JsonValidationError>> {
+    let mut errors = Vec::new();
+
+    match json["body"]["messages"].as_array() {
+        None => errors.push(JsonValidationError::new(
+            "body.messages",
+            "expected an array of messages",
+        )),
+        Some(messages) if messages.is_empty() => errors.push(JsonValidationError::new(
+            "body.messages",
+            "expected at least one message",
+        )),
+        Some(messages) => {
+            for (index, message) in messages.iter().enumerate() {
+                let path = format!("body.messages[{}]", index);
+                if !message.is_object() {
+                    errors.push(JsonValidationError::new(path, "expected an object"));
+                    continue;
+                }
+                if !message["@type"].is_string() {
+                    errors.push(JsonValidationError::new(format!("{}.@type", path), "expected a string"));
+                }
+                validate_amounts(message, &path, &mut errors);
+            }
+        }
+    }
+
+    let fee = &json["auth_info"]["fee"];
+    match fee["amount"].as_array() {
+        None => errors.push(JsonValidationError::new(
+            "auth_info.fee.amount",
+            "expected an array of coins",
+        )),
+        Some(coins) => {
+            for (index, coin) in coins.iter().enumerate() {
+                let path = format!("auth_info.fee.amount[{}]", index);
+                if !coin["denom"].is_string() {
+                    errors.push(JsonValidationError::new(format!("{}.denom", path), "expected a string"));
+                }
+                if !coin["amount"].as_str().is_some_and(is_numeric_string) {
+                    errors.push(JsonValidationError::new(
+                        format!("{}.amount", path),
+                        "expected a numeric string",
+                    ));
+                }
+            }
+        }
+    }
+    if !fee["gas_limit"].as_str().is_some_and(is_numeric_string) {
+        errors.push(JsonValidationError::new(
+            "auth_info.fee.gas_limit",
+            "expected a numeric string",
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that the `amount` fields nested in `value` that are strings are numeric strings
+fn validate_amounts(value: &serde_json::Value, path: &str, errors: &mut Vec<JsonValidationError>) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields {
+                let field_path = format!("{}.{}", path, key);
+                match field.as_str() {
+                    Some(amount) if key == "amount" && !is_numeric_string(amount) => {
+                        errors.push(JsonValidationError::new(field_path, "expected a numeric string"))
+                    }
+                    _ => validate_amounts(field, &field_path, errors),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                validate_amounts(item, &format!("{}[{}]", path, index), errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_numeric_string(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Utility function to convert a base58 encoded secp256k1 public key, compressed or not, to a
+/// Cosmos address
 pub fn public_key_to_cosmos_address(public_key: &str) -> Result<String, String> {
+    public_key_to_address(public_key, "cosmos")
+}
+
+/// Converts a base58 encoded secp256k1 public key, compressed or not, to the account address of
+/// a chain with the bech32 `hrp`, e.g. `osmo`
+pub fn public_key_to_address(public_key: &str, hrp: &str) -> Result<String, String> {
     let decoded = bs58::decode(public_key)
         .into_vec()
         .map_err(|e| format!("Failed to decode public key: {}", e))?;
+    let decoded = normalize_secp256k1_pubkey(&decoded).map_err(|e| e.to_string())?;
 
     let mut hasher = Sha256::new();
-    hasher.update(&decoded);
+    hasher.update(decoded);
     let sha256_hash = hasher.finalize();
 
     let mut hasher = Ripemd160::new();
@@ -62,7 +440,7 @@ pub fn public_key_to_cosmos_address(public_key: &str) -> Result<String, String>
 
     let data = ripemd160_hash.to_vec();
     let encoded =
-        encode("cosmos", data.to_base32(), Variant::Bech32).map_err(|e| format!("Failed to encode address: {}", e))?;
+        encode(hrp, data.to_base32(), Variant::Bech32).map_err(|e| format!("Failed to encode address: {}", e))?;
 
     Ok(encoded)
 }
@@ -77,92 +455,34 @@ pub fn parse_account_info_from_abci(response_value: &str) -> Result<(u64, u64),
 
     // Parse the protobuf response manually
     // We expect: QueryAccountResponse -> account (Any) -> BaseAccount
-
     let mut cursor = 0;
-
-    // Helper function to read a varint from the byte stream
-    let read_varint = |data: &[u8], pos: &mut usize| -> Result<u64, String> {
-        let mut value = 0u64;
-        let mut shift = 0;
-
-        while *pos < data.len() {
-            let byte = data[*pos];
-            *pos += 1;
-
-            value |= ((byte & 0x7F) as u64) << shift;
-
-            if byte & 0x80 == 0 {
-                return Ok(value);
-            }
-            shift += 7;
-
-            if shift >= 64 {
-                return Err("Varint too long".to_string());
-            }
-        }
-
-        Err("Unexpected end of data while reading varint".to_string())
-    };
-
-    // Helper function to read a length-delimited field
-    let read_bytes = |data: &[u8], pos: &mut usize| -> Result<Vec<u8>, String> {
-        let length = read_varint(data, pos)? as usize;
-        if *pos + length > data.len() {
-            return Err("Length exceeds remaining data".to_string());
-        }
-        let result = data[*pos..*pos + length].to_vec();
-        *pos += length;
-        Ok(result)
-    };
-
-    // Parse QueryAccountResponse
     while cursor < decoded.len() {
-        if cursor >= decoded.len() {
-            break;
-        }
-
-        let tag = decoded[cursor];
-        cursor += 1;
-
-        let field_number = tag >> 3;
-        let wire_type = tag & 0x07;
+        let (field_number, wire_type) = read_key(&decoded, &mut cursor)?;
 
         if field_number == 1 && wire_type == 2 {
             // account field (Any type)
-            // Read the Any message bytes
-            let any_bytes = read_bytes(&decoded, &mut cursor)?;
+            let any_bytes = read_length_delimited(&decoded, &mut cursor)?;
 
             // Parse the Any message to find the type_url and value
             let mut any_cursor = 0;
-            let mut base_account_bytes: Option<Vec<u8>> = None;
+            let mut base_account_bytes: Option<&[u8]> = None;
 
             while any_cursor < any_bytes.len() {
-                let any_tag = any_bytes[any_cursor];
-                any_cursor += 1;
-
-                let any_field = any_tag >> 3;
-                let any_wire = any_tag & 0x07;
+                let (any_field, any_wire) = read_key(any_bytes, &mut any_cursor)?;
 
                 if any_field == 1 && any_wire == 2 {
                     // type_url
-                    let type_url_bytes = read_bytes(&any_bytes, &mut any_cursor)?;
-                    let type_url = String::from_utf8_lossy(&type_url_bytes);
+                    let type_url = String::from_utf8_lossy(read_length_delimited(any_bytes, &mut any_cursor)?);
 
                     if !type_url.ends_with("BaseAccount") {
                         return Err(format!("Unexpected account type: {}", type_url));
                     }
                 } else if any_field == 2 && any_wire == 2 {
                     // value
-                    base_account_bytes = Some(read_bytes(&any_bytes, &mut any_cursor)?);
+                    base_account_bytes = Some(read_length_delimited(any_bytes, &mut any_cursor)?);
                 } else {
-                    // Skip unknown field
-                    if any_wire == 2 {
-                        read_bytes(&any_bytes, &mut any_cursor)?;
-                    } else if any_wire == 0 {
-                        read_varint(&any_bytes, &mut any_cursor)?;
-                    } else {
-                        return Err("Unsupported wire type in Any message".to_string());
-                    }
+                    skip_field(any_bytes, &mut any_cursor, any_wire)
+                        .map_err(|_| "Unsupported wire type in Any message".to_string())?;
                 }
             }
 
@@ -173,45 +493,15 @@ pub fn parse_account_info_from_abci(response_value: &str) -> Result<(u64, u64),
                 let mut sequence = 0u64;
 
                 while ba_cursor < base_account_data.len() {
-                    let ba_tag = base_account_data[ba_cursor];
-                    ba_cursor += 1;
-
-                    let ba_field = ba_tag >> 3;
-                    let ba_wire = ba_tag & 0x07;
-
-                    match ba_field {
-                        1 => {
-                            // address (string)
-                            if ba_wire == 2 {
-                                read_bytes(&base_account_data, &mut ba_cursor)?;
-                            }
-                        }
-                        2 => {
-                            // pub_key (Any)
-                            if ba_wire == 2 {
-                                read_bytes(&base_account_data, &mut ba_cursor)?;
-                            }
-                        }
-                        3 => {
-                            // account_number (uint64)
-                            if ba_wire == 0 {
-                                account_number = read_varint(&base_account_data, &mut ba_cursor)?;
-                            }
-                        }
-                        4 => {
-                            // sequence (uint64)
-                            if ba_wire == 0 {
-                                sequence = read_varint(&base_account_data, &mut ba_cursor)?;
-                            }
-                        }
-                        _ => {
-                            // Skip unknown field
-                            if ba_wire == 2 {
-                                read_bytes(&base_account_data, &mut ba_cursor)?;
-                            } else if ba_wire == 0 {
-                                read_varint(&base_account_data, &mut ba_cursor)?;
-                            }
-                        }
+                    let (ba_field, ba_wire) = read_key(base_account_data, &mut ba_cursor)?;
+
+                    match (ba_field, ba_wire) {
+                        // account_number (uint64)
+                        (3, 0) => account_number = read_varint(base_account_data, &mut ba_cursor)?,
+                        // sequence (uint64)
+                        (4, 0) => sequence = read_varint(base_account_data, &mut ba_cursor)?,
+                        // address (string), pub_key (Any) and unknown fields
+                        _ => skip_field(base_account_data, &mut ba_cursor, ba_wire)?,
                     }
                 }
 
@@ -222,68 +512,63 @@ pub fn parse_account_info_from_abci(response_value: &str) -> Result<(u64, u64),
                 return Ok((account_number, sequence));
             }
         } else {
-            // Skip unknown field
-            if wire_type == 2 {
-                read_bytes(&decoded, &mut cursor)?;
-            } else if wire_type == 0 {
-                read_varint(&decoded, &mut cursor)?;
-            } else {
-                return Err("Unsupported wire type in QueryAccountResponse".to_string());
-            }
+            skip_field(&decoded, &mut cursor, wire_type)
+                .map_err(|_| "Unsupported wire type in QueryAccountResponse".to_string())?;
         }
     }
 
     Err("No valid account found in response".to_string())
 }
 
-/// Helper function to encode varint
-fn encode_varint(value: u64) -> Vec<u8> {
-    let mut result = Vec::new();
-    let mut val = value;
-    while val >= 0x80 {
-        result.push(((val & 0x7F) | 0x80) as u8);
-        val >>= 7;
-    }
-    result.push(val as u8);
-    result
+/// Read the key of a protobuf field as `(field_number, wire_type)`
+fn read_key(data: &[u8], pos: &mut usize) -> Result<(u64, u64), String> {
+    let key = read_varint(data, pos)?;
+    Ok((key >> 3, key & 0x07))
 }
 
-/// Helper function to encode length-delimited field
-fn encode_length_delimited(tag: u8, data: &[u8]) -> Vec<u8> {
-    let mut result = vec![tag];
-    result.extend(encode_varint(data.len() as u64));
-    result.extend(data);
-    result
-}
-
-/// Helper function to encode string field
-fn encode_string(tag: u8, value: &str) -> Vec<u8> {
-    encode_length_delimited(tag, value.as_bytes())
-}
-
-/// Helper function to encode uint64 field
-fn encode_uint64(tag: u8, value: u64) -> Vec<u8> {
-    let mut result = vec![tag];
-    result.extend(encode_varint(value));
-    result
+/// Skip a varint or length-delimited field value, the only wire types of the account messages
+fn skip_field(data: &[u8], pos: &mut usize, wire_type: u64) -> Result<(), String> {
+    match wire_type {
+        0 => read_varint(data, pos).map(|_| ()),
+        2 => read_length_delimited(data, pos).map(|_| ()),
+        _ => Err(format!("Unsupported wire type {}", wire_type)),
+    }
 }
 
-/// Helper function to encode a generic message to protobuf bytes
-fn encode_message_to_protobuf(message: &CosmosMessage) -> Result<Vec<u8>, String> {
+/// Encode a message to the protobuf bytes of its type, failing on missing or invalid fields
+pub fn encode_message_to_protobuf(message: &CosmosMessage) -> Result<Vec<u8>, String> {
     match message.type_url.as_str() {
         "/cosmos.bank.v1beta1.MsgSend" => encode_msg_send(&message.value),
         "/cosmos.staking.v1beta1.MsgDelegate" => encode_msg_delegate(&message.value),
         "/cosmos.staking.v1beta1.MsgUndelegate" => encode_msg_undelegate(&message.value),
         "/cosmos.staking.v1beta1.MsgBeginRedelegate" => encode_msg_begin_redelegate(&message.value),
+        "/cosmos.staking.v1beta1.MsgEditValidator" => encode_msg_edit_validator(&message.value),
         "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => {
             encode_msg_withdraw_delegator_reward(&message.value)
         }
+        "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => encode_msg_set_withdraw_address(&message.value),
+        "/cosmos.gov.v1beta1.MsgVote" => encode_msg_vote(&message.value, GovVersion::V1Beta1),
+        "/cosmos.gov.v1beta1.MsgVoteWeighted" => encode_msg_vote_weighted(&message.value, GovVersion::V1Beta1),
+        "/cosmos.gov.v1beta1.MsgDeposit" | "/cosmos.gov.v1.MsgDeposit" => encode_msg_deposit(&message.value),
+        "/cosmos.gov.v1.MsgVote" => encode_msg_vote(&message.value, GovVersion::V1),
+        "/cosmos.gov.v1.MsgVoteWeighted" => encode_msg_vote_weighted(&message.value, GovVersion::V1),
+        "/cosmos.gov.v1.MsgSubmitProposal" => encode_msg_submit_proposal_v1(&message.value),
+        "/cosmos.gov.v1beta1.MsgSubmitProposal" => encode_msg_submit_proposal_v1beta1(&message.value),
+        MSG_TRANSFER_TYPE_URL => encode_msg_transfer(&message.value),
+        MSG_UPDATE_CLIENT_TYPE_URL => encode_msg_update_client(&message.value),
+        MSG_STORE_CODE_TYPE_URL => encode_msg_store_code(&message.value),
+        #[cfg(feature = "osmosis")]
+        OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL => encode_msg_swap_exact_amount_in(&message.value),
+        #[cfg(feature = "stride")]
+        STRIDE_MSG_LIQUID_STAKE_TYPE_URL => encode_msg_liquid_stake(&message.value),
+        #[cfg(feature = "stride")]
+        STRIDE_MSG_REDEEM_STAKE_TYPE_URL => encode_msg_liquid_unstake(&message.value),
         _ => Err(format!("Unsupported message type: {}", message.type_url)),
     }
 }
 
 /// Encode MsgSend to protobuf bytes
-fn encode_msg_send(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+pub fn encode_msg_send(value: &serde_json::Value) -> Result<Vec<u8>, String> {
     let from_address = value["from_address"]
         .as_str()
         .ok_or("Missing from_address in MsgSend")?;
@@ -364,17 +649,35 @@ fn encode_msg_undelegate(value: &serde_json::Value) -> Result<Vec<u8>, String> {
     Ok(msg_bytes)
 }
 
-/// Encode MsgBeginRedelegate to protobuf bytes
-fn encode_msg_begin_redelegate(value: &serde_json::Value) -> Result<Vec<u8>, String> {
-    let delegator_address = value["delegator_address"]
-        .as_str()
-        .ok_or("Missing delegator_address in MsgBeginRedelegate")?;
-    let validator_src_address = value["validator_src_address"]
-        .as_str()
-        .ok_or("Missing validator_src_address in MsgBeginRedelegate")?;
-    let validator_dst_address = value["validator_dst_address"]
-        .as_str()
-        .ok_or("Missing validator_dst_address in MsgBeginRedelegate")?;
+/// Returns the string field `name` of a JSON message, also accepting its camelCase
+/// spelling (`validatorSrcAddress`) as produced by CosmJS and the proto3 JSON mapping
+fn json_str_field<'a>(value: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    value[name].as_str().or_else(|| {
+        let mut camel_case = String::with_capacity(name.len());
+        let mut upper = false;
+        for c in name.chars() {
+            if c == '_' {
+                upper = true;
+            } else if upper {
+                camel_case.push(c.to_ascii_uppercase());
+                upper = false;
+            } else {
+                camel_case.push(c);
+            }
+        }
+        value[camel_case.as_str()].as_str()
+    })
+}
+
+/// Encode MsgBeginRedelegate to protobuf bytes.
+/// Field names may be given in snake_case (Amino/SDK JSON) or camelCase (proto3 JSON).
+pub fn encode_msg_begin_redelegate(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let delegator_address =
+        json_str_field(value, "delegator_address").ok_or("Missing delegator_address in MsgBeginRedelegate")?;
+    let validator_src_address =
+        json_str_field(value, "validator_src_address").ok_or("Missing validator_src_address in MsgBeginRedelegate")?;
+    let validator_dst_address =
+        json_str_field(value, "validator_dst_address").ok_or("Missing validator_dst_address in MsgBeginRedelegate")?;
     let amount = value["amount"]
         .as_object()
         .ok_or("Missing amount object in MsgBeginRedelegate")?;
@@ -396,6 +699,143 @@ fn encode_msg_begin_redelegate(value: &serde_json::Value) -> Result<Vec<u8>, Str
     Ok(msg_bytes)
 }
 
+/// Sentinel of the Cosmos SDK for `MsgEditValidator` fields that keep their current value
+pub const DO_NOT_MODIFY_DESC: &str = "[do-not-modify]";
+
+/// Description of a `MsgEditValidator`, `None` fields keep their current value
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ValidatorDescriptionUpdate {
+    pub moniker: Option<String>,
+    pub identity: Option<String>,
+    pub website: Option<String>,
+    pub security_contact: Option<String>,
+    pub details: Option<String>,
+}
+
+impl ValidatorDescriptionUpdate {
+    /// Read the description of a JSON message, where missing fields and the
+    /// [`DO_NOT_MODIFY_DESC`] sentinel both mean unchanged
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let field = |name: &str| unmodified_to_none(json_str_field(value, name));
+        Self {
+            moniker: field("moniker"),
+            identity: field("identity"),
+            website: field("website"),
+            security_contact: field("security_contact"),
+            details: field("details"),
+        }
+    }
+}
+
+fn unmodified_to_none(value: Option<&str>) -> Option<String> {
+    value.filter(|value| *value != DO_NOT_MODIFY_DESC).map(str::to_string)
+}
+
+/// Encode MsgEditValidator to protobuf bytes.
+///
+/// An empty description field clears it on chain, so unchanged description fields are sent as
+/// [`DO_NOT_MODIFY_DESC`]. The nullable `commission_rate` and `min_self_delegation` are only
+/// encoded when they are set to a value other than the sentinel.
+pub fn encode_msg_edit_validator(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let validator_address =
+        json_str_field(value, "validator_address").ok_or("Missing validator_address in MsgEditValidator")?;
+    let description = ValidatorDescriptionUpdate::from_json(&value["description"]);
+    let commission_rate = unmodified_to_none(json_str_field(value, "commission_rate"));
+    let min_self_delegation = unmodified_to_none(json_str_field(value, "min_self_delegation"));
+
+    let mut description_bytes = Vec::new();
+    for (tag, field) in [
+        (0x0a, &description.moniker),          // moniker = 1
+        (0x12, &description.identity),         // identity = 2
+        (0x1a, &description.website),          // website = 3
+        (0x22, &description.security_contact), // security_contact = 4
+        (0x2a, &description.details),          // details = 5
+    ] {
+        let field = field.as_deref().unwrap_or(DO_NOT_MODIFY_DESC);
+        if !field.is_empty() {
+            description_bytes.extend(encode_string(tag, field));
+        }
+    }
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_length_delimited(0x0a, &description_bytes)); // description = 1
+    msg_bytes.extend(encode_string(0x12, validator_address)); // validator_address = 2
+    if let Some(commission_rate) = commission_rate {
+        // The `Dec` commission rate is encoded as its integer representation
+        let atomics = parse_sdk_dec(&commission_rate)?;
+        if atomics > 10u128.pow(SDK_DEC_PRECISION as u32) {
+            return Err(format!("Commission rate {} is greater than 1", commission_rate));
+        }
+        msg_bytes.extend(encode_string(0x1a, &atomics.to_string())); // commission_rate = 3
+    }
+    if let Some(min_self_delegation) = min_self_delegation {
+        if min_self_delegation.is_empty() || !min_self_delegation.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid min_self_delegation: {}", min_self_delegation));
+        }
+        msg_bytes.extend(encode_string(0x22, &min_self_delegation)); // min_self_delegation = 4
+    }
+
+    Ok(msg_bytes)
+}
+
+/// Convert a validator operator address (`cosmosvaloper1...`) to the account address of its operator
+pub fn validator_operator_to_account_address(validator_address: &str) -> Result<String, String> {
+    let (hrp, data, variant) =
+        bech32::decode(validator_address).map_err(|e| format!("Invalid validator address: {}", e))?;
+    let account_hrp = hrp
+        .strip_suffix("valoper")
+        .ok_or_else(|| format!("Not a validator operator address: {}", validator_address))?;
+    encode(account_hrp, data, variant).map_err(|e| format!("Failed to encode account address: {}", e))
+}
+
+/// Bech32 prefix of Injective account addresses
+pub const INJECTIVE_ADDRESS_PREFIX: &str = "inj";
+
+/// Error converting between Ethereum-style hex addresses and Injective bech32 addresses
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AddressError {
+    #[error("Invalid hex address: {0}")]
+    InvalidHex(String),
+    #[error("Invalid bech32 address: {0}")]
+    InvalidBech32(String),
+    #[error("Expected a 20-byte address, got {0} bytes")]
+    InvalidLength(usize),
+    #[error("Expected an address with the {expected} prefix, got {actual}")]
+    WrongPrefix { expected: String, actual: String },
+}
+
+/// Convert a `0x`-prefixed Ethereum address to its Injective `inj1...` address.
+/// Injective keys are Ethereum keys, the 20 address bytes are encoded as is: unlike
+/// [`public_key_to_cosmos_address`], nothing is hashed.
+pub fn eth_address_to_injective_bech32(eth_address: &str) -> Result<String, AddressError> {
+    let hex_address = eth_address
+        .strip_prefix("0x")
+        .or_else(|| eth_address.strip_prefix("0X"))
+        .unwrap_or(eth_address);
+    let bytes = hex::decode(hex_address).map_err(|e| AddressError::InvalidHex(e.to_string()))?;
+    if bytes.len() != 20 {
+        return Err(AddressError::InvalidLength(bytes.len()));
+    }
+    encode(INJECTIVE_ADDRESS_PREFIX, bytes.to_base32(), Variant::Bech32)
+        .map_err(|e| AddressError::InvalidBech32(e.to_string()))
+}
+
+/// Convert an Injective `inj1...` address to its lowercase `0x`-prefixed Ethereum address
+pub fn injective_bech32_to_eth_address(inj_address: &str) -> Result<String, AddressError> {
+    let (hrp, data, _) = bech32::decode(inj_address).map_err(|e| AddressError::InvalidBech32(e.to_string()))?;
+    if hrp != INJECTIVE_ADDRESS_PREFIX {
+        return Err(AddressError::WrongPrefix {
+            expected: INJECTIVE_ADDRESS_PREFIX.to_string(),
+            actual: hrp,
+        });
+    }
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|e| AddressError::InvalidBech32(e.to_string()))?;
+    if bytes.len() != 20 {
+        return Err(AddressError::InvalidLength(bytes.len()));
+    }
+    Ok(format!("0x{}", hex::encode(bytes)))
+}
+
 /// Encode MsgWithdrawDelegatorReward to protobuf bytes
 fn encode_msg_withdraw_delegator_reward(value: &serde_json::Value) -> Result<Vec<u8>, String> {
     let delegator_address = value["delegator_address"]
@@ -412,89 +852,526 @@ fn encode_msg_withdraw_delegator_reward(value: &serde_json::Value) -> Result<Vec
     Ok(msg_bytes)
 }
 
-/// Create sign document bytes for Cosmos transaction signing using manual protobuf encoding
-pub fn create_sign_doc_bytes(transaction: &CosmosTransaction, public_key: &[u8]) -> Result<Vec<u8>, String> {
-    // Create TxBody with multiple messages
-    let mut tx_body_bytes = Vec::new();
+/// Encode MsgSetWithdrawAddress to protobuf bytes
+fn encode_msg_set_withdraw_address(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let delegator_address = value["delegator_address"]
+        .as_str()
+        .ok_or("Missing delegator_address in MsgSetWithdrawAddress")?;
+    let withdraw_address = value["withdraw_address"]
+        .as_str()
+        .ok_or("Missing withdraw_address in MsgSetWithdrawAddress")?;
 
-    // Encode each message
-    for message in &transaction.messages {
-        let msg_bytes = encode_message_to_protobuf(message)?;
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, delegator_address)); // delegator_address = 1
+    msg_bytes.extend(encode_string(0x12, withdraw_address)); // withdraw_address = 2
 
-        // Create Any message
-        let mut msg_any_bytes = Vec::new();
-        msg_any_bytes.extend(encode_string(0x0a, &message.type_url)); // type_url = 1
-        msg_any_bytes.extend(encode_length_delimited(0x12, &msg_bytes)); // value = 2
+    Ok(msg_bytes)
+}
 
-        tx_body_bytes.extend(encode_length_delimited(0x0a, &msg_any_bytes)); // messages = 1
+/// Helper function to read a uint64 field given either as a JSON string or number
+fn parse_u64_field(value: &serde_json::Value, field: &str, msg_name: &str) -> Result<u64, String> {
+    match &value[field] {
+        serde_json::Value::String(s) => s
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid {} in {}: {}", field, msg_name, e)),
+        serde_json::Value::Number(n) => n.as_u64().ok_or_else(|| format!("Invalid {} in {}", field, msg_name)),
+        _ => Err(format!("Missing {} in {}", field, msg_name)),
     }
+}
 
-    tx_body_bytes.extend(encode_string(0x12, &transaction.memo)); // memo = 2
-    tx_body_bytes.extend(encode_uint64(0x18, 0)); // timeout_height = 3
+/// Helper function to encode a repeated Coin field from a JSON array
+fn encode_coins(tag: u8, value: &serde_json::Value, field: &str, msg_name: &str) -> Result<Vec<u8>, String> {
+    let coins = value[field]
+        .as_array()
+        .ok_or_else(|| format!("Missing {} array in {}", field, msg_name))?;
 
-    // Create PubKey
-    let mut pub_key_bytes = Vec::new();
-    pub_key_bytes.extend(encode_length_delimited(0x0a, public_key)); // key = 1
+    let mut bytes = Vec::new();
+    for coin_value in coins {
+        let denom = coin_value["denom"].as_str().ok_or("Missing denom in coin")?;
+        let amount = coin_value["amount"].as_str().ok_or("Missing amount in coin")?;
 
-    // Create Any message for PubKey
-    let pub_key_type_url = "/cosmos.crypto.secp256k1.PubKey";
-    let mut pub_key_any_bytes = Vec::new();
-    pub_key_any_bytes.extend(encode_string(0x0a, pub_key_type_url)); // type_url = 1
-    pub_key_any_bytes.extend(encode_length_delimited(0x12, &pub_key_bytes)); // value = 2
+        let mut coin_bytes = Vec::new();
+        coin_bytes.extend(encode_string(0x0a, denom)); // denom = 1
+        coin_bytes.extend(encode_string(0x12, amount)); // amount = 2
+        bytes.extend(encode_length_delimited(tag, &coin_bytes));
+    }
+    Ok(bytes)
+}
 
-    // Create Fee
-    let mut fee_bytes = Vec::new();
-    // Encode fee amount array (field 1)
-    for coin in &transaction.fee {
+/// Helper function to encode a message as `google.protobuf.Any`
+fn encode_any(message: &CosmosMessage) -> Result<Vec<u8>, String> {
+    let msg_bytes = encode_message_to_protobuf(message)?;
+
+    let mut msg_any_bytes = Vec::new();
+    msg_any_bytes.extend(encode_string(0x0a, &message.type_url)); // type_url = 1
+    msg_any_bytes.extend(encode_length_delimited(0x12, &msg_bytes)); // value = 2
+    Ok(msg_any_bytes)
+}
+
+/// Encode MsgVote to protobuf bytes
+fn encode_msg_vote(value: &serde_json::Value, version: GovVersion) -> Result<Vec<u8>, String> {
+    let proposal_id = parse_u64_field(value, "proposal_id", "MsgVote")?;
+    let voter = value["voter"].as_str().ok_or("Missing voter in MsgVote")?;
+    let option = VoteOption::from_json(&value["option"])?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_uint64(0x08, proposal_id)); // proposal_id = 1
+    msg_bytes.extend(encode_string(0x12, voter)); // voter = 2
+    msg_bytes.extend(encode_uint64(0x18, option as u64)); // option = 3
+    if version == GovVersion::V1 {
+        let metadata = value["metadata"].as_str().unwrap_or("");
+        msg_bytes.extend(encode_non_empty_string(0x22, metadata)); // metadata = 4
+    }
+
+    Ok(msg_bytes)
+}
+
+/// Encode MsgVoteWeighted to protobuf bytes, see [`parse_weighted_vote_options`] for the format of `options`
+pub fn encode_msg_vote_weighted(value: &serde_json::Value, version: GovVersion) -> Result<Vec<u8>, String> {
+    let proposal_id = parse_u64_field(value, "proposal_id", "MsgVoteWeighted")?;
+    let voter = value["voter"].as_str().ok_or("Missing voter in MsgVoteWeighted")?;
+    let options = parse_weighted_vote_options(&value["options"])?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_uint64(0x08, proposal_id)); // proposal_id = 1
+    msg_bytes.extend(encode_string(0x12, voter)); // voter = 2
+
+    // Encode options array (field 3)
+    for option in &options {
+        // In v1beta1 the `Dec` weight is encoded as its integer representation,
+        // while v1 uses the plain decimal string
+        let weight = match version {
+            GovVersion::V1Beta1 => parse_sdk_dec(&option.weight)?.to_string(),
+            GovVersion::V1 => option.weight.clone(),
+        };
+
+        let mut option_bytes = Vec::new();
+        option_bytes.extend(encode_uint64(0x08, option.option as u64)); // option = 1
+        option_bytes.extend(encode_string(0x12, &weight)); // weight = 2
+        msg_bytes.extend(encode_length_delimited(0x1a, &option_bytes)); // options = 3
+    }
+
+    if version == GovVersion::V1 {
+        let metadata = value["metadata"].as_str().unwrap_or("");
+        msg_bytes.extend(encode_non_empty_string(0x22, metadata)); // metadata = 4
+    }
+
+    Ok(msg_bytes)
+}
+
+/// Encode MsgDeposit to protobuf bytes (identical in v1beta1 and v1)
+fn encode_msg_deposit(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let proposal_id = parse_u64_field(value, "proposal_id", "MsgDeposit")?;
+    let depositor = value["depositor"].as_str().ok_or("Missing depositor in MsgDeposit")?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_uint64(0x08, proposal_id)); // proposal_id = 1
+    msg_bytes.extend(encode_string(0x12, depositor)); // depositor = 2
+    msg_bytes.extend(encode_coins(0x1a, value, "amount", "MsgDeposit")?); // amount = 3
+
+    Ok(msg_bytes)
+}
+
+/// Encode the `x/gov` v1 MsgSubmitProposal to protobuf bytes
+pub fn encode_msg_submit_proposal_v1(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let messages = value["messages"]
+        .as_array()
+        .ok_or("Missing messages array in MsgSubmitProposal")?
+        .iter()
+        .map(|msg_json| -> Result<CosmosMessage, String> {
+            let type_url = msg_json["@type"]
+                .as_str()
+                .ok_or("Missing @type in MsgSubmitProposal message")?;
+            let mut msg_value = msg_json.clone();
+            if let Some(obj) = msg_value.as_object_mut() {
+                obj.remove("@type");
+            }
+            Ok(CosmosMessage {
+                type_url: type_url.to_string(),
+                value: msg_value,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let proposer = value["proposer"]
+        .as_str()
+        .ok_or("Missing proposer in MsgSubmitProposal")?;
+    let metadata = value["metadata"].as_str().unwrap_or("");
+    let title = value["title"].as_str().ok_or("Missing title in MsgSubmitProposal")?;
+    let summary = value["summary"]
+        .as_str()
+        .ok_or("Missing summary in MsgSubmitProposal")?;
+
+    let mut msg_bytes = Vec::new();
+    for message in &messages {
+        msg_bytes.extend(encode_length_delimited(0x0a, &encode_any(message)?)); // messages = 1
+    }
+    msg_bytes.extend(encode_coins(0x12, value, "initial_deposit", "MsgSubmitProposal")?); // initial_deposit = 2
+    msg_bytes.extend(encode_string(0x1a, proposer)); // proposer = 3
+    msg_bytes.extend(encode_non_empty_string(0x22, metadata)); // metadata = 4
+    msg_bytes.extend(encode_string(0x2a, title)); // title = 5
+    msg_bytes.extend(encode_string(0x32, summary)); // summary = 6
+    if value["expedited"].as_bool().unwrap_or(false) {
+        msg_bytes.extend(encode_uint64(0x38, 1)); // expedited = 7
+    }
+
+    Ok(msg_bytes)
+}
+
+/// Type URL of the `x/distribution` proposal content spending from the community pool
+pub const COMMUNITY_POOL_SPEND_PROPOSAL_TYPE_URL: &str = "/cosmos.distribution.v1beta1.CommunityPoolSpendProposal";
+
+/// Encode a CommunityPoolSpendProposal, the `content` of a v1beta1 MsgSubmitProposal, to protobuf bytes
+pub fn encode_community_pool_spend_proposal(
+    title: &str,
+    description: &str,
+    recipient: &str,
+    amount: &[CosmosCoin],
+) -> Result<Vec<u8>, String> {
+    if recipient.is_empty() {
+        return Err("Missing recipient in CommunityPoolSpendProposal".to_string());
+    }
+    if amount.is_empty() {
+        return Err("Missing amount in CommunityPoolSpendProposal".to_string());
+    }
+
+    let mut proposal_bytes = Vec::new();
+    proposal_bytes.extend(encode_string(0x0a, title)); // title = 1
+    proposal_bytes.extend(encode_string(0x12, description)); // description = 2
+    proposal_bytes.extend(encode_string(0x1a, recipient)); // recipient = 3
+    for coin in amount {
         let mut coin_bytes = Vec::new();
         coin_bytes.extend(encode_string(0x0a, &coin.denom)); // denom = 1
         coin_bytes.extend(encode_string(0x12, &coin.amount)); // amount = 2
-        fee_bytes.extend(encode_length_delimited(0x0a, &coin_bytes)); // amount = 1
+        proposal_bytes.extend(encode_length_delimited(0x22, &coin_bytes)); // amount = 4
     }
-    fee_bytes.extend(encode_uint64(0x10, transaction.gas_limit)); // gas_limit = 2
-    fee_bytes.extend(encode_string(0x1a, "")); // payer = 3
-    fee_bytes.extend(encode_string(0x22, "")); // granter = 4
+    Ok(proposal_bytes)
+}
 
-    // Create ModeInfo Single
-    let mut mode_info_single_bytes = Vec::new();
-    mode_info_single_bytes.extend(encode_uint64(0x08, 1)); // mode = SIGN_MODE_DIRECT = 1
+/// Encode the `x/gov` v1beta1 MsgSubmitProposal to protobuf bytes.
+/// Only a CommunityPoolSpendProposal `content` is supported.
+pub fn encode_msg_submit_proposal_v1beta1(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let content = &value["content"];
+    let content_type = content["@type"]
+        .as_str()
+        .ok_or("Missing content @type in MsgSubmitProposal")?;
+    if content_type != COMMUNITY_POOL_SPEND_PROPOSAL_TYPE_URL {
+        return Err(format!("Unsupported proposal content: {}", content_type));
+    }
+    let amount = serde_json::from_value::<Vec<CosmosCoin>>(content["amount"].clone())
+        .map_err(|e| format!("Invalid amount in CommunityPoolSpendProposal: {}", e))?;
+    let proposal_bytes = encode_community_pool_spend_proposal(
+        content["title"]
+            .as_str()
+            .ok_or("Missing title in CommunityPoolSpendProposal")?,
+        content["description"]
+            .as_str()
+            .ok_or("Missing description in CommunityPoolSpendProposal")?,
+        content["recipient"].as_str().unwrap_or(""),
+        &amount,
+    )?;
+    let proposer = value["proposer"]
+        .as_str()
+        .ok_or("Missing proposer in MsgSubmitProposal")?;
 
-    // Create ModeInfo
-    let mut mode_info_bytes = Vec::new();
-    mode_info_bytes.extend(encode_length_delimited(0x0a, &mode_info_single_bytes)); // single = 1
+    let mut content_any_bytes = Vec::new();
+    content_any_bytes.extend(encode_string(0x0a, content_type)); // type_url = 1
+    content_any_bytes.extend(encode_length_delimited(0x12, &proposal_bytes)); // value = 2
 
-    // Create SignerInfo
-    let mut signer_info_bytes = Vec::new();
-    signer_info_bytes.extend(encode_length_delimited(0x0a, &pub_key_any_bytes)); // public_key = 1
-    signer_info_bytes.extend(encode_length_delimited(0x12, &mode_info_bytes)); // mode_info = 2
-    signer_info_bytes.extend(encode_uint64(0x18, transaction.sequence)); // sequence = 3
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_length_delimited(0x0a, &content_any_bytes)); // content = 1
+    msg_bytes.extend(encode_coins(0x12, value, "initial_deposit", "MsgSubmitProposal")?); // initial_deposit = 2
+    msg_bytes.extend(encode_string(0x1a, proposer)); // proposer = 3
 
-    // Create AuthInfo
-    let mut auth_info_bytes = Vec::new();
-    auth_info_bytes.extend(encode_length_delimited(0x0a, &signer_info_bytes)); // signer_infos = 1
-    auth_info_bytes.extend(encode_length_delimited(0x12, &fee_bytes)); // fee = 2
+    Ok(msg_bytes)
+}
 
-    // Create SignDoc
-    let mut sign_doc_bytes = Vec::new();
-    sign_doc_bytes.extend(encode_length_delimited(0x0a, &tx_body_bytes)); // body_bytes = 1
-    sign_doc_bytes.extend(encode_length_delimited(0x12, &auth_info_bytes)); // auth_info_bytes = 2
-    sign_doc_bytes.extend(encode_string(0x1a, &transaction.chain_id)); // chain_id = 3
-    sign_doc_bytes.extend(encode_uint64(0x20, transaction.account_number)); // account_number = 4
+/// Gas of a transaction regardless of its size, in [`estimate_fee_by_tx_size`]
+pub const SIZE_ESTIMATE_BASE_GAS: u64 = 100_000;
 
-    Ok(sign_doc_bytes)
+/// Gas per byte of the encoded transaction, in [`estimate_fee_by_tx_size`]
+pub const SIZE_ESTIMATE_GAS_PER_BYTE: u64 = 10;
+
+/// Maximum transaction size used when the chain's consensus params are not available
+pub const DEFAULT_MAX_TX_BYTES: usize = 1024 * 1024;
+
+/// Estimated size of the encoded `AuthInfo` without its fee coins and of the signature:
+/// a secp256k1 signer info (~90 bytes), the fee gas limit and a 64 byte signature
+const AUTH_INFO_OVERHEAD_BYTES: usize = 192;
+
+/// Errors returned by [`check_tx_size`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TxSizeError {
+    #[error("Transaction too large: estimated {estimated} bytes, maximum is {max} bytes")]
+    BodyTooLarge { estimated: usize, max: usize },
+    #[error("Failed to encode transaction: {0}")]
+    Encoding(String),
 }
 
-/// Build final transaction for broadcasting using manual protobuf encoding
-pub fn build_transaction_for_broadcast(
-    transaction: &CosmosTransaction,
-    public_key: &[u8],
-    signature: &[u8],
-) -> Result<String, String> {
-    // Create TxBody with multiple messages
-    let mut tx_body_bytes = Vec::new();
+/// Maximum transaction size given by the `block.max_bytes` consensus param
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxTxBytes {
+    Limited(usize),
+    /// `block.max_bytes` is -1, the block size is only bounded by the maximum block gas
+    Unlimited,
+}
 
-    // Encode each message
+impl MaxTxBytes {
+    /// Returns the maximum size for the `block.max_bytes` consensus param `max_bytes`, `None` if
+    /// it is neither positive nor -1
+    pub fn from_param(max_bytes: i64) -> Option<Self> {
+        match max_bytes {
+            -1 => Some(Self::Unlimited),
+            max_bytes if max_bytes > 0 => usize::try_from(max_bytes).ok().map(Self::Limited),
+            _ => None,
+        }
+    }
+}
+
+/// Pre-estimates the encoded size of `transaction` and checks it against `max_bytes`,
+/// the `block.max_bytes` consensus param, or [`DEFAULT_MAX_TX_BYTES`] when not available.
+/// Only the messages are checked to be encodable when the size is unlimited.
+pub fn check_tx_size(transaction: &CosmosTransaction, max_bytes: Option<MaxTxBytes>) -> Result<(), TxSizeError> {
+    let max = match max_bytes {
+        Some(MaxTxBytes::Limited(max)) => Some(max),
+        Some(MaxTxBytes::Unlimited) => None,
+        None => Some(DEFAULT_MAX_TX_BYTES),
+    };
+
+    let mut estimated = transaction.memo.len() + AUTH_INFO_OVERHEAD_BYTES;
     for message in &transaction.messages {
+        let msg_len = encode_message_to_protobuf(message)
+            .map_err(TxSizeError::Encoding)?
+            .len();
+        // Any { type_url, value } wrapped in TxBody.messages, with up to 10 bytes of tags and lengths
+        estimated += message.type_url.len() + msg_len + 10;
+    }
+    for coin in &transaction.fee {
+        estimated += coin.denom.len() + coin.amount.len() + 6;
+    }
+
+    match max {
+        Some(max) if estimated > max => Err(TxSizeError::BodyTooLarge { estimated, max }),
+        _ => Ok(()),
+    }
+}
+
+/// Type URL of `MsgWithdrawDelegatorReward`
+pub const MSG_WITHDRAW_DELEGATOR_REWARD_TYPE_URL: &str = "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward";
+
+/// Returns one `MsgWithdrawDelegatorReward` per validator, to be packed into transactions with
+/// [`split_transaction`]
+pub fn encode_bulk_withdraw_rewards(delegator: &str, validators: &[&str]) -> Result<Vec<CosmosMessage>, String> {
+    if delegator.is_empty() {
+        return Err("Missing delegator address".to_string());
+    }
+    if validators.is_empty() {
+        return Err("No validator to withdraw rewards from".to_string());
+    }
+    validators
+        .iter()
+        .map(|validator| {
+            if validator.is_empty() {
+                return Err("Empty validator address".to_string());
+            }
+            Ok(CosmosMessage {
+                type_url: MSG_WITHDRAW_DELEGATOR_REWARD_TYPE_URL.to_string(),
+                value: serde_json::json!({
+                    "delegator_address": delegator,
+                    "validator_address": validator
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Splits the messages of `transaction` into as few transactions as possible, each passing
+/// [`check_tx_size`] against `max_bytes`. The gas limit and fee of `transaction` are shared
+/// between the transactions in proportion to their number of messages, rounded up, and their
+/// sequences follow the sequence of `transaction`.
+pub fn split_transaction(
+    transaction: &CosmosTransaction,
+    max_bytes: Option<MaxTxBytes>,
+) -> Result<Vec<CosmosTransaction>, TxSizeError> {
+    let template = CosmosTransaction {
+        messages: Vec::new(),
+        ..transaction.clone()
+    };
+    // Batches are checked with the whole fee, which is never shorter than its share
+    let mut batches: Vec<Vec<CosmosMessage>> = Vec::new();
+    let mut current = template.clone();
+    for message in &transaction.messages {
+        current.messages.push(message.clone());
+        match check_tx_size(&current, max_bytes) {
+            Ok(()) => {}
+            Err(e) if current.messages.len() == 1 => return Err(e),
+            Err(_) => {
+                let last = current.messages.split_off(current.messages.len() - 1);
+                batches.push(std::mem::replace(&mut current.messages, last));
+                check_tx_size(&current, max_bytes)?;
+            }
+        }
+    }
+    if !current.messages.is_empty() {
+        batches.push(current.messages);
+    }
+
+    let total = transaction.messages.len() as u128;
+    let share = |amount: u128, count: usize| (amount * count as u128).div_ceil(total);
+    batches
+        .into_iter()
+        .enumerate()
+        .map(|(index, messages)| {
+            let count = messages.len();
+            let fee = transaction
+                .fee
+                .iter()
+                .map(|coin| {
+                    let amount = coin
+                        .amount
+                        .parse::<u128>()
+                        .map_err(|e| TxSizeError::Encoding(format!("Invalid fee amount {}: {}", coin.amount, e)))?;
+                    Ok(CosmosCoin::new(coin.denom.clone(), share(amount, count).to_string()))
+                })
+                .collect::<Result<_, TxSizeError>>()?;
+            Ok(CosmosTransaction {
+                fee,
+                gas_limit: share(transaction.gas_limit as u128, count) as u64,
+                sequence: transaction.sequence + index as u64,
+                messages,
+                ..template.clone()
+            })
+        })
+        .collect()
+}
+
+/// Returns the transactions withdrawing the rewards of `delegator` from all `validators`, built
+/// from `template` as described by [`split_transaction`]. The gas limit and fee of `template`
+/// are those of a single transaction withdrawing from all validators.
+pub fn withdraw_rewards_transactions(
+    delegator: &str,
+    validators: &[&str],
+    template: &CosmosTransaction,
+    max_bytes: Option<MaxTxBytes>,
+) -> Result<Vec<CosmosTransaction>, String> {
+    let transaction = CosmosTransaction {
+        messages: encode_bulk_withdraw_rewards(delegator, validators)?,
+        ..template.clone()
+    };
+    split_transaction(&transaction, max_bytes).map_err(|e| e.to_string())
+}
+
+/// Errors returned by [`normalize_ecdsa_signature`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    #[error("Unknown signature format, expected 64 raw bytes, 65 recoverable bytes or a DER sequence")]
+    UnknownFormat,
+    #[error("Invalid DER signature: {0}")]
+    InvalidDer(String),
+    #[error("Invalid recovery ID {0} of a recoverable signature")]
+    InvalidRecoveryId(u8),
+}
+
+/// Returns the raw `r || s` signature expected by Cosmos from the signature returned by a signer:
+/// - 64 bytes, already `r || s`, as returned by `sign_with_ecdsa`
+/// - 65 bytes, a recoverable `r || s || v` signature, whose recovery ID `v` is dropped
+/// - a DER encoded ECDSA signature
+pub fn normalize_ecdsa_signature(bytes: &[u8]) -> Result<[u8; 64], SignatureError> {
+    if let Ok(signature) = <[u8; 64]>::try_from(bytes) {
+        return Ok(signature);
+    }
+    // A DER signature may also be 65 bytes long, when its sequence spans the 63 remaining bytes
+    let is_der_sequence = bytes.first() == Some(&0x30) && bytes.get(1) == Some(&63);
+    if let Some(recoverable) = <[u8; 65]>::try_from(bytes).ok().filter(|_| !is_der_sequence) {
+        // Recovery IDs are 0 to 3, or 27 to 30 in the Ethereum convention
+        let recovery_id = recoverable[64];
+        if !matches!(recovery_id, 0..=3 | 27..=30) {
+            return Err(SignatureError::InvalidRecoveryId(recovery_id));
+        }
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&recoverable[..64]);
+        return Ok(signature);
+    }
+    if bytes.first() != Some(&0x30) {
+        return Err(SignatureError::UnknownFormat);
+    }
+
+    let mut pos = 1;
+    let sequence_len = read_der_length(bytes, &mut pos)?;
+    if pos + sequence_len != bytes.len() {
+        return Err(SignatureError::InvalidDer(format!(
+            "sequence length {} does not match {} remaining bytes",
+            sequence_len,
+            bytes.len() - pos
+        )));
+    }
+    let r = read_der_integer(bytes, &mut pos)?;
+    let s = read_der_integer(bytes, &mut pos)?;
+    if pos != bytes.len() {
+        return Err(SignatureError::InvalidDer("trailing bytes after s".to_string()));
+    }
+
+    let mut signature = [0u8; 64];
+    signature[32 - r.len()..32].copy_from_slice(r);
+    signature[64 - s.len()..].copy_from_slice(s);
+    Ok(signature)
+}
+
+/// Reads a DER length in short form or in long form of up to 2 bytes
+fn read_der_length(bytes: &[u8], pos: &mut usize) -> Result<usize, SignatureError> {
+    let first = *bytes
+        .get(*pos)
+        .ok_or_else(|| SignatureError::InvalidDer("missing length".to_string()))?;
+    *pos += 1;
+    if first < 0x80 {
+        return Ok(first as usize);
+    }
+    let len_bytes = (first & 0x7f) as usize;
+    if len_bytes == 0 || len_bytes > 2 {
+        return Err(SignatureError::InvalidDer(format!(
+            "unsupported length of {} bytes",
+            len_bytes
+        )));
+    }
+    let value = bytes
+        .get(*pos..*pos + len_bytes)
+        .ok_or_else(|| SignatureError::InvalidDer("truncated length".to_string()))?;
+    *pos += len_bytes;
+    Ok(value.iter().fold(0, |len, byte| (len << 8) | *byte as usize))
+}
+
+/// Reads a DER integer and returns its big-endian value without leading zeros, at most 32 bytes
+fn read_der_integer<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a [u8], SignatureError> {
+    if bytes.get(*pos) != Some(&0x02) {
+        return Err(SignatureError::InvalidDer("expected an integer".to_string()));
+    }
+    *pos += 1;
+    let len = read_der_length(bytes, pos)?;
+    let value = bytes
+        .get(*pos..*pos + len)
+        .ok_or_else(|| SignatureError::InvalidDer("truncated integer".to_string()))?;
+    *pos += len;
+
+    let leading_zeros = value.iter().take_while(|byte| **byte == 0).count();
+    let value = &value[leading_zeros..];
+    if value.len() > 32 {
+        return Err(SignatureError::InvalidDer(format!(
+            "integer of {} bytes exceeds 32 bytes",
+            value.len()
+        )));
+    }
+    Ok(value)
+}
+
+/// Field key of `TxBody.extension_options` (field 1023, length-delimited)
+const TX_BODY_EXTENSION_OPTIONS_KEY: u64 = (1023 << 3) | 2;
+
+/// Field key of `TxBody.non_critical_extension_options` (field 2047, length-delimited)
+const TX_BODY_NON_CRITICAL_EXTENSION_OPTIONS_KEY: u64 = (2047 << 3) | 2;
+
+/// Encode the `messages` field of a `TxBody`, every message wrapped in an `Any`
+pub fn encode_message_bytes(messages: &[CosmosMessage]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for message in messages {
         let msg_bytes = encode_message_to_protobuf(message)?;
 
         // Create Any message
@@ -502,23 +1379,55 @@ pub fn build_transaction_for_broadcast(
         msg_any_bytes.extend(encode_string(0x0a, &message.type_url)); // type_url = 1
         msg_any_bytes.extend(encode_length_delimited(0x12, &msg_bytes)); // value = 2
 
-        tx_body_bytes.extend(encode_length_delimited(0x0a, &msg_any_bytes)); // messages = 1
+        bytes.extend(encode_length_delimited(0x0a, &msg_any_bytes)); // messages = 1
+    }
+    Ok(bytes)
+}
+
+/// Encode the `TxBody` of a transaction, identical in the sign doc and the broadcast transaction
+fn encode_tx_body(transaction: &CosmosTransaction) -> Result<Vec<u8>, String> {
+    let mut tx_body_bytes = encode_message_bytes(&transaction.messages)?;
+
+    // Default values are omitted, as in the encoding of the chains and of cosmrs, so that the
+    // bytes match the `SignDoc` the nodes re-encode to verify the signature
+    tx_body_bytes.extend(encode_non_empty_string(0x12, &transaction.memo)); // memo = 2
+
+    // The field numbers of the extension options do not fit a one-byte tag
+    for (key, options) in [
+        (TX_BODY_EXTENSION_OPTIONS_KEY, &transaction.extension_options), // extension_options = 1023
+        (
+            TX_BODY_NON_CRITICAL_EXTENSION_OPTIONS_KEY,
+            &transaction.non_critical_extension_options,
+        ), // non_critical_extension_options = 2047
+    ] {
+        for (type_url, value) in options {
+            let mut option_any_bytes = Vec::new();
+            option_any_bytes.extend(encode_string(0x0a, type_url)); // type_url = 1
+            option_any_bytes.extend(encode_length_delimited(0x12, value)); // value = 2
+
+            tx_body_bytes.extend(encode_varint(key));
+            tx_body_bytes.extend(encode_varint(option_any_bytes.len() as u64));
+            tx_body_bytes.extend(option_any_bytes);
+        }
     }
 
-    tx_body_bytes.extend(encode_string(0x12, &transaction.memo)); // memo = 2
-    tx_body_bytes.extend(encode_uint64(0x18, 0)); // timeout_height = 3
+    Ok(tx_body_bytes)
+}
 
+/// Encode the `AuthInfo` of a transaction with a single signer, identical in the sign doc and the
+/// broadcast transaction
+fn encode_auth_info(transaction: &CosmosTransaction, public_key: &[u8]) -> Vec<u8> {
     // Create PubKey
     let mut pub_key_bytes = Vec::new();
     pub_key_bytes.extend(encode_length_delimited(0x0a, public_key)); // key = 1
 
     // Create Any message for PubKey
-    let pub_key_type_url = "/cosmos.crypto.secp256k1.PubKey";
+    let pub_key_type_url = transaction.sign_mode.public_key_type_url();
     let mut pub_key_any_bytes = Vec::new();
     pub_key_any_bytes.extend(encode_string(0x0a, pub_key_type_url)); // type_url = 1
     pub_key_any_bytes.extend(encode_length_delimited(0x12, &pub_key_bytes)); // value = 2
 
-    // Create Fee
+    // Create Fee, without payer = 3 and granter = 4
     let mut fee_bytes = Vec::new();
     // Encode fee amount array (field 1)
     for coin in &transaction.fee {
@@ -527,13 +1436,11 @@ pub fn build_transaction_for_broadcast(
         coin_bytes.extend(encode_string(0x12, &coin.amount)); // amount = 2
         fee_bytes.extend(encode_length_delimited(0x0a, &coin_bytes)); // amount = 1
     }
-    fee_bytes.extend(encode_uint64(0x10, transaction.gas_limit)); // gas_limit = 2
-    fee_bytes.extend(encode_string(0x1a, "")); // payer = 3
-    fee_bytes.extend(encode_string(0x22, "")); // granter = 4
+    fee_bytes.extend(encode_non_zero_uint64(0x10, transaction.gas_limit)); // gas_limit = 2
 
     // Create ModeInfo Single
     let mut mode_info_single_bytes = Vec::new();
-    mode_info_single_bytes.extend(encode_uint64(0x08, 1)); // mode = SIGN_MODE_DIRECT = 1
+    mode_info_single_bytes.extend(encode_uint64(0x08, transaction.sign_mode.as_i32() as u64)); // mode = 1
 
     // Create ModeInfo
     let mut mode_info_bytes = Vec::new();
@@ -543,12 +1450,56 @@ pub fn build_transaction_for_broadcast(
     let mut signer_info_bytes = Vec::new();
     signer_info_bytes.extend(encode_length_delimited(0x0a, &pub_key_any_bytes)); // public_key = 1
     signer_info_bytes.extend(encode_length_delimited(0x12, &mode_info_bytes)); // mode_info = 2
-    signer_info_bytes.extend(encode_uint64(0x18, transaction.sequence)); // sequence = 3
+    signer_info_bytes.extend(encode_non_zero_uint64(0x18, transaction.sequence)); // sequence = 3
 
     // Create AuthInfo
     let mut auth_info_bytes = Vec::new();
     auth_info_bytes.extend(encode_length_delimited(0x0a, &signer_info_bytes)); // signer_infos = 1
     auth_info_bytes.extend(encode_length_delimited(0x12, &fee_bytes)); // fee = 2
+    auth_info_bytes
+}
+
+/// Create sign document bytes for Cosmos transaction signing in the sign mode of the transaction:
+/// the protobuf `SignDoc` in [`SignMode::Direct`], using manual protobuf encoding, and the
+/// canonical Amino JSON `StdSignDoc` in [`SignMode::LegacyAminoJson`]. Both are signed over their
+/// SHA-256 hash.
+///
+/// [`SignMode::Eip712Cw`] transactions are signed over the Keccak-256 hash of the typed data of
+/// [`create_eip712_sign_doc`] by an Ethereum signer, and are rejected.
+///
+/// [`create_eip712_sign_doc`]: crate::types::create_eip712_sign_doc
+pub fn create_sign_doc_bytes(transaction: &CosmosTransaction, public_key: &[u8]) -> Result<Vec<u8>, String> {
+    match transaction.sign_mode {
+        SignMode::Direct => {}
+        SignMode::LegacyAminoJson => {
+            let sign_doc = amino_sign_doc(transaction).map_err(|e| e.to_string())?;
+            return Ok(canonical_json_serialize(&sign_doc).into_bytes());
+        }
+        SignMode::Eip712Cw => {
+            return Err("EIP-712 transactions are signed over their typed data, not a sign doc".to_string());
+        }
+    }
+    let tx_body_bytes = encode_tx_body(transaction)?;
+    let auth_info_bytes = encode_auth_info(transaction, public_key);
+
+    // Create SignDoc
+    let mut sign_doc_bytes = Vec::new();
+    sign_doc_bytes.extend(encode_length_delimited(0x0a, &tx_body_bytes)); // body_bytes = 1
+    sign_doc_bytes.extend(encode_length_delimited(0x12, &auth_info_bytes)); // auth_info_bytes = 2
+    sign_doc_bytes.extend(encode_non_empty_string(0x1a, &transaction.chain_id)); // chain_id = 3
+    sign_doc_bytes.extend(encode_non_zero_uint64(0x20, transaction.account_number)); // account_number = 4
+
+    Ok(sign_doc_bytes)
+}
+
+/// Build final transaction for broadcasting using manual protobuf encoding
+pub fn build_transaction_for_broadcast(
+    transaction: &CosmosTransaction,
+    public_key: &[u8],
+    signature: &[u8],
+) -> Result<String, String> {
+    let tx_body_bytes = encode_tx_body(transaction)?;
+    let auth_info_bytes = encode_auth_info(transaction, public_key);
 
     // Create final Tx
     let mut tx_bytes = Vec::new();
@@ -560,6 +1511,50 @@ pub fn build_transaction_for_broadcast(
     Ok(STANDARD.encode(&tx_bytes))
 }
 
+/// Estimates the fee of `transaction` at `gas_price` from its encoded size, without simulating it:
+/// [`SIZE_ESTIMATE_BASE_GAS`] plus [`SIZE_ESTIMATE_GAS_PER_BYTE`] per byte, as wallets estimating
+/// fees offline do. Messages touching many store entries, e.g. swaps or contract calls, use more
+/// gas than their size accounts for.
+///
+/// The fee is in the denom of the fee of `transaction`, uatom when it has none.
+pub fn estimate_fee_by_tx_size(transaction: &CosmosTransaction, gas_price: f64) -> CosmosCoin {
+    let gas = estimate_gas_by_tx_size(transaction);
+    let denom = transaction
+        .fee
+        .first()
+        .map_or(DEFAULT_GAS_PRICE.0, |coin| coin.denom.as_str());
+    CosmosCoin::new(denom, ((gas as f64 * gas_price).ceil() as u64).to_string())
+}
+
+/// Returns the gas estimated by [`estimate_fee_by_tx_size`]
+pub fn estimate_gas_by_tx_size(transaction: &CosmosTransaction) -> u64 {
+    let tx_bytes = estimate_signed_tx_bytes(transaction) as u64;
+    SIZE_ESTIMATE_BASE_GAS.saturating_add(SIZE_ESTIMATE_GAS_PER_BYTE.saturating_mul(tx_bytes))
+}
+
+/// Returns the size of `transaction` once signed, with a compressed public key and a 64 byte
+/// signature. Messages without a known encoding are counted by the size of their JSON.
+fn estimate_signed_tx_bytes(transaction: &CosmosTransaction) -> usize {
+    let body_len = encode_tx_body(transaction).map_or_else(
+        |_| {
+            let messages_len: usize = transaction
+                .messages
+                .iter()
+                .map(|message| message.type_url.len() + message.value.to_string().len() + 10)
+                .sum();
+            messages_len + transaction.memo.len()
+        },
+        |body| body.len(),
+    );
+    let auth_info_len = encode_auth_info(transaction, &[0u8; 33]).len();
+
+    // TxRaw { body_bytes = 1, auth_info_bytes = 2, signatures = 3 }, each with a tag and a length
+    [body_len, auth_info_len, 64]
+        .iter()
+        .map(|len| 1 + encode_varint(*len as u64).len() + len)
+        .sum()
+}
+
 /// Helper function to extract signer address from a message
 pub fn extract_signer_address_from_message(message: &CosmosMessage) -> Result<String, String> {
     match message.type_url.as_str() {
@@ -567,16 +1562,906 @@ pub fn extract_signer_address_from_message(message: &CosmosMessage) -> Result<St
             .as_str()
             .ok_or("Missing from_address in MsgSend".to_string())
             .map(|s| s.to_string()),
+        "/cosmos.staking.v1beta1.MsgBeginRedelegate" => json_str_field(&message.value, "delegator_address")
+            .ok_or("Missing delegator_address in MsgBeginRedelegate".to_string())
+            .map(|s| s.to_string()),
+        "/cosmos.staking.v1beta1.MsgEditValidator" => json_str_field(&message.value, "validator_address")
+            .ok_or("Missing validator_address in MsgEditValidator".to_string())
+            .and_then(validator_operator_to_account_address),
         "/cosmos.staking.v1beta1.MsgDelegate"
         | "/cosmos.staking.v1beta1.MsgUndelegate"
-        | "/cosmos.staking.v1beta1.MsgBeginRedelegate"
-        | "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => message.value["delegator_address"]
+        | "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward"
+        | "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => message.value["delegator_address"]
             .as_str()
             .ok_or("Missing delegator_address in staking/distribution message".to_string())
             .map(|s| s.to_string()),
+        "/cosmos.gov.v1beta1.MsgVote"
+        | "/cosmos.gov.v1beta1.MsgVoteWeighted"
+        | "/cosmos.gov.v1.MsgVote"
+        | "/cosmos.gov.v1.MsgVoteWeighted" => message.value["voter"]
+            .as_str()
+            .ok_or("Missing voter in gov vote message".to_string())
+            .map(|s| s.to_string()),
+        "/cosmos.gov.v1beta1.MsgDeposit" | "/cosmos.gov.v1.MsgDeposit" => message.value["depositor"]
+            .as_str()
+            .ok_or("Missing depositor in MsgDeposit".to_string())
+            .map(|s| s.to_string()),
+        "/cosmos.gov.v1.MsgSubmitProposal" | "/cosmos.gov.v1beta1.MsgSubmitProposal" => message.value["proposer"]
+            .as_str()
+            .ok_or("Missing proposer in MsgSubmitProposal".to_string())
+            .map(|s| s.to_string()),
+        MSG_TRANSFER_TYPE_URL => message.value["sender"]
+            .as_str()
+            .ok_or("Missing sender in MsgTransfer".to_string())
+            .map(|s| s.to_string()),
+        MSG_UPDATE_CLIENT_TYPE_URL => message.value["signer"]
+            .as_str()
+            .ok_or("Missing signer in MsgUpdateClient".to_string())
+            .map(|s| s.to_string()),
+        MSG_STORE_CODE_TYPE_URL => message.value["sender"]
+            .as_str()
+            .ok_or("Missing sender in MsgStoreCode".to_string())
+            .map(|s| s.to_string()),
+        #[cfg(feature = "osmosis")]
+        OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL => message.value["sender"]
+            .as_str()
+            .ok_or("Missing sender in MsgSwapExactAmountIn".to_string())
+            .map(|s| s.to_string()),
+        #[cfg(feature = "stride")]
+        STRIDE_MSG_LIQUID_STAKE_TYPE_URL | STRIDE_MSG_REDEEM_STAKE_TYPE_URL => message.value["creator"]
+            .as_str()
+            .ok_or("Missing creator in Stride message".to_string())
+            .map(|s| s.to_string()),
         _ => Err(format!(
             "Unsupported message type for signer extraction: {}",
             message.type_url
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::{
+        cosmos::{
+            auth::v1beta1::{BaseAccount, QueryAccountResponse},
+            bank::v1beta1::MsgSend,
+            base::v1beta1::Coin,
+            distribution::v1beta1::CommunityPoolSpendProposal,
+            gov::{
+                v1,
+                v1beta1::{MsgSubmitProposal, MsgVoteWeighted, WeightedVoteOption as ProtoWeightedVoteOption},
+            },
+            staking::v1beta1::{Description, MsgBeginRedelegate, MsgEditValidator},
+            tx::v1beta1::{mode_info::Sum, AuthInfo, SignDoc, TxBody},
+        },
+        traits::Message,
+        Any,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn expected_begin_redelegate() -> Vec<u8> {
+        MsgBeginRedelegate {
+            delegator_address: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            validator_src_address: "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6".to_string(),
+            validator_dst_address: "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0".to_string(),
+            amount: Some(Coin {
+                denom: "uatom".to_string(),
+                amount: "1000".to_string(),
+            }),
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn test_encode_msg_begin_redelegate() {
+        let value = json!({
+            "delegator_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "validator_src_address": "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6",
+            "validator_dst_address": "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0",
+            "amount": { "denom": "uatom", "amount": "1000" }
+        });
+        assert_eq!(
+            encode_msg_begin_redelegate(&value).unwrap(),
+            expected_begin_redelegate()
+        );
+    }
+
+    #[test]
+    fn test_encode_msg_begin_redelegate_camel_case() {
+        let value = json!({
+            "delegatorAddress": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "validatorSrcAddress": "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6",
+            "validatorDstAddress": "cosmosvaloper1sjllsnramtg3ewxqwwrwjxfgc4n4ef9u2lcnj0",
+            "amount": { "denom": "uatom", "amount": "1000" }
+        });
+        assert_eq!(
+            encode_msg_begin_redelegate(&value).unwrap(),
+            expected_begin_redelegate()
+        );
+
+        let message = CosmosMessage {
+            type_url: "/cosmos.staking.v1beta1.MsgBeginRedelegate".to_string(),
+            value,
+        };
+        assert_eq!(
+            extract_signer_address_from_message(&message).unwrap(),
+            "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"
+        );
+    }
+
+    #[test]
+    fn test_encode_msg_edit_validator() {
+        let value = json!({
+            "validator_address": "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6",
+            "description": { "moniker": "ic-validator", "website": "[do-not-modify]", "details": "" },
+            "commission_rate": "0.05",
+            "min_self_delegation": "[do-not-modify]"
+        });
+        let expected = MsgEditValidator {
+            description: Some(Description {
+                moniker: "ic-validator".to_string(),
+                identity: DO_NOT_MODIFY_DESC.to_string(),
+                website: DO_NOT_MODIFY_DESC.to_string(),
+                security_contact: DO_NOT_MODIFY_DESC.to_string(),
+                details: String::new(),
+            }),
+            validator_address: "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6".to_string(),
+            commission_rate: "50000000000000000".to_string(),
+            min_self_delegation: String::new(),
+        }
+        .encode_to_vec();
+        assert_eq!(encode_msg_edit_validator(&value).unwrap(), expected);
+
+        let too_high = json!({
+            "validator_address": "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6",
+            "commission_rate": "1.5"
+        });
+        assert!(encode_msg_edit_validator(&too_high).is_err());
+
+        let message = CosmosMessage {
+            type_url: "/cosmos.staking.v1beta1.MsgEditValidator".to_string(),
+            value,
+        };
+        assert_eq!(
+            extract_signer_address_from_message(&message).unwrap(),
+            "cosmos1e5yfpc8l6g4808fclmlyd38tjgxuwshn7xzkvf"
+        );
+        assert!(validator_operator_to_account_address("cosmos1e5yfpc8l6g4808fclmlyd38tjgxuwshn7xzkvf").is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_encode_msg_submit_proposal_community_pool_spend() {
+        let value = json!({
+            "content": {
+                "@type": "/cosmos.distribution.v1beta1.CommunityPoolSpendProposal",
+                "title": "Fund the IC bridge",
+                "description": "Spend 100 ATOM on the IC bridge",
+                "recipient": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                "amount": [{ "denom": "uatom", "amount": "100000000" }]
+            },
+            "initial_deposit": [{ "denom": "uatom", "amount": "1000000" }],
+            "proposer": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"
+        });
+        let content = CommunityPoolSpendProposal {
+            title: "Fund the IC bridge".to_string(),
+            description: "Spend 100 ATOM on the IC bridge".to_string(),
+            recipient: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".to_string(),
+            amount: vec![Coin {
+                denom: "uatom".to_string(),
+                amount: "100000000".to_string(),
+            }],
+        };
+        let expected = MsgSubmitProposal {
+            content: Some(Any {
+                type_url: COMMUNITY_POOL_SPEND_PROPOSAL_TYPE_URL.to_string(),
+                value: content.encode_to_vec(),
+            }),
+            initial_deposit: vec![Coin {
+                denom: "uatom".to_string(),
+                amount: "1000000".to_string(),
+            }],
+            proposer: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+        }
+        .encode_to_vec();
+        assert_eq!(encode_msg_submit_proposal_v1beta1(&value).unwrap(), expected);
+
+        assert!(encode_community_pool_spend_proposal("title", "description", "", &[]).is_err());
+    }
+
+    #[test]
+    fn test_encode_msg_vote_weighted() {
+        let value = json!({
+            "proposal_id": "123",
+            "voter": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "options": [
+                { "option": "VOTE_OPTION_YES", "weight": "0.7000000000000000" },
+                { "option": "VOTE_OPTION_ABSTAIN", "weight": "0.3000000000000000" }
+            ]
+        });
+        let expected = MsgVoteWeighted {
+            proposal_id: 123,
+            voter: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            options: vec![
+                ProtoWeightedVoteOption {
+                    option: 1,
+                    weight: "700000000000000000".to_string(),
+                },
+                ProtoWeightedVoteOption {
+                    option: 2,
+                    weight: "300000000000000000".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            encode_msg_vote_weighted(&value, GovVersion::V1Beta1).unwrap(),
+            expected.encode_to_vec()
+        );
+
+        let mut wrong_sum = value.clone();
+        wrong_sum["options"][1]["weight"] = json!("0.2");
+        assert!(encode_msg_vote_weighted(&wrong_sum, GovVersion::V1Beta1).is_err());
+    }
+
+    #[test]
+    fn test_gov_version() {
+        assert_eq!(GovVersion::from_chain_flag(false), GovVersion::V1Beta1);
+        assert_eq!(GovVersion::from_chain_flag(true), GovVersion::V1);
+        assert_eq!(
+            GovVersion::V1Beta1.type_url("MsgDeposit"),
+            "/cosmos.gov.v1beta1.MsgDeposit"
+        );
+        assert_eq!(GovVersion::V1.type_url("MsgDeposit"), "/cosmos.gov.v1.MsgDeposit");
+
+        assert!(GovVersion::V1Beta1
+            .check_type_url("/cosmos.gov.v1beta1.MsgVote")
+            .is_ok());
+        assert!(GovVersion::V1Beta1.check_type_url("/cosmos.gov.v1.MsgVote").is_err());
+        assert!(GovVersion::V1Beta1
+            .check_type_url("/cosmos.bank.v1beta1.MsgSend")
+            .is_ok());
+        assert!(GovVersion::V1.check_type_url("/cosmos.gov.v1.MsgVote").is_ok());
+        assert!(GovVersion::V1.check_type_url("/cosmos.gov.v1beta1.MsgVote").is_ok());
+
+        let message = gov_message(GovVersion::V1, "MsgVote", json!({ "proposal_id": "1" })).unwrap();
+        assert_eq!(message.type_url, "/cosmos.gov.v1.MsgVote");
+        assert_eq!(message.value["proposal_id"], "1");
+        assert!(gov_message(GovVersion::V1Beta1, "MsgSubmitProposal", json!({})).is_err());
+    }
+
+    fn gov_v1_message(type_url: &str, value: serde_json::Value) -> Vec<u8> {
+        encode_message_to_protobuf(&CosmosMessage {
+            type_url: type_url.to_string(),
+            value,
+        })
+        .unwrap()
+    }
+
+    fn uatom(amount: &str) -> Coin {
+        Coin {
+            denom: "uatom".to_string(),
+            amount: amount.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_encode_gov_v1_msg_vote() {
+        let value = json!({
+            "proposal_id": "123",
+            "voter": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "option": "VOTE_OPTION_NO_WITH_VETO",
+            "metadata": "ipfs://vote"
+        });
+        let mut expected = v1::MsgVote {
+            proposal_id: 123,
+            voter: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            option: 4,
+            metadata: "ipfs://vote".to_string(),
+        };
+        assert_eq!(
+            gov_v1_message("/cosmos.gov.v1.MsgVote", value.clone()),
+            expected.encode_to_vec()
+        );
+
+        let mut without_metadata = value;
+        without_metadata.as_object_mut().unwrap().remove("metadata");
+        expected.metadata = String::new();
+        assert_eq!(
+            gov_v1_message("/cosmos.gov.v1.MsgVote", without_metadata),
+            expected.encode_to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_gov_v1_msg_vote_weighted() {
+        let value = json!({
+            "proposal_id": "123",
+            "voter": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "options": [
+                { "option": "VOTE_OPTION_YES", "weight": "0.700000000000000000" },
+                { "option": "VOTE_OPTION_ABSTAIN", "weight": "0.300000000000000000" }
+            ],
+            "metadata": "split"
+        });
+        // Unlike v1beta1, the weights are encoded as decimal strings
+        let expected = v1::MsgVoteWeighted {
+            proposal_id: 123,
+            voter: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            options: vec![
+                v1::WeightedVoteOption {
+                    option: 1,
+                    weight: "0.700000000000000000".to_string(),
+                },
+                v1::WeightedVoteOption {
+                    option: 2,
+                    weight: "0.300000000000000000".to_string(),
+                },
+            ],
+            metadata: "split".to_string(),
+        };
+        assert_eq!(
+            gov_v1_message("/cosmos.gov.v1.MsgVoteWeighted", value),
+            expected.encode_to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_gov_v1_msg_deposit() {
+        let value = json!({
+            "proposal_id": "7",
+            "depositor": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "amount": [{ "denom": "uatom", "amount": "5000000" }]
+        });
+        let expected = v1::MsgDeposit {
+            proposal_id: 7,
+            depositor: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            amount: vec![uatom("5000000")],
+        }
+        .encode_to_vec();
+        assert_eq!(gov_v1_message("/cosmos.gov.v1.MsgDeposit", value.clone()), expected);
+        assert_eq!(gov_v1_message("/cosmos.gov.v1beta1.MsgDeposit", value), expected);
+    }
+
+    #[test]
+    fn test_encode_gov_v1_msg_submit_proposal() {
+        let value = json!({
+            "messages": [{
+                "@type": "/cosmos.bank.v1beta1.MsgSend",
+                "from_address": "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn",
+                "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                "amount": [{ "denom": "uatom", "amount": "100000000" }]
+            }],
+            "initial_deposit": [{ "denom": "uatom", "amount": "1000000" }],
+            "proposer": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "metadata": "ipfs://proposal",
+            "title": "Fund the IC bridge",
+            "summary": "Send 100 ATOM to the IC bridge",
+            "expedited": true
+        });
+        let send = MsgSend {
+            from_address: "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn".to_string(),
+            to_address: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".to_string(),
+            amount: vec![uatom("100000000")],
+        };
+        let mut expected = v1::MsgSubmitProposal {
+            messages: vec![Any {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: send.encode_to_vec(),
+            }],
+            initial_deposit: vec![uatom("1000000")],
+            proposer: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            metadata: "ipfs://proposal".to_string(),
+            title: "Fund the IC bridge".to_string(),
+            summary: "Send 100 ATOM to the IC bridge".to_string(),
+            expedited: true,
+        };
+        assert_eq!(
+            gov_v1_message("/cosmos.gov.v1.MsgSubmitProposal", value.clone()),
+            expected.encode_to_vec()
+        );
+
+        let mut standard = value;
+        let fields = standard.as_object_mut().unwrap();
+        fields.remove("metadata");
+        fields.remove("expedited");
+        expected.metadata = String::new();
+        expected.expedited = false;
+        assert_eq!(
+            gov_v1_message("/cosmos.gov.v1.MsgSubmitProposal", standard),
+            expected.encode_to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_weighted_vote_options_rounding() {
+        let options = parse_weighted_vote_options(&json!([
+            { "option": "VOTE_OPTION_YES", "weight": "0.333333333333333333" },
+            { "option": 3, "weight": "0.333333333333333333" },
+            { "option": "no_with_veto", "weight": "0.333333333333333333" }
+        ]))
+        .unwrap();
+        assert_eq!(options[1].option, VoteOption::No);
+        assert_eq!(options[2].option, VoteOption::NoWithVeto);
+        assert_eq!(options[2].weight, "0.333333333333333334");
+
+        assert!(parse_weighted_vote_options(&json!([{ "option": "VOTE_OPTION_MAYBE", "weight": "1" }])).is_err());
+        assert!(parse_weighted_vote_options(&json!([])).is_err());
+    }
+
+    #[test]
+    fn test_validate_transaction_json() {
+        let valid = json!({
+            "body": {
+                "messages": [{
+                    "@type": "/cosmos.bank.v1beta1.MsgSend",
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "to_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                }],
+                "memo": ""
+            },
+            "auth_info": { "fee": { "amount": [{ "denom": "uatom", "amount": "5000" }], "gas_limit": "200000" } }
+        });
+        assert_eq!(validate_transaction_json(&valid), Ok(()));
+
+        let mut invalid = valid.clone();
+        invalid["body"]["messages"][0]["amount"][0]["amount"] = json!("1.5");
+        invalid["body"]["messages"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "@type": 7 }));
+        invalid["auth_info"]["fee"]["gas_limit"] = json!(200000);
+        let errors = validate_transaction_json(&invalid).unwrap_err();
+        assert_eq!(
+            errors.iter().map(|e| e.path.as_str()).collect::<Vec<_>>(),
+            vec![
+                "body.messages[0].amount[0].amount",
+                "body.messages[1].@type",
+                "auth_info.fee.gas_limit"
+            ]
+        );
+
+        let errors = validate_transaction_json(&json!({})).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].to_string(), "body.messages: expected an array of messages");
+    }
+
+    #[test]
+    fn test_check_tx_size() {
+        let transaction = CosmosTransaction {
+            messages: vec![CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: json!({
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                }),
+            }],
+            fee: vec![CosmosCoin::new("uatom", "5000")],
+            gas_limit: 200_000,
+            memo: "x".repeat(2048),
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 1,
+            sequence: 0,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Direct,
+        };
+        assert!(check_tx_size(&transaction, None).is_ok());
+
+        let pub_key = [2u8; 33];
+        let tx_len = STANDARD
+            .decode(build_transaction_for_broadcast(&transaction, &pub_key, &[0u8; 64]).unwrap())
+            .unwrap()
+            .len();
+        assert!(check_tx_size(&transaction, Some(MaxTxBytes::Limited(tx_len + 64))).is_ok());
+        assert!(matches!(
+            check_tx_size(&transaction, Some(MaxTxBytes::Limited(1024))),
+            Err(TxSizeError::BodyTooLarge { max: 1024, .. })
+        ));
+
+        // The default limit does not apply to chains without a block size limit
+        let large = CosmosTransaction {
+            memo: "x".repeat(DEFAULT_MAX_TX_BYTES + 1),
+            ..transaction
+        };
+        assert!(matches!(
+            check_tx_size(&large, None),
+            Err(TxSizeError::BodyTooLarge {
+                max: DEFAULT_MAX_TX_BYTES,
+                ..
+            })
+        ));
+        assert!(check_tx_size(&large, MaxTxBytes::from_param(-1)).is_ok());
+        assert_eq!(MaxTxBytes::from_param(-1), Some(MaxTxBytes::Unlimited));
+        assert_eq!(MaxTxBytes::from_param(22020096), Some(MaxTxBytes::Limited(22020096)));
+        assert_eq!(MaxTxBytes::from_param(0), None);
+        assert_eq!(MaxTxBytes::from_param(-2), None);
+    }
+
+    #[test]
+    fn test_estimate_fee_by_tx_size() {
+        let mut transaction = CosmosTransaction {
+            messages: vec![CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: json!({
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                }),
+            }],
+            fee: Vec::new(),
+            gas_limit: 0,
+            memo: String::new(),
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 1,
+            sequence: 0,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Direct,
+        };
+        let tx_len = STANDARD
+            .decode(build_transaction_for_broadcast(&transaction, &[0u8; 33], &[0u8; 64]).unwrap())
+            .unwrap()
+            .len() as u64;
+        let gas = SIZE_ESTIMATE_BASE_GAS + SIZE_ESTIMATE_GAS_PER_BYTE * tx_len;
+        assert_eq!(estimate_gas_by_tx_size(&transaction), gas);
+        assert_eq!(
+            estimate_fee_by_tx_size(&transaction, 0.025),
+            CosmosCoin::new("uatom", ((gas as f64 * 0.025).ceil() as u64).to_string())
+        );
+
+        transaction.fee = vec![CosmosCoin::new("uosmo", "1")];
+        assert_eq!(estimate_fee_by_tx_size(&transaction, 0.0).denom, "uosmo");
+
+        // A longer memo costs gas, messages without an encoding are estimated from their JSON
+        transaction.memo = "x".repeat(100);
+        assert!(estimate_gas_by_tx_size(&transaction) >= gas + 1000);
+        transaction.messages[0].type_url = "/unknown.v1.MsgUnknown".to_string();
+        assert!(estimate_gas_by_tx_size(&transaction) > SIZE_ESTIMATE_BASE_GAS);
+    }
+
+    #[test]
+    fn test_withdraw_rewards_transactions() {
+        let validators: Vec<String> = (0..100).map(|i| format!("cosmosvaloper1{:038}", i)).collect();
+        let validators: Vec<&str> = validators.iter().map(String::as_str).collect();
+        let template = CosmosTransaction {
+            messages: Vec::new(),
+            fee: vec![CosmosCoin::new("uatom", "100000")],
+            gas_limit: 10_000_000,
+            memo: String::new(),
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 1,
+            sequence: 7,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Direct,
+        };
+        let delegator = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+
+        let transactions = withdraw_rewards_transactions(delegator, &validators, &template, None).unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].messages.len(), 100);
+        assert_eq!(transactions[0].gas_limit, 10_000_000);
+
+        let transactions =
+            withdraw_rewards_transactions(delegator, &validators, &template, Some(MaxTxBytes::Limited(2048))).unwrap();
+        assert!(transactions.len() > 1);
+        let withdrawn: Vec<_> = transactions
+            .iter()
+            .flat_map(|tx| &tx.messages)
+            .map(|message| message.value["validator_address"].as_str().unwrap())
+            .collect();
+        assert_eq!(withdrawn, validators);
+        for (index, transaction) in transactions.iter().enumerate() {
+            assert!(check_tx_size(transaction, Some(MaxTxBytes::Limited(2048))).is_ok());
+            assert_eq!(transaction.sequence, 7 + index as u64);
+            assert_eq!(transaction.gas_limit, 100_000 * transaction.messages.len() as u64);
+            assert_eq!(
+                transaction.fee,
+                vec![CosmosCoin::new(
+                    "uatom",
+                    (1000 * transaction.messages.len()).to_string()
+                )]
+            );
+        }
+
+        assert!(
+            withdraw_rewards_transactions(delegator, &validators, &template, Some(MaxTxBytes::Limited(256))).is_err()
+        );
+        assert!(withdraw_rewards_transactions(delegator, &[], &template, None).is_err());
+    }
+
+    fn encode_account_response(type_url: &str, account_number: u64) -> String {
+        let account = BaseAccount {
+            address: "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6".to_string(),
+            pub_key: Some(Any {
+                type_url: "/cosmos.crypto.secp256k1.PubKey".to_string(),
+                value: vec![0x0a, 0x21, 0x02],
+            }),
+            account_number,
+            sequence: 300,
+        };
+        let response = QueryAccountResponse {
+            account: Some(Any {
+                type_url: type_url.to_string(),
+                value: account.encode_to_vec(),
+            }),
+        };
+        STANDARD.encode(response.encode_to_vec())
+    }
+
+    #[test]
+    fn test_parse_account_info_from_abci() {
+        let base_account = "/cosmos.auth.v1beta1.BaseAccount";
+        assert_eq!(
+            parse_account_info_from_abci(&encode_account_response(base_account, 577_723)),
+            Ok((577_723, 300))
+        );
+        assert!(parse_account_info_from_abci(&encode_account_response(base_account, 0)).is_err());
+        assert!(parse_account_info_from_abci(&encode_account_response(
+            "/cosmos.vesting.v1beta1.DelayedVestingAccount",
+            1
+        ))
+        .is_err());
+        // Truncated response
+        let truncated = STANDARD.decode(encode_account_response(base_account, 1)).unwrap();
+        assert!(parse_account_info_from_abci(&STANDARD.encode(&truncated[..truncated.len() - 1])).is_err());
+    }
+
+    #[test]
+    fn test_encode_tx_body_extension_options() {
+        let transaction = CosmosTransaction {
+            messages: vec![CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: json!({
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                }),
+            }],
+            fee: vec![CosmosCoin::new("uatom", "5000")],
+            gas_limit: 200_000,
+            memo: String::new(),
+            chain_id: "evmos_9001-2".to_string(),
+            account_number: 1,
+            sequence: 0,
+            extension_options: vec![("/ethermint.evm.v1.ExtensionOptionsEthereumTx".to_string(), vec![])],
+            non_critical_extension_options: vec![("/cosmos.tx.v1beta1.NonCritical".to_string(), vec![1, 2, 3])],
+            sign_mode: SignMode::Direct,
+        };
+        let sign_doc = SignDoc::decode(&create_sign_doc_bytes(&transaction, &[2u8; 33]).unwrap()[..]).unwrap();
+        let tx_body = TxBody::decode(&sign_doc.body_bytes[..]).unwrap();
+
+        assert_eq!(tx_body.messages.len(), 1);
+        assert_eq!(
+            tx_body.extension_options,
+            vec![Any {
+                type_url: "/ethermint.evm.v1.ExtensionOptionsEthereumTx".to_string(),
+                value: vec![],
+            }]
+        );
+        assert_eq!(
+            tx_body.non_critical_extension_options,
+            vec![Any {
+                type_url: "/cosmos.tx.v1beta1.NonCritical".to_string(),
+                value: vec![1, 2, 3],
+            }]
+        );
+    }
+
+    #[derive(Deserialize)]
+    struct SignDocVectors {
+        public_key: String,
+        vectors: Vec<SignDocVector>,
+    }
+
+    #[derive(Deserialize)]
+    struct SignDocVector {
+        name: String,
+        transaction: CosmosTransaction,
+        sign_doc: String,
+    }
+
+    /// Checks the encoder against the sign docs encoded by cosmrs, saved by the
+    /// `generate_test_vectors` test of the `generate-test-vectors` feature
+    #[test]
+    fn test_sign_doc_vectors() {
+        let vectors: SignDocVectors =
+            serde_json::from_str(include_str!("../../tests/vectors/sign_doc_vectors.json")).unwrap();
+        let public_key = hex::decode(&vectors.public_key).unwrap();
+
+        for vector in vectors.vectors {
+            let sign_doc = create_sign_doc_bytes(&vector.transaction, &public_key).unwrap();
+            assert_eq!(hex::encode(&sign_doc), vector.sign_doc, "{}", vector.name);
+            // Re-encoding the decoded sign doc gives the same bytes, as when a node verifies it
+            assert_eq!(
+                SignDoc::decode(&sign_doc[..]).unwrap().encode_to_vec(),
+                sign_doc,
+                "{}",
+                vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_mode_info() {
+        let mut transaction = CosmosTransaction {
+            messages: vec![CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: json!({
+                    "from_address": "evmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmrcp32r",
+                    "to_address": "evmos1zt50azupanqlfam5afhv3hexwyutnukezkkqnd",
+                    "amount": [{ "denom": "aevmos", "amount": "1000" }]
+                }),
+            }],
+            fee: vec![CosmosCoin::new("aevmos", "4000000000000000")],
+            gas_limit: 200_000,
+            memo: String::new(),
+            chain_id: "evmos_9001-2".to_string(),
+            account_number: 1,
+            sequence: 0,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Direct,
+        };
+        for (sign_mode, mode, public_key_type_url) in [
+            (SignMode::Direct, 1, "/cosmos.crypto.secp256k1.PubKey"),
+            (SignMode::Eip712Cw, 127, "/ethermint.crypto.v1.ethsecp256k1.PubKey"),
+        ] {
+            transaction.sign_mode = sign_mode;
+            let auth_info_bytes = encode_auth_info(&transaction, &[2u8; 33]);
+            let signer_info = &AuthInfo::decode(&auth_info_bytes[..]).unwrap().signer_infos[0];
+            assert_eq!(signer_info.public_key.as_ref().unwrap().type_url, public_key_type_url);
+            match &signer_info.mode_info.as_ref().unwrap().sum {
+                Some(Sum::Single(single)) => assert_eq!(single.mode, mode),
+                other => panic!("Unexpected mode info {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_amino_json_sign_bytes() {
+        let transaction = CosmosTransaction {
+            messages: vec![CosmosMessage {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                value: json!({
+                    "from_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+                    "to_address": "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+                    "amount": [{ "denom": "uatom", "amount": "1000" }]
+                }),
+            }],
+            fee: vec![CosmosCoin::new("uatom", "5000")],
+            gas_limit: 200_000,
+            memo: String::new(),
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 577723,
+            sequence: 7,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::LegacyAminoJson,
+        };
+        let private_key = ic_crypto_secp256k1::PrivateKey::deserialize_sec1(&[1u8; 32]).unwrap();
+        let public_key = private_key.public_key().serialize_sec1(true);
+
+        // The sign doc signed by Keplr in Amino JSON mode
+        let sign_bytes = create_sign_doc_bytes(&transaction, &public_key).unwrap();
+        assert_eq!(
+            String::from_utf8(sign_bytes.clone()).unwrap(),
+            r#"{"account_number":"577723","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"5000","denom":"uatom"}],"gas":"200000"},"memo":"","msgs":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"1000","denom":"uatom"}],"from_address":"cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6","to_address":"cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573"}}],"sequence":"7"}"#
+        );
+        // Nodes verify the signature over the SHA-256 hash of the sign doc, as signed by the wallet
+        let signature = hex::decode(
+            "e8be10fc7c0965026b7b0227909672e6cf4e79d76e49a0d4516de98b8eeadc54\
+             57b0679c0b833db18a4e8adcc5ef1006e2e53b22627e62cf1f0ecf39398591a3",
+        )
+        .unwrap();
+        assert_eq!(private_key.sign_message_with_ecdsa(&sign_bytes).to_vec(), signature);
+        assert!(private_key.public_key().verify_ecdsa_signature(&sign_bytes, &signature));
+    }
+
+    #[test]
+    fn test_eip712_has_no_sign_bytes() {
+        let transaction = CosmosTransaction {
+            messages: Vec::new(),
+            fee: Vec::new(),
+            gas_limit: 200_000,
+            memo: String::new(),
+            chain_id: "evmos_9001-2".to_string(),
+            account_number: 1,
+            sequence: 0,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Eip712Cw,
+        };
+        assert!(create_sign_doc_bytes(&transaction, &[2u8; 33]).is_err());
+    }
+
+    #[test]
+    fn test_encode_msg_begin_redelegate_missing_field() {
+        let value = json!({
+            "delegator_address": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "amount": { "denom": "uatom", "amount": "1000" }
+        });
+        assert!(encode_msg_begin_redelegate(&value).is_err());
+    }
+
+    #[test]
+    fn test_normalize_ecdsa_signature() {
+        let raw = [7u8; 64];
+        assert_eq!(normalize_ecdsa_signature(&raw).unwrap(), raw);
+
+        // r has its high bit set and is prefixed with 0x00, s is 31 bytes long
+        let r = [0x80u8; 32];
+        let s = [0x11u8; 31];
+        let mut der = vec![0x30, 2 + 33 + 2 + 31, 0x02, 33, 0x00];
+        der.extend(r);
+        der.extend([0x02, 31]);
+        der.extend(s);
+
+        let mut expected = [0u8; 64];
+        expected[..32].copy_from_slice(&r);
+        expected[33..].copy_from_slice(&s);
+        assert_eq!(normalize_ecdsa_signature(&der).unwrap(), expected);
+
+        let mut recoverable = raw.to_vec();
+        recoverable.push(1);
+        assert_eq!(normalize_ecdsa_signature(&recoverable).unwrap(), raw);
+        recoverable[64] = 28;
+        assert_eq!(normalize_ecdsa_signature(&recoverable).unwrap(), raw);
+        recoverable[64] = 4;
+        assert_eq!(
+            normalize_ecdsa_signature(&recoverable),
+            Err(SignatureError::InvalidRecoveryId(4))
+        );
+
+        // A DER signature of 65 bytes is not taken for a recoverable signature
+        let mut der_65 = vec![0x30, 63, 0x02, 33, 0x00];
+        der_65.extend(r);
+        der_65.extend([0x02, 26]);
+        der_65.extend([0x22u8; 26]);
+        assert_eq!(der_65.len(), 65);
+        assert_eq!(normalize_ecdsa_signature(&der_65).unwrap()[38..], [0x22u8; 26]);
+
+        assert_eq!(
+            normalize_ecdsa_signature(&[1u8; 66]),
+            Err(SignatureError::UnknownFormat)
+        );
+        assert_eq!(
+            normalize_ecdsa_signature(&[1u8; 63]),
+            Err(SignatureError::UnknownFormat)
+        );
+        assert!(matches!(
+            normalize_ecdsa_signature(&der[..der.len() - 1]),
+            Err(SignatureError::InvalidDer(_))
+        ));
+    }
+
+    #[test]
+    fn test_injective_address_conversion() {
+        let inj_address = "inj14au322k9munkmx5wrchz9q30juf5wjgz2cfqku";
+        assert_eq!(
+            eth_address_to_injective_bech32("0xAF79152AC5dF276D9A8e1E2E22822f9713474902").unwrap(),
+            inj_address
+        );
+        assert_eq!(
+            injective_bech32_to_eth_address(inj_address).unwrap(),
+            "0xaf79152ac5df276d9a8e1e2e22822f9713474902"
+        );
+
+        assert_eq!(
+            eth_address_to_injective_bech32("0xaf79"),
+            Err(AddressError::InvalidLength(2))
+        );
+        assert!(matches!(
+            eth_address_to_injective_bech32("0xnot-hex"),
+            Err(AddressError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            injective_bech32_to_eth_address("cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"),
+            Err(AddressError::WrongPrefix { .. })
+        ));
+    }
+}
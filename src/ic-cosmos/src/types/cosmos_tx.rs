@@ -1,7 +1,7 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
-use crate::types::cosmos_common::Event;
+use crate::types::{cosmos_block::BlockEvent, cosmos_common::Event};
 
 /// Represents the response from the /tx endpoint.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
@@ -45,6 +45,8 @@ pub struct TxResultData {
     pub gas_wanted: String,
     /// The gas used
     pub gas_used: String,
-    /// The transaction tags
+    /// The transaction tags, reported by older Tendermint versions
     pub tags: Option<Vec<Event>>,
+    /// The transaction events, replacing the tags in newer versions
+    pub events: Option<Vec<BlockEvent>>,
 }
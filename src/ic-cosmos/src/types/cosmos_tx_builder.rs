@@ -0,0 +1,337 @@
+use std::future::Future;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::json;
+
+use crate::types::{
+    cosmos_protobuf::{encode_length_delimited, find_field},
+    cosmos_transaction_utils::{
+        build_transaction_for_broadcast, CosmosCoin, CosmosMessage, CosmosTransaction, SignMode,
+    },
+};
+
+/// ABCI query path of the transaction simulation service
+pub const TX_SIMULATE_PATH: &str = "/cosmos.tx.v1beta1.Service/Simulate";
+
+/// Gas price used for the fee when none is set on the builder
+pub const DEFAULT_GAS_PRICE: (&str, f64) = ("uatom", 0.025);
+
+/// Multiplier applied to the simulated gas to absorb state changes between simulation and execution
+pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.3;
+
+/// Simulates transactions for [`CosmosTransactionBuilder::estimate_gas`], backed by reqwest
+/// in `cosmos-utils` or by the RPC canister in a canister.
+pub trait SigningBackend {
+    /// Simulates `transaction` and returns the gas it used
+    fn simulate(&self, transaction: &CosmosTransaction) -> impl Future<Output = Result<u64, String>>;
+}
+
+/// Builds a [`CosmosTransaction`] by method chaining, e.g.
+///
+/// ```ignore
+/// let transaction = CosmosTransactionBuilder::new()
+///     .on_chain("cosmoshub-4")
+///     .send(from, to, "1000000uatom")
+///     .delegate(delegator, validator, "5000000uatom")
+///     .set_memo("automated")
+///     .estimate_gas(&backend)
+///     .await?
+///     .build()?;
+/// ```
+///
+/// Invalid amounts are reported by `estimate_gas` or `build`, so that the chain is not interrupted.
+#[derive(Clone, Debug)]
+pub struct CosmosTransactionBuilder {
+    chain_id: String,
+    messages: Vec<CosmosMessage>,
+    memo: String,
+    account_number: u64,
+    sequence: u64,
+    gas_limit: u64,
+    fee: Vec<CosmosCoin>,
+    gas_price: (String, f64),
+    gas_adjustment: f64,
+    extension_options: Vec<(String, Vec<u8>)>,
+    non_critical_extension_options: Vec<(String, Vec<u8>)>,
+    sign_mode: SignMode,
+    error: Option<String>,
+}
+
+impl Default for CosmosTransactionBuilder {
+    fn default() -> Self {
+        Self {
+            chain_id: String::new(),
+            messages: Vec::new(),
+            memo: String::new(),
+            account_number: 0,
+            sequence: 0,
+            gas_limit: 0,
+            fee: Vec::new(),
+            gas_price: (DEFAULT_GAS_PRICE.0.to_string(), DEFAULT_GAS_PRICE.1),
+            gas_adjustment: DEFAULT_GAS_ADJUSTMENT,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Direct,
+            error: None,
+        }
+    }
+}
+
+impl CosmosTransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_chain(mut self, chain_id: impl Into<String>) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+
+    /// Sets the account number and sequence of the signer
+    pub fn with_account(mut self, account_number: u64, sequence: u64) -> Self {
+        self.account_number = account_number;
+        self.sequence = sequence;
+        self
+    }
+
+    /// Adds a `MsgSend` of `amount`, a coin such as `"1000000uatom"`
+    pub fn send(self, from_address: &str, to_address: &str, amount: &str) -> Self {
+        self.with_coin(amount, |coin| CosmosMessage {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: json!({
+                "from_address": from_address,
+                "to_address": to_address,
+                "amount": [coin],
+            }),
+        })
+    }
+
+    /// Adds a `MsgDelegate` of `amount`, a coin such as `"5000000uatom"`
+    pub fn delegate(self, delegator_address: &str, validator_address: &str, amount: &str) -> Self {
+        self.with_coin(amount, |coin| CosmosMessage {
+            type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+            value: json!({
+                "delegator_address": delegator_address,
+                "validator_address": validator_address,
+                "amount": coin,
+            }),
+        })
+    }
+
+    /// Adds any message supported by the transaction encoder
+    pub fn add_message(mut self, message: CosmosMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Adds an `extension_options` entry to the transaction body, the chain must know `type_url`
+    pub fn extension_option(mut self, type_url: impl Into<String>, value: Vec<u8>) -> Self {
+        self.extension_options.push((type_url.into(), value));
+        self
+    }
+
+    /// Adds a `non_critical_extension_options` entry to the transaction body
+    pub fn non_critical_extension_option(mut self, type_url: impl Into<String>, value: Vec<u8>) -> Self {
+        self.non_critical_extension_options.push((type_url.into(), value));
+        self
+    }
+
+    /// Sets the sign mode of the signer info, [`SignMode::Direct`] by default
+    pub fn sign_mode(mut self, sign_mode: SignMode) -> Self {
+        self.sign_mode = sign_mode;
+        self
+    }
+
+    pub fn set_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = memo.into();
+        self
+    }
+
+    /// Sets the gas price used to compute the fee, [`DEFAULT_GAS_PRICE`] when not set
+    pub fn gas_price(mut self, denom: impl Into<String>, price: f64) -> Self {
+        self.gas_price = (denom.into(), price);
+        self
+    }
+
+    /// Sets the multiplier applied to the simulated gas, [`DEFAULT_GAS_ADJUSTMENT`] when not set
+    pub fn gas_adjustment(mut self, gas_adjustment: f64) -> Self {
+        self.gas_adjustment = gas_adjustment;
+        self
+    }
+
+    /// Sets the gas limit and the matching fee without simulating the transaction
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.set_gas(gas_limit);
+        self
+    }
+
+    /// Simulates the transaction with `backend` and sets the gas limit and fee
+    pub async fn estimate_gas<B: SigningBackend>(mut self, backend: &B) -> Result<Self, String> {
+        let transaction = self.to_transaction()?;
+        let gas_used = backend.simulate(&transaction).await?;
+        self.set_gas((gas_used as f64 * self.gas_adjustment).ceil() as u64);
+        Ok(self)
+    }
+
+    pub fn build(self) -> Result<CosmosTransaction, String> {
+        let transaction = self.to_transaction()?;
+        if transaction.gas_limit == 0 {
+            return Err("Gas limit not set, call estimate_gas or gas_limit".to_string());
+        }
+        Ok(transaction)
+    }
+
+    fn with_coin(mut self, amount: &str, message: impl FnOnce(CosmosCoin) -> CosmosMessage) -> Self {
+        match parse_coin(amount) {
+            Ok(coin) => self.messages.push(message(coin)),
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    fn set_gas(&mut self, gas_limit: u64) {
+        let (denom, price) = &self.gas_price;
+        let amount = (gas_limit as f64 * price).ceil() as u64;
+        self.gas_limit = gas_limit;
+        self.fee = vec![CosmosCoin::new(denom.clone(), amount.to_string())];
+    }
+
+    fn to_transaction(&self) -> Result<CosmosTransaction, String> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        if self.chain_id.is_empty() {
+            return Err("Chain id not set, call on_chain".to_string());
+        }
+        if self.messages.is_empty() {
+            return Err("Transaction has no messages".to_string());
+        }
+        Ok(CosmosTransaction {
+            messages: self.messages.clone(),
+            fee: self.fee.clone(),
+            gas_limit: self.gas_limit,
+            memo: self.memo.clone(),
+            chain_id: self.chain_id.clone(),
+            account_number: self.account_number,
+            sequence: self.sequence,
+            extension_options: self.extension_options.clone(),
+            non_critical_extension_options: self.non_critical_extension_options.clone(),
+            sign_mode: self.sign_mode,
+        })
+    }
+}
+
+/// Parse a coin such as `"1000000uatom"` or `"5ibc/27394FB0"`
+pub fn parse_coin(value: &str) -> Result<CosmosCoin, String> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, denom) = value.split_at(split);
+    if amount.is_empty() || denom.is_empty() {
+        return Err(format!(
+            "Invalid coin {}: expected an amount followed by a denom",
+            value
+        ));
+    }
+    Ok(CosmosCoin::new(denom, amount))
+}
+
+/// Encode a `SimulateRequest` of `transaction` as hex `abci_query` data. The node skips
+/// signature verification while simulating, so the transaction is signed with a zero signature of
+/// the length of a secp256k1 one, which keeps the gas charged for the transaction size exact.
+pub fn encode_simulate_query(transaction: &CosmosTransaction, public_key: &[u8]) -> Result<String, String> {
+    let tx_base64 = build_transaction_for_broadcast(transaction, public_key, &[0u8; 64])?;
+    let tx_bytes = STANDARD
+        .decode(tx_base64)
+        .map_err(|e| format!("Failed to decode transaction: {}", e))?;
+    Ok(hex::encode(encode_length_delimited(0x12, &tx_bytes))) // tx_bytes = 2
+}
+
+/// Decode the `gas_used` of a `SimulateResponse` from the base64 ABCI response value
+pub fn decode_simulate_response(response_value: &str) -> Result<u64, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // gas_info = 1 -> gas_used = 2
+    let gas_info = find_field(&decoded, 1)?
+        .and_then(|f| f.as_bytes())
+        .ok_or("Gas info not found in simulation response")?;
+    find_field(gas_info, 2)?
+        .and_then(|f| f.as_varint())
+        .ok_or_else(|| "Gas used not found in simulation response".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::cosmos_protobuf::encode_uint64;
+
+    struct FixedGasBackend(u64);
+
+    impl SigningBackend for FixedGasBackend {
+        async fn simulate(&self, transaction: &CosmosTransaction) -> Result<u64, String> {
+            assert_eq!(transaction.messages.len(), 2);
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_builder_estimates_gas_and_fee() {
+        let transaction = futures::executor::block_on(
+            CosmosTransactionBuilder::new()
+                .on_chain("cosmoshub-4")
+                .send("cosmos1from", "cosmos1to", "1000000uatom")
+                .delegate("cosmos1from", "cosmosvaloper1validator", "5000000uatom")
+                .set_memo("automated")
+                .estimate_gas(&FixedGasBackend(100_000)),
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        assert_eq!(transaction.chain_id, "cosmoshub-4");
+        assert_eq!(transaction.memo, "automated");
+        assert_eq!(transaction.gas_limit, 130_000);
+        assert_eq!(transaction.fee[0].denom, "uatom");
+        assert_eq!(transaction.fee[0].amount, "3250");
+        assert_eq!(transaction.messages[0].value["amount"][0]["amount"], "1000000");
+        assert_eq!(transaction.messages[1].value["amount"]["denom"], "uatom");
+    }
+
+    #[test]
+    fn test_builder_reports_invalid_amount() {
+        let result = CosmosTransactionBuilder::new()
+            .on_chain("cosmoshub-4")
+            .send("cosmos1from", "cosmos1to", "uatom")
+            .gas_limit(200_000)
+            .build();
+        assert!(result.is_err());
+        assert!(CosmosTransactionBuilder::new().on_chain("cosmoshub-4").build().is_err());
+    }
+
+    #[test]
+    fn test_encode_simulate_query() {
+        let transaction = CosmosTransactionBuilder::new()
+            .on_chain("cosmoshub-4")
+            .send("cosmos1from", "cosmos1to", "1000000uatom")
+            .gas_limit(200_000)
+            .build()
+            .unwrap();
+        let query = hex::decode(encode_simulate_query(&transaction, &[2u8; 33]).unwrap()).unwrap();
+
+        // tx_bytes = 2 -> signatures = 3
+        let tx_bytes = find_field(&query, 2).unwrap().and_then(|f| f.as_bytes()).unwrap();
+        let signature = find_field(tx_bytes, 3).unwrap().and_then(|f| f.as_bytes()).unwrap();
+        assert_eq!(signature, [0u8; 64]);
+    }
+
+    #[test]
+    fn test_decode_simulate_response() {
+        let mut gas_info = encode_uint64(0x08, 200_000);
+        gas_info.extend(encode_uint64(0x10, 104_512));
+        let response = STANDARD.encode(encode_length_delimited(0x0a, &gas_info));
+
+        assert_eq!(decode_simulate_response(&response).unwrap(), 104_512);
+    }
+}
@@ -0,0 +1,161 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    cosmos_block::BlockEvent,
+    cosmos_protobuf::{decode_fields, find_field},
+    cosmos_tx::Tx,
+    CosmosCoin,
+};
+
+/// An event emitted by a transaction, with the same shape as the events of a block
+pub type TxEvent = BlockEvent;
+
+/// Outcome of a transaction included in a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct CosmosTransactionReceipt {
+    /// Hex encoded hash of the transaction
+    pub tx_hash: String,
+    /// Height of the block including the transaction
+    pub height: u64,
+    /// Time of the block, RFC 3339. Not part of the `tx` response, empty until set from the
+    /// header of the block
+    pub block_time: String,
+    /// Result code, 0 on success
+    pub code: u32,
+    pub raw_log: String,
+    pub gas_wanted: u64,
+    pub gas_used: u64,
+    pub events: Vec<TxEvent>,
+    /// Fee paid by the transaction, decoded from its `auth_info`
+    pub fee: Vec<CosmosCoin>,
+}
+
+/// Errors returned when parsing the result of a transaction or the time of a block
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    #[error("Invalid {field}: {value}")]
+    InvalidNumber { field: &'static str, value: String },
+    #[error("Invalid transaction bytes: {0}")]
+    InvalidTransaction(String),
+    #[error("Invalid block time: {0}")]
+    InvalidTime(String),
+}
+
+/// Builds the receipt of the transaction returned by `cos_getTx`, decoding its fee from the
+/// transaction bytes
+pub fn parse_transaction_receipt(tx_result: &Tx) -> Result<CosmosTransactionReceipt, ParseError> {
+    let number = |field: &'static str, value: &str| {
+        value.parse::<u64>().map_err(|_| ParseError::InvalidNumber {
+            field,
+            value: value.to_string(),
+        })
+    };
+    let result = &tx_result.tx_result;
+    Ok(CosmosTransactionReceipt {
+        tx_hash: tx_result.hash.clone(),
+        height: number("height", &tx_result.height)?,
+        block_time: String::new(),
+        code: u32::try_from(result.code).map_err(|_| ParseError::InvalidNumber {
+            field: "code",
+            value: result.code.to_string(),
+        })?,
+        raw_log: result.log.clone(),
+        gas_wanted: number("gas_wanted", &result.gas_wanted)?,
+        gas_used: number("gas_used", &result.gas_used)?,
+        events: result.events.clone().unwrap_or_default(),
+        fee: decode_fee_amount(&tx_result.tx).map_err(ParseError::InvalidTransaction)?,
+    })
+}
+
+/// Decodes the `auth_info.fee.amount` coins of a base64 encoded `TxRaw`
+fn decode_fee_amount(tx_base64: &str) -> Result<Vec<CosmosCoin>, String> {
+    let tx_bytes = STANDARD
+        .decode(tx_base64)
+        .map_err(|e| format!("Failed to decode base64 transaction: {}", e))?;
+    // TxRaw: auth_info_bytes = 2, AuthInfo: fee = 2
+    let Some(auth_info) = find_field(&tx_bytes, 2)?.and_then(|field| field.as_bytes()) else {
+        return Ok(Vec::new());
+    };
+    let Some(fee) = find_field(auth_info, 2)?.and_then(|field| field.as_bytes()) else {
+        return Ok(Vec::new());
+    };
+    let mut coins = Vec::new();
+    // Fee: amount = 1, Coin: denom = 1, amount = 2
+    for (number, field) in decode_fields(fee)? {
+        if let (1, Some(coin)) = (number, field.as_bytes()) {
+            let mut denom = String::new();
+            let mut amount = String::new();
+            for (number, field) in decode_fields(coin)? {
+                match (number, field.as_string()) {
+                    (1, Some(value)) => denom = value,
+                    (2, Some(value)) => amount = value,
+                    _ => {}
+                }
+            }
+            coins.push(CosmosCoin::new(denom, amount));
+        }
+    }
+    Ok(coins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        cosmos_common::Event,
+        cosmos_protobuf::{encode_length_delimited, encode_string, encode_uint64},
+        cosmos_tx::TxResultData,
+    };
+
+    #[test]
+    fn test_parse_transaction_receipt() {
+        let mut coin = encode_string(0x0a, "uatom");
+        coin.extend(encode_string(0x12, "5000"));
+        let mut fee = encode_length_delimited(0x0a, &coin);
+        fee.extend(encode_uint64(0x10, 200_000));
+        let auth_info = encode_length_delimited(0x12, &fee);
+        let mut tx_raw = encode_length_delimited(0x0a, b"body");
+        tx_raw.extend(encode_length_delimited(0x12, &auth_info));
+
+        let event = TxEvent {
+            r#type: "transfer".to_string(),
+            attributes: vec![Event {
+                key: "amount".to_string(),
+                value: "1000uatom".to_string(),
+                index: true,
+            }],
+        };
+        let mut tx = Tx {
+            hash: "D4C2F1".to_string(),
+            height: "1200042".to_string(),
+            index: 0,
+            tx_result: TxResultData {
+                code: 0,
+                data: String::new(),
+                log: String::new(),
+                gas_wanted: "200000".to_string(),
+                gas_used: "81234".to_string(),
+                tags: None,
+                events: Some(vec![event.clone()]),
+            },
+            tx: STANDARD.encode(&tx_raw),
+        };
+        let receipt = parse_transaction_receipt(&tx).unwrap();
+        assert_eq!(receipt.height, 1_200_042);
+        assert_eq!(receipt.gas_wanted, 200_000);
+        assert_eq!(receipt.gas_used, 81_234);
+        assert_eq!(receipt.events, vec![event]);
+        assert_eq!(receipt.fee, vec![CosmosCoin::new("uatom", "5000")]);
+
+        tx.tx_result.gas_used = "lots".to_string();
+        assert_eq!(
+            parse_transaction_receipt(&tx),
+            Err(ParseError::InvalidNumber {
+                field: "gas_used",
+                value: "lots".to_string()
+            })
+        );
+    }
+}
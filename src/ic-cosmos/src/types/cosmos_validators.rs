@@ -1,5 +1,6 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::types::cosmos_common::PubKey;
 
@@ -27,6 +28,36 @@ pub struct ValidatorsResult {
     pub count: String,
     /// The total number of validators
     pub total: String,
+    /// The number of bonded validators in `validators`, computed client-side
+    #[serde(default)]
+    pub bonded_validators: u32,
+    /// The sum of the voting power of the bonded validators in `validators`, computed client-side
+    #[serde(default)]
+    pub total_bonded_tokens: String,
+}
+
+impl ValidatorsResult {
+    /// Computes `bonded_validators` and `total_bonded_tokens` from `validators`.
+    /// The CometBFT validator set only contains bonded validators with a non-zero voting power.
+    pub fn compute_bonded_stats(&mut self) {
+        let bonded: Vec<u128> = self
+            .validators
+            .iter()
+            .filter_map(|validator| validator.voting_power.parse::<u128>().ok())
+            .filter(|voting_power| *voting_power > 0)
+            .collect();
+        self.bonded_validators = bonded.len() as u32;
+        self.total_bonded_tokens = bonded.iter().sum::<u128>().to_string();
+    }
+}
+
+/// Bond status of a validator in the staking module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum BondStatus {
+    Unspecified,
+    Unbonded,
+    Unbonding,
+    Bonded,
 }
 
 /// Represents a validator with priority.
@@ -41,4 +72,112 @@ pub struct ValidatorWithPriority {
     pub voting_power: String,
     /// The proposer priority
     pub proposer_priority: String,
+    /// The commission rate as a decimal (e.g. "0.05"), not part of the CometBFT validator set
+    /// and only known when looked up in the staking module
+    #[serde(default)]
+    pub commission_rate: Option<String>,
+}
+
+/// Maximum number of validators with the highest voting power excluded from recommendations
+pub const RECOMMENDATION_EXCLUDED_TOP_VALIDATORS: usize = 10;
+
+/// Recommends up to `count` validators to spread stake over. The validators with the highest
+/// voting power (at most a third of the set) and jailed validators are excluded, the others are
+/// ranked by their share of the voting power plus their commission rate. Validators with an
+/// unknown commission rate rank last. Ties are broken by a hash of `delegator_address`, so that
+/// different delegators are spread across equally ranked validators.
+pub fn recommend_validators(
+    validators: &[ValidatorWithPriority],
+    delegator_address: &str,
+    count: usize,
+) -> Vec<String> {
+    let mut active: Vec<(u128, &ValidatorWithPriority)> = validators
+        .iter()
+        .filter_map(|validator| {
+            let voting_power = validator.voting_power.parse::<u128>().ok()?;
+            (voting_power > 0).then_some((voting_power, validator))
+        })
+        .collect();
+    active.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let excluded = RECOMMENDATION_EXCLUDED_TOP_VALIDATORS.min(active.len() / 3);
+    let candidates = &active[excluded..];
+    let total_power: u128 = candidates.iter().map(|(voting_power, _)| voting_power).sum();
+
+    let mut scored: Vec<(f64, [u8; 32], &str)> = candidates
+        .iter()
+        .map(|(voting_power, validator)| {
+            let power_share = *voting_power as f64 / total_power as f64;
+            let commission = validator
+                .commission_rate
+                .as_deref()
+                .and_then(|rate| rate.parse::<f64>().ok())
+                .unwrap_or(1.0);
+            let tie_breaker: [u8; 32] = Sha256::digest(format!("{}{}", delegator_address, validator.address)).into();
+            (power_share + commission, tie_breaker, validator.address.as_str())
+        })
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    scored
+        .into_iter()
+        .take(count)
+        .map(|(_, _, address)| address.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_bonded_stats() {
+        let validator = |voting_power: &str| ValidatorWithPriority {
+            address: String::new(),
+            pub_key: PubKey {
+                type_field: "tendermint/PubKeyEd25519".to_string(),
+                value: String::new(),
+            },
+            voting_power: voting_power.to_string(),
+            proposer_priority: "0".to_string(),
+            commission_rate: None,
+        };
+        let mut result = ValidatorsResult {
+            block_height: "1".to_string(),
+            validators: vec![validator("100"), validator("250"), validator("0")],
+            count: "3".to_string(),
+            total: "3".to_string(),
+            bonded_validators: 0,
+            total_bonded_tokens: String::new(),
+        };
+        result.compute_bonded_stats();
+        assert_eq!(result.bonded_validators, 2);
+        assert_eq!(result.total_bonded_tokens, "350");
+    }
+
+    #[test]
+    fn test_recommend_validators() {
+        let validator = |address: &str, voting_power: &str, commission_rate: Option<&str>| ValidatorWithPriority {
+            address: address.to_string(),
+            pub_key: PubKey {
+                type_field: "tendermint/PubKeyEd25519".to_string(),
+                value: String::new(),
+            },
+            voting_power: voting_power.to_string(),
+            proposer_priority: "0".to_string(),
+            commission_rate: commission_rate.map(|rate| rate.to_string()),
+        };
+        let validators = vec![
+            validator("TOP", "1000", Some("0.01")),
+            validator("JAILED", "0", Some("0.01")),
+            validator("CHEAP", "100", Some("0.05")),
+            validator("EXPENSIVE", "100", Some("0.20")),
+            validator("UNKNOWN", "100", None),
+            validator("SMALL", "50", Some("0.05")),
+        ];
+
+        let recommended = recommend_validators(&validators, "cosmos1delegator", 3);
+        assert_eq!(recommended, vec!["SMALL", "CHEAP", "EXPENSIVE"]);
+        assert_eq!(recommend_validators(&validators, "cosmos1delegator", 10).len(), 4);
+    }
 }
@@ -0,0 +1,283 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bech32::FromBase32;
+
+use crate::types::cosmos_protobuf::{encode_length_delimited, encode_string, find_field};
+
+/// ABCI query path of the CosmWasm smart contract query
+pub const WASM_SMART_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+
+/// ABCI query path of the CosmWasm raw contract storage query
+pub const WASM_RAW_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/RawContractState";
+
+/// ABCI query path reading a key of the store of the `wasm` module directly
+pub const WASM_STORE_KEY_PATH: &str = "/store/wasm/key";
+
+/// Prefix of the keys of the contract storages in the store of the `wasm` module
+const CONTRACT_STORE_PREFIX: u8 = 0x03;
+
+/// Type URL of the message uploading a CosmWasm contract code
+pub const MSG_STORE_CODE_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgStoreCode";
+
+/// Largest Wasm code accepted by [`validate_wasm_binary`], 1.5 MiB
+pub const DEFAULT_MAX_WASM_CODE_SIZE: usize = 1536 * 1024;
+
+/// Magic bytes of a Wasm binary, `\0asm`
+const WASM_MAGIC: [u8; 4] = *b"\0asm";
+
+/// Version of the Wasm binary format
+const WASM_VERSION: u32 = 1;
+
+/// Reason why the code of a `MsgStoreCode` is not a valid Wasm binary
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WasmValidationError {
+    #[error("Wasm binary is empty")]
+    Empty,
+    #[error("Wasm binary of {size} bytes exceeds the maximum code size of {max_size} bytes")]
+    TooLarge { size: usize, max_size: usize },
+    #[error("Missing the Wasm magic bytes, gzipped code must be decompressed first")]
+    InvalidMagic,
+    #[error("Unsupported Wasm version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Invalid section at offset {offset}: {reason}")]
+    InvalidSection { offset: usize, reason: String },
+}
+
+/// Encode `QuerySmartContractStateRequest` as hex `abci_query` data
+pub fn encode_smart_contract_state_query(
+    contract_address: &str,
+    query_msg: &serde_json::Value,
+) -> Result<String, String> {
+    let query_data = serde_json::to_vec(query_msg).map_err(|e| format!("Failed to encode query message: {}", e))?;
+
+    let mut request_bytes = Vec::new();
+    request_bytes.extend(encode_string(0x0a, contract_address)); // address = 1
+    request_bytes.extend(encode_length_delimited(0x12, &query_data)); // query_data = 2
+
+    Ok(hex::encode(request_bytes))
+}
+
+/// Encode `QueryRawContractStateRequest` as hex `abci_query` data
+pub fn encode_raw_contract_state_query(contract_address: &str, key: &[u8]) -> String {
+    let mut request_bytes = Vec::new();
+    request_bytes.extend(encode_string(0x0a, contract_address)); // address = 1
+    request_bytes.extend(encode_length_delimited(0x12, key)); // query_data = 2
+
+    hex::encode(request_bytes)
+}
+
+/// Decode a contract storage key given in hex, with or without `0x`, or in base64
+pub fn decode_storage_key(storage_key: &str) -> Result<Vec<u8>, String> {
+    let hex_key = storage_key.strip_prefix("0x").unwrap_or(storage_key);
+    hex::decode(hex_key)
+        .or_else(|_| STANDARD.decode(storage_key))
+        .map_err(|_| format!("Invalid storage key {}, expected hex or base64", storage_key))
+}
+
+/// Encode the key of `storage_key` in the store of the `wasm` module as hex `abci_query` data for
+/// [`WASM_STORE_KEY_PATH`]: the contract storage prefix, the address bytes of the contract, then
+/// the storage key
+pub fn encode_contract_store_key(contract_address: &str, storage_key: &[u8]) -> Result<String, String> {
+    let (_, data, _) = bech32::decode(contract_address).map_err(|e| format!("Invalid contract address: {}", e))?;
+    let address = Vec::<u8>::from_base32(&data).map_err(|e| format!("Invalid contract address: {}", e))?;
+
+    let mut key = vec![CONTRACT_STORE_PREFIX];
+    key.extend(address);
+    key.extend(storage_key);
+    Ok(hex::encode(key))
+}
+
+/// Decode the `data` field of a `QuerySmartContractStateResponse` or
+/// `QueryRawContractStateResponse` from the base64 ABCI response value
+pub fn decode_contract_state_response(response_value: &str) -> Result<Vec<u8>, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // data = 1, empty when the raw storage key does not exist
+    Ok(find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .map(|data| data.to_vec())
+        .unwrap_or_default())
+}
+
+/// Check that `bytes` is a Wasm module of at most [`DEFAULT_MAX_WASM_CODE_SIZE`] bytes,
+/// see [`validate_wasm_binary_with_max_size`]
+pub fn validate_wasm_binary(bytes: &[u8]) -> Result<(), WasmValidationError> {
+    validate_wasm_binary_with_max_size(bytes, DEFAULT_MAX_WASM_CODE_SIZE)
+}
+
+/// Check that `bytes` is a Wasm module of at most `max_size` bytes: the header is the magic
+/// bytes and version 1, and the sections are well formed, known and in order. The contents
+/// of the sections are not validated, the chain still rejects modules which do not compile.
+pub fn validate_wasm_binary_with_max_size(bytes: &[u8], max_size: usize) -> Result<(), WasmValidationError> {
+    if bytes.is_empty() {
+        return Err(WasmValidationError::Empty);
+    }
+    if bytes.len() > max_size {
+        return Err(WasmValidationError::TooLarge {
+            size: bytes.len(),
+            max_size,
+        });
+    }
+    if bytes.len() < 8 || bytes[..4] != WASM_MAGIC {
+        return Err(WasmValidationError::InvalidMagic);
+    }
+    let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    if version != WASM_VERSION {
+        return Err(WasmValidationError::UnsupportedVersion(version));
+    }
+
+    let mut pos = 8;
+    let mut last_rank = 0;
+    while pos < bytes.len() {
+        let offset = pos;
+        let invalid = |reason: String| WasmValidationError::InvalidSection { offset, reason };
+        let id = bytes[pos];
+        pos += 1;
+        let size = read_leb128_u32(bytes, &mut pos).map_err(invalid)? as usize;
+        let end = pos
+            .checked_add(size)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| invalid(format!("section {} of {} bytes overflows the binary", id, size)))?;
+        // Custom sections may appear anywhere, the others once and in order
+        if id != 0 {
+            let rank = section_rank(id).ok_or_else(|| invalid(format!("unknown section id {}", id)))?;
+            if rank <= last_rank {
+                return Err(invalid(format!("section {} is duplicated or out of order", id)));
+            }
+            last_rank = rank;
+        }
+        pos = end;
+    }
+    Ok(())
+}
+
+/// Position of a non-custom section in a module, the data count section (12) comes before the code section (10)
+fn section_rank(id: u8) -> Option<u8> {
+    match id {
+        1..=9 => Some(id),
+        12 => Some(10),
+        10 | 11 => Some(id + 1),
+        _ => None,
+    }
+}
+
+/// Read an unsigned LEB128 `u32` at `pos`, advancing `pos` past it
+fn read_leb128_u32(bytes: &[u8], pos: &mut usize) -> Result<u32, String> {
+    let mut value = 0u64;
+    for i in 0..5 {
+        let byte = *bytes.get(*pos).ok_or("unexpected end of the section size")?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return u32::try_from(value).map_err(|_| "section size overflows u32".to_string());
+        }
+    }
+    Err("section size overflows u32".to_string())
+}
+
+/// Encode MsgStoreCode to protobuf bytes, with the `wasm_byte_code` given as base64.
+/// The code is validated first, the instantiate permission is left to the chain default.
+pub fn encode_msg_store_code(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let sender = value["sender"].as_str().ok_or("Missing sender in MsgStoreCode")?;
+    let wasm_byte_code = STANDARD
+        .decode(
+            value["wasm_byte_code"]
+                .as_str()
+                .ok_or("Missing wasm_byte_code in MsgStoreCode")?,
+        )
+        .map_err(|e| format!("Invalid base64 wasm_byte_code in MsgStoreCode: {}", e))?;
+    validate_wasm_binary(&wasm_byte_code).map_err(|e| e.to_string())?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, sender)); // sender = 1
+    msg_bytes.extend(encode_length_delimited(0x12, &wasm_byte_code)); // wasm_byte_code = 2
+    Ok(msg_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Module with an empty type section, a custom section and an empty code section
+    fn minimal_module() -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\x00\x00\x00".to_vec();
+        bytes.extend([0x01, 0x01, 0x00]); // type section, no types
+        bytes.extend([0x00, 0x05, 0x04, b'n', b'a', b'm', b'e']); // custom section "name"
+        bytes.extend([0x0a, 0x01, 0x00]); // code section, no functions
+        bytes
+    }
+
+    #[test]
+    fn test_validate_wasm_binary() {
+        let module = minimal_module();
+        assert_eq!(validate_wasm_binary(&module), Ok(()));
+        assert_eq!(validate_wasm_binary(&module[..8]), Ok(()));
+
+        assert_eq!(validate_wasm_binary(&[]), Err(WasmValidationError::Empty));
+        assert_eq!(
+            validate_wasm_binary_with_max_size(&module, 8),
+            Err(WasmValidationError::TooLarge {
+                size: module.len(),
+                max_size: 8
+            })
+        );
+        assert_eq!(
+            validate_wasm_binary(&[0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            Err(WasmValidationError::InvalidMagic)
+        );
+        assert_eq!(
+            validate_wasm_binary(b"\0asm\x02\x00\x00\x00"),
+            Err(WasmValidationError::UnsupportedVersion(2))
+        );
+
+        let mut truncated = module.clone();
+        truncated.truncate(truncated.len() - 1);
+        assert!(matches!(
+            validate_wasm_binary(&truncated),
+            Err(WasmValidationError::InvalidSection { offset: 18, .. })
+        ));
+        let mut out_of_order = module.clone();
+        out_of_order.extend([0x01, 0x01, 0x00]);
+        assert!(matches!(
+            validate_wasm_binary(&out_of_order),
+            Err(WasmValidationError::InvalidSection { offset: 21, .. })
+        ));
+        let mut unknown = module;
+        unknown.extend([0x0d, 0x00]);
+        assert!(validate_wasm_binary(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_encode_contract_store_key() {
+        let contract_address = "osmo1qypqzqspqgqsyqgzqypqzqspqgqsyqgzqypqzqspqgqsyqgzqypq8xwtve";
+        let key = decode_storage_key("0x636f6e666967").unwrap();
+        assert_eq!(key, b"config");
+        assert_eq!(decode_storage_key("Y29uZmln").unwrap(), key);
+        assert!(decode_storage_key("not a key!").is_err());
+
+        let expected = format!("03{}{}", "0102".repeat(16), hex::encode("config"));
+        assert_eq!(encode_contract_store_key(contract_address, &key).unwrap(), expected);
+        assert!(encode_contract_store_key("osmo1invalid", &key).is_err());
+    }
+
+    #[test]
+    fn test_encode_msg_store_code() {
+        let module = minimal_module();
+        let msg = json!({
+            "sender": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "wasm_byte_code": STANDARD.encode(&module),
+        });
+        let mut expected = encode_string(0x0a, "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6");
+        expected.extend(encode_length_delimited(0x12, &module));
+        assert_eq!(encode_msg_store_code(&msg).unwrap(), expected);
+
+        let invalid = json!({
+            "sender": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "wasm_byte_code": STANDARD.encode(b"not wasm"),
+        });
+        assert!(encode_msg_store_code(&invalid).is_err());
+    }
+}
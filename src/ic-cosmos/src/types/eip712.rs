@@ -0,0 +1,255 @@
+//! EIP-712 typed data of Cosmos transactions in the legacy Evmos `TypedData` format, signed by
+//! Ethereum wallets for transactions with [`SignMode::Eip712Cw`].
+//!
+//! [`SignMode::Eip712Cw`]: crate::types::SignMode::Eip712Cw
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    amino::{amino_sign_doc, canonical_json_serialize},
+    types::cosmos_transaction_utils::{extract_signer_address_from_message, CosmosTransaction},
+};
+
+/// Errors returned by [`create_eip712_sign_doc`] and [`amino_sign_doc`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignError {
+    #[error("Transaction has no messages")]
+    NoMessages,
+    #[error("Message {0} has no Amino encoding and cannot be signed in Amino JSON or EIP-712")]
+    UnsupportedMessage(String),
+    /// The types of the typed data describe a single message type
+    #[error("EIP-712 transactions contain messages of a single type, got {0} and {1}")]
+    MixedMessageTypes(String, String),
+    #[error("Invalid EVM chain id {0}, expected e.g. evmos_9001-2")]
+    InvalidChainId(String),
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+}
+
+/// Returns the EIP-155 chain id of an Ethermint chain id, e.g. 9001 for `evmos_9001-2`
+pub fn parse_evm_chain_id(chain_id: &str) -> Result<u64, SignError> {
+    chain_id
+        .split_once('_')
+        .and_then(|(_, rest)| rest.split_once('-'))
+        .and_then(|(number, _)| number.parse().ok())
+        .ok_or_else(|| SignError::InvalidChainId(chain_id.to_string()))
+}
+
+/// Returns the EIP-712 typed data signed for `transaction` on Evmos, with its `types`,
+/// `primaryType`, `domain` and `message`. The message is the Amino JSON sign doc of the
+/// transaction, with the signer of the first message as fee payer.
+pub fn create_eip712_sign_doc(transaction: &CosmosTransaction) -> Result<Value, SignError> {
+    let first = transaction.messages.first().ok_or(SignError::NoMessages)?;
+    let fee_payer = extract_signer_address_from_message(first).map_err(SignError::InvalidMessage)?;
+    if let Some(message) = transaction
+        .messages
+        .iter()
+        .find(|message| message.type_url != first.type_url)
+    {
+        return Err(SignError::MixedMessageTypes(
+            first.type_url.clone(),
+            message.type_url.clone(),
+        ));
+    }
+
+    let mut sign_doc = amino_sign_doc(transaction)?;
+    sign_doc["fee"]["feePayer"] = fee_payer.into();
+    // Amino JSON writes 64-bit integers as strings and drops null fields
+    let message: Value = serde_json::from_str(&canonical_json_serialize(&sign_doc))
+        .map_err(|e| SignError::InvalidMessage(e.to_string()))?;
+
+    let mut types = Map::new();
+    types.insert(
+        "EIP712Domain".to_string(),
+        json!([
+            { "name": "name", "type": "string" },
+            { "name": "version", "type": "string" },
+            { "name": "chainId", "type": "uint256" },
+            { "name": "verifyingContract", "type": "string" },
+            { "name": "salt", "type": "string" }
+        ]),
+    );
+    types.insert(
+        "Tx".to_string(),
+        json!([
+            { "name": "account_number", "type": "string" },
+            { "name": "chain_id", "type": "string" },
+            { "name": "fee", "type": "Fee" },
+            { "name": "memo", "type": "string" },
+            { "name": "msgs", "type": "Msg[]" },
+            { "name": "sequence", "type": "string" }
+        ]),
+    );
+    types.insert(
+        "Fee".to_string(),
+        json!([
+            { "name": "feePayer", "type": "string" },
+            { "name": "amount", "type": "Coin[]" },
+            { "name": "gas", "type": "string" }
+        ]),
+    );
+    types.insert(
+        "Coin".to_string(),
+        json!([
+            { "name": "denom", "type": "string" },
+            { "name": "amount", "type": "string" }
+        ]),
+    );
+    types.insert(
+        "Msg".to_string(),
+        json!([
+            { "name": "type", "type": "string" },
+            { "name": "value", "type": "MsgValue" }
+        ]),
+    );
+    let value = message["msgs"][0]["value"]
+        .as_object()
+        .ok_or_else(|| SignError::InvalidMessage(format!("{} value is not an object", first.type_url)))?;
+    add_struct_type("MsgValue", "Type", value, &mut types);
+
+    Ok(json!({
+        "types": types,
+        "primaryType": "Tx",
+        "domain": {
+            "name": "Cosmos Web3",
+            "version": "1.0.0",
+            "chainId": parse_evm_chain_id(&transaction.chain_id)?,
+            "verifyingContract": "cosmos",
+            "salt": "0"
+        },
+        "message": message
+    }))
+}
+
+/// Adds the type `name` of the fields of an Amino JSON object to `types`, and the types of its
+/// nested objects, named `prefix` followed by the field name in PascalCase (e.g. `TypeAmount`)
+fn add_struct_type(name: &str, prefix: &str, fields: &Map<String, Value>, types: &mut Map<String, Value>) {
+    let mut definitions = Vec::new();
+    for (field, value) in fields {
+        let mut nested_type = |fields: &Map<String, Value>| {
+            let nested_name = format!("{}{}", prefix, pascal_case(field));
+            add_struct_type(&nested_name, &nested_name, fields, types);
+            nested_name
+        };
+        let field_type = match value {
+            Value::Null => continue,
+            Value::Bool(_) => "bool".to_string(),
+            // 64-bit integers are strings in Amino JSON, the remaining numbers are int32 enums
+            Value::Number(_) => "int32".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Object(fields) => nested_type(fields),
+            Value::Array(items) => match items.first() {
+                Some(Value::Object(fields)) => format!("{}[]", nested_type(fields)),
+                Some(Value::Bool(_)) => "bool[]".to_string(),
+                Some(Value::Number(_)) => "int32[]".to_string(),
+                _ => "string[]".to_string(),
+            },
+        };
+        definitions.push(json!({ "name": field, "type": field_type }));
+    }
+    types.insert(name.to_string(), Value::Array(definitions));
+}
+
+fn pascal_case(field: &str) -> String {
+    field
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CosmosCoin, CosmosMessage, SignMode};
+
+    fn transaction(messages: Vec<CosmosMessage>) -> CosmosTransaction {
+        CosmosTransaction {
+            messages,
+            fee: vec![CosmosCoin::new("aevmos", "4000000000000000")],
+            gas_limit: 200_000,
+            memo: String::new(),
+            chain_id: "evmos_9001-2".to_string(),
+            account_number: 12,
+            sequence: 3,
+            extension_options: Vec::new(),
+            non_critical_extension_options: Vec::new(),
+            sign_mode: SignMode::Eip712Cw,
+        }
+    }
+
+    fn send() -> CosmosMessage {
+        CosmosMessage {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+            value: json!({
+                "from_address": "evmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmrcp32r",
+                "to_address": "evmos1zt50azupanqlfam5afhv3hexwyutnukezkkqnd",
+                "amount": [{ "denom": "aevmos", "amount": "1000" }]
+            }),
+        }
+    }
+
+    #[test]
+    fn test_create_eip712_sign_doc() {
+        let typed_data = create_eip712_sign_doc(&transaction(vec![send()])).unwrap();
+        assert_eq!(typed_data["primaryType"], "Tx");
+        assert_eq!(typed_data["domain"]["chainId"], 9001);
+        assert_eq!(
+            typed_data["types"]["MsgValue"],
+            json!([
+                { "name": "amount", "type": "TypeAmount[]" },
+                { "name": "from_address", "type": "string" },
+                { "name": "to_address", "type": "string" }
+            ])
+        );
+        assert_eq!(
+            typed_data["types"]["TypeAmount"],
+            json!([
+                { "name": "amount", "type": "string" },
+                { "name": "denom", "type": "string" }
+            ])
+        );
+        let message = &typed_data["message"];
+        assert_eq!(message["account_number"], "12");
+        assert_eq!(message["sequence"], "3");
+        assert_eq!(message["fee"]["gas"], "200000");
+        assert_eq!(
+            message["fee"]["feePayer"],
+            "evmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmrcp32r"
+        );
+        assert_eq!(message["msgs"][0]["type"], "cosmos-sdk/MsgSend");
+        assert_eq!(message["msgs"][0]["value"]["amount"][0]["amount"], "1000");
+    }
+
+    #[test]
+    fn test_create_eip712_sign_doc_errors() {
+        let delegate = CosmosMessage {
+            type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+            value: json!({
+                "delegator_address": "evmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmrcp32r",
+                "validator_address": "evmosvaloper1zt50azupanqlfam5afhv3hexwyutnuke2vxlck",
+                "amount": { "denom": "aevmos", "amount": "1000" }
+            }),
+        };
+        assert!(matches!(
+            create_eip712_sign_doc(&transaction(vec![send(), delegate])),
+            Err(SignError::MixedMessageTypes(..))
+        ));
+        assert_eq!(
+            create_eip712_sign_doc(&transaction(Vec::new())),
+            Err(SignError::NoMessages)
+        );
+
+        let mut tx = transaction(vec![send()]);
+        tx.chain_id = "cosmoshub-4".to_string();
+        assert_eq!(
+            create_eip712_sign_doc(&tx),
+            Err(SignError::InvalidChainId("cosmoshub-4".to_string()))
+        );
+    }
+}
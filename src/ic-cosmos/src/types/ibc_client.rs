@@ -0,0 +1,534 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::Value;
+
+use crate::types::cosmos_protobuf::{encode_length_delimited, encode_string, encode_uint64};
+
+/// Type URL of the message updating an IBC light client
+pub const MSG_UPDATE_CLIENT_TYPE_URL: &str = "/ibc.core.client.v1.MsgUpdateClient";
+
+/// Type URL of the client message of the Tendermint light clients
+pub const TENDERMINT_HEADER_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.Header";
+
+/// Encode MsgUpdateClient to protobuf bytes.
+/// `client_message` is either a Tendermint header, `{ "@type": "/ibc.lightclients.tendermint.v1.Header",
+/// "signed_header", "validator_set", "trusted_height", "trusted_validators" }`, or the message of any
+/// other client as `{ "@type": "...", "value": "<base64 protobuf>" }`.
+pub fn encode_msg_update_client(value: &Value) -> Result<Vec<u8>, String> {
+    let client_id = value["client_id"]
+        .as_str()
+        .ok_or("Missing client_id in MsgUpdateClient")?;
+    let signer = value["signer"].as_str().ok_or("Missing signer in MsgUpdateClient")?;
+    let client_message = &value["client_message"];
+    let type_url = client_message["@type"]
+        .as_str()
+        .ok_or("Missing @type in client_message of MsgUpdateClient")?;
+
+    let message_bytes = match client_message["value"].as_str() {
+        Some(message) => STANDARD
+            .decode(message)
+            .map_err(|e| format!("Invalid base64 client_message: {}", e))?,
+        None if type_url == TENDERMINT_HEADER_TYPE_URL => encode_tendermint_header(client_message)?,
+        None => return Err(format!("Missing value of client_message {}", type_url)),
+    };
+
+    let mut any_bytes = Vec::new();
+    any_bytes.extend(encode_string(0x0a, type_url)); // type_url = 1
+    any_bytes.extend(encode_length_delimited(0x12, &message_bytes)); // value = 2
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, client_id)); // client_id = 1
+    msg_bytes.extend(encode_length_delimited(0x12, &any_bytes)); // client_message = 2
+    msg_bytes.extend(encode_string(0x1a, signer)); // signer = 3
+
+    Ok(msg_bytes)
+}
+
+/// Encode an `ibc.lightclients.tendermint.v1.Header`. The signed header and the validator sets use
+/// the JSON of the CometBFT RPC: the `signed_header` of `commit` and the validators of `validators`,
+/// with hex hashes and addresses and base64 keys and signatures.
+/// The proposer of a validator set without one is its validator with the highest priority.
+pub fn encode_tendermint_header(value: &Value) -> Result<Vec<u8>, String> {
+    let signed_header = field(value, "signed_header")?;
+    let header_bytes = encode_header(field(signed_header, "header")?)?;
+    let commit_bytes = encode_commit(field(signed_header, "commit")?)?;
+    let mut signed_header_bytes = Vec::new();
+    signed_header_bytes.extend(encode_length_delimited(0x0a, &header_bytes)); // header = 1
+    signed_header_bytes.extend(encode_length_delimited(0x12, &commit_bytes)); // commit = 2
+
+    let validator_set_bytes = encode_validator_set(field(value, "validator_set")?)?;
+    let trusted_height_bytes = encode_height(field(value, "trusted_height")?)?;
+    let trusted_validators_bytes = encode_validator_set(field(value, "trusted_validators")?)?;
+
+    let mut bytes = Vec::new();
+    bytes.extend(encode_length_delimited(0x0a, &signed_header_bytes)); // signed_header = 1
+    bytes.extend(encode_length_delimited(0x12, &validator_set_bytes)); // validator_set = 2
+    bytes.extend(encode_length_delimited(0x1a, &trusted_height_bytes)); // trusted_height = 3
+    bytes.extend(encode_length_delimited(0x22, &trusted_validators_bytes)); // trusted_validators = 4
+
+    Ok(bytes)
+}
+
+/// Encode a `tendermint.types.Header`
+fn encode_header(header: &Value) -> Result<Vec<u8>, String> {
+    let version = field(header, "version")?;
+    let mut version_bytes = Vec::new();
+    version_bytes.extend(encode_uint64(0x08, json_u64(&version["block"], "version.block")?)); // block = 1
+    version_bytes.extend(encode_uint64(0x10, json_u64(&version["app"], "version.app")?)); // app = 2
+
+    let chain_id = header["chain_id"].as_str().ok_or("Missing chain_id in header")?;
+    let height = json_i64(&header["height"], "height")?;
+    let time = header["time"].as_str().ok_or("Missing time in header")?;
+    let last_block_id_bytes = encode_block_id(field(header, "last_block_id")?)?;
+
+    let mut bytes = Vec::new();
+    bytes.extend(encode_length_delimited(0x0a, &version_bytes)); // version = 1
+    bytes.extend(encode_string(0x12, chain_id)); // chain_id = 2
+    bytes.extend(encode_uint64(0x18, height as u64)); // height = 3
+    bytes.extend(encode_length_delimited(0x22, &encode_timestamp(time)?)); // time = 4
+    bytes.extend(encode_length_delimited(0x2a, &last_block_id_bytes)); // last_block_id = 5
+
+    // last_commit_hash = 6 to proposer_address = 14
+    let hashes = [
+        (0x32, "last_commit_hash"),
+        (0x3a, "data_hash"),
+        (0x42, "validators_hash"),
+        (0x4a, "next_validators_hash"),
+        (0x52, "consensus_hash"),
+        (0x5a, "app_hash"),
+        (0x62, "last_results_hash"),
+        (0x6a, "evidence_hash"),
+        (0x72, "proposer_address"),
+    ];
+    for (tag, name) in hashes {
+        bytes.extend(encode_length_delimited(tag, &json_hex(&header[name], name)?));
+    }
+
+    Ok(bytes)
+}
+
+/// Encode a `tendermint.types.Commit`
+fn encode_commit(commit: &Value) -> Result<Vec<u8>, String> {
+    let height = json_i64(&commit["height"], "commit.height")?;
+    let round = json_u64(&commit["round"], "commit.round")?;
+    let block_id_bytes = encode_block_id(field(commit, "block_id")?)?;
+    let signatures = commit["signatures"]
+        .as_array()
+        .ok_or("Missing signatures array in commit")?;
+
+    let mut bytes = Vec::new();
+    bytes.extend(encode_uint64(0x08, height as u64)); // height = 1
+    bytes.extend(encode_uint64(0x10, round)); // round = 2
+    bytes.extend(encode_length_delimited(0x1a, &block_id_bytes)); // block_id = 3
+
+    for signature in signatures {
+        let flag = block_id_flag(&signature["block_id_flag"])?;
+        let validator_address = json_hex(&signature["validator_address"], "validator_address")?;
+        let timestamp = signature["timestamp"]
+            .as_str()
+            .ok_or("Missing timestamp in commit signature")?;
+        // Absent votes have a null signature
+        let signature = json_base64(&signature["signature"], "signature")?;
+
+        let mut sig_bytes = Vec::new();
+        sig_bytes.extend(encode_uint64(0x08, flag)); // block_id_flag = 1
+        sig_bytes.extend(encode_length_delimited(0x12, &validator_address)); // validator_address = 2
+        sig_bytes.extend(encode_length_delimited(0x1a, &encode_timestamp(timestamp)?)); // timestamp = 3
+        sig_bytes.extend(encode_length_delimited(0x22, &signature)); // signature = 4
+        bytes.extend(encode_length_delimited(0x22, &sig_bytes)); // signatures = 4
+    }
+
+    Ok(bytes)
+}
+
+/// Encode a `tendermint.types.BlockID`, whose part set header is called `parts` in the RPC JSON
+fn encode_block_id(block_id: &Value) -> Result<Vec<u8>, String> {
+    let parts = match &block_id["parts"] {
+        Value::Null => &block_id["part_set_header"],
+        parts => parts,
+    };
+    let mut parts_bytes = Vec::new();
+    parts_bytes.extend(encode_uint64(0x08, json_u64(&parts["total"], "parts.total")?)); // total = 1
+    parts_bytes.extend(encode_length_delimited(0x12, &json_hex(&parts["hash"], "parts.hash")?)); // hash = 2
+
+    let hash = json_hex(&block_id["hash"], "block_id.hash")?;
+    let mut bytes = Vec::new();
+    bytes.extend(encode_length_delimited(0x0a, &hash)); // hash = 1
+    bytes.extend(encode_length_delimited(0x12, &parts_bytes)); // part_set_header = 2
+    Ok(bytes)
+}
+
+/// Encode a `tendermint.types.ValidatorSet`
+fn encode_validator_set(validator_set: &Value) -> Result<Vec<u8>, String> {
+    let validators = validator_set["validators"]
+        .as_array()
+        .ok_or("Missing validators array in validator set")?;
+
+    let mut bytes = Vec::new();
+    let mut total_voting_power = 0i64;
+    let mut highest_priority: Option<(i64, &Value)> = None;
+    for validator in validators {
+        bytes.extend(encode_length_delimited(0x0a, &encode_validator(validator)?)); // validators = 1
+        total_voting_power += json_i64(&validator["voting_power"], "voting_power")?;
+        let priority = json_i64(&validator["proposer_priority"], "proposer_priority")?;
+        if highest_priority.is_none_or(|(highest, _)| priority > highest) {
+            highest_priority = Some((priority, validator));
+        }
+    }
+
+    let proposer = match &validator_set["proposer"] {
+        Value::Null => highest_priority.map(|(_, validator)| validator),
+        proposer => Some(proposer),
+    };
+    if let Some(proposer) = proposer {
+        bytes.extend(encode_length_delimited(0x12, &encode_validator(proposer)?));
+        // proposer = 2
+    }
+
+    if !validator_set["total_voting_power"].is_null() {
+        total_voting_power = json_i64(&validator_set["total_voting_power"], "total_voting_power")?;
+    }
+    bytes.extend(encode_uint64(0x18, total_voting_power as u64)); // total_voting_power = 3
+
+    Ok(bytes)
+}
+
+/// Encode a `tendermint.types.Validator`
+fn encode_validator(validator: &Value) -> Result<Vec<u8>, String> {
+    let address = json_hex(&validator["address"], "address")?;
+    let pub_key = field(validator, "pub_key")?;
+    let key = json_base64(&pub_key["value"], "pub_key.value")?;
+    // PublicKey is a oneof of ed25519 = 1 and secp256k1 = 2
+    let pub_key_bytes = match pub_key["type"].as_str() {
+        Some("tendermint/PubKeyEd25519") => encode_length_delimited(0x0a, &key),
+        Some("tendermint/PubKeySecp256k1") => encode_length_delimited(0x12, &key),
+        key_type => return Err(format!("Unsupported validator key type {:?}", key_type)),
+    };
+    let voting_power = json_i64(&validator["voting_power"], "voting_power")?;
+    // Negative priorities are encoded as 10-byte two's complement varints
+    let proposer_priority = json_i64(&validator["proposer_priority"], "proposer_priority")?;
+
+    let mut bytes = Vec::new();
+    bytes.extend(encode_length_delimited(0x0a, &address)); // address = 1
+    bytes.extend(encode_length_delimited(0x12, &pub_key_bytes)); // pub_key = 2
+    bytes.extend(encode_uint64(0x18, voting_power as u64)); // voting_power = 3
+    bytes.extend(encode_uint64(0x20, proposer_priority as u64)); // proposer_priority = 4
+    Ok(bytes)
+}
+
+/// Encode an `ibc.core.client.v1.Height`
+fn encode_height(height: &Value) -> Result<Vec<u8>, String> {
+    let revision_number = json_u64(&height["revision_number"], "revision_number")?;
+    let revision_height = json_u64(&height["revision_height"], "revision_height")?;
+    let mut bytes = Vec::new();
+    bytes.extend(encode_uint64(0x08, revision_number)); // revision_number = 1
+    bytes.extend(encode_uint64(0x10, revision_height)); // revision_height = 2
+    Ok(bytes)
+}
+
+/// Encode an RFC 3339 time as a `google.protobuf.Timestamp`
+fn encode_timestamp(time: &str) -> Result<Vec<u8>, String> {
+    let (seconds, nanos) = parse_timestamp(time)?;
+    let mut bytes = Vec::new();
+    bytes.extend(encode_uint64(0x08, seconds as u64)); // seconds = 1
+    bytes.extend(encode_uint64(0x10, nanos as u64)); // nanos = 2
+    Ok(bytes)
+}
+
+/// Parse an RFC 3339 time, e.g. "2024-03-01T12:30:45.123456789Z", as seconds and nanoseconds
+/// since the Unix epoch
+pub fn parse_timestamp(time: &str) -> Result<(i64, i32), String> {
+    let invalid = || format!("Invalid timestamp {}", time);
+    let number = |s: &str| s.parse::<i64>().map_err(|_| invalid());
+
+    let (date, rest) = time.split_once('T').ok_or_else(invalid)?;
+    let (clock, offset_seconds) = match rest.strip_suffix('Z') {
+        Some(clock) => (clock, 0),
+        None => {
+            let split = rest.rfind(['+', '-']).ok_or_else(invalid)?;
+            let (clock, offset) = rest.split_at(split);
+            let (hours, minutes) = offset[1..].split_once(':').ok_or_else(invalid)?;
+            let offset_seconds = number(hours)? * 3600 + number(minutes)? * 60;
+            (
+                clock,
+                if offset.starts_with('-') {
+                    -offset_seconds
+                } else {
+                    offset_seconds
+                },
+            )
+        }
+    };
+
+    let [year, month, day] = split3(date, '-').ok_or_else(invalid)?;
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let [hours, minutes, seconds] = split3(clock, ':').ok_or_else(invalid)?;
+    let (year, month, day) = (number(year)?, number(month)?, number(day)?);
+    let (hours, minutes, seconds) = (number(hours)?, number(minutes)?, number(seconds)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return Err(invalid());
+    }
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let nanos = format!("{:0<9}", fraction).parse::<i32>().map_err(|_| invalid())?;
+
+    let days = days_from_civil(year, month, day);
+    Ok((
+        days * 86_400 + hours * 3600 + minutes * 60 + seconds - offset_seconds,
+        nanos,
+    ))
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn split3(s: &str, separator: char) -> Option<[&str; 3]> {
+    let mut parts = s.splitn(3, separator);
+    Some([parts.next()?, parts.next()?, parts.next()?])
+}
+
+/// `tendermint.types.BlockIDFlag`, given as a number or by name
+fn block_id_flag(value: &Value) -> Result<u64, String> {
+    match value.as_str() {
+        Some("BLOCK_ID_FLAG_ABSENT") => Ok(1),
+        Some("BLOCK_ID_FLAG_COMMIT") => Ok(2),
+        Some("BLOCK_ID_FLAG_NIL") => Ok(3),
+        _ => json_u64(value, "block_id_flag"),
+    }
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, String> {
+    match &value[name] {
+        Value::Null => Err(format!("Missing {} in Tendermint header", name)),
+        field => Ok(field),
+    }
+}
+
+/// Reads a uint64 given either as a JSON string or number, 0 when missing
+fn json_u64(value: &Value, name: &str) -> Result<u64, String> {
+    match value {
+        Value::Null => Ok(0),
+        Value::String(s) => s.parse::<u64>().map_err(|e| format!("Invalid {}: {}", name, e)),
+        Value::Number(n) => n.as_u64().ok_or_else(|| format!("Invalid {}: {}", name, n)),
+        _ => Err(format!("Invalid {}: {}", name, value)),
+    }
+}
+
+/// Reads an int64 given either as a JSON string or number, 0 when missing
+fn json_i64(value: &Value, name: &str) -> Result<i64, String> {
+    match value {
+        Value::Null => Ok(0),
+        Value::String(s) => s.parse::<i64>().map_err(|e| format!("Invalid {}: {}", name, e)),
+        Value::Number(n) => n.as_i64().ok_or_else(|| format!("Invalid {}: {}", name, n)),
+        _ => Err(format!("Invalid {}: {}", name, value)),
+    }
+}
+
+/// Reads hex bytes, empty when missing
+fn json_hex(value: &Value, name: &str) -> Result<Vec<u8>, String> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::String(s) => hex::decode(s).map_err(|e| format!("Invalid hex {}: {}", name, e)),
+        _ => Err(format!("Invalid {}: {}", name, value)),
+    }
+}
+
+/// Reads base64 bytes, empty when missing
+fn json_base64(value: &Value, name: &str) -> Result<Vec<u8>, String> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::String(s) => STANDARD
+            .decode(s)
+            .map_err(|e| format!("Invalid base64 {}: {}", name, e)),
+        _ => Err(format!("Invalid {}: {}", name, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmos_sdk_proto::{
+        tendermint::{
+            crypto::public_key::Sum,
+            types::{BlockIdFlag, SignedHeader, ValidatorSet},
+        },
+        traits::Message,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::cosmos_protobuf::decode_fields;
+
+    const VALIDATOR_ADDRESS: &str = "B00A6323737F321EB0B8D59C6FD497A14B60938A";
+
+    fn validator(priority: &str) -> Value {
+        json!({
+            "address": VALIDATOR_ADDRESS,
+            "pub_key": {
+                "type": "tendermint/PubKeyEd25519",
+                "value": STANDARD.encode([7u8; 32])
+            },
+            "voting_power": "1000",
+            "proposer_priority": priority
+        })
+    }
+
+    fn tendermint_header() -> Value {
+        let block_id = json!({
+            "hash": "A".repeat(64),
+            "parts": { "total": 1, "hash": "B".repeat(64) }
+        });
+        json!({
+            "@type": TENDERMINT_HEADER_TYPE_URL,
+            "signed_header": {
+                "header": {
+                    "version": { "block": "11", "app": "0" },
+                    "chain_id": "cosmoshub-4",
+                    "height": "20000000",
+                    "time": "2024-03-01T12:30:45.123456789Z",
+                    "last_block_id": block_id,
+                    "last_commit_hash": "C".repeat(64),
+                    "data_hash": "",
+                    "validators_hash": "D".repeat(64),
+                    "next_validators_hash": "D".repeat(64),
+                    "consensus_hash": "E".repeat(64),
+                    "app_hash": "F".repeat(64),
+                    "last_results_hash": "",
+                    "evidence_hash": "",
+                    "proposer_address": VALIDATOR_ADDRESS
+                },
+                "commit": {
+                    "height": "20000000",
+                    "round": 0,
+                    "block_id": block_id,
+                    "signatures": [
+                        {
+                            "block_id_flag": 2,
+                            "validator_address": VALIDATOR_ADDRESS,
+                            "timestamp": "2024-03-01T12:30:51.5Z",
+                            "signature": STANDARD.encode([9u8; 64])
+                        },
+                        {
+                            "block_id_flag": 1,
+                            "validator_address": "",
+                            "timestamp": "0001-01-01T00:00:00Z",
+                            "signature": null
+                        }
+                    ]
+                }
+            },
+            "validator_set": { "validators": [validator("-250"), validator("250")] },
+            "trusted_height": { "revision_number": "4", "revision_height": "19999990" },
+            "trusted_validators": {
+                "validators": [validator("0")],
+                "proposer": validator("0"),
+                "total_voting_power": "1000"
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Ok((0, 0)));
+        assert_eq!(
+            parse_timestamp("2024-03-01T12:30:45.123456789Z"),
+            Ok((1_709_296_245, 123_456_789))
+        );
+        assert_eq!(
+            parse_timestamp("2024-03-01T12:30:45.5+02:00"),
+            Ok((1_709_289_045, 500_000_000))
+        );
+        assert_eq!(parse_timestamp("0001-01-01T00:00:00Z"), Ok((-62_135_596_800, 0)));
+        assert!(parse_timestamp("2024-13-01T00:00:00Z").is_err());
+        assert!(parse_timestamp("2024-03-01 12:30:45Z").is_err());
+        assert!(parse_timestamp("2024-03-01T12:30:45.1234567890Z").is_err());
+    }
+
+    #[test]
+    fn test_encode_msg_update_client() {
+        let value = json!({
+            "client_id": "07-tendermint-0",
+            "client_message": tendermint_header(),
+            "signer": "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmdzw0mt"
+        });
+        let msg_bytes = encode_msg_update_client(&value).unwrap();
+        let fields = decode_fields(&msg_bytes).unwrap();
+        assert_eq!(fields[0].1.as_string().unwrap(), "07-tendermint-0");
+        assert_eq!(
+            fields[2].1.as_string().unwrap(),
+            "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmdzw0mt"
+        );
+
+        let any_fields = decode_fields(fields[1].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(any_fields[0].1.as_string().unwrap(), TENDERMINT_HEADER_TYPE_URL);
+        let header_fields = decode_fields(any_fields[1].1.as_bytes().unwrap()).unwrap();
+
+        let signed_header = SignedHeader::decode(header_fields[0].1.as_bytes().unwrap()).unwrap();
+        let header = signed_header.header.unwrap();
+        assert_eq!(header.chain_id, "cosmoshub-4");
+        assert_eq!(header.height, 20_000_000);
+        assert_eq!(header.version.unwrap().block, 11);
+        let time = header.time.unwrap();
+        assert_eq!((time.seconds, time.nanos), (1_709_296_245, 123_456_789));
+        assert_eq!(header.last_block_id.unwrap().part_set_header.unwrap().total, 1);
+        assert_eq!(header.validators_hash, vec![0xdd; 32]);
+        assert_eq!(header.proposer_address, hex::decode(VALIDATOR_ADDRESS).unwrap());
+
+        let commit = signed_header.commit.unwrap();
+        assert_eq!(commit.signatures.len(), 2);
+        assert_eq!(commit.signatures[0].block_id_flag, BlockIdFlag::Commit as i32);
+        assert_eq!(commit.signatures[0].signature, vec![9; 64]);
+        assert_eq!(commit.signatures[1].block_id_flag, BlockIdFlag::Absent as i32);
+        assert_eq!(commit.signatures[1].timestamp.unwrap().seconds, -62_135_596_800);
+
+        let validator_set = ValidatorSet::decode(header_fields[1].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(validator_set.validators[0].proposer_priority, -250);
+        assert_eq!(
+            validator_set.validators[0].pub_key.clone().unwrap().sum,
+            Some(Sum::Ed25519(vec![7; 32]))
+        );
+        // The validator with the highest priority is the proposer
+        assert_eq!(validator_set.proposer.unwrap().proposer_priority, 250);
+        assert_eq!(validator_set.total_voting_power, 2000);
+
+        let trusted_height = decode_fields(header_fields[2].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(trusted_height[0].1.as_varint(), Some(4));
+        assert_eq!(trusted_height[1].1.as_varint(), Some(19_999_990));
+        let trusted_validators = ValidatorSet::decode(header_fields[3].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(trusted_validators.total_voting_power, 1000);
+    }
+
+    #[test]
+    fn test_encode_msg_update_client_raw_message() {
+        let value = json!({
+            "client_id": "08-wasm-0",
+            "client_message": { "@type": "/ibc.lightclients.wasm.v1.ClientMessage", "value": "CgMBAgM=" },
+            "signer": "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmdzw0mt"
+        });
+        let msg_bytes = encode_msg_update_client(&value).unwrap();
+        let fields = decode_fields(&msg_bytes).unwrap();
+        let any_fields = decode_fields(fields[1].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(any_fields[1].1.as_bytes().unwrap(), &[0x0a, 0x03, 1, 2, 3]);
+
+        let mut missing_header = tendermint_header();
+        missing_header["signed_header"]
+            .as_object_mut()
+            .unwrap()
+            .remove("commit");
+        let value = json!({
+            "client_id": "07-tendermint-0",
+            "client_message": missing_header,
+            "signer": "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmdzw0mt"
+        });
+        assert_eq!(
+            encode_msg_update_client(&value),
+            Err("Missing commit in Tendermint header".to_string())
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    cosmos_pagination::{decode_page_response, encode_page_request, Pagination},
+    cosmos_protobuf::{decode_fields, encode_length_delimited, encode_string, ProtoField},
+};
+
+/// ABCI query path of the IBC connections
+pub const IBC_CONNECTIONS_PATH: &str = "/ibc.core.connection.v1.Query/Connections";
+
+/// ABCI query path of a single IBC connection
+pub const IBC_CONNECTION_PATH: &str = "/ibc.core.connection.v1.Query/Connection";
+
+/// State of the handshake of an IBC connection, as `ibc.core.connection.v1.State`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum ConnectionState {
+    #[default]
+    Uninitialized,
+    Init,
+    TryOpen,
+    Open,
+}
+
+impl ConnectionState {
+    pub fn from_proto(value: u64) -> Self {
+        match value {
+            1 => ConnectionState::Init,
+            2 => ConnectionState::TryOpen,
+            3 => ConnectionState::Open,
+            _ => ConnectionState::Uninitialized,
+        }
+    }
+}
+
+/// The end of an IBC connection on the counterparty chain
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, CandidType)]
+pub struct IBCCounterparty {
+    pub client_id: String,
+    /// Empty until the counterparty chain has acknowledged the handshake
+    pub connection_id: String,
+    /// Prefix of the commitment store of the counterparty chain, usually "ibc"
+    pub key_prefix: Vec<u8>,
+}
+
+/// An IBC connection between the light client `client_id` and a counterparty chain
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, CandidType)]
+pub struct IBCConnection {
+    pub id: String,
+    pub client_id: String,
+    pub state: ConnectionState,
+    pub counterparty: IBCCounterparty,
+}
+
+/// Page of connections returned by `Connections`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, CandidType)]
+pub struct IBCConnectionsResult {
+    pub connections: Vec<IBCConnection>,
+    /// Key of the next page, `None` on the last page
+    pub next_key: Option<Vec<u8>>,
+    /// Total number of connections, 0 unless `count_total` was requested
+    pub total: u64,
+}
+
+/// Encode `QueryConnectionsRequest` as hex `abci_query` data, using the default page of the node if
+/// `pagination` is `None`
+pub fn encode_connections_query(pagination: Option<&Pagination>) -> String {
+    let request_bytes = pagination
+        .map(|pagination| encode_length_delimited(0x0a, &encode_page_request(pagination))) // pagination = 1
+        .unwrap_or_default();
+    hex::encode(request_bytes)
+}
+
+/// Encode `QueryConnectionRequest` as hex `abci_query` data
+pub fn encode_connection_query(connection_id: &str) -> String {
+    hex::encode(encode_string(0x0a, connection_id)) // connection_id = 1
+}
+
+/// Decode a `QueryConnectionsResponse` from the base64 ABCI response value
+pub fn decode_connections_response(response_value: &str) -> Result<IBCConnectionsResult, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    let mut result = IBCConnectionsResult::default();
+    for (number, field) in decode_fields(&decoded)? {
+        match (number, field) {
+            // connections = 1
+            (1, ProtoField::Bytes(connection)) => result.connections.push(decode_identified_connection(connection)?),
+            // pagination = 2
+            (2, ProtoField::Bytes(pagination)) => (result.next_key, result.total) = decode_page_response(pagination)?,
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// Decode a `QueryConnectionResponse` from the base64 ABCI response value into the connection
+/// `connection_id`, which the response does not repeat
+pub fn decode_connection_response(connection_id: &str, response_value: &str) -> Result<IBCConnection, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    let mut connection = IBCConnection {
+        id: connection_id.to_string(),
+        ..Default::default()
+    };
+    let mut found = false;
+    for (number, field) in decode_fields(&decoded)? {
+        // connection = 1
+        if let (1, ProtoField::Bytes(end)) = (number, field) {
+            found = true;
+            // ConnectionEnd: client_id = 1, versions = 2, state = 3, counterparty = 4
+            for (number, field) in decode_fields(end)? {
+                match (number, field) {
+                    (1, field) => connection.client_id = field.as_string().unwrap_or_default(),
+                    (3, ProtoField::Varint(state)) => connection.state = ConnectionState::from_proto(state),
+                    (4, ProtoField::Bytes(counterparty)) => {
+                        connection.counterparty = decode_counterparty(counterparty)?
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    if !found {
+        return Err(format!("Connection {} not found", connection_id));
+    }
+    Ok(connection)
+}
+
+/// Decode an `IdentifiedConnection`
+fn decode_identified_connection(bytes: &[u8]) -> Result<IBCConnection, String> {
+    let mut connection = IBCConnection::default();
+    // id = 1, client_id = 2, versions = 3, state = 4, counterparty = 5
+    for (number, field) in decode_fields(bytes)? {
+        match (number, field) {
+            (1, field) => connection.id = field.as_string().unwrap_or_default(),
+            (2, field) => connection.client_id = field.as_string().unwrap_or_default(),
+            (4, ProtoField::Varint(state)) => connection.state = ConnectionState::from_proto(state),
+            (5, ProtoField::Bytes(counterparty)) => connection.counterparty = decode_counterparty(counterparty)?,
+            _ => {}
+        }
+    }
+    Ok(connection)
+}
+
+/// Decode a `Counterparty`: client_id = 1, connection_id = 2, prefix = 3
+fn decode_counterparty(bytes: &[u8]) -> Result<IBCCounterparty, String> {
+    let mut counterparty = IBCCounterparty::default();
+    for (number, field) in decode_fields(bytes)? {
+        match (number, field) {
+            (1, field) => counterparty.client_id = field.as_string().unwrap_or_default(),
+            (2, field) => counterparty.connection_id = field.as_string().unwrap_or_default(),
+            (3, ProtoField::Bytes(prefix)) => {
+                // MerklePrefix: key_prefix = 1
+                for (number, field) in decode_fields(prefix)? {
+                    if let (1, ProtoField::Bytes(key_prefix)) = (number, field) {
+                        counterparty.key_prefix = key_prefix.to_vec();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(counterparty)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::cosmos_protobuf::encode_uint64;
+
+    use super::*;
+
+    // cosmos-sdk-proto has no IBC types, the messages are encoded field by field
+    fn encode_counterparty(client_id: &str, connection_id: &str) -> Vec<u8> {
+        let mut bytes = encode_string(0x0a, client_id);
+        bytes.extend(encode_string(0x12, connection_id));
+        bytes.extend(encode_length_delimited(0x1a, &encode_string(0x0a, "ibc")));
+        bytes
+    }
+
+    fn encode_version() -> Vec<u8> {
+        let mut bytes = encode_string(0x0a, "1");
+        bytes.extend(encode_string(0x12, "ORDER_ORDERED"));
+        bytes
+    }
+
+    #[test]
+    fn test_encode_connection_queries() {
+        assert_eq!(encode_connections_query(None), "");
+        // pagination { limit: 10 }
+        assert_eq!(
+            encode_connections_query(Some(&Pagination::first(10, false))),
+            "0a02180a"
+        );
+        assert_eq!(
+            encode_connection_query("connection-0"),
+            hex::encode(b"\x0a\x0cconnection-0")
+        );
+    }
+
+    #[test]
+    fn test_decode_connections_response() {
+        let mut open = encode_string(0x0a, "connection-0");
+        open.extend(encode_string(0x12, "07-tendermint-0"));
+        open.extend(encode_length_delimited(0x1a, &encode_version()));
+        open.extend(encode_uint64(0x20, 3));
+        open.extend(encode_length_delimited(
+            0x2a,
+            &encode_counterparty("07-tendermint-141", "connection-257"),
+        ));
+        let mut init = encode_string(0x0a, "connection-1");
+        init.extend(encode_string(0x12, "07-tendermint-1"));
+        init.extend(encode_uint64(0x20, 1));
+        init.extend(encode_length_delimited(
+            0x2a,
+            &encode_counterparty("07-tendermint-9", ""),
+        ));
+
+        let mut response = encode_length_delimited(0x0a, &open);
+        response.extend(encode_length_delimited(0x0a, &init));
+        // pagination { next_key: [1], total: 12 }
+        response.extend(encode_length_delimited(0x12, &[0x0a, 0x01, 0x01, 0x10, 0x0c]));
+
+        let result = decode_connections_response(&STANDARD.encode(response)).unwrap();
+        assert_eq!(result.next_key, Some(vec![1]));
+        assert_eq!(result.total, 12);
+        assert_eq!(
+            result.connections,
+            vec![
+                IBCConnection {
+                    id: "connection-0".to_string(),
+                    client_id: "07-tendermint-0".to_string(),
+                    state: ConnectionState::Open,
+                    counterparty: IBCCounterparty {
+                        client_id: "07-tendermint-141".to_string(),
+                        connection_id: "connection-257".to_string(),
+                        key_prefix: b"ibc".to_vec(),
+                    },
+                },
+                IBCConnection {
+                    id: "connection-1".to_string(),
+                    client_id: "07-tendermint-1".to_string(),
+                    state: ConnectionState::Init,
+                    counterparty: IBCCounterparty {
+                        client_id: "07-tendermint-9".to_string(),
+                        connection_id: String::new(),
+                        key_prefix: b"ibc".to_vec(),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_connection_response() {
+        let mut end = encode_string(0x0a, "07-tendermint-0");
+        end.extend(encode_length_delimited(0x12, &encode_version()));
+        end.extend(encode_uint64(0x18, 2));
+        end.extend(encode_length_delimited(
+            0x22,
+            &encode_counterparty("07-tendermint-141", "connection-257"),
+        ));
+        let mut response = encode_length_delimited(0x0a, &end);
+        response.extend(encode_length_delimited(0x12, b"proof"));
+
+        let connection = decode_connection_response("connection-0", &STANDARD.encode(response)).unwrap();
+        assert_eq!(connection.id, "connection-0");
+        assert_eq!(connection.client_id, "07-tendermint-0");
+        assert_eq!(connection.state, ConnectionState::TryOpen);
+        assert_eq!(connection.counterparty.connection_id, "connection-257");
+
+        assert!(decode_connection_response("connection-0", "").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use std::time::Duration;
+
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::cosmos_protobuf::{encode_length_delimited, encode_string, encode_uint64};
+
+/// Type URL of the ICS20 fungible token transfer message
+pub const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+
+/// Port bound by the ICS20 transfer module
+pub const IBC_TRANSFER_PORT: &str = "transfer";
+
+/// Shortest time in which a transfer may time out, leaving the relayers time to pick up the packet
+pub const MIN_IBC_TIMEOUT_NANOS: u64 = 3_600_000_000_000;
+
+/// Number of destination chain blocks within which a transfer is expected to be relayed
+const IBC_TIMEOUT_BLOCKS: f64 = 10.0;
+
+/// IBC height of a chain, the `timeout_height` of a `MsgTransfer`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct RevisionHeight {
+    /// Revision of the chain, the number after the last `-` of its chain ID, e.g. 4 for `cosmoshub-4`
+    pub revision_number: u64,
+    pub revision_height: u64,
+}
+
+/// Well-known ICS20 channels as `(source chain, destination chain, source channel)`,
+/// taken from the Cosmos chain registry
+const KNOWN_IBC_CHANNELS: &[(&str, &str, &str)] = &[
+    ("cosmoshub-4", "osmosis-1", "channel-141"),
+    ("cosmoshub-4", "neutron-1", "channel-569"),
+    ("osmosis-1", "cosmoshub-4", "channel-0"),
+    ("osmosis-1", "neutron-1", "channel-874"),
+    ("neutron-1", "cosmoshub-4", "channel-1"),
+    ("neutron-1", "osmosis-1", "channel-10"),
+];
+
+/// Returns the `(port, channel)` of the well-known ICS20 channel from `source_chain_id`
+/// to `dest_chain_id`, if any
+pub fn get_ibc_channel_for_chain(source_chain_id: &str, dest_chain_id: &str) -> Option<(String, String)> {
+    KNOWN_IBC_CHANNELS
+        .iter()
+        .find(|(source, dest, _)| *source == source_chain_id && *dest == dest_chain_id)
+        .map(|(_, _, channel)| (IBC_TRANSFER_PORT.to_string(), channel.to_string()))
+}
+
+/// Returns the `(port, channel)` of a `MsgTransfer`: the explicit `source_channel` (and
+/// `source_port`, "transfer" by default), or the well-known channel from `source_chain_id`
+/// to `destination_chain_id`
+fn resolve_transfer_channel(value: &serde_json::Value) -> Result<(String, String), String> {
+    if let Some(channel) = value["source_channel"].as_str() {
+        let port = value["source_port"].as_str().unwrap_or(IBC_TRANSFER_PORT);
+        return Ok((port.to_string(), channel.to_string()));
+    }
+    let dest_chain_id = value["destination_chain_id"]
+        .as_str()
+        .ok_or("Missing source_channel or destination_chain_id in MsgTransfer")?;
+    let source_chain_id = value["source_chain_id"]
+        .as_str()
+        .ok_or("Missing source_chain_id in MsgTransfer")?;
+    get_ibc_channel_for_chain(source_chain_id, dest_chain_id)
+        .ok_or_else(|| format!("No known IBC channel from {} to {}", source_chain_id, dest_chain_id))
+}
+
+/// Encode MsgTransfer to protobuf bytes.
+/// The channel is either given as `source_channel` or looked up from `source_chain_id` and
+/// `destination_chain_id`. At least one of `timeout_height` (`{ revision_number, revision_height }`)
+/// and `timeout_timestamp` (nanoseconds since the epoch) must be set.
+pub fn encode_msg_transfer(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let (source_port, source_channel) = resolve_transfer_channel(value)?;
+    let sender = value["sender"].as_str().ok_or("Missing sender in MsgTransfer")?;
+    let receiver = value["receiver"].as_str().ok_or("Missing receiver in MsgTransfer")?;
+    let token = value["token"]
+        .as_object()
+        .ok_or("Missing token object in MsgTransfer")?;
+    let denom = token["denom"].as_str().ok_or("Missing denom in token")?;
+    let amount = token["amount"].as_str().ok_or("Missing amount in token")?;
+
+    let revision_number = json_u64(&value["timeout_height"]["revision_number"])?;
+    let revision_height = json_u64(&value["timeout_height"]["revision_height"])?;
+    let timeout_timestamp = json_u64(&value["timeout_timestamp"])?;
+    if revision_height == 0 && timeout_timestamp == 0 {
+        return Err("Missing timeout_height or timeout_timestamp in MsgTransfer".to_string());
+    }
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, &source_port)); // source_port = 1
+    msg_bytes.extend(encode_string(0x12, &source_channel)); // source_channel = 2
+
+    // Encode token (field 3)
+    let mut coin_bytes = Vec::new();
+    coin_bytes.extend(encode_string(0x0a, denom)); // denom = 1
+    coin_bytes.extend(encode_string(0x12, amount)); // amount = 2
+    msg_bytes.extend(encode_length_delimited(0x1a, &coin_bytes)); // token = 3
+
+    msg_bytes.extend(encode_string(0x22, sender)); // sender = 4
+    msg_bytes.extend(encode_string(0x2a, receiver)); // receiver = 5
+
+    // Encode timeout_height (field 6), always present in the SDK encoding
+    let mut height_bytes = Vec::new();
+    if revision_number != 0 {
+        height_bytes.extend(encode_uint64(0x08, revision_number)); // revision_number = 1
+    }
+    if revision_height != 0 {
+        height_bytes.extend(encode_uint64(0x10, revision_height)); // revision_height = 2
+    }
+    msg_bytes.extend(encode_length_delimited(0x32, &height_bytes)); // timeout_height = 6
+
+    if timeout_timestamp != 0 {
+        msg_bytes.extend(encode_uint64(0x38, timeout_timestamp)); // timeout_timestamp = 7
+    }
+    if let Some(memo) = value["memo"].as_str().filter(|memo| !memo.is_empty()) {
+        msg_bytes.extend(encode_string(0x42, memo)); // memo = 8
+    }
+
+    Ok(msg_bytes)
+}
+
+/// Returns the time a transfer is left to be relayed: 10 destination chain blocks, scaled by
+/// `safety_factor`, and at least [`MIN_IBC_TIMEOUT_NANOS`]
+fn ibc_timeout_window_nanos(dest_chain_block_time: Duration, safety_factor: f64) -> u64 {
+    let window = IBC_TIMEOUT_BLOCKS * dest_chain_block_time.as_nanos() as f64 * safety_factor;
+    // `as` saturates, and NaN ends up at the minimum
+    (window as u64).max(MIN_IBC_TIMEOUT_NANOS)
+}
+
+/// Returns the `timeout_timestamp` of a transfer sent now, in nanoseconds since the Unix epoch.
+///
+/// The timeout is checked against the block time of the destination chain, the source chain
+/// block time does not change it.
+pub fn calculate_ibc_timeout(
+    source_chain_block_time: Duration,
+    dest_chain_block_time: Duration,
+    safety_factor: f64,
+) -> u64 {
+    calculate_ibc_timeout_at(
+        source_chain_block_time,
+        dest_chain_block_time,
+        safety_factor,
+        ic_cdk::api::time(),
+    )
+}
+
+/// Returns the `timeout_timestamp` of a transfer sent at `now_nanos`
+pub fn calculate_ibc_timeout_at(
+    _source_chain_block_time: Duration,
+    dest_chain_block_time: Duration,
+    safety_factor: f64,
+    now_nanos: u64,
+) -> u64 {
+    now_nanos.saturating_add(ibc_timeout_window_nanos(dest_chain_block_time, safety_factor))
+}
+
+/// Returns the `timeout_height` of a transfer sent at the `current_height` of the destination
+/// chain, as many blocks later as [`calculate_ibc_timeout`] leaves time for.
+///
+/// The revision number is left at 0, set it from the destination chain ID with
+/// [`chain_revision_number`]: heights of an older revision are already past.
+pub fn calculate_ibc_timeout_height(
+    current_height: u64,
+    dest_chain_block_time: Duration,
+    safety_factor: f64,
+) -> RevisionHeight {
+    let block_time_nanos = u64::try_from(dest_chain_block_time.as_nanos())
+        .unwrap_or(u64::MAX)
+        .max(1);
+    let blocks = ibc_timeout_window_nanos(dest_chain_block_time, safety_factor).div_ceil(block_time_nanos);
+    RevisionHeight {
+        revision_number: 0,
+        revision_height: current_height.saturating_add(blocks),
+    }
+}
+
+/// Returns the IBC revision of a chain ID following the `{name}-{revision}` format, 0 otherwise
+pub fn chain_revision_number(chain_id: &str) -> u64 {
+    chain_id
+        .rsplit_once('-')
+        .and_then(|(_, revision)| revision.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Reads an optional uint64 given either as a JSON string or number, 0 when missing
+fn json_u64(value: &serde_json::Value) -> Result<u64, String> {
+    match value {
+        serde_json::Value::Null => Ok(0),
+        serde_json::Value::String(s) => s
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid {} in MsgTransfer: {}", s, e)),
+        serde_json::Value::Number(n) => n.as_u64().ok_or_else(|| format!("Invalid {} in MsgTransfer", n)),
+        _ => Err(format!("Invalid {} in MsgTransfer", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::cosmos_protobuf::{decode_fields, find_field};
+
+    #[test]
+    fn test_get_ibc_channel_for_chain() {
+        assert_eq!(
+            get_ibc_channel_for_chain("cosmoshub-4", "osmosis-1"),
+            Some(("transfer".to_string(), "channel-141".to_string()))
+        );
+        assert_eq!(
+            get_ibc_channel_for_chain("osmosis-1", "cosmoshub-4"),
+            Some(("transfer".to_string(), "channel-0".to_string()))
+        );
+        assert_eq!(get_ibc_channel_for_chain("cosmoshub-4", "unknown-1"), None);
+    }
+
+    #[test]
+    fn test_encode_msg_transfer() {
+        let value = json!({
+            "source_chain_id": "cosmoshub-4",
+            "destination_chain_id": "neutron-1",
+            "token": { "denom": "uatom", "amount": "1000" },
+            "sender": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "receiver": "neutron1pkptre7fdkl6gfrzlesjjvhxhlc3r4gm4t3dmf",
+            "timeout_timestamp": "1700000000000000000"
+        });
+        let bytes = encode_msg_transfer(&value).unwrap();
+
+        let fields = decode_fields(&bytes).unwrap();
+        assert_eq!(fields[0].1.as_string().unwrap(), "transfer");
+        assert_eq!(fields[1].1.as_string().unwrap(), "channel-569");
+        assert_eq!(
+            find_field(&bytes, 7).unwrap().unwrap().as_varint(),
+            Some(1_700_000_000_000_000_000)
+        );
+        assert_eq!(find_field(&bytes, 6).unwrap().unwrap().as_bytes(), Some(&[][..]));
+
+        let explicit = json!({
+            "source_channel": "channel-569",
+            "token": { "denom": "uatom", "amount": "1000" },
+            "sender": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+            "receiver": "neutron1pkptre7fdkl6gfrzlesjjvhxhlc3r4gm4t3dmf",
+            "timeout_timestamp": 1_700_000_000_000_000_000u64
+        });
+        assert_eq!(encode_msg_transfer(&explicit).unwrap(), bytes);
+
+        let mut no_timeout = explicit.clone();
+        no_timeout["timeout_timestamp"] = json!(null);
+        assert!(encode_msg_transfer(&no_timeout).is_err());
+    }
+
+    #[test]
+    fn test_calculate_ibc_timeout() {
+        let now = 1_700_000_000_000_000_000;
+        let source = Duration::from_secs(6);
+        // 10 blocks of 6 seconds are below the minimum of an hour
+        assert_eq!(
+            calculate_ibc_timeout_at(source, Duration::from_secs(6), 1.5, now),
+            now + MIN_IBC_TIMEOUT_NANOS
+        );
+        assert_eq!(
+            calculate_ibc_timeout_at(source, Duration::from_secs(600), 1.5, now),
+            now + 9_000_000_000_000
+        );
+        assert_eq!(
+            calculate_ibc_timeout_at(source, Duration::from_secs(600), f64::NAN, now),
+            now + MIN_IBC_TIMEOUT_NANOS
+        );
+        assert_eq!(calculate_ibc_timeout_at(source, Duration::MAX, 2.0, now), u64::MAX);
+
+        assert_eq!(
+            calculate_ibc_timeout_height(1_000, Duration::from_secs(6), 1.0),
+            RevisionHeight {
+                revision_number: 0,
+                revision_height: 1_600,
+            }
+        );
+        assert_eq!(
+            calculate_ibc_timeout_height(1_000, Duration::from_millis(7_000), 1.0).revision_height,
+            1_515
+        );
+        assert_eq!(
+            calculate_ibc_timeout_height(1_000, Duration::from_secs(600), 2.0).revision_height,
+            1_020
+        );
+
+        assert_eq!(chain_revision_number("cosmoshub-4"), 4);
+        assert_eq!(chain_revision_number("theta-testnet-001"), 1);
+        assert_eq!(chain_revision_number("localnet"), 0);
+    }
+}
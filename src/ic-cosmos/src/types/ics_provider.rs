@@ -0,0 +1,197 @@
+//! Queries of the provider module of Interchain Security (ICS), securing the consumer chains with
+//! the validator set of the provider chain, e.g. the Cosmos Hub.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    block_time::format_block_time,
+    cosmos_protobuf::{decode_fields, ProtoField},
+};
+
+/// ABCI query path of the consumer chains of the provider
+pub const ICS_CONSUMER_CHAINS_PATH: &str = "/interchain_security.ccv.provider.v1.Query/QueryConsumerChains";
+
+/// ABCI query path of the consumer chains whose removal was approved by governance
+pub const ICS_CONSUMER_CHAIN_STOPS_PATH: &str = "/interchain_security.ccv.provider.v1.Query/QueryConsumerChainStops";
+
+/// A consumer chain secured by the validators of the provider chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ConsumerChain {
+    pub chain_id: String,
+    /// IBC client of the consumer chain on the provider chain
+    pub client_id: String,
+    /// Percentage of the voting power of the provider whose validators must validate the
+    /// consumer chain, 0 for an opt-in chain
+    #[serde(rename = "top_N")]
+    pub top_n: u32,
+    /// Smallest voting power of the validators in the top N, who must validate the consumer chain
+    #[serde(rename = "min_power_in_top_N")]
+    pub min_power_in_top_n: i64,
+}
+
+/// A consumer chain to be removed from the provider chain at `stop_time`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ConsumerChainStop {
+    pub chain_id: String,
+    /// Title of the removal proposal
+    pub title: String,
+    /// Description of the removal proposal
+    pub description: String,
+    /// RFC 3339 time at which the provider stops securing the consumer chain
+    pub stop_time: String,
+}
+
+/// Decode a `QueryConsumerChainsResponse` from the base64 ABCI response value
+pub fn decode_consumer_chains_response(response_value: &str) -> Result<Vec<ConsumerChain>, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // chains = 1
+    decode_fields(&decoded)?
+        .into_iter()
+        .filter(|(number, _)| *number == 1)
+        .map(|(_, field)| decode_consumer_chain(field.as_bytes().ok_or("Invalid consumer chain")?))
+        .collect()
+}
+
+fn decode_consumer_chain(data: &[u8]) -> Result<ConsumerChain, String> {
+    let mut chain = ConsumerChain::default();
+    for (number, field) in decode_fields(data)? {
+        match number {
+            1 => chain.chain_id = field.as_string().unwrap_or_default(),
+            2 => chain.client_id = field.as_string().unwrap_or_default(),
+            3 => chain.top_n = field.as_varint().unwrap_or_default() as u32,
+            4 => chain.min_power_in_top_n = field.as_varint().unwrap_or_default() as i64,
+            _ => {}
+        }
+    }
+    Ok(chain)
+}
+
+/// Decode a `QueryConsumerChainStopProposalsResponse` from the base64 ABCI response value
+pub fn decode_consumer_chain_stops_response(response_value: &str) -> Result<Vec<ConsumerChainStop>, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // proposals = 1 -> pending = 1
+    let mut stops = Vec::new();
+    for (number, field) in decode_fields(&decoded)? {
+        if number != 1 {
+            continue;
+        }
+        let proposals = field.as_bytes().ok_or("Invalid consumer removal proposals")?;
+        for (number, field) in decode_fields(proposals)? {
+            if number == 1 {
+                stops.push(decode_consumer_chain_stop(
+                    field.as_bytes().ok_or("Invalid consumer removal proposal")?,
+                )?);
+            }
+        }
+    }
+    Ok(stops)
+}
+
+fn decode_consumer_chain_stop(data: &[u8]) -> Result<ConsumerChainStop, String> {
+    let mut stop = ConsumerChainStop::default();
+    for (number, field) in decode_fields(data)? {
+        match number {
+            1 => stop.title = field.as_string().unwrap_or_default(),
+            2 => stop.description = field.as_string().unwrap_or_default(),
+            3 => stop.chain_id = field.as_string().unwrap_or_default(),
+            4 => stop.stop_time = decode_timestamp(&field)?,
+            _ => {}
+        }
+    }
+    Ok(stop)
+}
+
+/// Decode a `google.protobuf.Timestamp` as an RFC 3339 time
+fn decode_timestamp(field: &ProtoField) -> Result<String, String> {
+    let data = field.as_bytes().ok_or("Invalid timestamp")?;
+    let (mut seconds, mut nanos) = (0, 0);
+    for (number, field) in decode_fields(data)? {
+        match number {
+            1 => seconds = field.as_varint().unwrap_or_default(), // seconds = 1
+            2 => nanos = field.as_varint().unwrap_or_default(),   // nanos = 2
+            _ => {}
+        }
+    }
+    seconds
+        .checked_mul(1_000_000_000)
+        .and_then(|seconds| seconds.checked_add(nanos))
+        .map(format_block_time)
+        .ok_or_else(|| format!("Invalid timestamp: {} seconds", seconds as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::cosmos_protobuf::{encode_length_delimited, encode_string, encode_uint64};
+
+    #[test]
+    fn test_decode_consumer_chains_response() {
+        let mut neutron = encode_string(0x0a, "neutron-1");
+        neutron.extend(encode_string(0x12, "07-tendermint-1119"));
+        neutron.extend(encode_uint64(0x18, 95)); // top_N = 3
+        neutron.extend(encode_uint64(0x20, 1_452_000)); // min_power_in_top_N = 4
+        neutron.extend(encode_uint64(0x28, 0)); // validators_power_cap = 5
+        let mut opt_in = encode_string(0x0a, "elys-1");
+        opt_in.extend(encode_string(0x12, "07-tendermint-1200"));
+        opt_in.extend(encode_uint64(0x20, u64::MAX)); // min_power_in_top_N = -1
+        let mut response = encode_length_delimited(0x0a, &neutron);
+        response.extend(encode_length_delimited(0x0a, &opt_in));
+        response.extend(encode_length_delimited(0x12, &[])); // pagination = 2
+
+        let chains = decode_consumer_chains_response(&STANDARD.encode(response)).unwrap();
+        assert_eq!(
+            chains,
+            vec![
+                ConsumerChain {
+                    chain_id: "neutron-1".to_string(),
+                    client_id: "07-tendermint-1119".to_string(),
+                    top_n: 95,
+                    min_power_in_top_n: 1_452_000,
+                },
+                ConsumerChain {
+                    chain_id: "elys-1".to_string(),
+                    client_id: "07-tendermint-1200".to_string(),
+                    top_n: 0,
+                    min_power_in_top_n: -1,
+                },
+            ]
+        );
+        assert_eq!(decode_consumer_chains_response(""), Ok(Vec::new()));
+        assert_eq!(
+            serde_json::to_value(&chains[0]).unwrap()["top_N"],
+            serde_json::json!(95)
+        );
+    }
+
+    #[test]
+    fn test_decode_consumer_chain_stops_response() {
+        let mut stop_time = encode_uint64(0x08, 1_705_322_096); // seconds = 1
+        stop_time.extend(encode_uint64(0x10, 789_000_000)); // nanos = 2
+        let mut proposal = encode_string(0x0a, "Remove Stride");
+        proposal.extend(encode_string(0x12, "Stride moves to its own validator set"));
+        proposal.extend(encode_string(0x1a, "stride-1"));
+        proposal.extend(encode_length_delimited(0x22, &stop_time));
+        let proposals = encode_length_delimited(0x0a, &proposal); // pending = 1
+        let response = encode_length_delimited(0x0a, &proposals); // proposals = 1
+
+        let stops = decode_consumer_chain_stops_response(&STANDARD.encode(response)).unwrap();
+        assert_eq!(
+            stops,
+            vec![ConsumerChainStop {
+                chain_id: "stride-1".to_string(),
+                title: "Remove Stride".to_string(),
+                description: "Stride moves to its own validator set".to_string(),
+                stop_time: "2024-01-15T12:34:56.789Z".to_string(),
+            }]
+        );
+        assert_eq!(decode_consumer_chain_stops_response(""), Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,105 @@
+//! Responses of the Cosmos REST API (LCD), served by the gRPC gateway of the nodes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BroadcastTxResult, CosmosCoin};
+
+/// Response of `/cosmos/bank/v1beta1/balances/{address}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LcdBalancesResponse {
+    pub balances: Vec<CosmosCoin>,
+}
+
+/// Response of `POST /cosmos/tx/v1beta1/txs`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LcdBroadcastTxResponse {
+    pub tx_response: LcdTxResponse,
+}
+
+/// `TxResponse` of the REST API, only with the fields of a broadcast in sync mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LcdTxResponse {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub codespace: String,
+    /// Hash of the transaction, in uppercase hex
+    pub txhash: String,
+    #[serde(default)]
+    pub data: String,
+    #[serde(default)]
+    pub raw_log: String,
+}
+
+impl From<LcdTxResponse> for BroadcastTxResult {
+    fn from(response: LcdTxResponse) -> Self {
+        BroadcastTxResult {
+            code: response.code,
+            data: response.data,
+            log: response.raw_log,
+            codespace: response.codespace,
+            hash: response.txhash,
+        }
+    }
+}
+
+/// Error returned by the gRPC gateway, e.g. for an unknown account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LcdError {
+    /// gRPC status code
+    pub code: i64,
+    pub message: String,
+}
+
+impl LcdError {
+    /// Returns the error of an LCD response, `None` for a successful response
+    pub fn from_response(response: &serde_json::Value) -> Option<Self> {
+        let code = response.get("code")?.as_i64()?;
+        let message = response.get("message")?.as_str()?;
+        (code != 0).then(|| LcdError {
+            code,
+            message: message.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_lcd_responses() {
+        let response: LcdBroadcastTxResponse = serde_json::from_value(json!({
+            "tx_response": {
+                "height": "0",
+                "txhash": "D9C2C7E2A4B3D1F0",
+                "codespace": "sdk",
+                "code": 32,
+                "data": "",
+                "raw_log": "account sequence mismatch",
+                "logs": [],
+                "gas_wanted": "0",
+                "gas_used": "0",
+                "tx": null
+            }
+        }))
+        .unwrap();
+        let result = BroadcastTxResult::from(response.tx_response);
+        assert_eq!(result.code, 32);
+        assert_eq!(result.hash, "D9C2C7E2A4B3D1F0");
+        assert_eq!(result.log, "account sequence mismatch");
+
+        let error = json!({ "code": 5, "message": "account cosmos1abc not found", "details": [] });
+        assert_eq!(
+            LcdError::from_response(&error),
+            Some(LcdError {
+                code: 5,
+                message: "account cosmos1abc not found".to_string()
+            })
+        );
+        let balances = json!({ "balances": [{ "denom": "uatom", "amount": "10" }], "pagination": {} });
+        assert_eq!(LcdError::from_response(&balances), None);
+    }
+}
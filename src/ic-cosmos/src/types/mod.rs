@@ -1,76 +1,126 @@
 pub mod account;
 pub mod block;
+pub mod block_time;
 pub mod blockhash;
 pub mod candid_value;
+pub mod chain_config;
+pub mod chain_version;
 pub mod cluster;
 pub mod commitment;
 pub mod compiled_keys;
 pub mod config;
 pub mod cosmos_abci;
 pub mod cosmos_abci_query;
+pub mod cosmos_bank;
 pub mod cosmos_block;
 pub mod cosmos_blockchain;
 pub mod cosmos_broadcast_tx_async;
+pub mod cosmos_broadcast_tx_commit;
 pub mod cosmos_check_tx;
 pub mod cosmos_commit;
 pub mod cosmos_common;
 pub mod cosmos_consensus_params;
 pub mod cosmos_consensus_status;
 pub mod cosmos_dump_consensus_state;
+pub mod cosmos_gov;
 pub mod cosmos_header;
+pub mod cosmos_message_encoder;
 pub mod cosmos_net_info;
+pub mod cosmos_node;
+pub mod cosmos_pagination;
+pub mod cosmos_protobuf;
+pub mod cosmos_staking;
 pub mod cosmos_status;
-pub mod cosmos_tx;
+pub mod cosmos_supply;
 pub mod cosmos_transaction_utils;
+pub mod cosmos_tx;
+pub mod cosmos_tx_builder;
+pub mod cosmos_tx_receipt;
 pub mod cosmos_unconfirmed_txs;
 pub mod cosmos_validators;
+pub mod cosmos_wasm;
+pub mod eip712;
 pub mod epoch;
 pub mod fees;
 pub mod filter;
+pub mod ibc_client;
+pub mod ibc_connection;
+pub mod ibc_transfer;
+pub mod ics_provider;
 pub mod instruction;
+pub mod lcd;
 pub mod message;
+#[cfg(feature = "osmosis")]
+pub mod osmosis;
 pub mod pubkey;
 pub mod response;
 pub mod reward;
 pub mod signature;
+#[cfg(feature = "stride")]
+pub mod stride;
 pub mod tagged;
 pub mod transaction;
 pub mod transaction_error;
 
 pub use account::*;
 pub use block::*;
+pub use block_time::*;
 pub use blockhash::*;
 pub use candid_value::*;
+pub use chain_config::*;
+pub use chain_version::*;
 pub use cluster::*;
 pub use commitment::*;
 pub use config::*;
 pub use cosmos_abci::*;
 pub use cosmos_abci_query::*;
+pub use cosmos_bank::*;
 pub use cosmos_block::*;
 pub use cosmos_blockchain::*;
 pub use cosmos_broadcast_tx_async::*;
+pub use cosmos_broadcast_tx_commit::*;
 pub use cosmos_check_tx::*;
 pub use cosmos_commit::*;
 pub use cosmos_common::*;
 pub use cosmos_consensus_params::*;
 pub use cosmos_consensus_status::*;
 pub use cosmos_dump_consensus_state::*;
+pub use cosmos_gov::*;
 pub use cosmos_header::*;
+pub use cosmos_message_encoder::*;
 pub use cosmos_net_info::*;
+pub use cosmos_node::*;
+pub use cosmos_pagination::*;
+pub use cosmos_protobuf::*;
+pub use cosmos_staking::*;
 pub use cosmos_status::*;
-pub use cosmos_tx::*;
+pub use cosmos_supply::*;
 pub use cosmos_transaction_utils::*;
+pub use cosmos_tx::*;
+pub use cosmos_tx_builder::*;
+pub use cosmos_tx_receipt::*;
 pub use cosmos_unconfirmed_txs::*;
 pub use cosmos_validators::*;
+pub use cosmos_wasm::*;
+pub use eip712::*;
 pub use epoch::*;
 pub use fees::*;
 pub use filter::*;
+pub use ibc_client::*;
+pub use ibc_connection::*;
+pub use ibc_transfer::*;
+pub use ics_provider::*;
 pub use instruction::*;
+pub use lcd::*;
 pub use message::*;
+#[cfg(feature = "osmosis")]
+pub use osmosis::*;
 pub use pubkey::*;
 pub use response::*;
 pub use reward::*;
 pub use signature::*;
+#[cfg(feature = "stride")]
+pub use stride::*;
 pub use transaction::*;
 pub use transaction_error::*;
 
@@ -87,4 +137,4 @@ pub type Slot = u64;
 /// An approximate measure of real-world time.
 ///
 /// Expressed as Unix time (i.e. seconds since the Unix epoch).
-pub type UnixTimestamp = i64;
\ No newline at end of file
+pub type UnixTimestamp = i64;
@@ -0,0 +1,413 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    cosmos_protobuf::{decode_fields, encode_length_delimited, encode_string, encode_uint64, find_field, ProtoField},
+    cosmos_transaction_utils::{format_sdk_dec, parse_sdk_dec, CosmosCoin, CosmosMessage, SDK_DEC_PRECISION},
+};
+
+/// ABCI query path of the Osmosis GAMM pool query
+pub const OSMOSIS_GAMM_POOL_PATH: &str = "/osmosis.gamm.v1beta1.Query/Pool";
+
+/// Type URL of the Osmosis balancer pool
+pub const OSMOSIS_BALANCER_POOL_TYPE_URL: &str = "/osmosis.gamm.v1beta1.Pool";
+
+/// Type URL of the Osmosis pool manager swap message, swapping an exact amount in over a route of pools
+pub const OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL: &str = "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn";
+
+/// Slippage tolerated by swaps that do not set `token_out_min_amount`, in basis points
+pub const DEFAULT_SLIPPAGE_BPS: u16 = 100;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Chain ID prefixes of the Osmosis mainnet and testnets
+pub const OSMOSIS_CHAIN_ID_PREFIXES: &[&str] = &["osmosis-", "osmo-test-"];
+
+/// Represents an Osmosis GAMM liquidity pool.
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct OsmosisPool {
+    /// The pool ID
+    pub id: u64,
+    /// The assets of the pool with their weights
+    pub pool_assets: Vec<PoolAsset>,
+    /// The swap and exit fees of the pool
+    pub pool_params: PoolParams,
+    /// The total amount of pool shares
+    pub total_shares: CosmosCoin,
+}
+
+/// Represents a single asset of an Osmosis liquidity pool.
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PoolAsset {
+    /// The amount of liquidity of the asset
+    pub token: CosmosCoin,
+    /// The weight of the asset in the pool
+    pub weight: String,
+}
+
+/// Represents the parameters of an Osmosis liquidity pool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, CandidType)]
+pub struct PoolParams {
+    /// The swap fee as a decimal (e.g. "0.002")
+    pub swap_fee: String,
+    /// The exit fee as a decimal
+    pub exit_fee: String,
+}
+
+/// Returns `true` if `chain_id` belongs to an Osmosis network
+pub fn is_osmosis_chain_id(chain_id: &str) -> bool {
+    OSMOSIS_CHAIN_ID_PREFIXES
+        .iter()
+        .any(|prefix| chain_id.starts_with(prefix))
+}
+
+/// Encode `QueryPoolRequest` as hex `abci_query` data
+pub fn encode_osmosis_pool_query(pool_id: u64) -> String {
+    hex::encode(encode_uint64(0x08, pool_id)) // pool_id = 1
+}
+
+/// Decode a `QueryPoolResponse` from the base64 ABCI response value
+pub fn decode_osmosis_pool_response(response_value: &str) -> Result<OsmosisPool, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // pool = 1 (google.protobuf.Any)
+    let any = find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .ok_or("Pool not found in response")?;
+
+    let mut type_url = String::new();
+    let mut value: &[u8] = &[];
+    for (number, field) in decode_fields(any)? {
+        match (number, field) {
+            (1, field) => type_url = field.as_string().unwrap_or_default(),
+            (2, ProtoField::Bytes(bytes)) => value = bytes,
+            _ => {}
+        }
+    }
+
+    if type_url != OSMOSIS_BALANCER_POOL_TYPE_URL {
+        return Err(format!("Unsupported pool type: {}", type_url));
+    }
+
+    decode_balancer_pool(value)
+}
+
+/// Returns the least amount accepted out of a swap expected to return `expected_out`,
+/// i.e. `expected_out * (10000 - slippage_bps) / 10000` rounded down.
+/// A slippage over 10000 basis points accepts any output.
+pub fn calculate_min_out_amount(expected_out: u64, slippage_bps: u16) -> u64 {
+    let kept_bps = BPS_DENOMINATOR.saturating_sub(slippage_bps as u64);
+    // Cannot overflow: both factors are below 2^64
+    (expected_out as u128 * kept_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Estimates the output of swapping `token_in` for `token_out_denom` in a balancer pool, at the
+/// spot price of the pool after the swap fee: `in * (1 - fee) * (balance_out / weight_out) / (balance_in / weight_in)`.
+/// The price impact of the swap itself is not accounted for, the slippage tolerance has to cover it.
+pub fn estimate_swap_output(pool: &OsmosisPool, token_in: &CosmosCoin, token_out_denom: &str) -> Result<u64, String> {
+    let asset = |denom: &str| {
+        pool.pool_assets
+            .iter()
+            .find(|asset| asset.token.denom == denom)
+            .ok_or_else(|| format!("Denom {} not found in pool {}", denom, pool.id))
+    };
+    let asset_in = asset(&token_in.denom)?;
+    let asset_out = asset(token_out_denom)?;
+
+    let amount_in = parse_amount(&token_in.amount)?;
+    let balance_in = parse_amount(&asset_in.token.amount)?;
+    let balance_out = parse_amount(&asset_out.token.amount)?;
+    if balance_in == 0 {
+        return Err(format!("Pool {} has no {} liquidity", pool.id, token_in.denom));
+    }
+
+    let one = 10u128.pow(SDK_DEC_PRECISION as u32);
+    let swap_fee = parse_sdk_dec(&pool.pool_params.swap_fee)?;
+    if swap_fee > one {
+        return Err(format!("Invalid swap fee: {}", pool.pool_params.swap_fee));
+    }
+
+    // Weights are large integers, usually equal, reduce them to keep the products within u128
+    let (weight_in, weight_out) = (parse_amount(&asset_in.weight)?, parse_amount(&asset_out.weight)?);
+    if weight_in == 0 || weight_out == 0 {
+        return Err(format!("Invalid weights in pool {}", pool.id));
+    }
+    let divisor = gcd(weight_in, weight_out);
+    let (weight_in, weight_out) = (weight_in / divisor, weight_out / divisor);
+
+    let amount_in_after_fee = mul_div(amount_in, one - swap_fee, one)?;
+    let expected_out = mul_div(
+        mul_div(amount_in_after_fee, balance_out, balance_in)?,
+        weight_in,
+        weight_out,
+    )?;
+    // The spot price never returns more than the whole balance out
+    u64::try_from(expected_out.min(balance_out)).map_err(|_| format!("Swap output overflows: {}", expected_out))
+}
+
+/// Returns a `MsgSwapExactAmountIn` of `token_in` for `token_out_denom` through `pool_id`,
+/// accepting `expected_out` minus `slippage_bps` basis points
+pub fn msg_swap_exact_amount_in(
+    sender: &str,
+    pool_id: u64,
+    token_in: &CosmosCoin,
+    token_out_denom: &str,
+    expected_out: u64,
+    slippage_bps: u16,
+) -> CosmosMessage {
+    CosmosMessage {
+        type_url: OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL.to_string(),
+        value: serde_json::json!({
+            "sender": sender,
+            "routes": [{ "pool_id": pool_id.to_string(), "token_out_denom": token_out_denom }],
+            "token_in": { "denom": token_in.denom, "amount": token_in.amount },
+            "token_out_min_amount": calculate_min_out_amount(expected_out, slippage_bps).to_string(),
+        }),
+    }
+}
+
+/// Encode MsgSwapExactAmountIn to protobuf bytes
+pub fn encode_msg_swap_exact_amount_in(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let sender = value["sender"]
+        .as_str()
+        .ok_or("Missing sender in MsgSwapExactAmountIn")?;
+    let routes = value["routes"]
+        .as_array()
+        .filter(|routes| !routes.is_empty())
+        .ok_or("Missing routes in MsgSwapExactAmountIn")?;
+    let token_in = &value["token_in"];
+    let denom = token_in["denom"]
+        .as_str()
+        .ok_or("Missing token_in denom in MsgSwapExactAmountIn")?;
+    let amount = token_in["amount"]
+        .as_str()
+        .ok_or("Missing token_in amount in MsgSwapExactAmountIn")?;
+    let token_out_min_amount = value["token_out_min_amount"]
+        .as_str()
+        .ok_or("Missing token_out_min_amount in MsgSwapExactAmountIn")?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, sender)); // sender = 1
+    for route in routes {
+        let pool_id = route["pool_id"]
+            .as_str()
+            .and_then(|id| id.parse::<u64>().ok())
+            .ok_or("Invalid pool_id in MsgSwapExactAmountIn route")?;
+        let token_out_denom = route["token_out_denom"]
+            .as_str()
+            .ok_or("Missing token_out_denom in MsgSwapExactAmountIn route")?;
+        let mut route_bytes = encode_uint64(0x08, pool_id); // pool_id = 1
+        route_bytes.extend(encode_string(0x12, token_out_denom)); // token_out_denom = 2
+        msg_bytes.extend(encode_length_delimited(0x12, &route_bytes)); // routes = 2
+    }
+    let mut coin_bytes = encode_string(0x0a, denom); // denom = 1
+    coin_bytes.extend(encode_string(0x12, amount)); // amount = 2
+    msg_bytes.extend(encode_length_delimited(0x1a, &coin_bytes)); // token_in = 3
+    msg_bytes.extend(encode_string(0x22, token_out_min_amount)); // token_out_min_amount = 4
+
+    Ok(msg_bytes)
+}
+
+fn parse_amount(amount: &str) -> Result<u128, String> {
+    amount
+        .parse::<u128>()
+        .map_err(|e| format!("Invalid amount {}: {}", amount, e))
+}
+
+/// `a * b / c` rounded down, failing instead of overflowing
+fn mul_div(a: u128, b: u128, c: u128) -> Result<u128, String> {
+    a.checked_mul(b)
+        .map(|product| product / c)
+        .ok_or_else(|| "Swap estimate overflows".to_string())
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn decode_balancer_pool(data: &[u8]) -> Result<OsmosisPool, String> {
+    let mut pool = OsmosisPool {
+        id: 0,
+        pool_assets: Vec::new(),
+        pool_params: PoolParams::default(),
+        total_shares: CosmosCoin::new("", "0"),
+    };
+
+    for (number, field) in decode_fields(data)? {
+        match (number, field) {
+            (2, ProtoField::Varint(id)) => pool.id = id,
+            (3, ProtoField::Bytes(bytes)) => pool.pool_params = decode_pool_params(bytes)?,
+            (5, ProtoField::Bytes(bytes)) => pool.total_shares = decode_coin(bytes)?,
+            (6, ProtoField::Bytes(bytes)) => pool.pool_assets.push(decode_pool_asset(bytes)?),
+            _ => {}
+        }
+    }
+
+    Ok(pool)
+}
+
+fn decode_pool_params(data: &[u8]) -> Result<PoolParams, String> {
+    let mut params = PoolParams::default();
+    for (number, field) in decode_fields(data)? {
+        match (number, field.as_string()) {
+            (1, Some(swap_fee)) => params.swap_fee = format_sdk_dec(&swap_fee)?,
+            (2, Some(exit_fee)) => params.exit_fee = format_sdk_dec(&exit_fee)?,
+            _ => {}
+        }
+    }
+    Ok(params)
+}
+
+fn decode_pool_asset(data: &[u8]) -> Result<PoolAsset, String> {
+    let mut asset = PoolAsset {
+        token: CosmosCoin::new("", "0"),
+        weight: String::new(),
+    };
+    for (number, field) in decode_fields(data)? {
+        match (number, field) {
+            (1, ProtoField::Bytes(bytes)) => asset.token = decode_coin(bytes)?,
+            (2, field) => asset.weight = field.as_string().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    Ok(asset)
+}
+
+fn decode_coin(data: &[u8]) -> Result<CosmosCoin, String> {
+    let mut coin = CosmosCoin::new("", "0");
+    for (number, field) in decode_fields(data)? {
+        match (number, field.as_string()) {
+            (1, Some(denom)) => coin.denom = denom,
+            (2, Some(amount)) => coin.amount = amount,
+            _ => {}
+        }
+    }
+    Ok(coin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_coin(denom: &str, amount: &str) -> Vec<u8> {
+        let mut coin = encode_string(0x0a, denom);
+        coin.extend(encode_string(0x12, amount));
+        coin
+    }
+
+    #[test]
+    fn test_decode_osmosis_pool_response() {
+        let mut params = encode_string(0x0a, "2000000000000000");
+        params.extend(encode_string(0x12, "0"));
+
+        let mut pool = encode_string(0x0a, "osmo1mw0ac6rwlp5r8wapwk3zs6g29h8fcscxqakdzw9emkne6c8wjp9q0t3v8t");
+        pool.extend(encode_uint64(0x10, 1));
+        pool.extend(encode_length_delimited(0x1a, &params));
+        pool.extend(encode_length_delimited(0x2a, &encode_coin("gamm/pool/1", "1000")));
+        for (denom, amount) in [("uatom", "10"), ("uosmo", "20")] {
+            let mut asset = encode_length_delimited(0x0a, &encode_coin(denom, amount));
+            asset.extend(encode_string(0x12, "536870912000000"));
+            pool.extend(encode_length_delimited(0x32, &asset));
+        }
+
+        let mut any = encode_string(0x0a, OSMOSIS_BALANCER_POOL_TYPE_URL);
+        any.extend(encode_length_delimited(0x12, &pool));
+        let response = STANDARD.encode(encode_length_delimited(0x0a, &any));
+
+        let pool = decode_osmosis_pool_response(&response).unwrap();
+        assert_eq!(pool.id, 1);
+        assert_eq!(pool.pool_params.swap_fee, "0.002");
+        assert_eq!(pool.pool_params.exit_fee, "0");
+        assert_eq!(pool.total_shares.denom, "gamm/pool/1");
+        assert_eq!(pool.pool_assets.len(), 2);
+        assert_eq!(pool.pool_assets[1].token.denom, "uosmo");
+        assert_eq!(pool.pool_assets[1].token.amount, "20");
+        assert_eq!(pool.pool_assets[1].weight, "536870912000000");
+    }
+
+    fn pool(weight_in: &str, weight_out: &str) -> OsmosisPool {
+        let asset = |denom: &str, amount: &str, weight: &str| PoolAsset {
+            token: CosmosCoin::new(denom, amount),
+            weight: weight.to_string(),
+        };
+        OsmosisPool {
+            id: 1,
+            pool_assets: vec![
+                asset("uatom", "1000000", weight_in),
+                asset("uosmo", "10000000", weight_out),
+            ],
+            pool_params: PoolParams {
+                swap_fee: "0.002".to_string(),
+                exit_fee: "0".to_string(),
+            },
+            total_shares: CosmosCoin::new("gamm/pool/1", "1000"),
+        }
+    }
+
+    #[test]
+    fn test_calculate_min_out_amount() {
+        assert_eq!(calculate_min_out_amount(10_000, 0), 10_000);
+        assert_eq!(calculate_min_out_amount(10_000, 100), 9_900);
+        assert_eq!(calculate_min_out_amount(999, 50), 994);
+        assert_eq!(calculate_min_out_amount(u64::MAX, 1), 18_444_899_399_302_180_659);
+        assert_eq!(calculate_min_out_amount(10_000, 20_000), 0);
+    }
+
+    #[test]
+    fn test_estimate_swap_output() {
+        let token_in = CosmosCoin::new("uatom", "1000");
+        let equal_weights = pool("536870912000000", "536870912000000");
+        // 1000 uatom - 0.2% fee at 10 uosmo per uatom
+        assert_eq!(estimate_swap_output(&equal_weights, &token_in, "uosmo").unwrap(), 9980);
+
+        let heavy_in = pool("1073741824000000", "536870912000000");
+        assert_eq!(estimate_swap_output(&heavy_in, &token_in, "uosmo").unwrap(), 19960);
+
+        assert!(estimate_swap_output(&equal_weights, &token_in, "uion").is_err());
+        assert!(estimate_swap_output(&equal_weights, &CosmosCoin::new("uatom", "-1"), "uosmo").is_err());
+    }
+
+    #[test]
+    fn test_encode_msg_swap_exact_amount_in() {
+        let message = msg_swap_exact_amount_in(
+            "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmzk4p7c",
+            1,
+            &CosmosCoin::new("uatom", "1000"),
+            "uosmo",
+            9980,
+            DEFAULT_SLIPPAGE_BPS,
+        );
+        assert_eq!(message.type_url, OSMOSIS_MSG_SWAP_EXACT_AMOUNT_IN_TYPE_URL);
+        assert_eq!(message.value["token_out_min_amount"], "9880");
+
+        let bytes = encode_msg_swap_exact_amount_in(&message.value).unwrap();
+        let fields = decode_fields(&bytes).unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(
+            fields[0].1.as_string().unwrap(),
+            "osmo1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmzk4p7c"
+        );
+        let route = decode_fields(fields[1].1.as_bytes().unwrap()).unwrap();
+        assert_eq!(route[0].1.as_varint(), Some(1));
+        assert_eq!(route[1].1.as_string().unwrap(), "uosmo");
+        assert_eq!(decode_coin(fields[2].1.as_bytes().unwrap()).unwrap().amount, "1000");
+        assert_eq!(fields[3].1.as_string().unwrap(), "9880");
+
+        let mut missing_min_out = message.value.clone();
+        missing_min_out["token_out_min_amount"] = serde_json::json!(null);
+        assert!(encode_msg_swap_exact_amount_in(&missing_min_out).is_err());
+    }
+
+    #[test]
+    fn test_is_osmosis_chain_id() {
+        assert!(is_osmosis_chain_id("osmosis-1"));
+        assert!(is_osmosis_chain_id("osmo-test-5"));
+        assert!(!is_osmosis_chain_id("cosmoshub-4"));
+    }
+}
@@ -5,7 +5,6 @@ use ic_crypto_secp256k1::PublicKey;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-
 /// Number of bytes in a pubkey
 pub const PUBKEY_BYTES: usize = 32 + 1;
 
@@ -27,7 +26,8 @@ impl<'de> Deserialize<'de> for Pubkey {
         D: serde::Deserializer<'de>,
     {
         let bytes: Vec<u8> = Vec::deserialize(deserializer)?;
-        bytes.try_into()
+        bytes
+            .try_into()
             .map(Self)
             .map_err(|_| serde::de::Error::custom("Invalid pubkey length"))
     }
@@ -42,6 +42,36 @@ impl Serialize for Pubkey {
     }
 }
 
+/// Errors returned by [`normalize_secp256k1_pubkey`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// Neither a 33-byte compressed key starting with 02 or 03, nor a 65-byte uncompressed key
+    /// starting with 04
+    #[error("Invalid secp256k1 public key of {0} bytes")]
+    InvalidLength(usize),
+}
+
+/// Returns the 33-byte compressed SEC1 form of a secp256k1 public key, compressing 65-byte
+/// uncompressed keys: the prefix is 02 for an even y-coordinate and 03 for an odd one, followed
+/// by the x-coordinate
+pub fn normalize_secp256k1_pubkey(raw: &[u8]) -> Result<[u8; PUBKEY_BYTES], KeyError> {
+    match raw {
+        [0x02 | 0x03, ..] if raw.len() == PUBKEY_BYTES => {
+            let mut compressed = [0u8; PUBKEY_BYTES];
+            compressed.copy_from_slice(raw);
+            Ok(compressed)
+        }
+        [0x04, coordinates @ ..] if coordinates.len() == 64 => {
+            let (x, y) = coordinates.split_at(32);
+            let mut compressed = [0u8; PUBKEY_BYTES];
+            compressed[0] = if y[31] & 1 == 0 { 0x02 } else { 0x03 };
+            compressed[1..].copy_from_slice(x);
+            Ok(compressed)
+        }
+        _ => Err(KeyError::InvalidLength(raw.len())),
+    }
+}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParsePubkeyError {
     #[error("String is the wrong size")]
@@ -79,7 +109,7 @@ impl FromStr for Pubkey {
     type Err = ParsePubkeyError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() > MAX_BASE58_LEN{
+        if s.len() > MAX_BASE58_LEN {
             return Err(ParsePubkeyError::WrongSize);
         }
         let pubkey_vec = bs58::decode(s).into_vec().map_err(|_| ParsePubkeyError::Invalid)?;
@@ -141,3 +171,40 @@ impl AsRef<[u8]> for Pubkey {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::public_key_to_cosmos_address;
+
+    /// Generator point of secp256k1, whose y-coordinate is even
+    const G_X: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const G_Y: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    #[test]
+    fn test_normalize_secp256k1_pubkey() {
+        let compressed = hex::decode(format!("02{}", G_X)).unwrap();
+        let uncompressed = hex::decode(format!("04{}{}", G_X, G_Y)).unwrap();
+        assert_eq!(normalize_secp256k1_pubkey(&compressed).unwrap().to_vec(), compressed);
+        assert_eq!(normalize_secp256k1_pubkey(&uncompressed).unwrap().to_vec(), compressed);
+
+        let mut odd = uncompressed.clone();
+        odd[64] |= 1;
+        assert_eq!(normalize_secp256k1_pubkey(&odd).unwrap()[0], 0x03);
+
+        assert_eq!(
+            normalize_secp256k1_pubkey(&uncompressed[..33]),
+            Err(KeyError::InvalidLength(33))
+        );
+        assert_eq!(
+            normalize_secp256k1_pubkey(&compressed[1..]),
+            Err(KeyError::InvalidLength(32))
+        );
+        assert_eq!(normalize_secp256k1_pubkey(&[]), Err(KeyError::InvalidLength(0)));
+
+        assert_eq!(
+            public_key_to_cosmos_address(&bs58::encode(&uncompressed).into_string()),
+            public_key_to_cosmos_address(&bs58::encode(&compressed).into_string())
+        );
+    }
+}
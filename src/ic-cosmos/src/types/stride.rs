@@ -0,0 +1,252 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    cosmos_protobuf::{decode_fields, encode_string, find_field},
+    cosmos_transaction_utils::{format_sdk_dec, parse_sdk_dec, SDK_DEC_PRECISION},
+};
+
+/// Type URL of the Stride liquid staking message, minting stTokens for the staked tokens
+pub const STRIDE_MSG_LIQUID_STAKE_TYPE_URL: &str = "/stride.stakeibc.MsgLiquidStake";
+
+/// Type URL of the Stride redemption message, unstaking stTokens on the host zone
+pub const STRIDE_MSG_REDEEM_STAKE_TYPE_URL: &str = "/stride.stakeibc.MsgRedeemStake";
+
+/// ABCI query path of the Stride host zone query
+pub const STRIDE_HOST_ZONE_PATH: &str = "/stride.stakeibc.Query/HostZone";
+
+/// Chain ID prefixes of the Stride mainnet and testnets
+pub const STRIDE_CHAIN_ID_PREFIXES: &[&str] = &["stride-"];
+
+/// A chain whose tokens are liquid staked through Stride, e.g. the Cosmos Hub for stATOM.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StrideHostZone {
+    /// Chain ID of the host zone, e.g. "cosmoshub-4"
+    pub chain_id: String,
+    /// Bech32 prefix of the addresses of the host zone
+    pub bech32prefix: String,
+    /// IBC connection from Stride to the host zone
+    pub connection_id: String,
+    /// IBC transfer channel from Stride to the host zone
+    pub transfer_channel_id: String,
+    /// Denom of the staked tokens on the host zone, e.g. "uatom"
+    pub host_denom: String,
+    /// IBC denom of the staked tokens on Stride
+    pub ibc_denom: String,
+    /// Amount of host tokens delegated by Stride on the host zone
+    pub total_delegations: String,
+    /// Host tokens per stToken as a decimal (e.g. "1.25"), stTokens of the host zone are worth
+    /// their amount times this rate
+    pub redemption_rate: String,
+    /// Redemption rate of the previous epoch
+    pub last_redemption_rate: String,
+    /// Redemption rates at which the host zone is halted
+    pub min_redemption_rate: String,
+    pub max_redemption_rate: String,
+    /// Unbonding period of the host zone, in days
+    pub unbonding_period: u64,
+    /// Whether liquid staking and redemptions are halted, e.g. after the redemption rate left its
+    /// bounds
+    pub halted: bool,
+}
+
+impl StrideHostZone {
+    /// Returns the amount of host tokens the `st_token_amount` stTokens of the host zone are worth,
+    /// truncated to the base unit
+    pub fn underlying_amount(&self, st_token_amount: u64) -> Result<u64, String> {
+        let rate = parse_sdk_dec(&self.redemption_rate)?;
+        let amount = (st_token_amount as u128)
+            .checked_mul(rate)
+            .ok_or("Underlying amount overflows")?
+            / 10u128.pow(SDK_DEC_PRECISION as u32);
+        u64::try_from(amount).map_err(|_| "Underlying amount overflows".to_string())
+    }
+}
+
+/// Returns `true` if `chain_id` belongs to a Stride network
+pub fn is_stride_chain_id(chain_id: &str) -> bool {
+    STRIDE_CHAIN_ID_PREFIXES
+        .iter()
+        .any(|prefix| chain_id.starts_with(prefix))
+}
+
+/// Encode `QueryGetHostZoneRequest` as hex `abci_query` data
+pub fn encode_stride_host_zone_query(host_zone_id: &str) -> String {
+    hex::encode(encode_string(0x0a, host_zone_id)) // chain_id = 1
+}
+
+/// Decode a `QueryGetHostZoneResponse` from the base64 ABCI response value
+pub fn decode_stride_host_zone_response(response_value: &str) -> Result<StrideHostZone, String> {
+    let decoded = STANDARD
+        .decode(response_value)
+        .map_err(|e| format!("Failed to decode base64 response: {}", e))?;
+
+    // host_zone = 1
+    let host_zone = find_field(&decoded, 1)?
+        .and_then(|field| field.as_bytes())
+        .ok_or("Host zone not found in response")?;
+
+    let mut zone = StrideHostZone {
+        redemption_rate: "0".to_string(),
+        last_redemption_rate: "0".to_string(),
+        min_redemption_rate: "0".to_string(),
+        max_redemption_rate: "0".to_string(),
+        total_delegations: "0".to_string(),
+        ..Default::default()
+    };
+    for (number, field) in decode_fields(host_zone)? {
+        let string = || field.as_string().unwrap_or_default();
+        match number {
+            1 => zone.chain_id = string(),
+            2 => zone.connection_id = string(),
+            8 => zone.ibc_denom = string(),
+            9 => zone.host_denom = string(),
+            10 => zone.last_redemption_rate = format_sdk_dec(&string())?,
+            11 => zone.redemption_rate = format_sdk_dec(&string())?,
+            12 => zone.transfer_channel_id = string(),
+            13 => zone.total_delegations = string(),
+            17 => zone.bech32prefix = string(),
+            19 => zone.halted = field.as_varint().unwrap_or_default() != 0,
+            20 => zone.min_redemption_rate = format_sdk_dec(&string())?,
+            21 => zone.max_redemption_rate = format_sdk_dec(&string())?,
+            26 => zone.unbonding_period = field.as_varint().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    Ok(zone)
+}
+
+/// Encode MsgLiquidStake to protobuf bytes, with `amount` given in the base unit of `host_denom`
+pub fn encode_msg_liquid_stake(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let creator = value["creator"].as_str().ok_or("Missing creator in MsgLiquidStake")?;
+    let amount = value["amount"].as_str().ok_or("Missing amount in MsgLiquidStake")?;
+    let host_denom = value["host_denom"]
+        .as_str()
+        .ok_or("Missing host_denom in MsgLiquidStake")?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, creator)); // creator = 1
+    msg_bytes.extend(encode_string(0x12, amount)); // amount = 2
+    msg_bytes.extend(encode_string(0x1a, host_denom)); // host_denom = 3
+
+    Ok(msg_bytes)
+}
+
+/// Encode MsgRedeemStake to protobuf bytes, redeeming `amount` stTokens of `host_zone`
+/// (e.g. "cosmoshub-4") to `receiver` on the host zone
+pub fn encode_msg_liquid_unstake(value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    let creator = value["creator"].as_str().ok_or("Missing creator in MsgRedeemStake")?;
+    let amount = value["amount"].as_str().ok_or("Missing amount in MsgRedeemStake")?;
+    let host_zone = value["host_zone"]
+        .as_str()
+        .ok_or("Missing host_zone in MsgRedeemStake")?;
+    let receiver = value["receiver"].as_str().ok_or("Missing receiver in MsgRedeemStake")?;
+
+    let mut msg_bytes = Vec::new();
+    msg_bytes.extend(encode_string(0x0a, creator)); // creator = 1
+    msg_bytes.extend(encode_string(0x12, amount)); // amount = 2
+    msg_bytes.extend(encode_string(0x1a, host_zone)); // host_zone = 3
+    msg_bytes.extend(encode_string(0x22, receiver)); // receiver = 4
+
+    Ok(msg_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::cosmos_protobuf::{encode_length_delimited, encode_varint};
+
+    /// Encodes a field numbered 16 or more, whose key takes two bytes
+    fn encode_two_byte_key_field(key: [u8; 2], value: Vec<u8>) -> Vec<u8> {
+        let mut bytes = key.to_vec();
+        bytes.extend(value);
+        bytes
+    }
+
+    #[test]
+    fn test_encode_msg_liquid_stake() {
+        let value = json!({
+            "creator": "stride1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmca8lyk",
+            "amount": "1000000",
+            "host_denom": "uatom"
+        });
+        let fields: Vec<_> = decode_fields(&encode_msg_liquid_stake(&value).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|(number, field)| (number, field.as_string().unwrap()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (1, "stride1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmca8lyk".to_string()),
+                (2, "1000000".to_string()),
+                (3, "uatom".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_msg_liquid_unstake() {
+        let value = json!({
+            "creator": "stride1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmca8lyk",
+            "amount": "1000000",
+            "host_zone": "cosmoshub-4",
+            "receiver": "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"
+        });
+        let bytes = encode_msg_liquid_unstake(&value).unwrap();
+        let fields = decode_fields(&bytes).unwrap();
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[2].1.as_string().unwrap(), "cosmoshub-4");
+        assert_eq!(
+            fields[3].1.as_string().unwrap(),
+            "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6"
+        );
+
+        let mut missing_receiver = value.clone();
+        missing_receiver["receiver"] = json!(null);
+        assert!(encode_msg_liquid_unstake(&missing_receiver).is_err());
+    }
+
+    #[test]
+    fn test_decode_stride_host_zone_response() {
+        let mut zone = encode_string(0x0a, "cosmoshub-4");
+        zone.extend(encode_string(0x12, "connection-0"));
+        zone.extend(encode_string(
+            0x42,
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+        ));
+        zone.extend(encode_string(0x4a, "uatom"));
+        zone.extend(encode_string(0x52, "1240000000000000000"));
+        zone.extend(encode_string(0x5a, "1250000000000000000"));
+        zone.extend(encode_string(0x62, "channel-0"));
+        zone.extend(encode_string(0x6a, "9000000000000"));
+        let mut bech32prefix = encode_varint(6);
+        bech32prefix.extend(b"cosmos");
+        zone.extend(encode_two_byte_key_field([0x8a, 0x01], bech32prefix)); // bech32prefix = 17
+        zone.extend(encode_two_byte_key_field([0x98, 0x01], encode_varint(1))); // halted = 19
+        zone.extend(encode_two_byte_key_field([0xd0, 0x01], encode_varint(21))); // unbonding_period = 26
+        let response = STANDARD.encode(encode_length_delimited(0x0a, &zone));
+
+        let zone = decode_stride_host_zone_response(&response).unwrap();
+        assert_eq!(zone.chain_id, "cosmoshub-4");
+        assert_eq!(zone.connection_id, "connection-0");
+        assert_eq!(zone.transfer_channel_id, "channel-0");
+        assert_eq!(zone.host_denom, "uatom");
+        assert_eq!(zone.bech32prefix, "cosmos");
+        assert_eq!(zone.total_delegations, "9000000000000");
+        assert_eq!(zone.redemption_rate, "1.25");
+        assert_eq!(zone.last_redemption_rate, "1.24");
+        assert_eq!(zone.min_redemption_rate, "0");
+        assert_eq!(zone.unbonding_period, 21);
+        assert!(zone.halted);
+
+        assert_eq!(zone.underlying_amount(1_000_000), Ok(1_250_000));
+        assert_eq!(zone.underlying_amount(3), Ok(3));
+        assert!(decode_stride_host_zone_response(&STANDARD.encode([])).is_err());
+        assert!(is_stride_chain_id("stride-1"));
+        assert!(!is_stride_chain_id("cosmoshub-4"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Waiting between the polls of a canister, which cannot block its thread.
+
+use std::time::Duration;
+
+use futures::channel::oneshot;
+
+/// Returns after `delay`, resuming the calling task from a one-off timer.
+pub async fn sleep(delay: Duration) {
+    let (sender, receiver) = oneshot::channel();
+    ic_cdk_timers::set_timer(delay, move || {
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
+
+/// Exponential backoff between the attempts of a poll: the delay doubles after each wait, up to
+/// `max_delay`, for at most `max_attempts` attempts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    delay: Duration,
+    max_delay: Duration,
+    attempts_left: u32,
+}
+
+impl Backoff {
+    pub fn new(initial_delay: Duration, max_delay: Duration, max_attempts: u32) -> Self {
+        Self {
+            delay: initial_delay,
+            max_delay,
+            attempts_left: max_attempts.saturating_sub(1),
+        }
+    }
+
+    /// Returns the delay before the next attempt, `None` once the attempts are exhausted.
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if self.attempts_left == 0 {
+            return None;
+        }
+        self.attempts_left -= 1;
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(self.max_delay);
+        Some(delay)
+    }
+
+    /// Waits before the next attempt, returns `false` without waiting once the attempts are exhausted.
+    pub async fn wait(&mut self) -> bool {
+        match self.next_delay() {
+            Some(delay) => {
+                sleep(delay).await;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delays() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5), 5);
+        let delays: Vec<_> = std::iter::from_fn(|| backoff.next_delay()).collect();
+        assert_eq!(
+            delays,
+            [1, 2, 4, 5].map(Duration::from_secs).to_vec(),
+            "4 waits between 5 attempts"
+        );
+        assert_eq!(
+            Backoff::new(Duration::from_secs(1), Duration::from_secs(5), 1).next_delay(),
+            None
+        );
+    }
+}
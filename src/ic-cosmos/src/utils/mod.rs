@@ -1,2 +1,3 @@
+pub mod backoff;
 mod option_serializer;
 pub mod short_vec;
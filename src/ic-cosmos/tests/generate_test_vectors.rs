@@ -0,0 +1,224 @@
+//! Generates the protobuf test vectors of `tests/vectors/sign_doc_vectors.json`.
+//!
+//! Every case is built twice, with the manual encoder of `create_sign_doc_bytes` and with the
+//! official API of cosmrs, and the two sign docs must be identical byte for byte. Run with:
+//!
+//! ```sh
+//! cargo test -p ic-cosmos --features generate-test-vectors --test generate_test_vectors
+//! ```
+//!
+//! The unit tests of the encoder then check it against the saved vectors without cosmrs.
+
+use cosmrs::{
+    bank::MsgSend,
+    crypto::PublicKey,
+    staking::MsgDelegate,
+    tendermint::{self, block, chain},
+    tx::{self, Fee, Msg, SignDoc, SignerInfo},
+    AccountId, Any, Coin,
+};
+use ic_cosmos::types::{create_sign_doc_bytes, CosmosCoin, CosmosMessage, CosmosTransaction, SignMode};
+use serde::Serialize;
+use serde_json::json;
+
+const VECTORS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/sign_doc_vectors.json");
+
+/// Compressed public key of the generator point of secp256k1
+const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const CHAIN_ID: &str = "cosmoshub-4";
+const ACCOUNT_NUMBER: u64 = 12345;
+const FROM_ADDRESS: &str = "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6";
+const TO_ADDRESS: &str = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+const VALIDATOR_ADDRESS: &str = "cosmosvaloper1e5yfpc8l6g4808fclmlyd38tjgxuwshnmjkrq6";
+const IBC_DENOM: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+#[derive(Serialize)]
+struct SignDocVectors {
+    public_key: String,
+    vectors: Vec<SignDocVector>,
+}
+
+#[derive(Serialize)]
+struct SignDocVector {
+    name: String,
+    transaction: CosmosTransaction,
+    /// Hex of the sign doc encoded by cosmrs
+    sign_doc: String,
+}
+
+fn msg_send(amount: &str) -> CosmosMessage {
+    CosmosMessage {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: json!({
+            "from_address": FROM_ADDRESS,
+            "to_address": TO_ADDRESS,
+            "amount": [{ "denom": "uatom", "amount": amount }]
+        }),
+    }
+}
+
+fn msg_delegate(amount: &str) -> CosmosMessage {
+    CosmosMessage {
+        type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+        value: json!({
+            "delegator_address": FROM_ADDRESS,
+            "validator_address": VALIDATOR_ADDRESS,
+            "amount": { "denom": "uatom", "amount": amount }
+        }),
+    }
+}
+
+fn transaction(
+    messages: Vec<CosmosMessage>,
+    fee: Vec<CosmosCoin>,
+    gas_limit: u64,
+    memo: &str,
+    account_number: u64,
+    sequence: u64,
+) -> CosmosTransaction {
+    CosmosTransaction {
+        messages,
+        fee,
+        gas_limit,
+        memo: memo.to_string(),
+        chain_id: CHAIN_ID.to_string(),
+        account_number,
+        sequence,
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+        sign_mode: SignMode::Direct,
+    }
+}
+
+fn cases() -> Vec<(&'static str, CosmosTransaction)> {
+    vec![
+        (
+            "single_msg_send",
+            transaction(
+                vec![msg_send("1000")],
+                vec![CosmosCoin::new("uatom", "5000")],
+                200_000,
+                "",
+                ACCOUNT_NUMBER,
+                3,
+            ),
+        ),
+        (
+            "multi_message",
+            transaction(
+                vec![msg_send("1000"), msg_delegate("5000")],
+                vec![CosmosCoin::new("uatom", "7500")],
+                300_000,
+                "multi",
+                ACCOUNT_NUMBER,
+                4,
+            ),
+        ),
+        (
+            "multi_denom_fee",
+            transaction(
+                vec![msg_send("1000")],
+                vec![CosmosCoin::new("uatom", "2500"), CosmosCoin::new(IBC_DENOM, "100")],
+                250_000,
+                "fees",
+                ACCOUNT_NUMBER,
+                5,
+            ),
+        ),
+        (
+            "zero_fee_first_transaction",
+            transaction(vec![msg_send("1000")], Vec::new(), 150_000, "", 0, 0),
+        ),
+    ]
+}
+
+fn cosmrs_coin(value: &serde_json::Value) -> Coin {
+    Coin {
+        denom: value["denom"].as_str().unwrap().parse().unwrap(),
+        amount: value["amount"].as_str().unwrap().parse().unwrap(),
+    }
+}
+
+fn account_id(value: &serde_json::Value) -> AccountId {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+/// Builds the message with the cosmrs type of its type URL
+fn cosmrs_message(message: &CosmosMessage) -> Any {
+    let value = &message.value;
+    match message.type_url.as_str() {
+        "/cosmos.bank.v1beta1.MsgSend" => MsgSend {
+            from_address: account_id(&value["from_address"]),
+            to_address: account_id(&value["to_address"]),
+            amount: value["amount"].as_array().unwrap().iter().map(cosmrs_coin).collect(),
+        }
+        .to_any()
+        .unwrap(),
+        "/cosmos.staking.v1beta1.MsgDelegate" => MsgDelegate {
+            delegator_address: account_id(&value["delegator_address"]),
+            validator_address: account_id(&value["validator_address"]),
+            amount: cosmrs_coin(&value["amount"]),
+        }
+        .to_any()
+        .unwrap(),
+        type_url => panic!("No cosmrs equivalent of {}", type_url),
+    }
+}
+
+/// Encodes the sign doc of `transaction` with cosmrs
+fn cosmrs_sign_doc_bytes(transaction: &CosmosTransaction, public_key: &[u8]) -> Vec<u8> {
+    let body = tx::Body::new(
+        transaction.messages.iter().map(cosmrs_message).collect::<Vec<_>>(),
+        transaction.memo.clone(),
+        block::Height::from(0u32),
+    );
+    let fee = Fee {
+        amount: transaction
+            .fee
+            .iter()
+            .map(|coin| cosmrs_coin(&json!({ "denom": coin.denom, "amount": coin.amount })))
+            .collect(),
+        gas_limit: transaction.gas_limit,
+        payer: None,
+        granter: None,
+    };
+    let public_key = PublicKey::from(tendermint::PublicKey::from_raw_secp256k1(public_key).unwrap());
+    let auth_info = SignerInfo::single_direct(Some(public_key), transaction.sequence).auth_info(fee);
+    let chain_id = chain::Id::try_from(transaction.chain_id.as_str()).unwrap();
+
+    SignDoc::new(&body, &auth_info, &chain_id, transaction.account_number)
+        .unwrap()
+        .into_bytes()
+        .unwrap()
+}
+
+#[test]
+fn generate_sign_doc_vectors() {
+    let public_key = hex::decode(PUBLIC_KEY).unwrap();
+
+    let vectors = cases()
+        .into_iter()
+        .map(|(name, transaction)| {
+            let expected = cosmrs_sign_doc_bytes(&transaction, &public_key);
+            let actual = create_sign_doc_bytes(&transaction, &public_key).unwrap();
+            assert_eq!(
+                hex::encode(&actual),
+                hex::encode(&expected),
+                "Manual encoding of {} differs from cosmrs",
+                name
+            );
+            SignDocVector {
+                name: name.to_string(),
+                transaction,
+                sign_doc: hex::encode(expected),
+            }
+        })
+        .collect();
+
+    let vectors = SignDocVectors {
+        public_key: PUBLIC_KEY.to_string(),
+        vectors,
+    };
+    let json = serde_json::to_string_pretty(&vectors).unwrap();
+    std::fs::write(VECTORS_PATH, json + "\n").unwrap();
+}
@@ -9,7 +9,7 @@ use candid::{encode_args, utils::ArgumentEncoder, CandidType, Decode, Encode, Pr
 use ic_cdk::api::management_canister::main::CanisterId;
 pub use mock::*;
 use pocket_ic::{
-    common::rest::{CanisterHttpResponse, MockCanisterHttpResponse, RawMessageId},
+    common::rest::{CanisterHttpRequest, CanisterHttpResponse, MockCanisterHttpResponse, RawMessageId},
     management_canister::CanisterSettings,
     PocketIc, PocketIcBuilder,
 };
@@ -30,13 +30,33 @@ pub struct TestSetup {
 
 impl TestSetup {
     pub fn new<T: CandidType>(wasm: Vec<u8>, args: T) -> Self {
+        let setup = Self::create();
+        setup.install_code(wasm, args);
+        setup
+    }
+
+    /// Creates an empty canister in a new environment, installed later with [`TestSetup::install_code`]
+    pub fn create() -> Self {
         let pic = PocketIcBuilder::new()
             .with_nns_subnet()
             .with_ii_subnet()
             .with_application_subnet()
             .build();
+        Self::create_in(Arc::new(pic))
+    }
+
+    /// Creates an empty canister in the environment of this setup, so that the canisters can call
+    /// each other
+    pub fn create_canister(&self) -> Self {
+        Self::create_in(self.env.clone())
+    }
 
-        let env = Arc::new(pic);
+    pub fn install_code<T: CandidType>(&self, wasm: Vec<u8>, args: T) {
+        self.env
+            .install_canister(self.canister_id, wasm, Encode!(&args).unwrap(), Some(self.controller));
+    }
+
+    fn create_in(env: Arc<PocketIc>) -> Self {
         let caller = Self::caller_id();
         let controller = Self::controller_id();
         let canister_id = env.create_canister_with_settings(
@@ -47,7 +67,6 @@ impl TestSetup {
             }),
         );
         env.add_cycles(canister_id, INITIAL_CYCLES);
-        env.install_canister(canister_id, wasm, Encode!(&args).unwrap(), Some(controller));
 
         Self {
             env,
@@ -172,6 +191,27 @@ impl<R: CandidType + DeserializeOwned> CallFlow<R> {
         self
     }
 
+    /// Calls `inspect` with the next pending HTTP request before it is mocked, for assertions on
+    /// request bodies which are not known in advance
+    pub fn inspect_http_request(self, inspect: impl FnOnce(&CanisterHttpRequest)) -> Self {
+        if self.setup.env.get_canister_http().is_empty() {
+            self.setup.tick_until_http_request();
+        }
+        let http_requests = self.setup.env.get_canister_http();
+        inspect(http_requests.first().expect("no pending HTTP request"));
+        self
+    }
+
+    /// Advances the time by `duration` once the canister handled the mocked responses, to fire
+    /// the timers it waits on between two HTTP outcalls
+    pub fn advance_time(self, duration: Duration) -> Self {
+        for _ in 0..MAX_TICKS {
+            self.setup.env.tick();
+        }
+        self.setup.env.advance_time(duration);
+        self
+    }
+
     fn mock_http_once_inner(&self, mock: &MockOutcall) {
         if !self.try_mock_http_inner(mock) {
             panic!("no pending HTTP request")